@@ -0,0 +1,41 @@
+//! Searches for still lifes whose living cells are connected,
+//! using a result filter.
+
+use rlifesrc_lib::{Config, Status, WorldSnapshot, ALIVE};
+use std::collections::HashSet;
+
+/// Whether the living cells in generation 0 are connected,
+/// where two cells are adjacent if they are neighbors in the Moore neighborhood.
+fn is_connected(snapshot: &WorldSnapshot) -> bool {
+    let config = snapshot.config();
+    let living = (0..config.width)
+        .flat_map(|x| (0..config.height).map(move |y| (x, y)))
+        .filter(|&(x, y)| snapshot.get_cell_state((x, y, 0)) == Some(ALIVE))
+        .collect::<HashSet<_>>();
+    let start = match living.iter().next() {
+        Some(&start) => start,
+        None => return true,
+    };
+    let mut seen = HashSet::from([start]);
+    let mut stack = vec![start];
+    while let Some((x, y)) = stack.pop() {
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                let neighbor = (x + dx, y + dy);
+                if living.contains(&neighbor) && seen.insert(neighbor) {
+                    stack.push(neighbor);
+                }
+            }
+        }
+    }
+    seen.len() == living.len()
+}
+
+fn main() {
+    let config = Config::new(6, 6, 1).set_max_cell_count(Some(8));
+    let mut search = config.world().unwrap();
+    search.set_result_filter(Some(Box::new(is_connected)));
+    while let Status::Found = search.search(None) {
+        println!("{}", search.rle_gen(0));
+    }
+}
@@ -0,0 +1,51 @@
+//! Finds the rules of the form `B3/S...` in which the glider
+//! of Conway's Game of Life is still a glider,
+//! by searching over all the 2^8 survival conditions without `S0`.
+
+use rlifesrc_lib::{Config, KnownCell, Status, ALIVE, DEAD};
+
+fn main() {
+    // The glider in generation 0. The other generations are unknown.
+    let glider = [".....", ".ooo.", ".o...", "..o..", "....."];
+    let known_cells = glider
+        .iter()
+        .enumerate()
+        .flat_map(|(y, row)| {
+            row.chars().enumerate().map(move |(x, c)| KnownCell {
+                coord: (x as i32, y as i32, 0),
+                state: if c == 'o' { ALIVE } else { DEAD },
+            })
+        })
+        .collect::<Vec<_>>();
+    let config = Config::new(5, 5, 4)
+        .set_translate(1, 1)
+        .set_known_cells(known_cells);
+
+    let rules = (0..1 << 8)
+        .map(|mask: u32| {
+            let survival = (1..=8)
+                .filter(|n| mask & 1 << (n - 1) != 0)
+                .map(|n| n.to_string())
+                .collect::<String>();
+            format!("B3/S{}", survival)
+        })
+        .collect::<Vec<_>>();
+
+    let outcomes = config.search_over_rules(&rules, Some(100000));
+    for outcome in &outcomes {
+        let status = match &outcome.status {
+            Ok(Status::Found) => "found",
+            Ok(Status::None) => "none",
+            Ok(_) => "unknown",
+            Err(e) => {
+                eprintln!("{}: {}", outcome.rule_string, e);
+                continue;
+            }
+        };
+        if status != "none" {
+            println!("{:<16} {}", outcome.rule_string, status);
+        }
+    }
+    let found = outcomes.iter().filter(|outcome| outcome.is_found()).count();
+    println!("The glider works in {} of {} rules.", found, outcomes.len());
+}
@@ -0,0 +1,34 @@
+//! Measures the time of constructing worlds repeatedly,
+//! with and without the rule cache.
+//!
+//! Run it with `cargo run --release --example world_construction`.
+
+use rlifesrc_lib::Config;
+use std::time::{Duration, Instant};
+
+/// Number of worlds to construct for each rule.
+const TIMES: u32 = 100;
+
+/// Constructs the world of the configuration [`TIMES`] times,
+/// and returns the average time.
+fn bench(config: &Config) -> Duration {
+    let start = Instant::now();
+    for _ in 0..TIMES {
+        config.world().unwrap();
+    }
+    start.elapsed() / TIMES
+}
+
+fn main() {
+    for rule in [
+        "B3/S23",
+        "MAPARYXfhZofugWaH7oaIDogBZofuhogOiAaIDogIAAgAAWaH7oaIDogGiA6ICAAIAAaIDogIAAgACAAIAAAAAAAA",
+    ] {
+        let config = Config::new(16, 16, 2).set_rule_string(rule);
+        let without_cache = bench(&config.clone().set_cache_rule(false));
+        let with_cache = bench(&config);
+        println!("{}", rule);
+        println!("    without cache: {:?}", without_cache);
+        println!("    with cache:    {:?}", with_cache);
+    }
+}
@@ -0,0 +1,836 @@
+//! Forward evolution and classification of patterns.
+//!
+//! This is independent of the search. It evolves a finite pattern on an infinite
+//! plane, so that a result can be checked against the config it was found under.
+
+use crate::{
+    cells::{State, StateRepr, ALIVE, DEAD},
+    config::{Config, KnownCell, Transform},
+    error::Error,
+    poly_world::PolyWorld,
+};
+use ca_rules::ParseNtLifeGen;
+use std::collections::{BTreeMap, HashSet};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The relative positions of the neighbors of a cell, from the highest bit
+/// to the lowest bit of a neighborhood given by `ca_rules`.
+///
+/// This is the order of MAP rules: NW, N, NE, W, E, SW, S, SE.
+const NBHD: [(i32, i32); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+/// A finite pattern on an infinite plane.
+///
+/// Only the cells that are not [`DEAD`] are stored.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Pattern {
+    cells: BTreeMap<(i32, i32), State>,
+}
+
+impl Pattern {
+    /// Creates a pattern from a list of cells and their states.
+    ///
+    /// Dead cells are ignored.
+    pub fn from_cells<I: IntoIterator<Item = ((i32, i32), State)>>(cells: I) -> Self {
+        Self {
+            cells: cells
+                .into_iter()
+                .filter(|&(_, state)| state != DEAD)
+                .collect(),
+        }
+    }
+
+    /// Generation `t` of a world, as a pattern.
+    ///
+    /// Unknown cells are regarded as dead.
+    pub fn from_world(world: &PolyWorld, t: i32) -> Self {
+        let config = world.config();
+        Self::from_cells((0..config.width).flat_map(|x| {
+            (0..config.height)
+                .filter_map(move |y| world.get_cell_state((x, y, t)).map(|state| ((x, y), state)))
+        }))
+    }
+
+    /// The state of the cell at `(x, y)`.
+    pub fn get(&self, coord: (i32, i32)) -> State {
+        self.cells.get(&coord).copied().unwrap_or(DEAD)
+    }
+
+    /// The cells that are not dead, and their states.
+    pub fn cells(&self) -> impl Iterator<Item = ((i32, i32), State)> + '_ {
+        self.cells.iter().map(|(&coord, &state)| (coord, state))
+    }
+
+    /// Whether all the cells are dead.
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Number of living cells.
+    ///
+    /// For Generations rules, dying cells are not counted.
+    pub fn population(&self) -> u32 {
+        self.cells.values().filter(|&&state| state == ALIVE).count() as u32
+    }
+
+    /// The smallest rectangle containing all the cells that are not dead,
+    /// as `(x0, y0, x1, y1)`.
+    ///
+    /// Returns `None` if the pattern is empty.
+    pub fn bounding_box(&self) -> Option<(i32, i32, i32, i32)> {
+        self.cells.keys().fold(None, |bounding_box, &(x, y)| {
+            Some(match bounding_box {
+                Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x), y1.max(y)),
+                None => (x, y, x, y),
+            })
+        })
+    }
+
+    /// Moves the pattern by `(dx, dy)`.
+    pub fn translate(&self, dx: i32, dy: i32) -> Self {
+        Self {
+            cells: self
+                .cells
+                .iter()
+                .map(|(&(x, y), &state)| ((x + dx, y + dy), state))
+                .collect(),
+        }
+    }
+
+    /// If `other` is a translation of this pattern, returns the translation,
+    /// i.e., `(dx, dy)` such that `other` at `(x, y)` is this pattern at `(x + dx, y + dy)`.
+    fn translation_to(&self, other: &Self) -> Option<(i32, i32)> {
+        let (x0, y0, _, _) = self.bounding_box()?;
+        let (x1, y1, _, _) = other.bounding_box()?;
+        let (dx, dy) = (x0 - x1, y0 - y1);
+        (self.cells.len() == other.cells.len() && self.translate(-dx, -dy) == *other)
+            .then_some((dx, dy))
+    }
+
+    /// The living cells in [extended Wechsler format](https://conwaylife.com/wiki/Apgcode#Extended_Wechsler_format),
+    /// in the orientation with the shortest, and then lexicographically smallest, code.
+    ///
+    /// The pattern must not be empty.
+    fn wechsler(&self) -> String {
+        let (x0, y0, x1, y1) = self.bounding_box().unwrap();
+        let (width, height) = (x1 - x0 + 1, y1 - y0 + 1);
+        // The cell at `(u, v)` of an orientation is at `(x + a * u + b * v, y + c * u + d * v)`,
+        // where `u < length` and `v < breadth`, in the same order as in apgsearch.
+        [
+            (width, height, x0, y0, 1, 0, 0, 1),
+            (width, height, x1, y0, -1, 0, 0, 1),
+            (width, height, x0, y1, 1, 0, 0, -1),
+            (width, height, x1, y1, -1, 0, 0, -1),
+            (height, width, x0, y0, 0, 1, 1, 0),
+            (height, width, x1, y0, 0, -1, 1, 0),
+            (height, width, x0, y1, 0, 1, -1, 0),
+            (height, width, x1, y1, 0, -1, -1, 0),
+        ]
+        .into_iter()
+        .map(|(length, breadth, x, y, a, b, c, d)| {
+            let mut code = String::new();
+            for strip in 0..(breadth + 4) / 5 {
+                if strip > 0 {
+                    code.push('z');
+                }
+                let mut zeros = 0;
+                for u in 0..length {
+                    let column = (0..5).fold(0, |column, i| {
+                        let v = 5 * strip + i;
+                        if self.get((x + a * u + b * v, y + c * u + d * v)) == ALIVE {
+                            column | 1 << i
+                        } else {
+                            column
+                        }
+                    });
+                    if column == 0 {
+                        zeros += 1;
+                        continue;
+                    }
+                    // Runs of blank columns are shortened.
+                    while zeros > 39 {
+                        code.push_str("yz");
+                        zeros -= 39;
+                    }
+                    match zeros {
+                        0 => (),
+                        1 => code.push('0'),
+                        2 => code.push('w'),
+                        3 => code.push('x'),
+                        _ => {
+                            code.push('y');
+                            code.push(BASE36[zeros - 4] as char);
+                        }
+                    }
+                    zeros = 0;
+                    code.push(BASE36[column] as char);
+                }
+            }
+            code
+        })
+        .min_by(|code0, code1| code0.len().cmp(&code1.len()).then(code0.cmp(code1)))
+        .unwrap()
+    }
+
+    /// The [apgcode](https://conwaylife.com/wiki/Apgcode) of the pattern,
+    /// as used by [Catagolue](https://catagolue.hatsya.com),
+    /// given its classification under the rule of the evolver.
+    ///
+    /// The prefix is `xs` followed by the population for still lifes,
+    /// `xp` followed by the period for oscillators, and `xq` followed by the period
+    /// for spaceships. It is followed by the pattern in extended Wechsler format,
+    /// in the phase and orientation with the shortest, and then lexicographically
+    /// smallest, code.
+    ///
+    /// Returns `None` if the pattern is not periodic, or if the rule is a Generations
+    /// rule, whose apgcodes are in another format.
+    pub fn apgcode(&self, evolver: &Evolver, classification: Classification) -> Option<String> {
+        if evolver.gen() > 2 {
+            return None;
+        }
+        let (prefix, period) = match classification {
+            Classification::StillLife => (format!("xs{}", self.population()), 1),
+            Classification::Oscillator { period } => (format!("xp{}", period), period),
+            Classification::Spaceship { period, .. } => (format!("xq{}", period), period),
+            _ => return None,
+        };
+        let mut phase = self.clone();
+        let mut codes = Vec::with_capacity(period as usize);
+        for t in 0..period {
+            if t > 0 {
+                phase = evolver.step(&phase);
+            }
+            codes.push(phase.wechsler());
+        }
+        let code = codes
+            .into_iter()
+            .min_by(|code0, code1| code0.len().cmp(&code1.len()).then(code0.cmp(code1)))?;
+        Some(format!("{}_{}", prefix, code))
+    }
+}
+
+/// The digits of base 36, used in apgcodes.
+const BASE36: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+/// A forward evolver of a rule, independent of the search.
+///
+/// Supports the same rules as the search, except those with `B0`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Evolver {
+    /// Whether a dead cell with the given neighborhood is born.
+    birth: Vec<bool>,
+    /// Whether a living cell with the given neighborhood survives.
+    survival: Vec<bool>,
+    /// Number of states.
+    gen: usize,
+}
+
+impl ParseNtLifeGen for Evolver {
+    fn from_bsg(b: Vec<u8>, s: Vec<u8>, gen: usize) -> Self {
+        let mut birth = vec![false; 256];
+        let mut survival = vec![false; 256];
+        for n in b {
+            birth[n as usize] = true;
+        }
+        for n in s {
+            survival[n as usize] = true;
+        }
+        Self {
+            birth,
+            survival,
+            gen,
+        }
+    }
+}
+
+impl Evolver {
+    /// Parses the rule string.
+    pub fn new(rule_string: &str) -> Result<Self, Error> {
+        let evolver: Self = ParseNtLifeGen::parse_rule(rule_string)?;
+        if evolver.birth[0] {
+            return Err(Error::B0AnalysisError);
+        }
+        Ok(evolver)
+    }
+
+    /// Number of states of the rule.
+    pub const fn gen(&self) -> usize {
+        self.gen
+    }
+
+    /// The next generation of the pattern.
+    pub fn step(&self, pattern: &Pattern) -> Pattern {
+        let candidates = pattern
+            .cells
+            .keys()
+            .flat_map(|&(x, y)| {
+                NBHD.iter()
+                    .map(move |&(nx, ny)| (x + nx, y + ny))
+                    .chain(Some((x, y)))
+            })
+            .collect::<HashSet<_>>();
+        Pattern::from_cells(candidates.into_iter().map(|(x, y)| {
+            let alives = NBHD.iter().enumerate().fold(0, |n, (i, &(nx, ny))| {
+                if pattern.get((x + nx, y + ny)) == ALIVE {
+                    n | 1 << (7 - i)
+                } else {
+                    n
+                }
+            });
+            let state = match pattern.get((x, y)) {
+                DEAD if self.birth[alives] => ALIVE,
+                DEAD => DEAD,
+                ALIVE if self.survival[alives] => ALIVE,
+                State(j) => State((j + 1) % self.gen as StateRepr),
+            };
+            ((x, y), state)
+        }))
+    }
+}
+
+/// The kind of a pattern, according to its evolution.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "snake_case"))]
+pub enum Classification {
+    /// All the cells die in `gen` generations.
+    DiesOut {
+        /// The first generation where all the cells are dead.
+        gen: i32,
+    },
+    /// The pattern does not change.
+    StillLife,
+    /// The pattern returns to itself after `period` generations.
+    Oscillator {
+        /// The fundamental period.
+        period: i32,
+    },
+    /// The pattern returns to itself after `period` generations, translated.
+    ///
+    /// The translation `(dx, dy)` follows the same convention as
+    /// [`Config::dx`](Config#structfield.dx) and [`Config::dy`](Config#structfield.dy):
+    /// generation `period` at `(x, y)` is generation 0 at `(x + dx, y + dy)`.
+    Spaceship {
+        /// The fundamental period.
+        period: i32,
+        /// The horizontal translation in a period.
+        dx: i32,
+        /// The vertical translation in a period.
+        dy: i32,
+    },
+    /// No periodicity is found within the given number of generations.
+    Unknown,
+}
+
+/// The direction of a spaceship.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Direction {
+    /// Moves horizontally or vertically.
+    Orthogonal,
+    /// Moves along a diagonal.
+    Diagonal,
+    /// Moves in any other direction.
+    Oblique,
+}
+
+impl Classification {
+    /// Classifies a pattern by evolving it for at most `max_period` generations.
+    ///
+    /// The pattern is regarded as periodic only if it returns to itself,
+    /// possibly translated. Patterns that become periodic after a few generations
+    /// are [`Unknown`](Self::Unknown).
+    pub fn new(evolver: &Evolver, pattern: &Pattern, max_period: i32) -> Self {
+        if pattern.is_empty() {
+            return Self::DiesOut { gen: 0 };
+        }
+        let mut current = pattern.clone();
+        for t in 1..=max_period {
+            current = evolver.step(&current);
+            if current.is_empty() {
+                return Self::DiesOut { gen: t };
+            }
+            match pattern.translation_to(&current) {
+                Some((0, 0)) if t == 1 => return Self::StillLife,
+                Some((0, 0)) => return Self::Oscillator { period: t },
+                Some((dx, dy)) => return Self::Spaceship { period: t, dx, dy },
+                None => (),
+            }
+        }
+        Self::Unknown
+    }
+
+    /// The velocity, as `(dx, dy, period)`, if the pattern is periodic.
+    ///
+    /// This is in the same format as [`World::velocity`](crate::World::velocity).
+    pub const fn velocity(&self) -> Option<(i32, i32, i32)> {
+        match *self {
+            Self::StillLife => Some((0, 0, 1)),
+            Self::Oscillator { period } => Some((0, 0, period)),
+            Self::Spaceship { period, dx, dy } => Some((dx, dy, period)),
+            _ => None,
+        }
+    }
+
+    /// The direction of a spaceship.
+    pub const fn direction(&self) -> Option<Direction> {
+        match *self {
+            Self::Spaceship { dx: 0, .. } | Self::Spaceship { dy: 0, .. } => {
+                Some(Direction::Orthogonal)
+            }
+            Self::Spaceship { dx, dy, .. } if dx == dy || dx == -dy => Some(Direction::Diagonal),
+            Self::Spaceship { .. } => Some(Direction::Oblique),
+            _ => None,
+        }
+    }
+}
+
+/// A way in which a pattern does not satisfy a [`Config`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "snake_case"))]
+pub enum Mismatch {
+    /// The pattern does not fit in the world.
+    Size {
+        /// The bounding box of the pattern.
+        bounding_box: (i32, i32, i32, i32),
+    },
+    /// The rule of the pattern is different from the rule of the config.
+    Rule {
+        /// The rule of the pattern.
+        rule: String,
+    },
+    /// The fundamental period does not match the period of the config.
+    Period {
+        /// The fundamental period, or `None` if no periodicity is found.
+        period: Option<i32>,
+    },
+    /// The translation in a period of the config does not match.
+    Translation {
+        /// The translation of the pattern.
+        translation: (i32, i32),
+    },
+    /// The pattern does not evolve into its image under the transformation and
+    /// translation of the config after a period.
+    Evolution,
+}
+
+impl Config {
+    /// Checks whether a pattern, given as generation 0 in the coordinates of the world,
+    /// satisfies the size, rule, period and translation of this config,
+    /// and returns all the mismatches.
+    ///
+    /// `rule` is the rule of the pattern, e.g., from the header of an RLE, if known.
+    /// `classification` should be obtained with at least the period of this config.
+    ///
+    /// Symmetries and other constraints of the search are not checked.
+    pub fn check_pattern(
+        &self,
+        pattern: &Pattern,
+        rule: Option<&str>,
+        classification: Classification,
+    ) -> Result<Vec<Mismatch>, Error> {
+        let evolver = Evolver::new(&self.rule_string)?;
+        let mut mismatches = Vec::new();
+
+        if let Some(bounding_box @ (x0, y0, x1, y1)) = pattern.bounding_box() {
+            if x0 < 0 || y0 < 0 || x1 >= self.width || y1 >= self.height {
+                mismatches.push(Mismatch::Size { bounding_box });
+            }
+        }
+
+        if let Some(rule) = rule {
+            if Evolver::new(rule).ok().as_ref() != Some(&evolver) {
+                mismatches.push(Mismatch::Rule {
+                    rule: rule.to_string(),
+                });
+            }
+        }
+
+        match classification.velocity() {
+            Some((dx, dy, period)) if self.period % period == 0 => {
+                if self.skip_subperiod && period != self.period {
+                    mismatches.push(Mismatch::Period {
+                        period: Some(period),
+                    });
+                }
+                let translation = (dx * self.period / period, dy * self.period / period);
+                if self.transform == Transform::Id && translation != (self.dx, self.dy) {
+                    mismatches.push(Mismatch::Translation { translation });
+                }
+            }
+            Some((_, _, period)) => mismatches.push(Mismatch::Period {
+                period: Some(period),
+            }),
+            None if matches!(classification, Classification::DiesOut { .. }) => (),
+            None => mismatches.push(Mismatch::Period { period: None }),
+        }
+
+        // Generation `period` at `(x, y)` should be generation 0 at
+        // `transform(x + dx, y + dy)`, as in the search.
+        let mut current = pattern.clone();
+        for _ in 0..self.period {
+            current = evolver.step(&current);
+        }
+        let expected = Pattern::from_cells(pattern.cells().map(|((x, y), state)| {
+            let (x, y, _) = self
+                .transform
+                .inverse()
+                .act_on((x, y, 0), self.width, self.height);
+            ((x - self.dx, y - self.dy), state)
+        }));
+        if current != expected {
+            mismatches.push(Mismatch::Evolution);
+        }
+
+        Ok(mismatches)
+    }
+
+    /// Evolves the known cells in generation 0 for `steps` generations with the rule
+    /// of this config, independent of the search.
+    ///
+    /// Known cells in other generations are ignored.
+    /// The pattern evolves on an infinite plane, but only the cells in the world are returned.
+    ///
+    /// Returns the generations `0..=steps`, each as a list of the states of the cells
+    /// in the world, row by row, i.e., the cell at `(x, y)` is at index `y * width + x`.
+    pub fn evolve(&self, known_cells: &[KnownCell], steps: u32) -> Result<Vec<Vec<State>>, Error> {
+        let evolver = Evolver::new(&self.rule_string)?;
+        for &KnownCell { coord, state } in known_cells {
+            let (x, y, _) = coord;
+            if x < 0 || x >= self.width || y < 0 || y >= self.height {
+                return Err(Error::KnownCellOutOfBounds(coord));
+            }
+            if state.0 as usize >= evolver.gen() {
+                return Err(Error::InvalidState(coord, state));
+            }
+        }
+        let mut pattern = Pattern::from_cells(
+            known_cells
+                .iter()
+                .filter(|cell| cell.coord.2 == 0)
+                .map(|cell| ((cell.coord.0, cell.coord.1), cell.state)),
+        );
+        let mut generations = Vec::with_capacity(steps as usize + 1);
+        for t in 0..=steps {
+            if t > 0 {
+                pattern = evolver.step(&pattern);
+            }
+            generations.push(
+                (0..self.height)
+                    .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+                    .map(|coord| pattern.get(coord))
+                    .collect(),
+            );
+        }
+        Ok(generations)
+    }
+}
+
+#[cfg(feature = "read-rle")]
+#[cfg_attr(any(docs_rs, github_io), doc(cfg(feature = "read-rle")))]
+impl Config {
+    /// Adds a fixed object, e.g., a spaceship, which is not searched,
+    /// but evolves with the rule of this config in every generation.
+    ///
+    /// This is useful for searching for tagalongs and pushalongs.
+    ///
+    /// The object is given by the first RLE of `rle`, read as in
+    /// [`KnownCell::from_rles`], with its first cell at `position` in generation 0.
+    /// Its `velocity` `(dx, dy, period)` follows the same convention as
+    /// [`Classification::velocity`]: generation `period` at `(x, y)` is generation 0
+    /// at `(x + dx, y + dy)`.
+    ///
+    /// The cells of the object that are not dead are added as known cells in each
+    /// generation of the world. If `clear_vacated` is `true`, the cells that the object
+    /// leaves in each generation are also added as known dead cells.
+    ///
+    /// Returns an error if the object does not have this velocity in the rule,
+    /// if the velocity does not match the period and the translation of this config,
+    /// or if the object does not stay inside the world.
+    pub fn add_fixed_object(
+        mut self,
+        rle: &str,
+        position: (i32, i32),
+        velocity: (i32, i32, i32),
+        clear_vacated: bool,
+    ) -> Result<Self, Error> {
+        let evolver = Evolver::new(&self.rule_string)?;
+        let (dx, dy, period) = velocity;
+        if period <= 0 {
+            return Err(Error::FixedObjectVelocityError(velocity));
+        }
+
+        let mut cells = Vec::new();
+        for KnownCell { coord, state } in KnownCell::from_rles(rle)? {
+            if coord.2 == 0 {
+                if state.0 as usize >= evolver.gen() {
+                    return Err(Error::InvalidState(coord, state));
+                }
+                cells.push(((coord.0 + position.0, coord.1 + position.1), state));
+            }
+        }
+        let object = Pattern::from_cells(cells);
+
+        // Generations `0..=period` of the object.
+        let mut generations = vec![object];
+        for t in 0..period as usize {
+            generations.push(evolver.step(&generations[t]));
+        }
+        if generations[period as usize] != generations[0].translate(-dx, -dy) {
+            return Err(Error::FixedObjectVelocityError(velocity));
+        }
+        let repeats = self.period / period;
+        if self.period % period != 0
+            || (self.dx, self.dy) != (dx * repeats, dy * repeats)
+            || self.transform != Transform::Id
+        {
+            return Err(Error::FixedObjectWorldError(velocity));
+        }
+
+        // Generation `-1` of the object.
+        let before = generations[period as usize - 1].translate(dx, dy);
+
+        for t in 0..self.period {
+            let (repeat, i) = (t / period, (t % period) as usize);
+            let current = &generations[i];
+            let last = if i == 0 { &before } else { &generations[i - 1] };
+            let coord = |(x, y)| (x - dx * repeat, y - dy * repeat, t);
+            for (cell, state) in current.cells() {
+                let coord = coord(cell);
+                if !self.contains(coord, false, false) {
+                    return Err(Error::KnownCellOutOfBounds(coord));
+                }
+                self.known_cells.push(KnownCell { coord, state });
+            }
+            if clear_vacated {
+                for (cell, _) in last.cells() {
+                    let coord = coord(cell);
+                    if current.get(cell) == DEAD && self.contains(coord, false, false) {
+                        self.known_cells.push(KnownCell { coord, state: DEAD });
+                    }
+                }
+            }
+        }
+        Ok(self)
+    }
+}
+
+impl PolyWorld {
+    /// The [apgcode](https://conwaylife.com/wiki/Apgcode) of the result,
+    /// as used by [Catagolue](https://catagolue.hatsya.com), e.g., `xs4_33`
+    /// for the block, or `xp2_7` for the blinker.
+    ///
+    /// Generation 0 of the world is evolved on an infinite plane, and classified
+    /// within 8 times the period of the world, so that results with a
+    /// [`transform`](Config#structfield.transform) are also recognized.
+    /// See [`Pattern::apgcode`] for the format.
+    ///
+    /// Returns `None` if some cells are unknown, e.g., before a result is found,
+    /// if the result is empty or not periodic, or if the rule is a Generations
+    /// rule or contains `B0`.
+    pub fn apgcode(&self) -> Option<String> {
+        if self.frontier_coord().is_some() {
+            return None;
+        }
+        let evolver = Evolver::new(&self.config().rule_string).ok()?;
+        let pattern = Pattern::from_world(self, 0);
+        let classification = Classification::new(&evolver, &pattern, 8 * self.config().period);
+        pattern.apgcode(&evolver, classification)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parses a pattern in a simplified RLE format without run counts,
+    /// where `.` is dead, `o` is alive, and other letters are dying states.
+    fn pattern(rows: &str) -> Pattern {
+        Pattern::from_cells(rows.split('$').enumerate().flat_map(|(y, row)| {
+            row.chars().enumerate().map(move |(x, c)| {
+                let state = match c {
+                    '.' => DEAD,
+                    'o' | 'A' => ALIVE,
+                    c => State((c as u8 - b'A' + 1) as _),
+                };
+                ((x as i32, y as i32), state)
+            })
+        }))
+    }
+
+    #[test]
+    fn block() -> Result<(), Error> {
+        let life = Evolver::new("B3/S23")?;
+        let block = pattern("oo$oo");
+        assert_eq!(life.step(&block), block);
+        assert_eq!(
+            Classification::new(&life, &block, 10),
+            Classification::StillLife
+        );
+        assert_eq!(block.population(), 4);
+        assert_eq!(block.bounding_box(), Some((0, 0, 1, 1)));
+        Ok(())
+    }
+
+    #[test]
+    fn blinker() -> Result<(), Error> {
+        let life = Evolver::new("B3/S23")?;
+        let blinker = pattern("ooo");
+        assert_eq!(life.step(&blinker), pattern(".o$.o$.o").translate(0, -1));
+        assert_eq!(
+            Classification::new(&life, &blinker, 10),
+            Classification::Oscillator { period: 2 }
+        );
+        assert_eq!(
+            Classification::new(&life, &blinker, 1),
+            Classification::Unknown
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn glider() -> Result<(), Error> {
+        let life = Evolver::new("B3/S23")?;
+        let glider = pattern(".o$..o$ooo");
+        let classification = Classification::new(&life, &glider, 10);
+        assert_eq!(
+            classification,
+            Classification::Spaceship {
+                period: 4,
+                dx: -1,
+                dy: -1
+            }
+        );
+        assert_eq!(classification.direction(), Some(Direction::Diagonal));
+
+        let config = Config::new(5, 5, 4).set_translate(-1, -1);
+        let mismatches = config.check_pattern(&glider, Some("B3/S23"), classification)?;
+        assert_eq!(mismatches, vec![]);
+
+        let config = Config::new(2, 5, 8).set_translate(-1, -1);
+        let mismatches = config.check_pattern(&glider, Some("B36/S23"), classification)?;
+        assert_eq!(
+            mismatches,
+            vec![
+                Mismatch::Size {
+                    bounding_box: (0, 0, 2, 2)
+                },
+                Mismatch::Rule {
+                    rule: String::from("B36/S23")
+                },
+                Mismatch::Period { period: Some(4) },
+                Mismatch::Translation {
+                    translation: (-2, -2)
+                },
+                Mismatch::Evolution,
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn apgcode() -> Result<(), Error> {
+        let life = Evolver::new("B3/S23")?;
+        let apgcode = |rows| {
+            let pattern = pattern(rows);
+            pattern.apgcode(&life, Classification::new(&life, &pattern, 20))
+        };
+        assert_eq!(apgcode("oo$oo").as_deref(), Some("xs4_33"));
+        assert_eq!(apgcode(".oo.$o..o$.oo.").as_deref(), Some("xs6_696"));
+        assert_eq!(apgcode("oo.$o.o$.o.").as_deref(), Some("xs5_253"));
+        assert_eq!(apgcode("ooo").as_deref(), Some("xp2_7"));
+        assert_eq!(apgcode(".o$.o$.o").as_deref(), Some("xp2_7"));
+        assert_eq!(apgcode(".o.$..o$ooo").as_deref(), Some("xq4_153"));
+        assert_eq!(
+            apgcode(".o..o$o....$o...o$oooo.").as_deref(),
+            Some("xq4_6frc")
+        );
+        // Spread over more than one strip of 5 rows.
+        assert_eq!(
+            apgcode("..o....o..$oo.oooo.oo$..o....o..").as_deref(),
+            Some("xp15_4r4z4r4")
+        );
+        assert_eq!(apgcode("o"), None);
+
+        let brain = Evolver::new("B2/S/C3")?;
+        let oscillator = pattern(".BA.$A..B$B..A$.AB.");
+        let classification = Classification::new(&brain, &oscillator, 10);
+        assert_eq!(oscillator.apgcode(&brain, classification), None);
+        Ok(())
+    }
+
+    #[test]
+    fn generations_oscillator() -> Result<(), Error> {
+        // A period 3 oscillator in Brian's Brain.
+        let brain = Evolver::new("B2/S/C3")?;
+        let oscillator = pattern(".BA.$A..B$B..A$.AB.");
+        assert_eq!(
+            Classification::new(&brain, &oscillator, 10),
+            Classification::Oscillator { period: 3 }
+        );
+        assert_eq!(oscillator.population(), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn evolve() -> Result<(), Error> {
+        let config = Config::new(3, 3, 1);
+        let blinker = (0..3)
+            .map(|x| KnownCell {
+                coord: (x, 1, 0),
+                state: ALIVE,
+            })
+            .collect::<Vec<_>>();
+        let generations = config.evolve(&blinker, 2)?;
+        let horizontal = vec![DEAD, DEAD, DEAD, ALIVE, ALIVE, ALIVE, DEAD, DEAD, DEAD];
+        let vertical = vec![DEAD, ALIVE, DEAD, DEAD, ALIVE, DEAD, DEAD, ALIVE, DEAD];
+        assert_eq!(generations, vec![horizontal.clone(), vertical, horizontal]);
+
+        // The glider leaves the world, but the evolution goes on outside.
+        let glider = pattern(".o$..o$ooo")
+            .cells()
+            .map(|((x, y), state)| KnownCell {
+                coord: (x, y, 0),
+                state,
+            })
+            .collect::<Vec<_>>();
+        let generations = config.evolve(&glider, 12)?;
+        for x in 1..3 {
+            for y in 1..3 {
+                assert_eq!(
+                    generations[4][(y * 3 + x) as usize],
+                    generations[0][((y - 1) * 3 + x - 1) as usize]
+                );
+            }
+        }
+        assert!(generations[12].iter().all(|&state| state == DEAD));
+
+        let outside = KnownCell {
+            coord: (3, 0, 0),
+            state: ALIVE,
+        };
+        assert_eq!(
+            config.evolve(&[outside], 1),
+            Err(Error::KnownCellOutOfBounds((3, 0, 0)))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn b0() {
+        assert_eq!(Evolver::new("B0/S8").err(), Some(Error::B0AnalysisError));
+    }
+}
@@ -1,10 +1,10 @@
 //! Cells in the cellular automaton.
 
 use crate::rules::Rule;
-use educe::Educe;
 use std::{
     cell::Cell,
     fmt::{Debug, Error, Formatter},
+    hash::{Hash, Hasher},
     ops::{Deref, Not},
     ptr::NonNull,
 };
@@ -18,13 +18,32 @@ use serde::{Deserialize, Serialize};
 /// where `None` means that the state of the cell is unknown.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-pub struct State(pub usize);
+pub struct State(pub StateRepr);
+
+/// The integer type inside a [`State`].
+///
+/// It is `usize` by default. With the `small-state` feature, it is `u16`,
+/// which makes the cells smaller, but the search is usually a little slower.
+#[cfg(not(feature = "small-state"))]
+pub type StateRepr = usize;
+
+/// The integer type inside a [`State`].
+///
+/// It is `usize` by default. With the `small-state` feature, it is `u16`,
+/// which makes the cells smaller, but the search is usually a little slower.
+#[cfg(feature = "small-state")]
+pub type StateRepr = u16;
 
 /// The Dead state.
 pub const DEAD: State = State(0);
 /// The Alive state.
 pub const ALIVE: State = State(1);
 
+/// The maximal number of states of a Generations rule.
+///
+/// Golly's extended RLE format can only represent states up to 255.
+pub(crate) const MAX_GEN: usize = 256;
+
 /// Flips the state.
 ///
 /// For Generations rules, the `not` of a dying state is [`ALIVE`].
@@ -96,9 +115,20 @@ pub struct LifeCell<R: Rule> {
     /// Here the choice of row or column depends on the search order.
     pub(crate) is_front: bool,
 
+    /// Index of the list of the
+    /// [non-empty sets](crate::World#structfield.non_empty_sets) containing the cell,
+    /// in [`non_empty_lists`](crate::World#structfield.non_empty_lists).
+    ///
+    /// It is an index rather than the list itself, to keep the cell small.
+    pub(crate) non_empty_list: u32,
+
     /// The decision level for assigning the cell state.
     ///
-    /// Only used when backjumping is enabled.
+    /// Only used when backjumping is enabled, or when the default algorithm uses
+    /// [`RetreatStrategy::ConflictDirected`](crate::RetreatStrategy::ConflictDirected).
+    /// In the latter case, it is an upper bound of the positions in the
+    /// [`set_stack`](crate::World#structfield.set_stack) of the decisions
+    /// this cell depends on, plus one.
     pub(crate) level: Cell<u32>,
 
     /// Whether the cell has been seen in the analysis.
@@ -125,6 +155,7 @@ impl<R: Rule> LifeCell<R> {
             sym: Vec::new(),
             next: None,
             is_front: false,
+            non_empty_list: 0,
             level: Cell::new(0),
             seen: Cell::new(false),
         }
@@ -162,8 +193,6 @@ impl<R: Rule<Desc = D>, D: Copy + Debug> Debug for LifeCell<R> {
 /// Furthermore, a [`CellRef`] referring to a cell in one world should never be
 /// used in any function or method involving another world.
 #[repr(transparent)]
-#[derive(Educe)]
-#[educe(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct CellRef<R: Rule> {
     /// The [`LifeCell`] it refers to.
     cell: NonNull<LifeCell<R>>,
@@ -179,6 +208,33 @@ impl<R: Rule> CellRef<R> {
     }
 }
 
+// These traits are implemented by hand, because the derived implementations
+// would require the rule to implement them, too.
+impl<R: Rule> Clone for CellRef<R> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<R: Rule> Copy for CellRef<R> {}
+
+impl<R: Rule> PartialEq for CellRef<R> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.cell == other.cell
+    }
+}
+
+impl<R: Rule> Eq for CellRef<R> {}
+
+impl<R: Rule> Hash for CellRef<R> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.cell.hash(state);
+    }
+}
+
 impl<R: Rule> Deref for CellRef<R> {
     type Target = LifeCell<R>;
 
@@ -195,3 +251,23 @@ impl<R: Rule> Debug for CellRef<R> {
             .finish()
     }
 }
+
+#[cfg(all(test, feature = "small-state"))]
+mod tests {
+    use super::*;
+    use crate::rules::{Life, LifeGen};
+    use std::mem::size_of;
+
+    #[test]
+    fn small_state() {
+        assert_eq!(size_of::<State>(), 2);
+        assert_eq!(size_of::<Option<State>>(), 4);
+
+        // With `State(usize)`, they are 160 and 184 bytes.
+        #[cfg(target_pointer_width = "64")]
+        {
+            assert!(size_of::<LifeCell<Life>>() < 160);
+            assert!(size_of::<LifeCell<LifeGen>>() < 184);
+        }
+    }
+}
@@ -5,9 +5,11 @@
 //!
 //! 10 different symmetries correspond to 10 subgroups of _D_<sub>8</sub>.
 
-use super::{Config, Coord};
+use super::{Config, Coord, KnownCell};
+use crate::error::Error;
 use std::{
     cmp::Ordering,
+    collections::HashMap,
     fmt::{self, Display, Formatter},
     matches,
     ops::Mul,
@@ -236,6 +238,13 @@ impl Transform {
         !self.is_in(Symmetry::D4Diag)
     }
 
+    /// Whether this transformation requires the world to have no skew.
+    ///
+    /// Returns `true` for all transformations except `Id` and `R180`.
+    pub const fn require_no_skew(self) -> bool {
+        !self.is_in(Symmetry::C2)
+    }
+
     /// The order of this transformation in the symmetry group.
     pub const fn order(self) -> u8 {
         match self {
@@ -378,10 +387,20 @@ pub enum Symmetry {
     D8,
 }
 
+/// Besides the names of the symmetries, it also accepts an explicit list of
+/// the members of the symmetry group, e.g., `{Id,F\}` for `D2\`.
+/// See [`Symmetry::from_members`].
 impl FromStr for Symmetry {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(list) = s.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+            let members = list
+                .split(',')
+                .map(|t| t.trim().parse())
+                .collect::<Result<Vec<Transform>, _>>()?;
+            return Self::from_members(&members).map_err(|e| e.to_string());
+        }
         match s {
             "C1" => Ok(Self::C1),
             "C2" => Ok(Self::C2),
@@ -485,6 +504,13 @@ impl Symmetry {
         !self.is_subgroup_of(Self::D4Diag)
     }
 
+    /// Whether this symmetry requires the world to have no skew.
+    ///
+    /// Returns `true` for all symmetries except `C1` and `C2`.
+    pub const fn require_no_skew(self) -> bool {
+        !self.is_subgroup_of(Self::C2)
+    }
+
     /// Transformations contained in the symmetry group.
     pub fn members(self) -> Vec<Transform> {
         match self {
@@ -623,11 +649,85 @@ impl Symmetry {
             .into_iter()
             .fold(Self::C1, |sym, transform| sym.generated_with(transform))
     }
+
+    /// The conjugate of the symmetry by a transformation.
+    ///
+    /// If a pattern has this symmetry, then it has the conjugate symmetry
+    /// after applying the transformation.
+    pub fn conjugate(self, transform: Transform) -> Self {
+        Self::generated_by(
+            self.members()
+                .into_iter()
+                .map(|t| transform * t * transform.inverse()),
+        )
+    }
+
+    /// The symmetry whose symmetry group is generated by the given transformations.
+    ///
+    /// Since every subgroup of _D_<sub>8</sub> is one of the 10 symmetries,
+    /// this currently always returns `Some`. It is the same as
+    /// [`generated_by`](Self::generated_by), but would return `None`
+    /// if the generated group were not supported.
+    #[inline]
+    pub fn from_transforms(transforms: &[Transform]) -> Option<Self> {
+        Some(Self::generated_by(transforms.iter().copied()))
+    }
+
+    /// The symmetry whose symmetry group consists of exactly the given transformations.
+    ///
+    /// Every subgroup of _D_<sub>8</sub> is one of the 10 symmetries,
+    /// so any list of transformations which contains [`Transform::Id`]
+    /// and is closed under multiplication defines a symmetry.
+    ///
+    /// Returns an error if the transformations do not form a group.
+    pub fn from_members(transforms: &[Transform]) -> Result<Self, Error> {
+        let is_group = transforms.contains(&Transform::Id)
+            && transforms
+                .iter()
+                .all(|&a| transforms.iter().all(|&b| transforms.contains(&(a * b))));
+        if is_group {
+            Ok(Self::generated_by(transforms.iter().copied()))
+        } else {
+            Err(Error::NonGroupError(transforms.to_vec()))
+        }
+    }
 }
 
 impl Config {
-    /// Applies the transformation and translation to a coord.
-    pub(crate) const fn translate(&self, coord: Coord) -> Coord {
+    /// The transformation applied at the period boundary.
+    ///
+    /// This is just [`transform`](Config#structfield.transform).
+    /// See [`map_coord_across_period`](Self::map_coord_across_period) for how it
+    /// is combined with the translation.
+    #[inline]
+    pub const fn period_transform(&self) -> Transform {
+        self.transform
+    }
+
+    /// Maps a coord in any generation to the coord of the same cell
+    /// in generations `0..period`.
+    ///
+    /// Generation `period` is generation 0 shifted and transformed:
+    /// the cell at `(x, y, period)` is first translated to `(x + dx, y + dy, 0)`,
+    /// and then mapped by [`Transform::act_on`] with
+    /// the [`period_transform`](Self::period_transform).
+    /// This is repeated for later generations, and inverted for negative generations.
+    /// On a [torus](Config#structfield.torus), the result wraps around.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rlifesrc_lib::{Config, Transform};
+    ///
+    /// let config = Config::new(5, 5, 2)
+    ///     .set_translate(0, 1)
+    ///     .set_transform(Transform::FlipDiag);
+    /// // (1, 2, 2) is translated to (1, 3, 0), and then flipped to (3, 1, 0).
+    /// assert_eq!(config.map_coord_across_period((1, 2, 2)), (3, 1, 0));
+    /// assert_eq!(config.map_coord_across_period((3, 1, 0)), (3, 1, 0));
+    /// assert_eq!(config.map_coord_across_period((3, 1, -2)), (1, 2, 0));
+    /// ```
+    pub const fn map_coord_across_period(&self, coord: Coord) -> Coord {
         let mut coord = coord;
         while coord.2 < 0 {
             coord = self
@@ -644,7 +744,86 @@ impl Config {
             coord.2 -= self.period;
             coord = self.transform.act_on(coord, self.width, self.height);
         }
-        coord
+        self.wrap_torus(coord)
+    }
+
+    /// The symmetry of generation `t`.
+    ///
+    /// This is the `t`-th element of [`phase_symmetries`](Config#structfield.phase_symmetries)
+    /// if it is given, and [`symmetry`](Config#structfield.symmetry) otherwise.
+    pub fn symmetry_at(&self, t: i32) -> Symmetry {
+        self.phase_symmetries
+            .as_ref()
+            .and_then(|syms| syms.get(t as usize))
+            .copied()
+            .unwrap_or(self.symmetry)
+    }
+
+    /// The largest symmetry contained in the symmetries of all generations.
+    ///
+    /// This is used to decide which cells can be skipped in the search order.
+    pub(crate) fn min_symmetry(&self) -> Symmetry {
+        match &self.phase_symmetries {
+            Some(syms) => Symmetry::generated_by(
+                Transform::ALL
+                    .into_iter()
+                    .filter(|t| syms.iter().all(|&sym| t.is_in(sym))),
+            ),
+            None => self.symmetry,
+        }
+    }
+
+    /// Whether any generation has a nontrivial symmetry.
+    #[inline]
+    pub(crate) fn has_symmetry(&self) -> bool {
+        self.max_symmetry() != Symmetry::C1
+    }
+
+    /// The smallest symmetry containing the symmetries of all generations.
+    ///
+    /// This is used to decide whether the front can be forced to be nonempty.
+    pub(crate) fn max_symmetry(&self) -> Symmetry {
+        match &self.phase_symmetries {
+            Some(syms) => Symmetry::generated_by(syms.iter().flat_map(|sym| sym.members())),
+            None => self.symmetry,
+        }
+    }
+
+    /// Adds the images of the [known cells](Config#structfield.known_cells)
+    /// under the symmetry of their generations to the known cells.
+    ///
+    /// Returns an error if two known cells at the same position have different states,
+    /// e.g., a known cell and the image of another known cell.
+    pub fn symmetrize_known_cells(&mut self) -> Result<(), Error> {
+        let mut states = HashMap::new();
+        for &KnownCell { coord, state } in &self.known_cells {
+            if let Some(old_state) = states.insert(coord, state) {
+                if old_state != state {
+                    return Err(Error::ConflictingKnownCells(coord));
+                }
+            }
+        }
+        let mut images = Vec::new();
+        for &KnownCell { coord, state } in &self.known_cells {
+            for transform in self.symmetry_at(coord.2).members() {
+                let image = transform.act_on(coord, self.width, self.height);
+                match states.get(&image) {
+                    Some(&old_state) if old_state != state => {
+                        return Err(Error::ConflictingKnownCells(image));
+                    }
+                    Some(_) => {}
+                    None => {
+                        states.insert(image, state);
+                        images.push(KnownCell {
+                            coord: image,
+                            state,
+                        });
+                    }
+                }
+            }
+        }
+        self.known_cells.extend(images);
+        Ok(())
     }
 }
 
@@ -721,6 +900,48 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_from_members() {
+        for sym in Symmetry::ALL {
+            assert_eq!(Symmetry::from_members(&sym.members()), Ok(sym));
+            let list = sym
+                .members()
+                .iter()
+                .map(Transform::to_string)
+                .collect::<Vec<_>>()
+                .join(",");
+            assert_eq!(Symmetry::from_str(&format!("{{{list}}}")), Ok(sym));
+        }
+        assert_eq!(Symmetry::from_str("{Id,F\\}"), Ok(Symmetry::D2Diag));
+        assert!(Symmetry::from_members(&[Transform::Id, Transform::Rotate90]).is_err());
+        assert!(Symmetry::from_members(&[Transform::FlipRow]).is_err());
+        assert!(Symmetry::from_str("{Id,R90}").is_err());
+    }
+
+    #[test]
+    fn test_from_transforms() {
+        assert_eq!(Symmetry::from_transforms(&[]), Some(Symmetry::C1));
+        assert_eq!(
+            Symmetry::from_transforms(&[Transform::FlipRow, Transform::Rotate180]),
+            Some(Symmetry::D4Ortho)
+        );
+        for a in Transform::ALL {
+            for b in Transform::ALL {
+                let mut group = vec![Transform::Id];
+                while let Some(t) = group
+                    .iter()
+                    .flat_map(|&x| [a, b].into_iter().map(move |y| x * y))
+                    .find(|t| !group.contains(t))
+                {
+                    group.push(t);
+                }
+                let sym = Symmetry::from_transforms(&[a, b]).unwrap();
+                assert_eq!(sym.members().len(), group.len());
+                assert!(group.iter().all(|t| t.is_in(sym)));
+            }
+        }
+    }
+
     #[test]
     fn test_transform_inverse() {
         let width = 16;
@@ -744,6 +965,42 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_has_symmetry() {
+        for sym in Symmetry::ALL {
+            let config = Config::new(8, 8, 2).set_symmetry(sym);
+            assert_eq!(config.has_symmetry(), sym != Symmetry::C1);
+            let config = Config::new(8, 8, 2).set_phase_symmetries(vec![Symmetry::C1, sym]);
+            assert_eq!(config.has_symmetry(), sym != Symmetry::C1);
+        }
+        let config = Config::new(8, 8, 2)
+            .set_symmetry(Symmetry::D8)
+            .set_phase_symmetries(vec![Symmetry::C1, Symmetry::C1]);
+        assert!(!config.has_symmetry());
+    }
+
+    #[test]
+    fn test_conjugate() {
+        assert_eq!(
+            Symmetry::D2Row.conjugate(Transform::Rotate90),
+            Symmetry::D2Col
+        );
+        assert_eq!(
+            Symmetry::D2Diag.conjugate(Transform::FlipRow),
+            Symmetry::D2Antidiag
+        );
+        for sym in Symmetry::ALL {
+            for transform in Transform::ALL {
+                let conjugate = sym.conjugate(transform);
+                assert_eq!(conjugate.members().len(), sym.members().len());
+                assert_eq!(conjugate.conjugate(transform.inverse()), sym);
+                if transform.is_in(sym) {
+                    assert_eq!(conjugate, sym);
+                }
+            }
+        }
+    }
+
     #[test]
     fn test_transform_mul() {
         let width = 16;
@@ -792,6 +1049,10 @@ mod tests {
                         | Transform::FlipCol
                 )
             );
+            assert_eq!(
+                tran.require_no_skew(),
+                !matches!(tran, Transform::Id | Transform::Rotate180)
+            );
         }
         for sym in Symmetry::ALL {
             assert_eq!(
@@ -816,6 +1077,10 @@ mod tests {
                         | Symmetry::D8
                 )
             );
+            assert_eq!(
+                sym.require_no_skew(),
+                !matches!(sym, Symmetry::C1 | Symmetry::C2)
+            );
         }
     }
 }
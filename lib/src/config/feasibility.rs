@@ -0,0 +1,131 @@
+//! Cheap checks for configurations that can never have a result.
+
+use super::{Config, Transform};
+use ca_rules::ParseNtLifeGen;
+
+/// The minimal numbers of living neighbors for a cell to be born or to survive.
+///
+/// `None` means that the cell can never be born or survive.
+struct MinNeighbors {
+    birth: Option<u32>,
+    survival: Option<u32>,
+    has_b0: bool,
+}
+
+impl ParseNtLifeGen for MinNeighbors {
+    fn from_bsg(b: Vec<u8>, s: Vec<u8>, _gen: usize) -> Self {
+        Self {
+            birth: b.iter().map(|n| n.count_ones()).min(),
+            survival: s.iter().map(|n| n.count_ones()).min(),
+            has_b0: b.contains(&0),
+        }
+    }
+}
+
+impl Config {
+    /// Checks whether the configuration is obviously hopeless,
+    /// and returns a hint explaining why.
+    ///
+    /// These checks only look at the rule, the period, the translation,
+    /// the transformation and the bounds of the cell counts.
+    /// They assume that the result is nonempty, so they might be wrong when the
+    /// known cells allow an empty result. Thus the hint is only a warning,
+    /// and [`world`](Self::world) does not reject such configurations.
+    ///
+    /// The first check only looks at the cell counts:
+    ///
+    /// - The first generation cannot have at least
+    ///   [`min_cell_count`](#structfield.min_cell_count) living cells if that is more
+    ///   than the [`max_cell_count_per_gen`](#structfield.max_cell_count_per_gen),
+    ///   or, when the period is 1, more than the [`max_cell_count`](#structfield.max_cell_count).
+    ///
+    /// Rules with `B0` and invalid rules are not checked. The other checks are:
+    ///
+    /// - If the rule has neither birth nor survival conditions,
+    ///   every pattern dies out in one generation.
+    /// - If the rule has no birth conditions, a pattern can only lose living cells,
+    ///   so every periodic pattern is a still life. Thus no pattern moves, and every
+    ///   oscillator is skipped when [`skip_subperiod`](#structfield.skip_subperiod)
+    ///   is `true`.
+    /// - In a still life, every living cell survives, so it has at least as many
+    ///   living neighbors as the smallest survival condition. So there is no still
+    ///   life if the rule has no survival conditions, and otherwise a still life
+    ///   has at least one more living cell than the smallest survival condition.
+    ///   For example, a still life in Conway's Game of Life has at least 3 cells.
+    /// - In a periodic pattern, every living cell in the next generation is
+    ///   either born or survives, so every generation has at least as many living
+    ///   cells as the smaller one of the smallest birth condition and the
+    ///   smallest survival condition plus one.
+    pub fn infeasibility_check(&self) -> Option<String> {
+        if let Some(min) = self.min_cell_count {
+            let max = if self.period == 1 {
+                self.max_cell_count
+                    .into_iter()
+                    .chain(self.max_cell_count_per_gen)
+                    .min()
+            } else {
+                self.max_cell_count_per_gen
+            };
+            if let Some(max) = max.filter(|&max| max < min) {
+                return Some(format!(
+                    "The min cell count is {}, but the max cell count is {}.",
+                    min, max
+                ));
+            }
+        }
+        let rule: MinNeighbors = ParseNtLifeGen::parse_rule(&self.rule_string).ok()?;
+        if rule.has_b0 {
+            return None;
+        }
+        if rule.birth.is_none() && rule.survival.is_none() {
+            return Some(String::from(
+                "The rule has neither birth nor survival conditions, so every pattern dies out.",
+            ));
+        }
+        let is_still_life =
+            self.period == 1 && self.dx == 0 && self.dy == 0 && self.transform == Transform::Id;
+
+        if rule.birth.is_none()
+            && (self.dx != 0
+                || self.dy != 0
+                || (self.period > 1 && self.transform == Transform::Id && self.skip_subperiod))
+        {
+            return Some(String::from(
+                "The rule has no birth conditions, so every periodic pattern is a still life.",
+            ));
+        }
+
+        if is_still_life {
+            let s =
+                match rule.survival {
+                    Some(s) => s,
+                    None => return Some(String::from(
+                        "The rule has no survival conditions, so there is no nonempty still life.",
+                    )),
+                };
+            let max = self.max_cell_count?;
+            return (max < s + 1).then(|| {
+                format!(
+                    "A still life in this rule has at least {} living cells, \
+                     but the max cell count is {}.",
+                    s + 1,
+                    max
+                )
+            });
+        }
+
+        let max = self.max_cell_count?;
+        let min = rule
+            .birth
+            .into_iter()
+            .chain(rule.survival.map(|s| s + 1))
+            .min()?;
+        (max < min).then(|| {
+            format!(
+                "Every generation of a nonempty pattern in this rule has at least \
+                 {} living cells, but the max cell count is {}.",
+                min, max
+            )
+        })
+    }
+}
@@ -1,23 +1,29 @@
 //! World configuration.
 
 use crate::{
-    cells::{Coord, State},
+    cells::{Coord, State, MAX_GEN},
     error::Error,
     poly_world::PolyWorld,
-    rules::{Life, LifeGen, NtLife, NtLifeGen, Rule},
+    rules::{parse_rule, CachedRule, ParsedRule, Rule, RuleCache, RuleKey},
+    search::Status,
     world::World,
 };
-use educe::Educe;
+use std::{
+    collections::HashSet,
+    hash::{Hash, Hasher},
+    mem,
+};
 
 #[cfg(feature = "read-rle")]
-#[cfg_attr(any(docs_rs, github_io), doc(cfg(feature = "read-rle")))]
-use ca_formats::{
-    rle::{Error as RleError, Rle},
-    CellData, Input,
-};
+use crate::cells::StateRepr;
+#[cfg(feature = "read-rle")]
+use ca_formats::{rle::Rle, CellData};
 
 mod d8;
+mod feasibility;
 mod search_order;
+mod spec;
+mod speed;
 
 pub use d8::{Symmetry, Transform};
 pub use search_order::SearchOrder;
@@ -25,10 +31,10 @@ pub use search_order::SearchOrder;
 #[cfg(doc)]
 use crate::cells::{ALIVE, DEAD};
 #[cfg(feature = "serde")]
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 
 /// How to choose a state for an unknown cell.
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, Default)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum NewState {
     /// Chooses the background state.
@@ -59,7 +65,176 @@ pub enum NewState {
     ///
     /// For Generations rules with `n` states,
     /// the probability of each state is `1/n`.
+    ///
+    /// For non-Generations rules, this is the same as
+    /// [`RandomBiased`](Self::RandomBiased) with `alive_probability: 0.5`.
     Random,
+
+    /// Random, choosing [`ALIVE`] with the given probability.
+    ///
+    /// For Generations rules, the remaining probability is distributed
+    /// uniformly over the other states, i.e., [`DEAD`] and the dying states.
+    ///
+    /// The probability must be strictly between `0` and `1`.
+    RandomBiased {
+        /// The probability of choosing [`ALIVE`].
+        alive_probability: f64,
+    },
+}
+
+/// The search algorithm.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum AlgorithmChoice {
+    /// Chooses an algorithm automatically.
+    ///
+    /// Currently it always chooses [`LifeSrc`](Self::LifeSrc),
+    /// which is faster for most searches.
+    #[default]
+    Auto,
+
+    /// The default algorithm, based on David Bell's
+    /// [lifesrc](https://github.com/DavidKinder/Xlife/tree/master/Xlife35/source/lifesearch).
+    ///
+    /// It supports every rule and every option.
+    LifeSrc,
+
+    /// __(Experimental)__ [Backjumping](https://en.wikipedia.org/wiki/Backjumping).
+    ///
+    /// Backjumping will reduce the number of steps, but each step will takes
+    /// a much longer time. The current implementation is slower for most search,
+    /// only useful for large (e.g., 64x64) still lifes.
+    ///
+    /// Currently it does not support Generations rules,
+    /// [`max_cell_count`](Config#structfield.max_cell_count),
+    /// [`max_cell_count_per_gen`](Config#structfield.max_cell_count_per_gen),
+    /// [`max_defects`](Config#structfield.max_defects), and the
+    /// [transposition cache](Config#structfield.transposition_cache).
+    Backjump,
+
+    /// [`Backjump`](Self::Backjump) if it supports the rule and the other options,
+    /// otherwise [`LifeSrc`](Self::LifeSrc).
+    ///
+    /// This is what the old `backjump` option means.
+    PreferBackjump,
+}
+
+/// Also accepts a boolean in human-readable formats, for the old `backjump` field,
+/// where `true` means [`PreferBackjump`](AlgorithmChoice::PreferBackjump),
+/// and `false` means [`Auto`](AlgorithmChoice::Auto).
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for AlgorithmChoice {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(rename = "AlgorithmChoice")]
+        enum Choice {
+            Auto,
+            LifeSrc,
+            Backjump,
+            PreferBackjump,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Compat {
+            Backjump(bool),
+            Choice(Choice),
+        }
+
+        let choice = if deserializer.is_human_readable() {
+            match Compat::deserialize(deserializer)? {
+                Compat::Backjump(true) => Choice::PreferBackjump,
+                Compat::Backjump(false) => Choice::Auto,
+                Compat::Choice(choice) => choice,
+            }
+        } else {
+            Choice::deserialize(deserializer)?
+        };
+        Ok(match choice {
+            Choice::Auto => Self::Auto,
+            Choice::LifeSrc => Self::LifeSrc,
+            Choice::Backjump => Self::Backjump,
+            Choice::PreferBackjump => Self::PreferBackjump,
+        })
+    }
+}
+
+/// How to retreat when the search finds a conflict.
+///
+/// Only used by the [default algorithm](AlgorithmChoice::LifeSrc).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum RetreatStrategy {
+    /// Always switches the most recent decision.
+    #[default]
+    Chronological,
+
+    /// Skips the recent decisions that the conflict does not depend on,
+    /// and switches the most recent one that it may depend on.
+    ///
+    /// Each cell remembers an upper bound of the decisions it depends on,
+    /// which is cheap to compute when it is set. A conflict depends on the cells
+    /// involved in it, so decisions after all of these bounds can be skipped
+    /// without losing any result. This is a much lighter version of
+    /// [backjumping](AlgorithmChoice::Backjump), which analyzes the conflicts
+    /// to learn new clauses.
+    ///
+    /// The bounds are coarse: a cell deduced after a decision is always assumed
+    /// to depend on it. So most conflicts still depend on the most recent decision,
+    /// and on typical searches this strategy rarely skips anything.
+    ///
+    /// Conflicts caused by the [`max_cell_count`](Config#structfield.max_cell_count),
+    /// the non-empty front, or the number of [defects](Config#structfield.max_defects)
+    /// depend on the whole world, so they are handled chronologically. This strategy
+    /// falls back to [`Chronological`](Self::Chronological) when the
+    /// [transposition cache](Config#structfield.transposition_cache) is enabled.
+    ConflictDirected,
+}
+
+/// Which population the [`max_cell_count`](Config#structfield.max_cell_count)
+/// bounds, and thus which one [`reduce_max`](Config#structfield.reduce_max) minimizes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ReduceTarget {
+    /// The bound applies across all generations: the result must have
+    /// at most that many living cells in some generation.
+    ///
+    /// This is the population returned by [`World::cell_count`](crate::World::cell_count).
+    #[default]
+    MaxAcrossGens,
+
+    /// The bound applies to the given generation only,
+    /// e.g., `Gen(0)` minimizes the population of the first generation
+    /// of a spaceship, whatever its other phases look like.
+    Gen(i32),
+}
+
+/// Probabilities are compared by their bits, so that this is an equivalence relation.
+impl PartialEq for NewState {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                Self::RandomBiased {
+                    alive_probability: p,
+                },
+                Self::RandomBiased {
+                    alive_probability: q,
+                },
+            ) => p.to_bits() == q.to_bits(),
+            _ => mem::discriminant(self) == mem::discriminant(other),
+        }
+    }
+}
+
+impl Eq for NewState {}
+
+impl Hash for NewState {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        mem::discriminant(self).hash(state);
+        if let Self::RandomBiased { alive_probability } = self {
+            alive_probability.to_bits().hash(state);
+        }
+    }
 }
 
 /// A cell whose state is known before the search.
@@ -80,65 +255,199 @@ impl KnownCell {
     pub const fn from_cell_data(data: CellData, gen: i32) -> Self {
         let (x, y) = data.position;
         let coord = (x as i32, y as i32, gen);
-        let state = State(data.state as usize);
+        let state = State(data.state as StateRepr);
         Self { coord, state }
     }
 
     /// Get a list [`KnownCell`] from multiple RLE's in one string.
-    pub fn from_rles<I, L>(input: I) -> Result<Vec<Self>, RleError>
-    where
-        I: Input<Lines = L>,
-        L: Input<Lines = L>,
-    {
+    ///
+    /// Each RLE must end with `!`. By default, the `n`-th RLE (counting from 0)
+    /// gives the known cells in generation `n`. A blank RLE, i.e., an RLE with
+    /// nothing but an optional header before `!`, gives no known cells, but still
+    /// counts as a generation, so it can be used to skip a generation.
+    ///
+    /// The RLE's are read with [unknown cells](Rle::with_unknown), so `?` is an
+    /// unknown cell, and so are the cells that are not written at all,
+    /// e.g., omitted dead cells at the end of a line.
+    ///
+    /// By default, all the RLE's share the same origin: the first cell of the
+    /// first line of each RLE is at `(0, 0)` in the world, no matter how large
+    /// its bounding box is. The sizes in the headers are ignored. Thus, to align
+    /// RLE's of different sizes, pad them with `?`.
+    ///
+    /// The comment lines before the `!` of an RLE can change its placement:
+    ///
+    /// - `#P x y` or `#R x y` puts the first cell of the first line at `(x, y)`.
+    /// - `#C gen n` puts the RLE in generation `n`. The RLE's after it without
+    ///   this comment are in generations `n + 1`, `n + 2`, and so on.
+    ///
+    /// Other comment lines are ignored.
+    pub fn from_rles(input: &str) -> Result<Vec<Self>, Error> {
         let mut known_cells = Vec::new();
-        let rle = Rle::new(input)?;
-
-        Self::from_rles_iter(rle, &mut known_cells, 0)?;
-
+        let mut gen = 0;
+        for block in RleBlock::split(input)? {
+            let gen_block = block.gen.unwrap_or(gen);
+            let (dx, dy) = block.offset;
+            for data in Rle::new(block.text.as_str())?.with_unknown() {
+                let mut known_cell = Self::from_cell_data(data?, gen_block);
+                known_cell.coord.0 += dx;
+                known_cell.coord.1 += dy;
+                known_cells.push(known_cell);
+            }
+            gen = gen_block + 1;
+        }
         Ok(known_cells)
     }
+}
 
-    fn from_rles_iter<I, L>(
-        rle: Rle<I>,
-        known_cells: &mut Vec<Self>,
-        gen: i32,
-    ) -> Result<(), RleError>
-    where
-        I: Input<Lines = L>,
-        L: Input<Lines = L>,
-    {
-        let mut rle = rle.with_unknown();
+/// One RLE in a string of multiple RLE's,
+/// together with the placement given by its comments.
+///
+/// See [`KnownCell::from_rles`].
+#[cfg(feature = "read-rle")]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct RleBlock {
+    /// The lines of the RLE, ending with `!`.
+    text: String,
 
-        for data in &mut rle {
-            known_cells.push(Self::from_cell_data(data?, gen));
-        }
+    /// The position of the first cell of the first line,
+    /// given by a `#P` or `#R` comment.
+    offset: (i32, i32),
 
-        if let Some(rle) = rle.try_remains()? {
-            Self::from_rles_iter(rle, known_cells, gen + 1)?;
+    /// The generation, given by a `#C gen` comment.
+    gen: Option<i32>,
+}
+
+#[cfg(feature = "read-rle")]
+impl RleBlock {
+    /// Splits a string of multiple RLE's after each `!`.
+    ///
+    /// Comment lines after the last `!` are ignored.
+    fn split(input: &str) -> Result<Vec<Self>, Error> {
+        let mut blocks = Vec::new();
+        let mut block = RleBlock::default();
+        let mut has_body = false;
+        for line in input.lines() {
+            let trimmed = line.trim();
+            if let Some(comment) = trimmed.strip_prefix('#') {
+                block.read_comment(comment)?;
+                continue;
+            }
+            let mut rest = line;
+            while let Some(i) = rest.find('!') {
+                block.text.push_str(&rest[..=i]);
+                blocks.push(mem::take(&mut block));
+                has_body = false;
+                rest = &rest[i + 1..];
+            }
+            has_body |= !rest.trim().is_empty();
+            block.text.push_str(rest);
+            block.text.push('\n');
         }
+        if has_body {
+            blocks.push(block);
+        }
+        Ok(blocks)
+    }
 
+    /// Reads a comment line, without the leading `#`.
+    fn read_comment(&mut self, comment: &str) -> Result<(), Error> {
+        let invalid = || Error::RleCommentError(format!("#{}", comment));
+        let mut words = comment.split_whitespace();
+        match words.next() {
+            Some("P" | "R") => {
+                let mut coord = || words.next()?.parse::<i32>().ok();
+                self.offset = (coord().ok_or_else(invalid)?, coord().ok_or_else(invalid)?);
+            }
+            Some("C") => {
+                if let (Some("gen"), Some(gen), None) = (words.next(), words.next(), words.next()) {
+                    self.gen = Some(gen.parse().map_err(|_| invalid())?);
+                }
+            }
+            _ => (),
+        }
         Ok(())
     }
 }
 
+/// A row or a column of the world.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Line {
+    /// The row with the given `y` coordinate.
+    Row(i32),
+
+    /// The column with the given `x` coordinate.
+    Column(i32),
+}
+
+/// A constraint on the parity of the number of living cells
+/// in a row or a column of some generation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LineParity {
+    /// The row or the column.
+    pub line: Line,
+
+    /// The generation.
+    pub gen: i32,
+
+    /// `true` if the number of living cells should be odd,
+    /// `false` if it should be even.
+    pub odd: bool,
+}
+
+/// What a [`PhaseMask`] requires of the cells in its rectangle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PhaseMaskMode {
+    /// Every cell in the rectangle has the background state.
+    ForceBackground,
+
+    /// Some cell in the rectangle does not have the background state.
+    ForceNonBackground,
+}
+
+/// A constraint on a rectangle of cells in some generations,
+/// e.g., for stroboscopic patterns which must leave some region empty
+/// in some phases, but may use it in the other phases.
+///
+/// The generations which are not listed are unconstrained.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PhaseMask {
+    /// The rectangle `(x0, y0, x1, y1)`, from `(x0, y0)` to `(x1, y1)`,
+    /// both inclusive.
+    pub rect: (i32, i32, i32, i32),
+
+    /// The generations where the constraint applies.
+    pub gens: Vec<i32>,
+
+    /// The constraint.
+    ///
+    /// For [`ForceNonBackground`](PhaseMaskMode::ForceNonBackground),
+    /// each of the generations must have a non-background cell in the rectangle.
+    pub mode: PhaseMaskMode,
+}
+
 /// World configuration.
 ///
 /// The world will be generated from this configuration.
-#[derive(Clone, Debug, Educe, PartialEq, Eq, Hash)]
-#[educe(Default)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "serde", serde(default))]
 pub struct Config {
     /// Width.
-    #[educe(Default = 16)]
     pub width: i32,
 
     /// Height.
-    #[educe(Default = 16)]
     pub height: i32,
 
     /// Period.
-    #[educe(Default = 1)]
+    ///
+    /// For rules with `B0`, the background goes through all the states in turn,
+    /// so the period must be a multiple of the number of states, i.e., even
+    /// for non-Generations rules.
     pub period: i32,
 
     /// Horizontal translation.
@@ -153,11 +462,25 @@ pub struct Config {
     /// After the last generation in a period, the pattern will return to
     /// the first generation, applying this transformation first,
     /// and then the translation defined by `dx` and `dy`.
+    ///
+    /// See [`map_coord_across_period`](Self::map_coord_across_period)
+    /// for the precise relation between the coordinates.
     pub transform: Transform,
 
     /// Symmetries of the pattern.
     pub symmetry: Symmetry,
 
+    /// Symmetries of the pattern in each generation.
+    ///
+    /// If this is not `None`, its length must equal the period,
+    /// and the symmetry of generation `t` is its `t`-th element,
+    /// instead of [`symmetry`](#structfield.symmetry).
+    ///
+    /// The rule preserves symmetries, so the symmetry of generation 0 must contain
+    /// the symmetry of the last generation, conjugated by the
+    /// [`transform`](#structfield.transform).
+    pub phase_symmetries: Option<Vec<Symmetry>>,
+
     /// The order to find a new unknown cell.
     ///
     /// It will always search all generations of one cell
@@ -173,28 +496,127 @@ pub struct Config {
     /// The number of minimum living cells in all generations must not
     /// exceed this number.
     ///
+    /// The cells are counted as displayed, i.e., when the world has a symmetry,
+    /// all the symmetric images of a cell are counted.
+    ///
+    /// The [`reduce_target`](#structfield.reduce_target) can make it bound
+    /// a single generation instead.
+    ///
     /// `None` means that there is no limit for the cell count.
     pub max_cell_count: Option<u32>,
 
+    /// The number of living cells in every generation must not
+    /// exceed this number.
+    ///
+    /// Unlike [`max_cell_count`](#structfield.max_cell_count), which only bounds
+    /// the generation with the fewest living cells, this bounds all of them.
+    /// The cells are counted in the same way. It is not changed by
+    /// [`reduce_max`](#structfield.reduce_max).
+    ///
+    /// `None` means that there is no such limit.
+    pub max_cell_count_per_gen: Option<u32>,
+
+    /// The number of living cells in the first generation must be
+    /// at least this number.
+    ///
+    /// The cells are counted in the same way as
+    /// [`max_cell_count`](#structfield.max_cell_count).
+    /// A result below this bound is skipped like a boring one, so with a
+    /// positive bound, the trivial result is skipped even if it is
+    /// [allowed](#structfield.allow_trivial).
+    ///
+    /// `None` means that there is no lower bound for the cell count.
+    pub min_cell_count: Option<u32>,
+
     /// Whether to automatically reduce the [`max_cell_count`](#structfield.max_cell_count)
     /// when a result is found.
     ///
     /// The [`max_cell_count`](#structfield.max_cell_count) will be set to the cell count of
-    /// the current result minus one.
+    /// the current result minus one, but never less than the
+    /// [`min_cell_count`](#structfield.min_cell_count).
     pub reduce_max: bool,
 
+    /// Which population the [`max_cell_count`](#structfield.max_cell_count)
+    /// bounds, and thus which one [`reduce_max`](#structfield.reduce_max) minimizes.
+    ///
+    /// The default is [`ReduceTarget::MaxAcrossGens`].
+    pub reduce_target: ReduceTarget,
+
+    /// __(Experimental)__ Maximal number of defects allowed in a result.
+    ///
+    /// A defect is a cell in the last generation whose successor, i.e., the
+    /// corresponding cell in the (transformed and translated) first generation,
+    /// does not obey the rule. In other words, the constraint that the last generation
+    /// evolves back into the first generation is relaxed for up to this many cells.
+    ///
+    /// A result with defects is not a solution, but a partial result that might
+    /// be completed by hand. Use [`World::defects`] to check the defects of a result.
+    ///
+    /// `None` or `Some(0)` means that no defect is allowed, which is the usual search.
+    ///
+    /// Currently it is only supported for non-Generations rules. Generations rules
+    /// will ignore this option. [Backjumping](AlgorithmChoice::Backjump) is not
+    /// supported when defects are allowed.
+    pub max_defects: Option<u32>,
+
     /// The rule string of the cellular automaton.
-    #[educe(Default = "B3/S23")]
     pub rule_string: String,
 
+    /// Whether to search a `B0S8` rule by searching its inverted rule.
+    ///
+    /// `B0S8` rules are not supported directly, because the background would
+    /// be living in every generation. If this is `true` and the rule is a
+    /// non-Generations rule with `B0` and `S8`, the world uses the inverted rule
+    /// instead, where every cell has the opposite state, so that the background
+    /// is dead. The [known cells](#structfield.known_cells) are given, and
+    /// [`World::get_cell_state`], [`World::candidate_states`], the
+    /// [snapshots](World::snapshot) and all the text outputs are shown,
+    /// in the given rule, i.e., on a living background.
+    ///
+    /// The cell counts, including [`max_cell_count`](#structfield.max_cell_count),
+    /// count the cells which differ from the background, i.e., the dead cells
+    /// in the given rule. Other rules are not affected.
+    pub auto_invert_b0s8: bool,
+
     /// Diagonal width.
     ///
     /// If the diagonal width is `n`, the cells at position `(x, y)`
     /// where `abs(x - y) >= n` are assumed to be dead.
     pub diagonal_width: Option<i32>,
 
+    /// Skew `(p, q, w)`.
+    ///
+    /// If this is set, the cells at position `(x, y)` where `abs(q * x - p * y) >= w`
+    /// are assumed to be dead. So the cells are in a strip of slope `q / p`,
+    /// e.g., `(1, 2, w)` for a knightship moving by `(1, 2)`.
+    ///
+    /// A [`diagonal_width`](#structfield.diagonal_width) of `n` is the same as
+    /// a skew of `(1, 1, n)`. If both are set, the cells must be in both strips.
+    pub skew: Option<(i32, i32, i32)>,
+
+    /// Containment box `(x0, y0, x1, y1)`.
+    ///
+    /// If this is set, the cells outside the rectangle from `(x0, y0)` to `(x1, y1)`
+    /// (both inclusive) are assumed to have the background state in every generation.
+    ///
+    /// Unlike a smaller world, the cells outside the box are still part of the world,
+    /// and the rule is still checked there. So no cell can be born outside the box.
+    pub containment_box: Option<(i32, i32, i32, i32)>,
+
+    /// Torus size `(width, height)`.
+    ///
+    /// If this is set, the world is a torus: the left edge is glued to the
+    /// right edge, and the top edge to the bottom edge. For example, the cell
+    /// at `(width - 1, y)` is a neighbor of the cell at `(0, y)`.
+    /// The translations [`dx`](#structfield.dx) and [`dy`](#structfield.dy)
+    /// still apply once per period, and wrap around the torus.
+    ///
+    /// The size must be the same as the [`width`](#structfield.width) and the
+    /// [`height`](#structfield.height) of the world, and a torus cannot have a
+    /// [`diagonal_width`](#structfield.diagonal_width).
+    pub torus: Option<(i32, i32)>,
+
     /// Whether to skip patterns whose fundamental period are smaller than the given period.
-    #[educe(Default = true)]
     pub skip_subperiod: bool,
 
     /// Whether to skip patterns which are invariant under more transformations than
@@ -204,18 +626,272 @@ pub struct Config {
     /// the given symmetry group.
     pub skip_subsymmetry: bool,
 
+    /// Whether to skip patterns which are invariant under a space-time symmetry,
+    /// i.e., a non-identity transformation composed with a time shift.
+    ///
+    /// A pattern is skipped if some generation `t`, where `0 < t < period`,
+    /// is the image of generation 0 under one of the coset representatives
+    /// of the given symmetry group, translated by `t / period` of the given
+    /// translations. Time shifts for which this translation is not an integer
+    /// are not tested.
+    ///
+    /// Time shifts composed with the identity are handled by
+    /// [`skip_subperiod`](#structfield.skip_subperiod) instead.
+    pub skip_spacetime_subsymmetry: bool,
+
+    /// Whether to skip patterns with debris, i.e., patterns which fall apart
+    /// into separate objects surrounded by dead cells.
+    ///
+    /// Two living cells belong to the same object if some cell is adjacent
+    /// to both of them, or if they are adjacent to each other. A pattern is skipped
+    /// if, in some generation, the living cells within the world form more than
+    /// one object, e.g., a spaceship with a still life or another spaceship
+    /// nearby. For Generations rules, dying cells also count as living here.
+    ///
+    /// Like [`skip_subperiod`](#structfield.skip_subperiod), this is checked
+    /// when a result is found, and the search goes on if it is skipped.
+    pub clean_border: bool,
+
+    /// Whether to report the trivial result, i.e., the pattern in which
+    /// every cell has the background state.
+    ///
+    /// The trivial result satisfies many configurations, but by default it is
+    /// never reported as found. Reporting it is mostly useful for testing whether
+    /// the other constraints are consistent at all, or, together with a second
+    /// search, whether the trivial result is the only one.
+    ///
+    /// When this is set, the trivial result is not skipped by
+    /// [`skip_subperiod`](#structfield.skip_subperiod) and the like either.
+    /// The search also no longer requires the first row or column to be
+    /// non-empty, so it might be slower, and report translated copies of
+    /// the same result.
+    pub allow_trivial: bool,
+
+    /// Patterns that are already known.
+    ///
+    /// Each pattern is given by its cells in generation 0, and unmentioned cells
+    /// are dead. A result is skipped if some generation of it is the same as one
+    /// of these patterns, up to translation, rotation and reflection.
+    ///
+    /// Like [`skip_subperiod`](#structfield.skip_subperiod), this is checked
+    /// when a result is found, and the search goes on if it is skipped.
+    pub known_results: Vec<Vec<KnownCell>>,
+
+    /// Parities of the populations of each generation.
+    ///
+    /// If this is not `None`, its length must equal the period. Its `t`-th element
+    /// is `Some(true)` if generation `t` should have an odd number of living cells,
+    /// `Some(false)` if it should have an even number, and `None` if it is
+    /// unconstrained. The cells are counted as in [`World::cell_count_gen`].
+    ///
+    /// Unlike [`max_cell_count`](#structfield.max_cell_count), this is only
+    /// a filter, not a constraint that is propagated during the search:
+    /// like [`skip_subperiod`](#structfield.skip_subperiod), it is checked
+    /// when a result is found, and the search goes on if it is skipped.
+    /// See [`prune_parity`](#structfield.prune_parity) for a stronger mode.
+    pub population_parity: Option<Vec<Option<bool>>>,
+
+    /// Parities of the numbers of living cells in some rows or columns.
+    ///
+    /// Like [`population_parity`](#structfield.population_parity),
+    /// this is only checked when a result is found.
+    pub line_parities: Vec<LineParity>,
+
+    /// Exact numbers of living cells in each row of generation 0, from top to bottom.
+    ///
+    /// The cells are counted as in [`World::cell_count_gen`]. Unlike
+    /// [`line_parities`](#structfield.line_parities), this is a constraint that is
+    /// propagated during the search: the search backtracks as soon as a row has
+    /// more living cells than its count, or too few unknown cells left to reach it.
+    /// Together with a period of 1, this is useful for searching for still lifes
+    /// of maximal density.
+    ///
+    /// When this is set and the [`search_order`](#structfield.search_order) is `None`,
+    /// the search order is always [`SearchOrder::RowFirst`].
+    ///
+    /// [Backjumping](AlgorithmChoice::Backjump) does not support this option.
+    pub row_live_counts: Option<Vec<u32>>,
+
+    /// Whether to also check the [`population_parity`](#structfield.population_parity)
+    /// during the search.
+    ///
+    /// If this is `true`, the search backtracks as soon as all the cells of
+    /// a generation are known and the parity of its population is wrong.
+    /// This does not change the results, but might reduce the number of steps.
+    ///
+    /// [Backjumping](AlgorithmChoice::Backjump) does not support this option.
+    pub prune_parity: bool,
+
+    /// Whether to skip still lifes which have no predecessor in the world
+    /// other than themselves.
+    ///
+    /// Every still life is a predecessor of itself, so it is never a
+    /// [Garden of Eden](https://conwaylife.com/wiki/Garden_of_Eden).
+    /// Instead, this option skips the still lifes which can only be reached
+    /// from themselves, when the predecessor must fit in the world.
+    ///
+    /// When a still life is found, another search in a world of the same size
+    /// and period 2 looks for a different pattern which evolves into it.
+    /// Like [`skip_subperiod`](#structfield.skip_subperiod), this is checked
+    /// when a result is found, and the search goes on if it is skipped.
+    /// It might be slow for large worlds.
+    ///
+    /// It only takes effect when the period is 1, and there is no translation
+    /// or transformation. Currently it is ignored for Generations rules and
+    /// rules with `B0`.
+    pub require_predecessor: bool,
+
     /// Cells whose states are known before the search.
     pub known_cells: Vec<KnownCell>,
 
-    /// __(Experimental)__ Whether to enable [backjumping](https://en.wikipedia.org/wiki/Backjumping).
+    /// Cells whose states are decided before the search, as the first decisions
+    /// of a larger search which is [split](Config::split) into [work units](crate::WorkUnit).
     ///
-    /// Backjumping will reduce the number of steps, but each step will takes
-    /// a much longer time. The current implementation is slower for most search,
-    /// only useful for large (e.g., 64x64) still lifes.
+    /// They are set in the same way as the [known cells](#structfield.known_cells).
+    /// But unlike the known cells, they do not turn off the pruning of patterns
+    /// which are translations of other patterns, since the larger search
+    /// made these decisions with that pruning.
+    pub prefix_cells: Vec<KnownCell>,
+
+    /// Cells whose states never change, i.e., a forced stator.
     ///
-    /// Currently it is only supported for non-Generations rules. Generations rules
-    /// will ignore this option.
-    pub backjump: bool,
+    /// Each of these cells is linked to its successor in every generation,
+    /// so that only patterns where they keep the same state are accepted.
+    /// Unlike [`known_cells`](#structfield.known_cells), their states
+    /// are not given.
+    ///
+    /// The generation of each coordinate is ignored.
+    /// Coordinates outside the world are ignored.
+    pub frozen_cells: Vec<Coord>,
+
+    /// Constraints on some rectangles of cells in some generations.
+    ///
+    /// See [`PhaseMask`].
+    pub phase_masks: Vec<PhaseMask>,
+
+    /// The search algorithm.
+    ///
+    /// [`world`](Self::world) returns an error if [`Backjump`](AlgorithmChoice::Backjump)
+    /// is chosen but does not support the rule or the other options.
+    ///
+    /// When deserializing, the old boolean field `backjump` is also accepted,
+    /// where `true` means [`PreferBackjump`](AlgorithmChoice::PreferBackjump),
+    /// which falls back to the default algorithm as before.
+    #[cfg_attr(feature = "serde", serde(alias = "backjump"))]
+    pub algorithm: AlgorithmChoice,
+
+    /// Whether to consistify the predecessor of a newly set cell
+    /// before the cell itself.
+    ///
+    /// When a cell is set, the search consistifies the cell, its predecessor,
+    /// and its neighbors. By default the cell itself comes first. If this is `true`,
+    /// the predecessor comes first, so that deductions in the time direction are
+    /// made before those in the space direction. This does not change the results,
+    /// but may find conflicts sooner for some searches, especially spaceships
+    /// with large periods.
+    pub pred_first: bool,
+
+    /// How to retreat when the search finds a conflict.
+    ///
+    /// This does not change the results or their order, only the number of steps.
+    pub retreat_strategy: RetreatStrategy,
+
+    /// __(Experimental)__ Capacity of the transposition cache of refuted states.
+    ///
+    /// When a branch of the search is refuted, the part of the state that can still
+    /// affect the rest of the search is stored in a cache. The search will then skip
+    /// any other branch which reaches the same state. When the cache is full,
+    /// the least recently used state is evicted.
+    ///
+    /// This does not change the results, but computing the states takes time,
+    /// so it is only consulted when the search enters a new row or column.
+    /// It may reduce the number of steps when many branches reach the same state,
+    /// e.g., oscillators in thin strips, but it rarely helps spaceship searches,
+    /// and the search is usually slower in time. Use [`World::cache_stats`]
+    /// to see how often it is hit.
+    ///
+    /// `None` or `Some(0)` means that the cache is disabled.
+    ///
+    /// The cache is ignored when [defects](#structfield.max_defects) are allowed.
+    /// [Backjumping](AlgorithmChoice::Backjump) is not supported when the cache is enabled.
+    pub transposition_cache: Option<usize>,
+
+    /// Whether to look up the rule in the [global rule cache](RuleCache::global).
+    ///
+    /// If this is `true`, worlds with the same rule share the same table of
+    /// the rule, which is only computed once. If this is `false`, the table
+    /// is computed for each world and freed with it, which gives a more
+    /// predictable memory usage.
+    ///
+    /// The table of a non-totalistic rule takes 4 MiB, and that of a totalistic
+    /// rule takes 4 KiB. The global cache is shared by the whole process, and keeps
+    /// the [`DEFAULT_CAPACITY`](RuleCache::DEFAULT_CAPACITY) most recently used
+    /// rules even after their worlds are dropped, so it takes at most 16 MiB.
+    pub cache_rule: bool,
+
+    /// How often to check the internal consistency of the world during the search.
+    ///
+    /// With `Some(n)`, [`World::assert_consistent`] is called at every retreat,
+    /// and every `n` steps if `n` is not `0`. It panics if the neighborhood
+    /// descriptors or the counters maintained by the search are corrupted,
+    /// which is usually much closer to the cause than a wrong result.
+    ///
+    /// This only takes effect with the `debug-invariants` feature.
+    /// Otherwise it is ignored. Each check walks through the whole world,
+    /// so checking every step makes the search many times slower.
+    pub invariant_check_interval: Option<u64>,
+}
+
+impl Default for Config {
+    /// A 16x16 still life search in Conway's Game of Life,
+    /// skipping subperiodic patterns and using the global rule cache.
+    fn default() -> Self {
+        Self {
+            width: 16,
+            height: 16,
+            period: 1,
+            dx: 0,
+            dy: 0,
+            transform: Transform::Id,
+            symmetry: Symmetry::C1,
+            phase_symmetries: None,
+            search_order: None,
+            new_state: NewState::ChooseAlive,
+            max_cell_count: None,
+            max_cell_count_per_gen: None,
+            min_cell_count: None,
+            reduce_max: false,
+            reduce_target: ReduceTarget::MaxAcrossGens,
+            max_defects: None,
+            rule_string: String::from("B3/S23"),
+            auto_invert_b0s8: false,
+            diagonal_width: None,
+            skew: None,
+            containment_box: None,
+            torus: None,
+            skip_subperiod: true,
+            skip_subsymmetry: false,
+            skip_spacetime_subsymmetry: false,
+            clean_border: false,
+            allow_trivial: false,
+            known_results: Vec::new(),
+            population_parity: None,
+            line_parities: Vec::new(),
+            row_live_counts: None,
+            prune_parity: false,
+            require_predecessor: false,
+            known_cells: Vec::new(),
+            prefix_cells: Vec::new(),
+            frozen_cells: Vec::new(),
+            phase_masks: Vec::new(),
+            algorithm: AlgorithmChoice::Auto,
+            pred_first: false,
+            retreat_strategy: RetreatStrategy::Chronological,
+            transposition_cache: None,
+            cache_rule: true,
+            invariant_check_interval: None,
+        }
+    }
 }
 
 impl Config {
@@ -248,6 +924,63 @@ impl Config {
         self
     }
 
+    /// Repeats the period `times` times, with the given transformation
+    /// after each of the original periods.
+    ///
+    /// The current [`period`](#structfield.period), `dx` and `dy` describe
+    /// one repetition, and `transform` replaces the current
+    /// [`transform`](#structfield.transform). The pattern must return to itself,
+    /// untransformed, after all the repetitions, so `transform` multiplied by itself
+    /// `times` times must be [`Transform::Id`].
+    ///
+    /// The new period is `period * times`, the new transformation is
+    /// [`Transform::Id`], and the new translation is accumulated across the
+    /// repetitions. For example, a period 2 config with `dx = 0`, `dy = 1`,
+    /// repeated twice with [`Transform::FlipDiag`], becomes a period 4 config
+    /// with `dx = 1`, `dy = 1`. Every result of the original config is a result
+    /// of the new one, but not the other way around.
+    ///
+    /// [Phase symmetries](#structfield.phase_symmetries) and a search order
+    /// given by [`SearchOrder::FromVec`] are repeated accordingly.
+    ///
+    /// Returns an error if `times` or the period is not positive,
+    /// if the transformation requires a square world but the world is not square,
+    /// or if the transformation does not return to the identity.
+    pub fn set_repeat(mut self, transform: Transform, times: i32) -> Result<Self, Error> {
+        if times <= 0 || self.period <= 0 {
+            return Err(Error::NonPositiveError);
+        }
+        if transform.require_square_world() && self.width != self.height {
+            return Err(Error::SquareWorldError);
+        }
+        if (1..times).fold(transform, |power, _| power * transform) != Transform::Id {
+            return Err(Error::RepeatError(transform, times));
+        }
+        let period = self.period;
+        self.transform = transform;
+        let (dx, dy, _) = self.map_coord_across_period((0, 0, period * times));
+        if let Some(phase_symmetries) = &mut self.phase_symmetries {
+            let mut conjugate = Transform::Id;
+            let mut repeated = Vec::with_capacity(phase_symmetries.len() * times as usize);
+            for _ in 0..times {
+                repeated.extend(phase_symmetries.iter().map(|sym| sym.conjugate(conjugate)));
+                conjugate = conjugate * transform.inverse();
+            }
+            *phase_symmetries = repeated;
+        }
+        if let Some(SearchOrder::FromVec(vec)) = &mut self.search_order {
+            *vec = vec
+                .iter()
+                .flat_map(|&(x, y, t)| (0..times).map(move |i| (x, y, t + i * period)))
+                .collect();
+        }
+        self.period = period * times;
+        self.dx = dx;
+        self.dy = dy;
+        self.transform = Transform::Id;
+        Ok(self)
+    }
+
     /// Sets the symmetry.
     #[must_use]
     #[inline]
@@ -256,6 +989,17 @@ impl Config {
         self
     }
 
+    /// Sets the symmetries of each generation.
+    #[must_use]
+    #[inline]
+    pub fn set_phase_symmetries<T: Into<Option<Vec<Symmetry>>>>(
+        mut self,
+        phase_symmetries: T,
+    ) -> Self {
+        self.phase_symmetries = phase_symmetries.into();
+        self
+    }
+
     /// Sets the search order.
     #[must_use]
     #[inline]
@@ -280,6 +1024,33 @@ impl Config {
         self
     }
 
+    /// Sets the maximal number of living cells in every generation.
+    #[must_use]
+    #[inline]
+    pub fn set_max_cell_count_per_gen<T: Into<Option<u32>>>(
+        mut self,
+        max_cell_count_per_gen: T,
+    ) -> Self {
+        self.max_cell_count_per_gen = max_cell_count_per_gen.into();
+        self
+    }
+
+    /// Sets the minimal number of living cells in the first generation.
+    #[must_use]
+    #[inline]
+    pub fn set_min_cell_count<T: Into<Option<u32>>>(mut self, min_cell_count: T) -> Self {
+        self.min_cell_count = min_cell_count.into();
+        self
+    }
+
+    /// Sets the maximal number of defects allowed in a result.
+    #[must_use]
+    #[inline]
+    pub fn set_max_defects<T: Into<Option<u32>>>(mut self, max_defects: T) -> Self {
+        self.max_defects = max_defects.into();
+        self
+    }
+
     /// Sets whether to automatically reduce the `max_cell_count`
     /// when a result is found.
     #[must_use]
@@ -289,6 +1060,15 @@ impl Config {
         self
     }
 
+    /// Sets which population the `max_cell_count` bounds,
+    /// and thus which one `reduce_max` minimizes.
+    #[must_use]
+    #[inline]
+    pub const fn set_reduce_target(mut self, reduce_target: ReduceTarget) -> Self {
+        self.reduce_target = reduce_target;
+        self
+    }
+
     /// Sets the rule string.
     #[must_use]
     #[inline]
@@ -305,6 +1085,38 @@ impl Config {
         self
     }
 
+    /// Sets the skew.
+    #[must_use]
+    #[inline]
+    pub fn set_skew<T: Into<Option<(i32, i32, i32)>>>(mut self, skew: T) -> Self {
+        self.skew = skew.into();
+        self
+    }
+
+    /// Sets the containment box.
+    #[must_use]
+    #[inline]
+    pub fn set_containment_box<T: Into<Option<(i32, i32, i32, i32)>>>(
+        mut self,
+        containment_box: T,
+    ) -> Self {
+        self.containment_box = containment_box.into();
+        self
+    }
+
+    /// Makes the world a torus of the given size,
+    /// and sets the width and the height to the same size.
+    ///
+    /// See [`torus`](#structfield.torus).
+    #[must_use]
+    #[inline]
+    pub const fn set_torus(mut self, width: i32, height: i32) -> Self {
+        self.width = width;
+        self.height = height;
+        self.torus = Some((width, height));
+        self
+    }
+
     /// Sets whether to skip patterns whose fundamental period
     /// is smaller than the given period.
     #[must_use]
@@ -323,6 +1135,34 @@ impl Config {
         self
     }
 
+    /// Sets whether to skip patterns which are invariant under
+    /// a transformation composed with a time shift.
+    #[must_use]
+    #[inline]
+    pub const fn set_skip_spacetime_subsymmetry(
+        mut self,
+        skip_spacetime_subsymmetry: bool,
+    ) -> Self {
+        self.skip_spacetime_subsymmetry = skip_spacetime_subsymmetry;
+        self
+    }
+
+    /// Sets whether to skip patterns with debris.
+    #[must_use]
+    #[inline]
+    pub const fn set_clean_border(mut self, clean_border: bool) -> Self {
+        self.clean_border = clean_border;
+        self
+    }
+
+    /// Sets whether to report the trivial result.
+    #[must_use]
+    #[inline]
+    pub const fn set_allow_trivial(mut self, allow_trivial: bool) -> Self {
+        self.allow_trivial = allow_trivial;
+        self
+    }
+
     /// Sets cells whose states are known before the search.
     #[must_use]
     #[inline]
@@ -331,48 +1171,349 @@ impl Config {
         self
     }
 
+    /// Sets cells whose states are decided before the search.
+    ///
+    /// See [`prefix_cells`](#structfield.prefix_cells).
+    #[must_use]
+    #[inline]
+    pub fn set_prefix_cells<T: Into<Vec<KnownCell>>>(mut self, prefix_cells: T) -> Self {
+        self.prefix_cells = prefix_cells.into();
+        self
+    }
+
     /// Sets cells whose states are known before the search.
     ///
     /// The cells are specified by a list of RLE strings.
+    /// See [`KnownCell::from_rles`].
     #[cfg(feature = "read-rle")]
     #[cfg_attr(any(docs_rs, github_io), doc(cfg(feature = "read-rle")))]
     #[inline]
-    pub fn set_known_cells_from_rles<I, L>(mut self, input: I) -> Result<Self, RleError>
+    pub fn set_known_cells_from_rles(mut self, input: &str) -> Result<Self, Error> {
+        self.known_cells = KnownCell::from_rles(input)?;
+        Ok(self)
+    }
+
+    /// Adds cells whose states are known before the search,
+    /// from a list of RLE strings placed at the given generation and position.
+    ///
+    /// The RLE's are read as in [`KnownCell::from_rles`], and then moved by
+    /// `offset`, and by `gen` generations. So without any comment, the first cell
+    /// of the first line of the first RLE is at `(offset.0, offset.1, gen)`.
+    ///
+    /// Returns an error if some cell is outside the world after moving.
+    #[cfg(feature = "read-rle")]
+    #[cfg_attr(any(docs_rs, github_io), doc(cfg(feature = "read-rle")))]
+    pub fn add_known_cells_from_rle_at(
+        mut self,
+        input: &str,
+        gen: i32,
+        offset: (i32, i32),
+    ) -> Result<Self, Error> {
+        for KnownCell {
+            coord: (x, y, t),
+            state,
+        } in KnownCell::from_rles(input)?
+        {
+            let coord = (x + offset.0, y + offset.1, t + gen);
+            if !self.contains(coord, false, false) {
+                return Err(Error::RleCellOutOfBounds((x, y, t), coord));
+            }
+            self.known_cells.push(KnownCell { coord, state });
+        }
+        Ok(self)
+    }
+
+    /// Sets patterns that are already known.
+    #[must_use]
+    #[inline]
+    pub fn set_known_results<T: Into<Vec<Vec<KnownCell>>>>(mut self, known_results: T) -> Self {
+        self.known_results = known_results.into();
+        self
+    }
+
+    /// Sets patterns that are already known.
+    ///
+    /// Each pattern is specified by an RLE string.
+    #[cfg(feature = "read-rle")]
+    #[cfg_attr(any(docs_rs, github_io), doc(cfg(feature = "read-rle")))]
+    #[inline]
+    pub fn set_known_results_from_rles<I, S>(mut self, input: I) -> Result<Self, Error>
     where
-        I: Input<Lines = L>,
-        L: Input<Lines = L>,
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
     {
-        self.known_cells = KnownCell::from_rles(input)?;
+        self.known_results = input
+            .into_iter()
+            .map(|rle| KnownCell::from_rles(rle.as_ref()))
+            .collect::<Result<_, _>>()?;
         Ok(self)
     }
 
+    /// Sets the parities of the populations of each generation.
+    #[must_use]
+    #[inline]
+    pub fn set_population_parity<T: Into<Option<Vec<Option<bool>>>>>(
+        mut self,
+        population_parity: T,
+    ) -> Self {
+        self.population_parity = population_parity.into();
+        self
+    }
+
+    /// Sets the parities of the numbers of living cells in some rows or columns.
+    #[must_use]
+    #[inline]
+    pub fn set_line_parities<T: Into<Vec<LineParity>>>(mut self, line_parities: T) -> Self {
+        self.line_parities = line_parities.into();
+        self
+    }
+
+    /// Sets the exact numbers of living cells in each row of generation 0.
+    #[must_use]
+    #[inline]
+    pub fn set_row_live_counts<T: Into<Option<Vec<u32>>>>(mut self, row_live_counts: T) -> Self {
+        self.row_live_counts = row_live_counts.into();
+        self
+    }
+
+    /// Sets whether to also check the population parity during the search.
+    #[must_use]
+    #[inline]
+    pub const fn set_prune_parity(mut self, prune_parity: bool) -> Self {
+        self.prune_parity = prune_parity;
+        self
+    }
+
+    /// Sets whether to skip still lifes which have no other predecessor in the world.
+    #[must_use]
+    #[inline]
+    pub const fn set_require_predecessor(mut self, require_predecessor: bool) -> Self {
+        self.require_predecessor = require_predecessor;
+        self
+    }
+
+    /// Sets cells whose states never change.
+    #[must_use]
+    #[inline]
+    pub fn set_frozen_cells<T: Into<Vec<Coord>>>(mut self, frozen_cells: T) -> Self {
+        self.frozen_cells = frozen_cells.into();
+        self
+    }
+
+    /// Sets constraints on some rectangles of cells in some generations.
+    #[must_use]
+    #[inline]
+    pub fn set_phase_masks<T: Into<Vec<PhaseMask>>>(mut self, phase_masks: T) -> Self {
+        self.phase_masks = phase_masks.into();
+        self
+    }
+
+    /// Sets the search algorithm.
+    #[must_use]
+    #[inline]
+    pub const fn set_algorithm(mut self, algorithm: AlgorithmChoice) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
     /// Sets whether to enable backjumping.
+    ///
+    /// `true` means [`AlgorithmChoice::PreferBackjump`],
+    /// and `false` means [`AlgorithmChoice::Auto`].
+    #[deprecated(note = "use `set_algorithm` instead")]
+    #[must_use]
+    #[inline]
+    pub const fn set_backjump(self, backjump: bool) -> Self {
+        self.set_algorithm(if backjump {
+            AlgorithmChoice::PreferBackjump
+        } else {
+            AlgorithmChoice::Auto
+        })
+    }
+
+    /// Sets whether to consistify the predecessor of a cell before the cell itself.
+    #[must_use]
+    #[inline]
+    pub const fn set_pred_first(mut self, pred_first: bool) -> Self {
+        self.pred_first = pred_first;
+        self
+    }
+
+    /// Sets how to retreat when the search finds a conflict.
+    #[must_use]
+    #[inline]
+    pub const fn set_retreat_strategy(mut self, retreat_strategy: RetreatStrategy) -> Self {
+        self.retreat_strategy = retreat_strategy;
+        self
+    }
+
+    /// Sets the capacity of the transposition cache.
+    #[must_use]
+    #[inline]
+    pub fn set_transposition_cache<T: Into<Option<usize>>>(
+        mut self,
+        transposition_cache: T,
+    ) -> Self {
+        self.transposition_cache = transposition_cache.into();
+        self
+    }
+
+    /// Sets whether to search a `B0S8` rule by searching its inverted rule.
+    #[must_use]
+    #[inline]
+    pub const fn set_auto_invert_b0s8(mut self, auto_invert_b0s8: bool) -> Self {
+        self.auto_invert_b0s8 = auto_invert_b0s8;
+        self
+    }
+
+    /// Sets whether to look up the rule in the global rule cache.
     #[must_use]
     #[inline]
-    pub const fn set_backjump(mut self, backjump: bool) -> Self {
-        self.backjump = backjump;
+    pub const fn set_cache_rule(mut self, cache_rule: bool) -> Self {
+        self.cache_rule = cache_rule;
         self
     }
 
+    /// Sets how often to check the internal consistency of the world during the search.
+    #[must_use]
+    #[inline]
+    pub fn set_invariant_check_interval<T: Into<Option<u64>>>(
+        mut self,
+        invariant_check_interval: T,
+    ) -> Self {
+        self.invariant_check_interval = invariant_check_interval.into();
+        self
+    }
+
+    /// The [`rule_string`](#structfield.rule_string) in a canonical notation,
+    /// as given by [`Rule::rule_string_canonical`](crate::rules::Rule::rule_string_canonical).
+    ///
+    /// This is the given rule, even if the world uses the
+    /// [inverted rule](#structfield.auto_invert_b0s8).
+    /// If the rule string is invalid, it is returned unchanged.
+    pub fn rule_string_canonical(&self) -> String {
+        match parse_rule(&self.rule_string) {
+            Ok(ParsedRule::Neumann(rule)) => rule.rule_string_canonical(),
+            Ok(ParsedRule::Hex(rule)) => rule.rule_string_canonical(),
+            Ok(ParsedRule::Key(key)) => key.to_string(),
+            Err(_) => self.rule_string.clone(),
+        }
+    }
+
+    /// Whether the world uses the inverted rule of the given `B0S8` rule.
+    ///
+    /// See [`auto_invert_b0s8`](#structfield.auto_invert_b0s8).
+    pub(crate) fn is_rule_inverted(&self) -> bool {
+        self.auto_invert_b0s8
+            && self
+                .rule_string
+                .parse::<RuleKey>()
+                .is_ok_and(|key| key.is_b0s8())
+    }
+
+    /// The maximal number of defects, if defects are allowed.
+    ///
+    /// See [`max_defects`](#structfield.max_defects).
+    #[inline]
+    pub(crate) fn max_defects(&self) -> Option<u32> {
+        self.max_defects.filter(|&max| max > 0)
+    }
+
+    /// The capacity of the transposition cache, if enabled.
+    ///
+    /// See [`transposition_cache`](#structfield.transposition_cache).
+    #[inline]
+    pub(crate) fn transposition_cache(&self) -> Option<usize> {
+        self.transposition_cache.filter(|&capacity| capacity > 0)
+    }
+
+    /// Whether the cell is inside the containment box.
+    ///
+    /// See [`containment_box`](#structfield.containment_box).
+    #[inline]
+    pub(crate) fn in_containment_box(&self, (x, y, _): Coord) -> bool {
+        match self.containment_box {
+            Some((x0, y0, x1, y1)) => x0 <= x && x <= x1 && y0 <= y && y <= y1,
+            None => true,
+        }
+    }
+
+    /// Wraps a coordinate around the [`torus`](#structfield.torus),
+    /// so that it is inside the world.
+    ///
+    /// Does nothing if the world is not a torus.
+    #[inline]
+    pub(crate) const fn wrap_torus(&self, (x, y, t): Coord) -> Coord {
+        match self.torus {
+            Some((width, height)) => (x.rem_euclid(width), y.rem_euclid(height), t),
+            None => (x, y, t),
+        }
+    }
+
+    /// Whether the cell is inside the [`diagonal_width`](#structfield.diagonal_width)
+    /// and the [`skew`](#structfield.skew).
+    ///
+    /// If `including_border` is true, this includes the cells just outside of them,
+    /// which are neighbors of the cells inside.
+    #[inline]
+    pub(crate) fn in_strips(&self, (x, y, _): Coord, including_border: bool) -> bool {
+        self.diagonal_width
+            .map(|d| (1, 1, d))
+            .into_iter()
+            .chain(self.skew)
+            .all(|(p, q, w)| {
+                let dist = (q * x - p * y).abs();
+                if including_border {
+                    dist < w + p.abs() + q.abs()
+                } else {
+                    dist < w
+                }
+            })
+    }
+
     /// Whether the configuration requires the world to be square.
     #[inline]
-    pub const fn require_square_world(&self) -> bool {
+    pub fn require_square_world(&self) -> bool {
         self.symmetry.require_square_world()
+            || self
+                .phase_symmetries
+                .iter()
+                .flatten()
+                .any(|sym| sym.require_square_world())
             || self.transform.require_square_world()
             || matches!(self.search_order, Some(SearchOrder::Diagonal))
     }
 
     /// Whether the configuration requires the world to have no diagonal width.
     #[inline]
-    pub const fn require_no_diagonal_width(&self) -> bool {
-        self.symmetry.require_no_diagonal_width() || self.transform.require_no_diagonal_width()
+    pub fn require_no_diagonal_width(&self) -> bool {
+        self.symmetry.require_no_diagonal_width()
+            || self
+                .phase_symmetries
+                .iter()
+                .flatten()
+                .any(|sym| sym.require_no_diagonal_width())
+            || self.transform.require_no_diagonal_width()
+    }
+
+    /// Whether the configuration requires the world to have no skew.
+    #[inline]
+    pub fn require_no_skew(&self) -> bool {
+        self.symmetry.require_no_skew()
+            || self
+                .phase_symmetries
+                .iter()
+                .flatten()
+                .any(|sym| sym.require_no_skew())
+            || self.transform.require_no_skew()
     }
 
     /// Whether the cell is contained in the world.
     ///
     /// If `including_border` is true, this includes the cells at the border.
     ///
-    /// If `check_diagonal_width` is true, this excludes the cells outside of the diagonal_width.
+    /// If `check_diagonal_width` is true, this excludes the cells outside of the diagonal_width
+    /// and the skew.
     pub(crate) fn contains(
         &self,
         (x, y, t): Coord,
@@ -386,9 +1527,7 @@ impl Config {
                 && y <= self.height
                 && t >= 0
                 && t < self.period
-                && (!check_diagonal_width
-                    || (self.diagonal_width.is_none()
-                        || (x - y).abs() <= self.diagonal_width.unwrap() + 1))
+                && (!check_diagonal_width || self.in_strips((x, y, t), true))
         } else {
             x >= 0
                 && x < self.width
@@ -396,9 +1535,7 @@ impl Config {
                 && y < self.height
                 && t >= 0
                 && t < self.period
-                && (!check_diagonal_width
-                    || (self.diagonal_width.is_none()
-                        || (x - y).abs() < self.diagonal_width.unwrap()))
+                && (!check_diagonal_width || self.in_strips((x, y, t), false))
         }
     }
 
@@ -407,27 +1544,66 @@ impl Config {
     pub fn world(&self) -> Result<PolyWorld, Error> {
         macro_rules! new_world {
             ($rule:expr) => {{
-                for known_cell in &self.known_cells {
+                for known_cell in self.known_cells.iter().chain(&self.prefix_cells) {
                     if known_cell.state.0 >= 2 {
                         return Err(Error::InvalidState(known_cell.coord, known_cell.state));
                     }
                 }
-                if self.backjump && self.max_cell_count.is_none() {
-                    Ok(World::new_backjump(&self, $rule).into())
-                } else {
-                    Ok(World::new_lifesrc(&self, $rule).into())
+                match self.algorithm {
+                    AlgorithmChoice::Auto | AlgorithmChoice::LifeSrc => {
+                        Ok(World::new_lifesrc(&self, $rule).into())
+                    }
+                    AlgorithmChoice::Backjump | AlgorithmChoice::PreferBackjump => {
+                        let unsupported = if self.max_cell_count.is_some()
+                            || self.max_cell_count_per_gen.is_some()
+                        {
+                            Some("max cell count")
+                        } else if self.prune_parity && self.population_parity.is_some() {
+                            Some("parity pruning")
+                        } else if self.max_defects().is_some() {
+                            Some("defects")
+                        } else if self.transposition_cache().is_some() {
+                            Some("transposition cache")
+                        } else if self.row_live_counts.is_some() {
+                            Some("row live counts")
+                        } else {
+                            None
+                        };
+                        match unsupported {
+                            None => Ok(World::new_backjump(&self, $rule).into()),
+                            Some(_) if self.algorithm == AlgorithmChoice::PreferBackjump => {
+                                Ok(World::new_lifesrc(&self, $rule).into())
+                            }
+                            Some(option) => {
+                                Err(Error::UnsupportedAlgorithm(self.algorithm, option))
+                            }
+                        }
+                    }
                 }
             }};
         }
 
         macro_rules! new_world_gen {
             ($rule:expr) => {{
+                if $rule.gen() > MAX_GEN {
+                    return Err(Error::TooManyStatesError($rule.gen()));
+                }
+                // The background goes through all the states in turn.
+                if $rule.has_b0() && self.period as usize % $rule.gen() != 0 {
+                    return Err(Error::B0PeriodError($rule.gen()));
+                }
                 if $rule.gen() > 2 {
-                    for known_cell in &self.known_cells {
-                        if known_cell.state.0 >= $rule.gen() {
+                    for known_cell in self.known_cells.iter().chain(&self.prefix_cells) {
+                        if known_cell.state.0 as usize >= $rule.gen() {
                             return Err(Error::InvalidState(known_cell.coord, known_cell.state));
                         }
                     }
+                    if self.algorithm == AlgorithmChoice::Backjump {
+                        return Err(Error::UnsupportedAlgorithm(
+                            self.algorithm,
+                            "Generations rules",
+                        ));
+                    }
                     Ok(World::new_lifesrc(&self, $rule).into())
                 } else {
                     new_world!($rule.non_gen())
@@ -443,22 +1619,208 @@ impl Config {
                 return Err(Error::NonPositiveError);
             }
         }
+        if let Some((_, _, w)) = self.skew {
+            if w <= 0 {
+                return Err(Error::NonPositiveError);
+            }
+        }
+        if let Some((x0, y0, x1, y1)) = self.containment_box {
+            if x0 > x1 || y0 > y1 {
+                return Err(Error::ContainmentBoxError);
+            }
+        }
+        if let Some(torus) = self.torus {
+            if torus != (self.width, self.height) {
+                return Err(Error::TorusSizeError(torus));
+            }
+            if self.diagonal_width.is_some() || self.skew.is_some() {
+                return Err(Error::TorusDiagonalWidthError);
+            }
+        }
+        for known_cell in self.known_cells.iter().chain(&self.prefix_cells) {
+            if !self.contains(known_cell.coord, false, false) {
+                return Err(Error::KnownCellOutOfBounds(known_cell.coord));
+            }
+            if !self.in_containment_box(known_cell.coord) {
+                return Err(Error::KnownCellOutsideContainmentBox(known_cell.coord));
+            }
+        }
+        if let Some(SearchOrder::FromVec(vec)) = &self.search_order {
+            let mut coords = HashSet::new();
+            for &coord in vec {
+                if !coords.insert(coord) {
+                    return Err(Error::DuplicateSearchOrderCell(coord));
+                }
+            }
+        }
+        self.check_search_order_coverage()?;
+        if let Some(phase_symmetries) = &self.phase_symmetries {
+            if phase_symmetries.len() != self.period as usize {
+                return Err(Error::PhaseSymmetriesLengthError);
+            }
+            let first = phase_symmetries[0];
+            let last = phase_symmetries[self.period as usize - 1].conjugate(self.transform);
+            if !last.is_subgroup_of(first) {
+                return Err(Error::PhaseSymmetryError(first, last));
+            }
+        }
+        if let Some(population_parity) = &self.population_parity {
+            if population_parity.len() != self.period as usize {
+                return Err(Error::PopulationParityLengthError);
+            }
+        }
+        if let Some(row_live_counts) = &self.row_live_counts {
+            if row_live_counts.len() != self.height as usize {
+                return Err(Error::RowLiveCountsLengthError);
+            }
+            for (y, &count) in row_live_counts.iter().enumerate() {
+                if count > self.width as u32 {
+                    return Err(Error::RowLiveCountTooLarge(y as i32, count));
+                }
+            }
+        }
+        for &LineParity { line, gen, .. } in &self.line_parities {
+            let in_world = match line {
+                Line::Row(y) => (0..self.height).contains(&y),
+                Line::Column(x) => (0..self.width).contains(&x),
+            };
+            if !in_world || !(0..self.period).contains(&gen) {
+                return Err(Error::LineOutOfBounds(line, gen));
+            }
+        }
+        if let ReduceTarget::Gen(t) = self.reduce_target {
+            if !(0..self.period).contains(&t) {
+                return Err(Error::ReduceTargetOutOfBounds(t));
+            }
+        }
+        for phase_mask in &self.phase_masks {
+            let (x0, y0, x1, y1) = phase_mask.rect;
+            if x0 > x1
+                || y0 > y1
+                || x0 < 0
+                || y0 < 0
+                || x1 >= self.width
+                || y1 >= self.height
+                || phase_mask
+                    .gens
+                    .iter()
+                    .any(|gen| !(0..self.period).contains(gen))
+            {
+                return Err(Error::PhaseMaskOutOfBounds(phase_mask.clone()));
+            }
+            if phase_mask.gens.is_empty() {
+                return Err(Error::EmptyPhaseMask(phase_mask.clone()));
+            }
+        }
+        if let NewState::RandomBiased { alive_probability } = self.new_state {
+            if !(alive_probability > 0.0 && alive_probability < 1.0) {
+                return Err(Error::ProbabilityError);
+            }
+        }
         if self.require_square_world() && self.width != self.height {
             return Err(Error::SquareWorldError);
         }
         if self.require_no_diagonal_width() && self.diagonal_width.is_some() {
             return Err(Error::DiagonalWidthError);
         }
+        if self.require_no_skew() && self.skew.is_some() {
+            return Err(Error::SkewError);
+        }
 
-        if let Ok(rule) = self.rule_string.parse::<Life>() {
-            new_world!(rule)
-        } else if let Ok(rule) = self.rule_string.parse::<NtLife>() {
-            new_world!(rule)
-        } else if let Ok(rule) = self.rule_string.parse::<LifeGen>() {
-            new_world_gen!(rule)
+        let mut key = match parse_rule(&self.rule_string)? {
+            ParsedRule::Neumann(rule) => return new_world!(rule),
+            ParsedRule::Hex(rule) => return new_world!(rule),
+            ParsedRule::Key(key) => key,
+        };
+        if self.auto_invert_b0s8 && key.is_b0s8() {
+            key = key.inverted();
+        }
+        let rule = if self.cache_rule {
+            RuleCache::global().get(key)?
         } else {
-            let rule = self.rule_string.parse::<NtLifeGen>()?;
-            new_world_gen!(rule)
+            key.build()?
+        };
+        match rule {
+            CachedRule::Life(rule) => new_world_gen!(rule),
+            CachedRule::NtLife(rule) => new_world_gen!(rule),
+        }
+    }
+
+    /// Searches in worlds of increasing sizes, and returns the first world
+    /// where a result is found.
+    ///
+    /// The sizes range from the `width` and `height` of this configuration
+    /// to `max_width` and `max_height`. Smaller areas are tried first,
+    /// and among sizes with the same area, narrower ones are tried first.
+    /// When the configuration requires the world to be square,
+    /// only square sizes are tried.
+    ///
+    /// Returns the world and [`Status::Found`] if a result is found,
+    /// otherwise the last world that is tried and [`Status::None`].
+    /// Returns an error if the configuration is invalid,
+    /// or if a square world is required but there is no square size in the range.
+    pub fn search_growing(
+        &self,
+        max_width: i32,
+        max_height: i32,
+    ) -> Result<(PolyWorld, Status), Error> {
+        let require_square_world = self.require_square_world();
+        let mut sizes = (self.width..=max_width.max(self.width))
+            .flat_map(|width| {
+                (self.height..=max_height.max(self.height)).map(move |height| (width, height))
+            })
+            .filter(|&(width, height)| !require_square_world || width == height)
+            .collect::<Vec<_>>();
+        sizes.sort_by_key(|&(width, height)| (width * height, width));
+
+        let mut last_world = None;
+        for (width, height) in sizes {
+            let config = Self {
+                width,
+                height,
+                ..self.clone()
+            };
+            let mut world = config.world()?;
+            if world.search(None) == Status::Found {
+                return Ok((world, Status::Found));
+            }
+            last_world = Some(world);
+        }
+        match last_world {
+            Some(world) => Ok((world, Status::None)),
+            None => Err(Error::SquareWorldError),
+        }
+    }
+
+    /// Searches with an increasing [`max_cell_count`](#structfield.max_cell_count),
+    /// and returns the first world where a result is found.
+    ///
+    /// The `max_cell_count` of this configuration is ignored. The cap goes from
+    /// 1 to `upper_bound`, so the first result has the minimal population,
+    /// i.e., the minimal number of living cells in its smallest generation.
+    /// This is the opposite of [`reduce_max`](#structfield.reduce_max),
+    /// which starts from a large cap and lowers it after each result.
+    ///
+    /// A world whose search is exhausted cannot be searched again,
+    /// so a new world is created for each cap.
+    ///
+    /// Returns the world and [`Status::Found`] if a result is found,
+    /// otherwise the last world that is tried and [`Status::None`].
+    /// Returns an error if the configuration is invalid,
+    /// or if `upper_bound` is 0.
+    pub fn search_min_population(&self, upper_bound: u32) -> Result<(PolyWorld, Status), Error> {
+        let mut last_world = None;
+        for max_cell_count in 1..=upper_bound {
+            let config = self.clone().set_max_cell_count(max_cell_count);
+            let mut world = config.world()?;
+            if world.search(None) == Status::Found {
+                return Ok((world, Status::Found));
+            }
+            last_world = Some(world);
+        }
+        match last_world {
+            Some(world) => Ok((world, Status::None)),
+            None => Err(Error::NonPositiveError),
         }
     }
 }
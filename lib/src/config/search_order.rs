@@ -1,8 +1,13 @@
 //! Configurations related to the the search order.
 
 use super::{Config, Coord, Symmetry};
+use crate::error::Error;
 use auto_enums::auto_enum;
-use std::{borrow::Cow, cmp::Ordering};
+use std::{
+    borrow::Cow,
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+};
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
@@ -48,16 +53,28 @@ pub enum SearchOrder {
     /// This vector should cover every cell in the search range,
     /// and should not have any duplication, otherwise rlifesrc
     /// would give a wrong result.
+    ///
+    /// When the world has a nontrivial symmetry or some
+    /// [frozen cells](Config#structfield.frozen_cells), it suffices to cover one cell
+    /// of each set of cells linked by them, e.g., the cells in a
+    /// [fundamental domain](Config::fundamental_domain).
+    /// In this case, the coverage is checked when the world is created.
     FromVec(Vec<Coord>),
 }
 
 impl Config {
     /// Automatically determines the search order if `search_order` is `None`.
+    ///
+    /// When [`row_live_counts`](Config#structfield.row_live_counts) is set,
+    /// it is always [`RowFirst`](SearchOrder::RowFirst), so that each row
+    /// is completed as early as possible.
     pub(crate) fn auto_search_order(&self) -> Cow<'_, SearchOrder> {
         if let Some(search_order) = &self.search_order {
             Cow::Borrowed(search_order)
+        } else if self.row_live_counts.is_some() {
+            Cow::Owned(SearchOrder::RowFirst)
         } else {
-            let (width, height) = match self.symmetry {
+            let (width, height) = match self.min_symmetry() {
                 Symmetry::D2Row => (self.width, (self.height + 1) / 2),
                 Symmetry::D2Col => ((self.width + 1) / 2, self.height),
                 _ => (self.width, self.height),
@@ -90,12 +107,13 @@ impl Config {
         let width = self.width;
         let height = self.height;
         let period = self.period;
-        let x_start = if self.symmetry >= Symmetry::D2Col {
+        let symmetry = self.min_symmetry();
+        let x_start = if symmetry >= Symmetry::D2Col {
             self.width / 2
         } else {
             0
         };
-        let y_start = if self.symmetry >= Symmetry::D2Row {
+        let y_start = if symmetry >= Symmetry::D2Row {
             self.height / 2
         } else {
             0
@@ -113,7 +131,7 @@ impl Config {
             }),
             #[nested]
             SearchOrder::Diagonal => {
-                if self.symmetry >= Symmetry::D2Diag {
+                if symmetry >= Symmetry::D2Diag {
                     (0..width)
                         .rev()
                         .flat_map(move |d| {
@@ -145,6 +163,135 @@ impl Config {
         }
     }
 
+    /// Links between the generations of the [frozen cells](Config#structfield.frozen_cells),
+    /// in both directions.
+    fn frozen_links(&self) -> HashMap<Coord, Vec<Coord>> {
+        let mut links = HashMap::<Coord, Vec<Coord>>::new();
+        for &(x, y, _) in &self.frozen_cells {
+            for t in 0..self.period {
+                let succ = self.map_coord_across_period((x, y, t + 1));
+                if self.contains((x, y, t), false, true) && self.contains(succ, false, true) {
+                    links.entry((x, y, t)).or_default().push(succ);
+                    links.entry(succ).or_default().push((x, y, t));
+                }
+            }
+        }
+        links
+    }
+
+    /// Adds the cells which are linked to the given cells, directly or not,
+    /// by the symmetry or the frozen cells, to `visited`.
+    ///
+    /// These cells are known as soon as one of them is known.
+    fn visit_linked_cells(
+        &self,
+        start: impl IntoIterator<Item = Coord>,
+        frozen_links: &HashMap<Coord, Vec<Coord>>,
+        visited: &mut HashSet<Coord>,
+    ) {
+        let mut stack = start
+            .into_iter()
+            .filter(|&coord| visited.insert(coord))
+            .collect::<Vec<_>>();
+        while let Some(coord) = stack.pop() {
+            let sym = self
+                .symmetry_at(coord.2)
+                .members()
+                .into_iter()
+                .map(|transform| transform.act_on(coord, self.width, self.height));
+            let frozen = frozen_links.get(&coord).into_iter().flatten().copied();
+            for linked in sym.chain(frozen) {
+                if self.contains(linked, false, true) && visited.insert(linked) {
+                    stack.push(linked);
+                }
+            }
+        }
+    }
+
+    /// The cells in the search range, in row-major order,
+    /// with all the generations of a cell before the next cell.
+    fn cells_in_range(&self) -> impl Iterator<Item = Coord> + '_ {
+        (0..self.height).flat_map(move |y| {
+            (0..self.width).flat_map(move |x| {
+                (0..self.period)
+                    .map(move |t| (x, y, t))
+                    .filter(|&coord| self.contains(coord, false, true))
+            })
+        })
+    }
+
+    /// A fundamental domain of the [symmetry](Config#structfield.symmetry), i.e.,
+    /// a set of cells containing exactly one cell of each set of cells which are
+    /// linked by the symmetry or the [frozen cells](Config#structfield.frozen_cells).
+    ///
+    /// It is a valid search order for [`SearchOrder::FromVec`], and can be reordered
+    /// to make a custom search order. The cells are listed in row-major order,
+    /// with all the generations of a cell before the next cell, and each cell is
+    /// the first of its set in this order.
+    pub fn fundamental_domain(&self) -> Vec<Coord> {
+        let frozen_links = self.frozen_links();
+        let mut visited = HashSet::new();
+        let mut domain = Vec::new();
+        for coord in self.cells_in_range() {
+            if !visited.contains(&coord) {
+                domain.push(coord);
+                self.visit_linked_cells([coord], &frozen_links, &mut visited);
+            }
+        }
+        domain
+    }
+
+    /// Checks that a search order given by [`SearchOrder::FromVec`] covers every cell
+    /// in the search range which is not known before the search, up to the symmetry
+    /// and the [frozen cells](Config#structfield.frozen_cells).
+    ///
+    /// Nothing is checked when there is neither symmetry nor frozen cell,
+    /// because then the search order is allowed to cover only part of the world.
+    pub(crate) fn check_search_order_coverage(&self) -> Result<(), Error> {
+        let Some(SearchOrder::FromVec(vec)) = &self.search_order else {
+            return Ok(());
+        };
+        if !self.has_symmetry() && self.frozen_cells.is_empty() {
+            return Ok(());
+        }
+        let frozen_links = self.frozen_links();
+        // Cells whose symmetric cells are out of the search range are known,
+        // and so are the known cells and the cells outside the containment box.
+        let known = self.cells_in_range().filter(|&coord| {
+            !self.in_containment_box(coord)
+                || self
+                    .symmetry_at(coord.2)
+                    .members()
+                    .into_iter()
+                    .any(|transform| {
+                        !self.contains(
+                            transform.act_on(coord, self.width, self.height),
+                            false,
+                            true,
+                        )
+                    })
+        });
+        let mut visited = HashSet::new();
+        self.visit_linked_cells(
+            vec.iter()
+                .copied()
+                .filter(|&coord| self.contains(coord, false, true))
+                .chain(
+                    self.known_cells
+                        .iter()
+                        .chain(&self.prefix_cells)
+                        .map(|known_cell| known_cell.coord),
+                )
+                .chain(known),
+            &frozen_links,
+            &mut visited,
+        );
+        match self.cells_in_range().find(|coord| !visited.contains(coord)) {
+            Some(coord) => Err(Error::SearchOrderIncompleteForSymmetry(coord)),
+            None => Ok(()),
+        }
+    }
+
     /// Generates a closure to determine whether a cell is in the front.
     ///
     /// Return `None` when we should not force the front to be nonempty,
@@ -161,13 +308,19 @@ impl Config {
         let width = self.width;
         let height = self.height;
         let max_t = if rule_is_b0 { rule_gen as i32 } else { 1 };
-        if !self.known_cells.is_empty() {
+        let symmetry = self.max_symmetry();
+        if !self.known_cells.is_empty()
+            || self.containment_box.is_some()
+            || self.torus.is_some()
+            || self.skew.is_some()
+            || self.allow_trivial
+        {
             return None;
         }
 
         match search_order {
             SearchOrder::RowFirst => {
-                if self.symmetry <= Symmetry::D2Col
+                if symmetry <= Symmetry::D2Col
                     && self.transform.is_in(Symmetry::D2Col)
                     && self.diagonal_width.is_none()
                 {
@@ -189,7 +342,7 @@ impl Config {
                 }
             }
             SearchOrder::ColumnFirst => {
-                if self.symmetry <= Symmetry::D2Row
+                if symmetry <= Symmetry::D2Row
                     && self.transform.is_in(Symmetry::D2Row)
                     && self.diagonal_width.is_none()
                 {
@@ -211,7 +364,7 @@ impl Config {
                 }
             }
             SearchOrder::Diagonal => {
-                if self.symmetry <= Symmetry::D2Diag && self.transform.is_in(Symmetry::D2Diag) {
+                if symmetry <= Symmetry::D2Diag && self.transform.is_in(Symmetry::D2Diag) {
                     if dx >= 0 && dx == dy {
                         if rule_symmetry >= Symmetry::D2Diag && self.width == self.height {
                             Some(Box::new(move |(x, _, t)| x == (dx - 1).max(0) && t < max_t))
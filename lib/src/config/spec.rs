@@ -0,0 +1,95 @@
+//! Compact specifications of the size, the rule and the symmetries.
+
+use super::Config;
+use crate::{error::Error, rules::NtLifeGen};
+
+/// Splits a specification at the commas which are not inside braces.
+fn split_items(spec: &str) -> Vec<&str> {
+    let mut items = Vec::new();
+    let mut depth = 0_u32;
+    let mut start = 0;
+    for (i, c) in spec.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                items.push(&spec[start..i]);
+                start = i + 1;
+            }
+            _ => (),
+        }
+    }
+    items.push(&spec[start..]);
+    items
+}
+
+impl Config {
+    /// Updates the configuration from a compact specification.
+    ///
+    /// The specification is a comma-separated list of `key=value` pairs,
+    /// e.g., `rule=B3/S23, sym=D2|, trans=R180, dx=0, dy=1, w=16, h=16, p=2`.
+    /// Spaces around keys and values are ignored. Commas inside braces do not
+    /// separate pairs, so a symmetry can also be given by its members,
+    /// e.g., `sym={Id, R180}`. The keys are:
+    ///
+    /// - `w` or `width`: the [`width`](#structfield.width);
+    /// - `h` or `height`: the [`height`](#structfield.height);
+    /// - `p` or `period`: the [`period`](#structfield.period);
+    /// - `dx` and `dy`: the translations;
+    /// - `trans` or `transform`: the [`transform`](#structfield.transform);
+    /// - `sym` or `symmetry`: the [`symmetry`](#structfield.symmetry);
+    /// - `rule`: the [`rule_string`](#structfield.rule_string).
+    ///
+    /// Only the given fields are changed, so that it can be used to apply
+    /// overrides onto a loaded configuration. When a key appears more than once,
+    /// the last value is used.
+    ///
+    /// If the specification is invalid, an error is returned
+    /// and the configuration is not changed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rlifesrc_lib::{Config, Symmetry};
+    ///
+    /// let mut config = Config::new(16, 16, 1);
+    /// config.apply_spec("sym=D2|, p=2, dy=1").unwrap();
+    /// assert_eq!(
+    ///     config,
+    ///     Config::new(16, 16, 2)
+    ///         .set_symmetry(Symmetry::D2Col)
+    ///         .set_translate(0, 1)
+    /// );
+    /// ```
+    pub fn apply_spec(&mut self, spec: &str) -> Result<(), Error> {
+        let mut config = self.clone();
+        for item in split_items(spec) {
+            let item = item.trim();
+            if item.is_empty() {
+                continue;
+            }
+            let (key, value) = item.split_once('=').ok_or_else(|| {
+                Error::SpecError(format!("expected `key=value`, found {:?}", item))
+            })?;
+            let (key, value) = (key.trim(), value.trim());
+            let invalid = || Error::SpecError(format!("invalid value {:?} for {:?}", value, key));
+            let int = || value.parse::<i32>().map_err(|_| invalid());
+            match key {
+                "w" | "width" => config.width = int()?,
+                "h" | "height" => config.height = int()?,
+                "p" | "period" => config.period = int()?,
+                "dx" => config.dx = int()?,
+                "dy" => config.dy = int()?,
+                "trans" | "transform" => config.transform = value.parse().map_err(|_| invalid())?,
+                "sym" | "symmetry" => config.symmetry = value.parse().map_err(|_| invalid())?,
+                "rule" => {
+                    value.parse::<NtLifeGen>()?;
+                    config.rule_string = value.to_owned();
+                }
+                _ => return Err(Error::SpecError(format!("unknown key {:?}", key))),
+            }
+        }
+        *self = config;
+        Ok(())
+    }
+}
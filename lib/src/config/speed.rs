@@ -0,0 +1,122 @@
+//! Speeds of spaceships, in the usual notation like `c/4` or `(2, 1)c/6`.
+
+use super::Config;
+use crate::error::Error;
+
+/// Parses a speed into the translation and the period `(dx, dy, period)`.
+///
+/// See [`Config::set_speed`] for the syntax.
+fn parse_speed(speed: &str) -> Result<(i32, i32, i32), Error> {
+    let invalid = || {
+        Error::SpeedError(format!(
+            "expected `c/N`, `Kc/N` or `(a, b)c/N`, found {:?}",
+            speed
+        ))
+    };
+    let int = |s: &str| s.trim().parse::<i32>().map_err(|_| invalid());
+
+    let (displacement, period) = speed.trim().split_once("c/").ok_or_else(invalid)?;
+    let period = period.trim_end();
+    let (period, diagonal) = match period.strip_suffix('d') {
+        Some(period) => (period, Some(true)),
+        None => match period.strip_suffix('o') {
+            Some(period) => (period, Some(false)),
+            None => (period, None),
+        },
+    };
+    let period = int(period)?;
+
+    let displacement = displacement.trim();
+    let (dx, dy) = if let Some(pair) = displacement
+        .strip_prefix('(')
+        .and_then(|s| s.strip_suffix(')'))
+    {
+        if diagonal.is_some() {
+            return Err(Error::SpeedError(format!(
+                "a direction suffix cannot follow a displacement (a, b) in {:?}",
+                speed
+            )));
+        }
+        let (dx, dy) = pair.split_once(',').ok_or_else(invalid)?;
+        (int(dx)?, int(dy)?)
+    } else {
+        let k = if displacement.is_empty() {
+            1
+        } else {
+            int(displacement)?
+        };
+        if k <= 0 {
+            return Err(invalid());
+        }
+        if diagonal == Some(true) {
+            (k, k)
+        } else {
+            (0, k)
+        }
+    };
+
+    if period <= 0 {
+        return Err(Error::SpeedError(format!(
+            "the period should be positive in {:?}",
+            speed
+        )));
+    }
+    // Every supported rule only looks at the cells in the Moore neighborhood,
+    // so nothing can move more than one cell in each direction per generation.
+    if dx.abs().max(dy.abs()) > period {
+        return Err(Error::SpeedError(format!(
+            "{:?} is faster than the speed of light",
+            speed
+        )));
+    }
+    Ok((dx, dy, period))
+}
+
+impl Config {
+    /// Sets the [`period`](#structfield.period) and the translations
+    /// [`dx`](#structfield.dx) and [`dy`](#structfield.dy) from a speed.
+    ///
+    /// The speed is written in one of the usual notations:
+    ///
+    /// - `c/N` or `Kc/N`: moves `K` cells orthogonally every `N` generations,
+    ///   i.e., `dx = 0` and `dy = K`;
+    /// - `(a, b)c/N`: moves by `(a, b)` every `N` generations.
+    ///
+    /// The first notation can be followed by a direction suffix:
+    /// `o` for orthogonal, which is the default, or `d` for diagonal,
+    /// i.e., `dx = dy = K`. For example, `c/4d` is the speed of the glider,
+    /// and `2c/5` and `(0, 2)c/5o` are the same.
+    ///
+    /// Returns [`Error::SpeedError`] if the speed is invalid, or if it is
+    /// faster than the speed of light, i.e., one cell per generation
+    /// in each direction.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rlifesrc_lib::Config;
+    ///
+    /// let config = Config::new(16, 16, 1).set_speed("(2, 1)c/6").unwrap();
+    /// assert_eq!(config, Config::new(16, 16, 6).set_translate(2, 1));
+    /// assert_eq!(config.speed_string(), "(2, 1)c/6");
+    /// ```
+    pub fn set_speed(mut self, speed: &str) -> Result<Self, Error> {
+        (self.dx, self.dy, self.period) = parse_speed(speed)?;
+        Ok(self)
+    }
+
+    /// Displays the period and the translations as a speed.
+    ///
+    /// This is the shortest notation accepted by [`set_speed`](Self::set_speed)
+    /// which gives the same period and translations, e.g., `c/3` for
+    /// `dx = 0, dy = 1, period = 3`, `c/4d` for `dx = dy = 1, period = 4`,
+    /// and `(0, -1)c/3` for `dx = 0, dy = -1, period = 3`.
+    pub fn speed_string(&self) -> String {
+        let multiple = |k: i32| if k == 1 { String::new() } else { k.to_string() };
+        match (self.dx, self.dy) {
+            (0, dy) if dy > 0 => format!("{}c/{}", multiple(dy), self.period),
+            (dx, dy) if dx == dy && dx > 0 => format!("{}c/{}d", multiple(dx), self.period),
+            (dx, dy) => format!("({}, {})c/{}", dx, dy, self.period),
+        }
+    }
+}
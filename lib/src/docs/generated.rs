@@ -0,0 +1,150 @@
+// This file is generated by `cargo xtask docs` from the doc comments
+// of the variants. Do not edit it by hand.
+
+use super::VariantDoc;
+
+/// The variants of [`Symmetry`](crate::Symmetry).
+pub const SYMMETRY: &[VariantDoc] = &[
+    VariantDoc {
+        variant: "C1",
+        name: "C1",
+        doc: "No symmetry at all.",
+    },
+    VariantDoc {
+        variant: "C2",
+        name: "C2",
+        doc: "Symmetry under 180° rotation.",
+    },
+    VariantDoc {
+        variant: "C4",
+        name: "C4",
+        doc: "Symmetry under 90° rotation.\n\nRequires the world to be square and have no diagonal width.",
+    },
+    VariantDoc {
+        variant: "D2Row",
+        name: "D2-",
+        doc: "Symmetry under reflection across the middle row.\n\nRequires the world to have no diagonal width.",
+    },
+    VariantDoc {
+        variant: "D2Col",
+        name: "D2|",
+        doc: "Symmetry under reflection across the middle column.\n\nRequires the world to have no diagonal width.",
+    },
+    VariantDoc {
+        variant: "D2Diag",
+        name: "D2\\",
+        doc: "Symmetry under reflection across the diagonal.\n\nRequires the world to be square.",
+    },
+    VariantDoc {
+        variant: "D2Antidiag",
+        name: "D2/",
+        doc: "Symmetry under reflection across the antidiagonal.\n\nRequires the world to be square.",
+    },
+    VariantDoc {
+        variant: "D4Ortho",
+        name: "D4+",
+        doc: "Symmetry under reflections across the middle row\nand the middle column.\n\nRequires the world to have no diagonal width.",
+    },
+    VariantDoc {
+        variant: "D4Diag",
+        name: "D4X",
+        doc: "Symmetry under reflections across the diagonal\nand the antidiagonal.\n\nRequires the world to be square.",
+    },
+    VariantDoc {
+        variant: "D8",
+        name: "D8",
+        doc: "Symmetry under all 8 transformations.\n\nRequires the world to be square and have no diagonal width.",
+    },
+];
+
+/// The variants of [`Transform`](crate::Transform).
+pub const TRANSFORM: &[VariantDoc] = &[
+    VariantDoc {
+        variant: "Id",
+        name: "Id",
+        doc: "Identity transformation.",
+    },
+    VariantDoc {
+        variant: "Rotate90",
+        name: "R90",
+        doc: "90° rotation counterclockwise.\n\nRequires the world to be square and have no diagonal width.",
+    },
+    VariantDoc {
+        variant: "Rotate180",
+        name: "R180",
+        doc: "180° rotation counterclockwise.",
+    },
+    VariantDoc {
+        variant: "Rotate270",
+        name: "R270",
+        doc: "270° rotation counterclockwise.\n\nRequires the world to be square and have no diagonal width.",
+    },
+    VariantDoc {
+        variant: "FlipRow",
+        name: "F-",
+        doc: "Reflection across the middle row.\n\nRequires the world to have no diagonal width.",
+    },
+    VariantDoc {
+        variant: "FlipCol",
+        name: "F|",
+        doc: "Reflection across the middle column.\n\nRequires the world to have no diagonal width.",
+    },
+    VariantDoc {
+        variant: "FlipDiag",
+        name: "F\\",
+        doc: "Reflection across the diagonal.\n\nRequires the world to be square.",
+    },
+    VariantDoc {
+        variant: "FlipAntidiag",
+        name: "F/",
+        doc: "Reflection across the antidiagonal.\n\nRequires the world to be square.",
+    },
+];
+
+/// The variants of [`SearchOrder`](crate::SearchOrder).
+pub const SEARCH_ORDER: &[VariantDoc] = &[
+    VariantDoc {
+        variant: "RowFirst",
+        name: "RowFirst",
+        doc: "Searches all cells of one row before going to the next row.\n\n```plaintext\n123\n456\n789\n```",
+    },
+    VariantDoc {
+        variant: "ColumnFirst",
+        name: "ColumnFirst",
+        doc: "Searches all cells of one column before going to the next column.\n\n```plaintext\n147\n258\n369\n```",
+    },
+    VariantDoc {
+        variant: "Diagonal",
+        name: "Diagonal",
+        doc: "Diagonal.\n\n```plaintext\n136\n258\n479\n```\n\nThis search order requires the world to be square.",
+    },
+    VariantDoc {
+        variant: "FromVec",
+        name: "FromVec",
+        doc: "Specify the search order by a vector of coordinates.\n\nThis vector should cover every cell in the search range,\nand should not have any duplication, otherwise rlifesrc\nwould give a wrong result.\n\nWhen the world has a nontrivial symmetry or some\nfrozen cells, it suffices to cover one cell\nof each set of cells linked by them, e.g., the cells in a\nfundamental domain.\nIn this case, the coverage is checked when the world is created.",
+    },
+];
+
+/// The variants of [`NewState`](crate::NewState).
+pub const NEW_STATE: &[VariantDoc] = &[
+    VariantDoc {
+        variant: "ChooseDead",
+        name: "ChooseDead",
+        doc: "Chooses the background state.\n\nFor rules without `B0`, it always chooses `DEAD`.\n\nFor rules with `B0`, the background changes periodically.\nFor example, for non-Generations rules,\nit chooses `DEAD` on even generations,\n`ALIVE` on odd generations.",
+    },
+    VariantDoc {
+        variant: "ChooseAlive",
+        name: "ChooseAlive",
+        doc: "Chooses the opposite of the background state.\n\nFor rules without `B0`, it always chooses `ALIVE`.\n\nFor rules with `B0`, the background changes periodically.\nFor example, for non-Generations rules,\nit chooses `ALIVE` on even generations,\n`DEAD` on odd generations.",
+    },
+    VariantDoc {
+        variant: "Random",
+        name: "Random",
+        doc: "Random.\n\nFor non-Generations rules,\nthe probability of either state is `1/2`.\n\nFor Generations rules with `n` states,\nthe probability of each state is `1/n`.\n\nFor non-Generations rules, this is the same as\n`RandomBiased` with `alive_probability: 0.5`.",
+    },
+    VariantDoc {
+        variant: "RandomBiased",
+        name: "RandomBiased",
+        doc: "Random, choosing `ALIVE` with the given probability.\n\nFor Generations rules, the remaining probability is distributed\nuniformly over the other states, i.e., `DEAD` and the dying states.\n\nThe probability must be strictly between `0` and `1`.",
+    },
+];
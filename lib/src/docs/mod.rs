@@ -0,0 +1,42 @@
+//! Documentation of the choices of some options in the [configuration](crate::Config),
+//! for the help texts of user interfaces.
+//!
+//! The texts are the doc comments of the variants of [`Symmetry`], [`Transform`],
+//! [`SearchOrder`] and [`NewState`], with the links to other items removed.
+//! They are extracted by `cargo xtask docs`, so that they never drift
+//! from the documentation of the library.
+
+#[cfg(doc)]
+use crate::{NewState, SearchOrder, Symmetry, Transform};
+
+mod generated;
+
+pub use generated::{NEW_STATE, SEARCH_ORDER, SYMMETRY, TRANSFORM};
+
+/// The documentation of a variant of an enum.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct VariantDoc {
+    /// The name of the variant in the code, e.g., `D2Row`.
+    pub variant: &'static str,
+
+    /// The name of the variant shown to users, e.g., `D2-`.
+    ///
+    /// This is the same as [`variant`](Self::variant) if the
+    /// doc comment does not start with another name.
+    pub name: &'static str,
+
+    /// The doc comment, in Markdown, without the name.
+    pub doc: &'static str,
+}
+
+impl VariantDoc {
+    /// The first paragraph of the doc comment.
+    pub fn summary(&self) -> &'static str {
+        self.doc.split("\n\n").next().unwrap_or_default()
+    }
+}
+
+/// Finds the documentation of a variant by its name in the code.
+pub fn find(docs: &'static [VariantDoc], variant: &str) -> Option<&'static VariantDoc> {
+    docs.iter().find(|doc| doc.variant == variant)
+}
@@ -1,6 +1,9 @@
 //! All kinds of errors in this crate.
 
-use crate::cells::{Coord, State};
+use crate::{
+    cells::{Coord, State},
+    config::{AlgorithmChoice, Line, PhaseMask, Symmetry, Transform},
+};
 use ca_rules::ParseRuleError;
 use displaydoc::Display;
 use thiserror::Error;
@@ -20,6 +23,101 @@ pub enum Error {
     DiagonalWidthError,
     /// Width / height / period should be positive.
     NonPositiveError,
+    /// Generations rules with {0} states are not supported. The maximum is 256.
+    TooManyStatesError(usize),
     /// Cell at {0:?} has invalid state: {1:?}.
     InvalidState(Coord, State),
+    /// Known cell at {0:?} is outside the world.
+    KnownCellOutOfBounds(Coord),
+    /// Known cells at {0:?} have different states.
+    ConflictingKnownCells(Coord),
+    /// The containment box should not be empty.
+    ContainmentBoxError,
+    /// Known cell at {0:?} is outside the containment box.
+    KnownCellOutsideContainmentBox(Coord),
+    /// Cell at {0:?} appears more than once in the search order.
+    DuplicateSearchOrderCell(Coord),
+    /// Cell at {0:?} is neither in the search order nor linked to a cell in it by the symmetry.
+    SearchOrderIncompleteForSymmetry(Coord),
+    /// Transformations {0:?} do not form a group.
+    NonGroupError(Vec<Transform>),
+    /// The number of phase symmetries should equal the period.
+    PhaseSymmetriesLengthError,
+    /// Symmetry {0} of generation 0 does not contain symmetry {1} inherited from the last generation.
+    PhaseSymmetryError(Symmetry, Symmetry),
+    /// The probability of choosing a living cell should be strictly between 0 and 1.
+    ProbabilityError,
+    /// Rules with `B0` are not supported in the analysis.
+    B0AnalysisError,
+    /// Transformation {0:?} repeated {1} times is not the identity.
+    RepeatError(Transform, i32),
+    /// The new size of the world should not be smaller than the old size.
+    ShrinkError,
+    /// The known cells can only be moved to the center of an enlarged world
+    /// by an even number of cells, because of the symmetry or transformation.
+    EnlargeSymmetryError,
+    /// Algorithm {0:?} does not support {1}.
+    UnsupportedAlgorithm(AlgorithmChoice, &'static str),
+    /// The number of population parities should equal the period.
+    PopulationParityLengthError,
+    /// {0:?} of generation {1} is outside the world.
+    LineOutOfBounds(Line, i32),
+    /// Invalid spec: {0}.
+    SpecError(String),
+    /// For this rule with `B0`, the background repeats every {0} generations, so the period should be a multiple of {0}.
+    B0PeriodError(usize),
+    /// Phase mask {0:?} is not inside the world.
+    PhaseMaskOutOfBounds(PhaseMask),
+    /// Phase mask {0:?} applies to no generation.
+    EmptyPhaseMask(PhaseMask),
+    /// The number of row live counts should equal the height.
+    RowLiveCountsLengthError,
+    /// Row {0} cannot have {1} living cells, which is more than the width.
+    RowLiveCountTooLarge(i32, u32),
+    /// Invalid RLE: {0}
+    RleError(String),
+    /// Invalid comment in RLE: {0:?}.
+    RleCommentError(String),
+    /// Cell at {0:?} of the RLE is moved to {1:?}, which is outside the world.
+    RleCellOutOfBounds(Coord, Coord),
+    /// The object does not move with velocity {0:?} in this rule.
+    FixedObjectVelocityError((i32, i32, i32)),
+    /// The velocity {0:?} of the object does not match the period and the translation of the world.
+    FixedObjectWorldError((i32, i32, i32)),
+    /// Setting cell at {0:?} to state {1:?} leads to a conflict.
+    AssignmentConflict(Coord, State),
+    /// Invalid resume token: {0}.
+    ResumeTokenError(String),
+    /// The resume token diverges from the search at decision {0}.
+    ResumeTokenDiverged(usize),
+    /// Invalid speed: {0}.
+    SpeedError(String),
+    /// The torus size {0:?} is not the same as the size of the world.
+    TorusSizeError((i32, i32)),
+    /// A torus cannot have a diagonal width or a skew.
+    TorusDiagonalWidthError,
+    /// Symmetry or transformation requires the world to have no skew.
+    SkewError,
+    /// Generation {0} of the reduce target is out of bounds.
+    ReduceTargetOutOfBounds(i32),
+    /// Invalid von Neumann rule: {0}.
+    ParseNeumannRuleError(String),
+    /// Invalid hexagonal rule: {0}.
+    ParseHexRuleError(String),
+}
+
+#[cfg(feature = "read-rle")]
+impl From<ca_formats::rle::Error> for Error {
+    fn from(e: ca_formats::rle::Error) -> Self {
+        Self::RleError(e.to_string())
+    }
+}
+
+/// Errors when displaying a pattern in a [text format](crate::format).
+#[derive(Clone, Debug, PartialEq, Eq, Display, Error)]
+pub enum FormatError {
+    /// A formatter named {0:?} is already registered.
+    DuplicateFormatter(&'static str),
+    /// {0}
+    Custom(String),
 }
@@ -0,0 +1,464 @@
+//! Displaying the world as text.
+//!
+//! Each text format is a [`PatternFormatter`]. The built-in formats are
+//! [`Rle`], [`AnnotatedRle`], [`Plaintext`] and [`Python`]. Other crates can
+//! [`register`] their own formats, and user interfaces can list all the formats
+//! with [`formatters`] and look them up with [`by_name`].
+//!
+//! The methods of [`World`] and [`WorldSnapshot`] such as
+//! [`rle_gen`](World::rle_gen) and [`plaintext_gen`](World::plaintext_gen)
+//! are kept for compatibility, and give the same outputs as the built-in formatters.
+
+use crate::{
+    cells::{Coord, State, ALIVE, DEAD},
+    config::Config,
+    error::FormatError,
+    snapshot::WorldSnapshot,
+};
+use std::{
+    fmt::Write,
+    sync::{PoisonError, RwLock},
+};
+
+#[cfg(doc)]
+use crate::world::World;
+
+/// Appends a living or dying state of a Generations rule in Golly's extended
+/// RLE format: `A` to `X` for states 1 to 24, `pA` to `pX` for states 25 to 48,
+/// `qA` to `qX` for states 49 to 72, and so on, up to `yO` for state 255.
+fn push_gen_state(str: &mut String, i: usize) {
+    let i = i - 1;
+    if i >= 24 {
+        str.push((b'p' + (i / 24 - 1) as u8) as char);
+    }
+    str.push((b'A' + (i % 24) as u8) as char);
+}
+
+/// Displays the world as text.
+///
+/// Implemented for both [`World`] and [`WorldSnapshot`],
+/// so that they are displayed in exactly the same way.
+/// See the methods of the same names on [`World`] for the formats.
+pub(crate) trait Format {
+    /// World configuration.
+    fn config(&self) -> &Config;
+
+    /// Whether the rule is a Generations rule.
+    fn is_gen_rule(&self) -> bool;
+
+    /// Gets the state of a cell.
+    fn get_cell_state(&self, coord: Coord) -> Option<State>;
+
+    /// The background state of some generation.
+    fn background(&self, t: i32) -> State;
+
+    /// A note saying that the background of some generation is not dead,
+    /// which only happens when the rule contains `B0`.
+    ///
+    /// Without this note, such a generation is displayed as a confusing
+    /// solid block of living or dying cells.
+    fn background_note(&self, t: i32) -> Option<String> {
+        match self.background(t) {
+            DEAD => None,
+            ALIVE => Some(format!(
+                "Generation {}: the background is alive, as the rule contains B0.",
+                t
+            )),
+            State(i) => Some(format!(
+                "Generation {}: the background is in state {}, as the rule contains B0.",
+                t, i
+            )),
+        }
+    }
+
+    /// Calls `f` on each cell of the world in some generation, with its state,
+    /// in row-major order. See [`World::iter_cells`].
+    fn for_each_cell(&self, t: i32, f: &mut dyn FnMut(Coord, Option<State>)) {
+        for y in 0..self.config().height {
+            for x in 0..self.config().width {
+                f((x, y, t), self.get_cell_state((x, y, t)));
+            }
+        }
+    }
+
+    /// Appends a state in the format of [`rle_gen`](World::rle_gen).
+    fn push_state(&self, str: &mut String, state: Option<State>) {
+        match state {
+            Some(DEAD) => str.push('.'),
+            Some(ALIVE) => {
+                if self.is_gen_rule() {
+                    str.push('A');
+                } else {
+                    str.push('o');
+                }
+            }
+            Some(State(i)) => push_gen_state(str, i as usize),
+            _ => str.push('?'),
+        };
+    }
+
+    /// Displays the whole world in some generation.
+    fn rle_gen(&self, t: i32) -> String {
+        let (width, height) = (self.config().width, self.config().height);
+        let mut str = String::new();
+        if let Some(note) = self.background_note(t) {
+            writeln!(str, "#C {}", note).unwrap();
+        }
+        writeln!(
+            str,
+            "x = {}, y = {}, rule = {}",
+            width,
+            height,
+            self.config().rule_string_canonical()
+        )
+        .unwrap();
+        self.for_each_cell(t, &mut |(x, y, _), state| {
+            self.push_state(&mut str, state);
+            if x == width - 1 {
+                str.push(if y == height - 1 { '!' } else { '$' });
+                str.push('\n');
+            }
+        });
+        str
+    }
+
+    /// Number of known living cells in some generation.
+    fn cell_count_gen(&self, t: i32) -> u32;
+
+    /// Displays the whole world in some generation, with the cell at `(x, y)`
+    /// showing the state of the cell at `(x + sx, y + sy)`.
+    fn rle_gen_shifted(&self, t: i32, sx: i32, sy: i32) -> String {
+        let (width, height) = (self.config().width, self.config().height);
+        self.rle_rect(t, (sx, sy), (width, height))
+    }
+
+    /// Displays a `width` by `height` rectangle of some generation,
+    /// whose upper left corner is at `(x0, y0)`.
+    fn rle_rect(&self, t: i32, (x0, y0): (i32, i32), (width, height): (i32, i32)) -> String {
+        let mut str = String::new();
+        if let Some(note) = self.background_note(t) {
+            writeln!(str, "#C {}", note).unwrap();
+        }
+        writeln!(
+            str,
+            "x = {}, y = {}, rule = {}",
+            width,
+            height,
+            self.config().rule_string_canonical()
+        )
+        .unwrap();
+        if height == 0 {
+            str.push_str("!\n");
+        }
+        for y in 0..height {
+            for x in 0..width {
+                self.push_state(&mut str, self.get_cell_state((x + x0, y + y0, t)));
+            }
+            if y == height - 1 {
+                str.push('!');
+            } else {
+                str.push('$');
+            };
+            str.push('\n');
+        }
+        str
+    }
+
+    /// The first generation with the minimum number of known living cells.
+    fn min_population_gen(&self) -> i32 {
+        (0..self.config().period)
+            .min_by_key(|&t| self.cell_count_gen(t))
+            .unwrap()
+    }
+
+    /// Displays all the generations in a period, starting from generation `offset`.
+    fn rle_gen_rotated(&self, offset: i32) -> Vec<String> {
+        let period = self.config().period;
+        let offset = offset.rem_euclid(period);
+        (offset..offset + period)
+            .map(|t| {
+                if t < period {
+                    self.rle_gen(t)
+                } else {
+                    self.rle_gen_shifted(t, -self.config().dx, -self.config().dy)
+                }
+            })
+            .collect()
+    }
+
+    /// Displays all the generations in a period in one string,
+    /// each preceded by a `#C gen` comment line.
+    fn rle_all(&self) -> String {
+        let mut str = String::new();
+        for t in 0..self.config().period {
+            writeln!(str, "#C gen {}", t).unwrap();
+            str.push_str(&self.rle_gen(t));
+        }
+        str
+    }
+
+    /// Displays the whole world in some generation,
+    /// preceded by `#C` comment lines describing the search parameters.
+    fn rle_gen_annotated(&self, t: i32) -> String {
+        let config = self.config();
+        let mut str = String::new();
+        writeln!(
+            str,
+            "#C width = {}, height = {}, period = {}",
+            config.width, config.height, config.period
+        )
+        .unwrap();
+        writeln!(str, "#C dx = {}, dy = {}", config.dx, config.dy).unwrap();
+        writeln!(
+            str,
+            "#C symmetry = {}, transform = {}",
+            config.symmetry, config.transform
+        )
+        .unwrap();
+        writeln!(str, "#C rule = {}", config.rule_string_canonical()).unwrap();
+        str.push_str(&self.rle_gen(t));
+        str
+    }
+
+    /// Displays the whole world in some generation in Plaintext format.
+    fn plaintext_gen(&self, t: i32) -> String {
+        let width = self.config().width;
+        let mut str = String::new();
+        if let Some(note) = self.background_note(t) {
+            writeln!(str, "!{}", note).unwrap();
+        }
+        writeln!(
+            str,
+            "!rule = {}, period = {}",
+            self.config().rule_string_canonical(),
+            self.config().period
+        )
+        .unwrap();
+        self.for_each_cell(t, &mut |(x, _, _), state| {
+            self.push_state(&mut str, state);
+            if x == width - 1 {
+                str.push('\n');
+            }
+        });
+        str
+    }
+
+    /// The smallest rectangle containing all the known non-dead cells in some generation.
+    fn bounding_box_gen(&self, t: i32) -> Option<(i32, i32, i32, i32)> {
+        let mut bounding_box: Option<(i32, i32, i32, i32)> = None;
+        for y in 0..self.config().height {
+            for x in 0..self.config().width {
+                if matches!(self.get_cell_state((x, y, t)), Some(state) if state != DEAD) {
+                    bounding_box = Some(match bounding_box {
+                        Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x), y1.max(y)),
+                        None => (x, y, x, y),
+                    });
+                }
+            }
+        }
+        bounding_box
+    }
+
+    /// The smallest rectangle containing all the known non-dead cells in all generations,
+    /// as the upper left corner and the size.
+    ///
+    /// The size is `(0, 0)` if there is no such cell.
+    fn frames_box(&self) -> ((i32, i32), (i32, i32)) {
+        (0..self.config().period)
+            .filter_map(|t| self.bounding_box_gen(t))
+            .reduce(|(x0, y0, x1, y1), (u0, v0, u1, v1)| {
+                (x0.min(u0), y0.min(v0), x1.max(u1), y1.max(v1))
+            })
+            .map_or(((0, 0), (0, 0)), |(x0, y0, x1, y1)| {
+                ((x0, y0), (x1 - x0 + 1, y1 - y0 + 1))
+            })
+    }
+
+    /// Displays all the generations in a period,
+    /// trimmed to the same rectangle.
+    fn frames(&self) -> Vec<String> {
+        let (corner, size) = self.frames_box();
+        (0..self.config().period)
+            .map(|t| self.rle_rect(t, corner, size))
+            .collect()
+    }
+
+    /// The states of the cells in all the generations in a period,
+    /// trimmed to the same rectangle.
+    fn frames_grid(&self) -> Vec<Vec<Vec<State>>> {
+        let ((x0, y0), (width, height)) = self.frames_box();
+        (0..self.config().period)
+            .map(|t| {
+                (y0..y0 + height)
+                    .map(|y| {
+                        (x0..x0 + width)
+                            .map(|x| self.get_cell_state((x, y, t)).unwrap_or(DEAD))
+                            .collect()
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Displays the living cells in some generation as a Golly cell list.
+    fn python_gen(&self, t: i32) -> String {
+        let mut list = Vec::new();
+        for y in 0..self.config().height {
+            for x in 0..self.config().width {
+                if let Some(State(i)) = self.get_cell_state((x, y, t)) {
+                    if i != 0 {
+                        list.push(x as usize);
+                        list.push(y as usize);
+                        if self.is_gen_rule() {
+                            list.push(i as usize);
+                        }
+                    }
+                }
+            }
+        }
+        if self.is_gen_rule() && list.len() % 2 == 0 {
+            list.push(0);
+        }
+        let list = list.iter().map(usize::to_string).collect::<Vec<_>>();
+        match self.background_note(t) {
+            Some(note) => format!("[{}]  # {}", list.join(", "), note),
+            None => format!("[{}]", list.join(", ")),
+        }
+    }
+}
+
+/// A text format of patterns.
+///
+/// A formatter displays one generation of a [`WorldSnapshot`].
+/// Use [`register`] to make a new formatter available by [`by_name`].
+pub trait PatternFormatter: Send + Sync {
+    /// The name of the format, which is used to look up the formatter.
+    ///
+    /// It should be short, in lower case, and unique among all formatters.
+    fn name(&self) -> &'static str;
+
+    /// The usual extension of files in this format, without the leading dot.
+    fn extension(&self) -> &'static str {
+        "txt"
+    }
+
+    /// Displays some generation of the world.
+    fn format(&self, snapshot: &WorldSnapshot, gen: i32) -> Result<String, FormatError>;
+}
+
+/// The [RLE](https://conwaylife.com/wiki/Rle) format, with some differences.
+///
+/// See [`World::rle_gen`] for details.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Rle;
+
+impl PatternFormatter for Rle {
+    fn name(&self) -> &'static str {
+        "rle"
+    }
+
+    fn extension(&self) -> &'static str {
+        "rle"
+    }
+
+    fn format(&self, snapshot: &WorldSnapshot, gen: i32) -> Result<String, FormatError> {
+        Ok(Format::rle_gen(snapshot, gen))
+    }
+}
+
+/// The RLE format, preceded by `#C` comment lines describing the search parameters.
+///
+/// See [`World::rle_gen_annotated`] for details.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct AnnotatedRle;
+
+impl PatternFormatter for AnnotatedRle {
+    fn name(&self) -> &'static str {
+        "rle-annotated"
+    }
+
+    fn extension(&self) -> &'static str {
+        "rle"
+    }
+
+    fn format(&self, snapshot: &WorldSnapshot, gen: i32) -> Result<String, FormatError> {
+        Ok(Format::rle_gen_annotated(snapshot, gen))
+    }
+}
+
+/// The [Plaintext](https://conwaylife.com/wiki/Plaintext) format.
+///
+/// See [`World::plaintext_gen`] for details.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Plaintext;
+
+impl PatternFormatter for Plaintext {
+    fn name(&self) -> &'static str {
+        "plaintext"
+    }
+
+    fn extension(&self) -> &'static str {
+        "cells"
+    }
+
+    fn format(&self, snapshot: &WorldSnapshot, gen: i32) -> Result<String, FormatError> {
+        Ok(Format::plaintext_gen(snapshot, gen))
+    }
+}
+
+/// A Golly cell list, which can be used in Python scripts.
+///
+/// See [`World::python_gen`] for details.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Python;
+
+impl PatternFormatter for Python {
+    fn name(&self) -> &'static str {
+        "python"
+    }
+
+    fn extension(&self) -> &'static str {
+        "py"
+    }
+
+    fn format(&self, snapshot: &WorldSnapshot, gen: i32) -> Result<String, FormatError> {
+        Ok(Format::python_gen(snapshot, gen))
+    }
+}
+
+/// The built-in formatters.
+static BUILTIN: [&dyn PatternFormatter; 4] = [&Rle, &AnnotatedRle, &Plaintext, &Python];
+
+/// The formatters added by [`register`].
+static REGISTERED: RwLock<Vec<&'static dyn PatternFormatter>> = RwLock::new(Vec::new());
+
+/// All the available formatters, the built-in ones first,
+/// and then the registered ones in the order of registration.
+pub fn formatters() -> Vec<&'static dyn PatternFormatter> {
+    let registered = REGISTERED.read().unwrap_or_else(PoisonError::into_inner);
+    BUILTIN.iter().chain(registered.iter()).copied().collect()
+}
+
+/// Finds the formatter with the given name.
+pub fn by_name(name: &str) -> Option<&'static dyn PatternFormatter> {
+    formatters()
+        .into_iter()
+        .find(|formatter| formatter.name() == name)
+}
+
+/// Makes a new formatter available by [`by_name`] and [`formatters`].
+///
+/// Returns an error if there is already a formatter with the same name.
+pub fn register(formatter: &'static dyn PatternFormatter) -> Result<(), FormatError> {
+    let mut registered = REGISTERED.write().unwrap_or_else(PoisonError::into_inner);
+    let name = formatter.name();
+    if BUILTIN
+        .iter()
+        .chain(registered.iter())
+        .any(|formatter| formatter.name() == name)
+    {
+        return Err(FormatError::DuplicateFormatter(name));
+    }
+    registered.push(formatter);
+    Ok(())
+}
@@ -49,21 +49,38 @@ o..o.oo...o..oo.$
 
 #![cfg_attr(any(docs_rs, github_io), feature(doc_cfg))]
 
+#[cfg(feature = "analysis")]
+#[cfg_attr(any(docs_rs, github_io), doc(cfg(feature = "analysis")))]
+pub mod analysis;
 mod cells;
 mod config;
+pub mod docs;
 mod error;
+pub mod format;
 mod poly_world;
+mod probe;
+mod resume;
 pub mod rules;
 pub mod search;
+mod snapshot;
+mod split;
+mod sweep;
 mod world;
 
 #[cfg(feature = "serde")]
 #[cfg_attr(any(docs_rs, github_io), doc(cfg(feature = "serde")))]
 pub mod save;
 
-pub use cells::{Coord, State, ALIVE, DEAD};
-pub use config::{Config, KnownCell, NewState, SearchOrder, Symmetry, Transform};
-pub use error::Error;
+pub use cells::{Coord, State, StateRepr, ALIVE, DEAD};
+pub use config::{
+    AlgorithmChoice, Config, KnownCell, Line, LineParity, NewState, PhaseMask, PhaseMaskMode,
+    ReduceTarget, RetreatStrategy, SearchOrder, Symmetry, Transform,
+};
+pub use error::{Error, FormatError};
 pub use poly_world::PolyWorld;
+pub use probe::{ProbeReport, ProbeRun};
 pub use search::Status;
-pub use world::World;
+pub use snapshot::{ResultInfo, Solution, WorldSnapshot};
+pub use split::WorkUnit;
+pub use sweep::RuleOutcome;
+pub use world::{Anchor, ResultFilter, World};
@@ -3,14 +3,18 @@
 use crate::{
     cells::{Coord, State},
     config::Config,
-    rules::{Life, LifeGen, NtLife, NtLifeGen},
-    search::{Backjump, LifeSrc, Status},
-    world::World,
+    error::Error,
+    probe::ProbeRun,
+    rules::{Hex, Life, LifeGen, Neumann, NtLife, NtLifeGen},
+    search::{Backjump, CacheStats, LifeSrc, SearchStats, Status},
+    snapshot::{ResultInfo, Solution, WorldSnapshot},
+    world::{Anchor, ResultFilter, World},
 };
 use from_variants::FromVariants;
+use std::{array, iter, ops::ControlFlow, time::Duration};
 
 #[cfg(feature = "serde")]
-use crate::{error::Error, save::WorldSer};
+use crate::save::WorldSer;
 
 #[cfg(doc)]
 use crate::cells::ALIVE;
@@ -27,10 +31,18 @@ pub enum PolyWorld {
     NtLife(World<NtLife, LifeSrc>),
     /// A [`World`] with [`NtLifeGen`] rule and [`LifeSrc`] algorithm.
     NtLifeGen(World<NtLifeGen, LifeSrc>),
+    /// A [`World`] with [`Neumann`] rule and [`LifeSrc`] algorithm.
+    Neumann(World<Neumann, LifeSrc>),
+    /// A [`World`] with [`Hex`] rule and [`LifeSrc`] algorithm.
+    Hex(World<Hex, LifeSrc>),
     /// A [`World`] with [`Life`] rule and [`Backjump`] algorithm.
     LifeBackjump(World<Life, Backjump<Life>>),
     /// A [`World`] with [`NtLife`] rule and [`Backjump`] algorithm.
     NtLifeBackjump(World<NtLife, Backjump<NtLife>>),
+    /// A [`World`] with [`Neumann`] rule and [`Backjump`] algorithm.
+    NeumannBackjump(World<Neumann, Backjump<Neumann>>),
+    /// A [`World`] with [`Hex`] rule and [`Backjump`] algorithm.
+    HexBackjump(World<Hex, Backjump<Hex>>),
 }
 
 macro_rules! dispatch {
@@ -40,8 +52,12 @@ macro_rules! dispatch {
             PolyWorld::LifeGen($world) => $action,
             PolyWorld::NtLife($world) => $action,
             PolyWorld::NtLifeGen($world) => $action,
+            PolyWorld::Neumann($world) => $action,
+            PolyWorld::Hex($world) => $action,
             PolyWorld::LifeBackjump($world) => $action,
             PolyWorld::NtLifeBackjump($world) => $action,
+            PolyWorld::NeumannBackjump($world) => $action,
+            PolyWorld::HexBackjump($world) => $action,
         }
     };
 }
@@ -53,17 +69,249 @@ impl PolyWorld {
     /// [`Status::None`] if such pattern does not exist,
     /// [`Status::Searching`] if the number of steps exceeds `max_step`
     /// and no results are found.
+    ///
+    /// The trivial result, where every cell has the background state,
+    /// is never reported as found, unless
+    /// [`allow_trivial`](Config#structfield.allow_trivial) is set.
     #[inline]
     pub fn search(&mut self, max_step: Option<u64>) -> Status {
         dispatch!(self, world => world.search(max_step))
     }
 
+    /// Searches until a result is found, the search space is exhausted,
+    /// or the given time has passed.
+    ///
+    /// See [`World::search_timeout`].
+    #[inline]
+    pub fn search_timeout(&mut self, timeout: Duration) -> Status {
+        dispatch!(self, world => world.search_timeout(timeout))
+    }
+
+    /// Searches until a result is found, the search space is exhausted,
+    /// or the callback asks to stop.
+    ///
+    /// See [`World::search_with`].
+    #[inline]
+    pub fn search_with(&mut self, f: impl FnMut(&SearchStats) -> ControlFlow<()>) -> Status {
+        dispatch!(self, world => world.search_with(f))
+    }
+
+    /// An iterator of the results, together with some statistics about each result.
+    ///
+    /// See [`World::results_with_stats`].
+    pub fn results_with_stats(&mut self) -> impl Iterator<Item = ResultInfo> + '_ {
+        let (mut steps, mut conflicts) = (self.steps(), self.conflicts());
+        let mut border_conflicts = self.border_conflict_summary();
+        iter::from_fn(move || {
+            if self.search(None) != Status::Found {
+                return None;
+            }
+            let summary = self.border_conflict_summary();
+            let info = ResultInfo {
+                rle: self.rle_gen(0),
+                steps: self.steps() - steps,
+                conflicts: self.conflicts() - conflicts,
+                border_conflicts: array::from_fn(|i| summary[i] - border_conflicts[i]),
+                population: self.cell_count(),
+                bounding_box: self.bounding_box_gen(0),
+            };
+            steps = self.steps();
+            conflicts = self.conflicts();
+            border_conflicts = summary;
+            Some(info)
+        })
+    }
+
+    /// An iterator of the results.
+    ///
+    /// See [`World::solutions`].
+    ///
+    /// # Examples
+    ///
+    /// Counts the still lifes in a 4×4 box. As usual, the search only looks for
+    /// the results with a living cell in the first row, and skips some of their
+    /// mirror images, so the same still life may still be found at several positions:
+    ///
+    /// ```rust
+    /// use rlifesrc_lib::{Config, Error};
+    ///
+    /// let mut world = Config::new(4, 4, 1).world()?;
+    /// assert_eq!(world.solutions().count(), 54);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn solutions(&mut self) -> impl Iterator<Item = Solution> + '_ {
+        iter::from_fn(move || {
+            (self.search(None) == Status::Found).then(|| dispatch!(self, world => world.solution()))
+        })
+        .fuse()
+    }
+
+    /// Whether another call of [`search`](Self::search) can make progress.
+    ///
+    /// Returns `false` when the search space is exhausted, i.e., when the last
+    /// search returned [`Status::None`], or when a contradiction was found before
+    /// the search started. In this case, [`search`](Self::search) would
+    /// return [`Status::None`] immediately.
+    ///
+    /// Otherwise, e.g., after [`Status::Searching`] or [`Status::Found`],
+    /// the search can be resumed to look for the next result. Note that
+    /// this does not guarantee that there is another result.
+    #[inline]
+    pub fn can_continue(&self) -> bool {
+        dispatch!(self, world => world.can_continue())
+    }
+
+    /// Restarts the search from the beginning, without rebuilding the world.
+    ///
+    /// See [`World::reset`].
+    #[inline]
+    pub fn reset(&mut self) {
+        dispatch!(self, world => world.reset())
+    }
+
+    /// A hint explaining why the search returned [`Status::None`].
+    ///
+    /// Returns `None` if the search [can continue](Self::can_continue).
+    /// This is only a heuristic. See [`World::failure_hint`] for details.
+    #[inline]
+    pub fn failure_hint(&self) -> Option<String> {
+        dispatch!(self, world => world.failure_hint())
+    }
+
+    /// Recomputes the neighborhood descriptor of every cell from scratch,
+    /// and asserts that it matches the one maintained during the search.
+    ///
+    /// Only available in debug builds, or with the `debug-invariants` feature.
+    ///
+    /// # Panics
+    ///
+    /// Panics if some descriptor does not match.
+    #[cfg(any(debug_assertions, feature = "debug-invariants"))]
+    #[inline]
+    pub fn verify_descriptors(&self) {
+        dispatch!(self, world => world.verify_descriptors())
+    }
+
+    /// Asserts that the internal data of the world are consistent.
+    ///
+    /// See [`World::assert_consistent`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if some check fails.
+    #[cfg(feature = "debug-invariants")]
+    #[cfg_attr(any(docs_rs, github_io), doc(cfg(feature = "debug-invariants")))]
+    #[inline]
+    pub fn assert_consistent(&self) {
+        dispatch!(self, world => world.assert_consistent())
+    }
+
+    /// Asserts that the current result satisfies the configuration.
+    ///
+    /// In debug builds, this also calls [`verify_descriptors`](Self::verify_descriptors).
+    /// See [`World::assert_result_valid`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if some constraint is not satisfied.
+    #[inline]
+    pub fn assert_result_valid(&self) {
+        dispatch!(self, world => {
+            #[cfg(debug_assertions)]
+            world.verify_descriptors();
+            world.assert_result_valid();
+        })
+    }
+
+    /// Coordinates of the defects of the current result.
+    ///
+    /// Each defect is a cell in the last generation whose successor does not obey
+    /// the rule. See [`Config::max_defects`](Config#structfield.max_defects).
+    ///
+    /// If this is not empty, the result is not a solution.
+    #[inline]
+    pub fn defects(&self) -> Vec<Coord> {
+        dispatch!(self, world => world.defects())
+    }
+
+    /// Statistics of the transposition cache.
+    ///
+    /// Returns `None` if the cache is not enabled.
+    /// See [`Config::transposition_cache`](Config#structfield.transposition_cache).
+    #[inline]
+    pub fn cache_stats(&self) -> Option<CacheStats> {
+        dispatch!(self, world => world.cache_stats())
+    }
+
     /// Gets the state of a cell. Returns `Err(())` if there is no such cell.
     #[inline]
     pub fn get_cell_state(&self, coord: Coord) -> Option<State> {
         dispatch!(self, world => world.get_cell_state(coord))
     }
 
+    /// The coordinates of the cell of the next decision.
+    ///
+    /// See [`World::next_decision`].
+    #[inline]
+    pub(crate) fn next_decision(&self) -> Option<Coord> {
+        dispatch!(self, world => world.next_decision())
+    }
+
+    /// Iterates over the cells of the world in some generation,
+    /// together with their states, in row-major order.
+    ///
+    /// See [`World::iter_cells`].
+    #[inline]
+    pub fn iter_cells(&self, t: i32) -> Box<dyn Iterator<Item = (Coord, Option<State>)> + '_> {
+        dispatch!(self, world => Box::new(world.iter_cells(t)))
+    }
+
+    /// Calls `f` on each cell of the world in some generation, with its state,
+    /// in row-major order.
+    ///
+    /// See [`World::for_each_cell`].
+    #[inline]
+    pub fn for_each_cell(&self, t: i32, f: impl FnMut(Coord, Option<State>)) {
+        dispatch!(self, world => world.for_each_cell(t, f))
+    }
+
+    /// The states which are still possible for a cell,
+    /// judging from the neighborhood descriptors and the rule.
+    ///
+    /// See [`World::candidate_states`].
+    #[inline]
+    pub fn candidate_states(&self, coord: Coord) -> Vec<State> {
+        dispatch!(self, world => world.candidate_states(coord))
+    }
+
+    /// The cells that would become known if the cell at `coord` were set to `state`,
+    /// without actually setting it.
+    ///
+    /// See [`World::probe_assignment`].
+    #[inline]
+    pub fn probe_assignment(
+        &mut self,
+        coord: Coord,
+        state: State,
+    ) -> Result<Vec<(Coord, State)>, Error> {
+        dispatch!(self, world => world.probe_assignment(coord, state))
+    }
+
+    /// A short string recording the current position of the search,
+    /// so that the search can be resumed later with [`Config::world_at_token`].
+    ///
+    /// See [`World::resume_token`].
+    #[inline]
+    pub fn resume_token(&self) -> Option<String> {
+        dispatch!(self, world => world.resume_token())
+    }
+
+    /// See [`World::resume`].
+    #[inline]
+    pub(crate) fn resume(&mut self, token: &str) -> Result<(), Error> {
+        dispatch!(self, world => world.resume(token))
+    }
+
     /// World configuration.
     #[inline]
     pub const fn config(&self) -> &Config {
@@ -93,20 +341,122 @@ impl PolyWorld {
         dispatch!(self, world => world.cell_count_gen(t))
     }
 
-    /// Minimum number of known living cells in all generation.
+    /// Minimum number of known living cells in all generations.
     ///
     /// For Generations rules, dying cells are not counted.
+    ///
+    /// With the default [`reduce_target`](Config#structfield.reduce_target),
+    /// this is the number bounded by [`max_cell_count`](Config#structfield.max_cell_count).
     #[inline]
     pub fn cell_count(&self) -> u32 {
         dispatch!(self, world => world.cell_count())
     }
 
+    /// Whether there is no known living cell in generation 0.
+    ///
+    /// After a result is found, this means that the result is the trivial one,
+    /// where every cell has the background state, which is only reported when
+    /// [`allow_trivial`](Config#structfield.allow_trivial) is set.
+    #[inline]
+    pub fn is_trivial(&self) -> bool {
+        dispatch!(self, world => world.is_trivial())
+    }
+
+    /// Number of known living cells in each generation.
+    ///
+    /// For Generations rules, dying cells are not counted.
+    #[inline]
+    pub fn populations(&self) -> Vec<u32> {
+        dispatch!(self, world => world.populations())
+    }
+
+    /// Number of known living cells and number of unknown cells
+    /// in each row of some generation.
+    #[inline]
+    pub fn row_summary(&self, t: i32) -> Vec<(u32, u32)> {
+        dispatch!(self, world => world.row_summary(t))
+    }
+
+    /// Coordinates of the next unknown cell to be decided in the search.
+    ///
+    /// Returns `None` if all the cells are known.
+    #[inline]
+    pub fn frontier_coord(&self) -> Option<Coord> {
+        dispatch!(self, world => world.frontier_coord())
+    }
+
+    /// A rough estimate of the size of the search space, in bits.
+    ///
+    /// This is the number of unknown cells, times `log2` of the number of states.
+    /// Cells that are symmetric to each other are only counted once.
+    #[inline]
+    pub fn search_space_bits(&self) -> f64 {
+        dispatch!(self, world => world.search_space_bits())
+    }
+
     /// Number of conflicts during the search.
     #[inline]
     pub const fn conflicts(&self) -> u64 {
         dispatch!(self, world => world.conflicts())
     }
 
+    /// Statistics of the search so far.
+    ///
+    /// See [`World::stats`].
+    #[inline]
+    pub fn stats(&self) -> SearchStats {
+        dispatch!(self, world => world.stats())
+    }
+
+    /// Number of conflicts found when consistifying a cell on or next to
+    /// each edge of the world, in the order top, bottom, left, right.
+    ///
+    /// See [`World::border_conflict_summary`].
+    #[inline]
+    pub const fn border_conflict_summary(&self) -> [u64; 4] {
+        dispatch!(self, world => world.border_conflict_summary())
+    }
+
+    /// Number of conflicts because a row of generation 0 has too many
+    /// or too few living cells.
+    ///
+    /// See [`World::row_count_conflicts`].
+    #[inline]
+    pub const fn row_count_conflicts(&self) -> [u64; 2] {
+        dispatch!(self, world => world.row_count_conflicts())
+    }
+
+    /// Number of steps walked during the search.
+    ///
+    /// This is the total number of steps over all calls of [`search`](Self::search).
+    /// See [`World::steps`] for the definition of a step.
+    #[inline]
+    pub const fn steps(&self) -> u64 {
+        dispatch!(self, world => world.steps())
+    }
+
+    /// Sets the numbers of steps and conflicts.
+    #[inline]
+    pub(crate) fn set_stats(&mut self, steps: u64, conflicts: u64) {
+        dispatch!(self, world => {
+            world.steps = steps;
+            world.conflicts = conflicts;
+        })
+    }
+
+    /// Creates a new world of a larger size, with the same configuration.
+    ///
+    /// See [`World::enlarge`].
+    #[inline]
+    pub fn enlarge(
+        &self,
+        new_width: i32,
+        new_height: i32,
+        anchor: Anchor,
+    ) -> Result<PolyWorld, Error> {
+        dispatch!(self, world => world.enlarge(new_width, new_height, anchor))
+    }
+
     /// Set the max cell counts.
     ///
     /// Currently this is the only parameter that you can change
@@ -116,6 +466,28 @@ impl PolyWorld {
         dispatch!(self, world => world.set_max_cell_count(max_cell_count));
     }
 
+    /// Sets a filter of results.
+    ///
+    /// See [`World::set_result_filter`].
+    #[inline]
+    pub fn set_result_filter(&mut self, result_filter: Option<ResultFilter>) {
+        dispatch!(self, world => world.set_result_filter(result_filter));
+    }
+
+    /// Uses a random number generator with the given seed to choose random states.
+    ///
+    /// See [`World::set_seed`].
+    #[inline]
+    pub fn set_seed(&mut self, seed: u64) {
+        dispatch!(self, world => world.set_seed(seed));
+    }
+
+    /// Searches for about `budget_steps` steps, and records how deep it goes.
+    #[inline]
+    pub(crate) fn probe_run(&mut self, budget_steps: u64) -> ProbeRun {
+        dispatch!(self, world => world.probe_run(budget_steps))
+    }
+
     /// Displays the whole world in some generation,
     /// in a mix of [Plaintext](https://conwaylife.com/wiki/Plaintext) and
     /// [RLE](https://conwaylife.com/wiki/Rle) format.
@@ -123,7 +495,8 @@ impl PolyWorld {
     /// * **Dead** cells are represented by `.`;
     /// * **Living** cells are represented by `o` for rules with 2 states,
     ///   `A` for rules with more states;
-    /// * **Dying** cells are represented by uppercase letters starting from `B`;
+    /// * **Dying** cells are represented by uppercase letters starting from `B`,
+    ///   and by `pA`, `pB`, ... for states above 24, as in Golly;
     /// * **Unknown** cells are represented by `?`;
     /// * Each line is ended with `$`;
     /// * The whole pattern is ended with `!`.
@@ -132,19 +505,152 @@ impl PolyWorld {
         dispatch!(self, world => world.rle_gen(t))
     }
 
+    /// The first generation with the minimum number of known living cells.
+    ///
+    /// For Generations rules, dying cells are not counted.
+    #[inline]
+    pub fn min_population_gen(&self) -> i32 {
+        dispatch!(self, world => world.min_population_gen())
+    }
+
+    /// Displays all the generations in a period, starting from generation `offset`,
+    /// in the same format as [`rle_gen`](Self::rle_gen).
+    ///
+    /// Generations after the end of the period are obtained by applying the
+    /// [`transform`](Config#structfield.transform) to the corresponding generations
+    /// at the beginning of the period, and then shifting them back by `(dx, dy)`,
+    /// so that they stay inside the world. Thus each generation evolves into the next
+    /// one, except that there is a translation of `(dx, dy)` across the period boundary.
+    #[inline]
+    pub fn rle_gen_rotated(&self, offset: i32) -> Vec<String> {
+        dispatch!(self, world => world.rle_gen_rotated(offset))
+    }
+
+    /// Displays all the generations in a period, starting from the generation
+    /// with the minimum number of living cells.
+    ///
+    /// See [`min_population_gen`](Self::min_population_gen)
+    /// and [`rle_gen_rotated`](Self::rle_gen_rotated).
+    #[inline]
+    pub fn rle_all_gens_min_phase(&self) -> Vec<String> {
+        dispatch!(self, world => world.rle_all_gens_min_phase())
+    }
+
+    /// Displays all the generations in a period in one string, from generation 0
+    /// to generation `period - 1`, in the same format as [`rle_gen`](Self::rle_gen).
+    ///
+    /// Each generation `t` is a separate RLE, preceded by a `#C gen t`
+    /// comment line.
+    #[inline]
+    pub fn rle_all(&self) -> String {
+        dispatch!(self, world => world.rle_all())
+    }
+
+    /// The velocity of the result, as `(dx, dy, period)`.
+    ///
+    /// Here `period` is the fundamental period of the result,
+    /// and `(dx, dy)` is the translation in a fundamental period.
+    #[inline]
+    pub fn velocity(&self) -> (i32, i32, i32) {
+        dispatch!(self, world => world.velocity())
+    }
+
+    /// Displays the whole world in generation 0, preceded by a `#C` comment line
+    /// with the velocity of the result.
+    #[inline]
+    pub fn rle_with_velocity(&self) -> String {
+        dispatch!(self, world => world.rle_with_velocity())
+    }
+
+    /// Displays the whole world in some generation in the same format as
+    /// [`rle_gen`](Self::rle_gen), preceded by `#C` comment lines describing
+    /// the search parameters.
+    #[inline]
+    pub fn rle_gen_annotated(&self, t: i32) -> String {
+        dispatch!(self, world => world.rle_gen_annotated(t))
+    }
+
     /// Displays the whole world in some generation in
     /// [Plaintext](https://conwaylife.com/wiki/Plaintext) format.
     ///
-    /// Do not use this for Generations rules.
+    /// The pattern is preceded by a `!` comment line with the rule and the period,
+    /// e.g., `!rule = B3/S23, period = 3`. Each row of the world is a line,
+    /// without run-length encoding. The cells are represented by the same
+    /// characters as in [`rle_gen`](Self::rle_gen):
     ///
     /// * **Dead** cells are represented by `.`;
-    /// * **Living** and **Dying** cells are represented by `o`;
+    /// * **Living** cells are represented by `o` for rules with 2 states,
+    ///   `A` for rules with more states;
+    /// * **Dying** cells are represented by uppercase letters starting from `B`;
     /// * **Unknown** cells are represented by `?`.
+    ///
+    /// So each row is the same as the corresponding line of `rle_gen`
+    /// without the ending `$` or `!`, and the cells line up, unless the rule
+    /// has more than 25 states, where the later dying states take two characters.
     #[inline]
     pub fn plaintext_gen(&self, t: i32) -> String {
         dispatch!(self, world => world.plaintext_gen(t))
     }
 
+    /// The smallest rectangle containing all the known non-dead cells in some generation,
+    /// as `(x0, y0, x1, y1)`, where `(x0, y0)` is the upper left corner,
+    /// and `(x1, y1)` is the lower right corner.
+    ///
+    /// Returns `None` if there is no such cell.
+    #[inline]
+    pub fn bounding_box_gen(&self, t: i32) -> Option<(i32, i32, i32, i32)> {
+        dispatch!(self, world => world.bounding_box_gen(t))
+    }
+
+    /// Displays the living cells in some generation as a cell list
+    /// for Golly's Python API, which can be passed to `g.putcells`.
+    ///
+    /// For rules with 2 states, it is a flat list `[x0, y0, x1, y1, ...]`.
+    ///
+    /// For Generations rules, it is a list of triples `[x0, y0, state0, ...]`
+    /// which also includes the dying cells,
+    /// padded with a `0` when the length would otherwise be even,
+    /// as Golly requires for multi-state cell lists.
+    ///
+    /// Unknown cells are omitted.
+    #[inline]
+    pub fn python_gen(&self, t: i32) -> String {
+        dispatch!(self, world => world.python_gen(t))
+    }
+
+    /// Displays all the generations in a period as a sequence of frames,
+    /// which can be overlaid or played as an animation.
+    ///
+    /// Each frame is in the format of [`rle_gen`](Self::rle_gen),
+    /// but trimmed to the smallest rectangle containing all the known
+    /// non-dead cells in __all__ generations, rather than in each generation.
+    /// So all the frames have the same size, and a cell at the same position
+    /// in different frames is the same cell of the world.
+    ///
+    /// If there is no such cell, each frame is an empty pattern with
+    /// `x = 0, y = 0`.
+    #[inline]
+    pub fn frames(&self) -> Vec<String> {
+        dispatch!(self, world => world.frames())
+    }
+
+    /// The states of the cells in all the generations in a period,
+    /// trimmed to the same rectangle as [`frames`](Self::frames).
+    ///
+    /// It is indexed as `grid[t][y][x]`, where `(x, y)` is relative to the
+    /// upper left corner of the rectangle. Unknown cells are treated as dead.
+    #[inline]
+    pub fn frames_grid(&self) -> Vec<Vec<Vec<State>>> {
+        dispatch!(self, world => world.frames_grid())
+    }
+
+    /// Takes a snapshot of the world, which owns all its data,
+    /// and can be sent to another thread.
+    #[inline]
+    pub fn snapshot(&self) -> WorldSnapshot {
+        dispatch!(self, world => world.snapshot())
+    }
+
     /// Saves the world as a [`WorldSer`],
     /// which can be easily serialized.
     #[cfg(feature = "serde")]
@@ -154,6 +660,17 @@ impl PolyWorld {
         dispatch!(self, world => world.ser())
     }
 
+    /// Saves the world as a compact [`WorldSer`],
+    /// which leaves out the cells that can be deduced again.
+    ///
+    /// See [`World::ser_compact`].
+    #[cfg(feature = "serde")]
+    #[cfg_attr(any(docs_rs, github_io), doc(cfg(feature = "serde")))]
+    #[inline]
+    pub fn ser_compact(&self) -> WorldSer {
+        dispatch!(self, world => world.ser_compact())
+    }
+
     /// Restores the world from the [`WorldSer`].
     #[cfg(feature = "serde")]
     #[cfg_attr(any(docs_rs, github_io), doc(cfg(feature = "serde")))]
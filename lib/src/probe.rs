@@ -0,0 +1,160 @@
+//! Estimating the difficulty of a configuration by short random searches.
+
+use crate::{
+    config::{Config, NewState},
+    error::Error,
+    rules::Rule,
+    search::{Algorithm, Status},
+    world::World,
+};
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The outcome of one probe of [`Config::probe`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ProbeRun {
+    /// The largest fraction of the unknown cells that were known
+    /// at the same time during the probe, between `0` and `1`.
+    ///
+    /// It is `1` if a result is found.
+    pub depth: f64,
+
+    /// Number of steps walked.
+    pub steps: u64,
+
+    /// Number of conflicts met.
+    pub conflicts: u64,
+
+    /// The status at the end of the probe.
+    ///
+    /// [`Status::Searching`] means that the budget was used up.
+    pub status: Status,
+}
+
+impl ProbeRun {
+    /// Number of conflicts per step.
+    pub fn conflict_rate(&self) -> f64 {
+        if self.steps == 0 {
+            0.0
+        } else {
+            self.conflicts as f64 / self.steps as f64
+        }
+    }
+
+    /// How close the probe came to settling the search, between `0` and `1`.
+    ///
+    /// A probe which finds a result or exhausts the search space settles it.
+    /// Otherwise the depth is reduced by the conflict rate, since a descent
+    /// which keeps backtracking is less likely to go on.
+    fn progress(&self) -> f64 {
+        if self.status == Status::Searching {
+            self.depth * (1.0 - self.conflict_rate().min(1.0))
+        } else {
+            1.0
+        }
+    }
+}
+
+/// The report of [`Config::probe`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ProbeReport {
+    /// The outcomes of the probes.
+    pub runs: Vec<ProbeRun>,
+
+    /// The mean [depth](ProbeRun::depth) of the probes.
+    pub mean_depth: f64,
+
+    /// Number of conflicts per step, over all the probes.
+    pub conflict_rate: f64,
+
+    /// The fraction of the probes which found a result.
+    pub success_fraction: f64,
+
+    /// A coarse difficulty score between `0` and `1`. Higher is harder.
+    ///
+    /// It is one minus the mean progress of the probes, where a probe which
+    /// finds a result or exhausts the search space has progress `1`,
+    /// and any other probe has its depth times one minus its conflict rate.
+    ///
+    /// It is only a heuristic, and is only comparable between reports
+    /// with the same budget and number of probes.
+    pub score: f64,
+}
+
+impl<R: Rule, A: Algorithm<R>> World<R, A> {
+    /// Searches for about `budget_steps` steps, and records how deep it goes.
+    pub(crate) fn probe_run(&mut self, budget_steps: u64) -> ProbeRun {
+        let (steps, conflicts) = (self.steps, self.conflicts);
+        let unknown = self.unknown_count.iter().sum::<u32>();
+        let mut max_known = 0;
+        let mut status = Status::Searching;
+        while self.steps - steps < budget_steps {
+            status = self.search(Some(0));
+            max_known = max_known.max(self.set_stack.len());
+            if status != Status::Searching {
+                break;
+            }
+        }
+        let depth = if status == Status::Found || unknown == 0 {
+            1.0
+        } else {
+            (max_known as f64 / unknown as f64).min(1.0)
+        };
+        ProbeRun {
+            depth,
+            steps: self.steps - steps,
+            conflicts: self.conflicts - conflicts,
+            status,
+        }
+    }
+}
+
+impl Config {
+    /// Estimates how hard the search is, by `probes` independent random
+    /// searches of about `budget_steps` steps each.
+    ///
+    /// Each probe searches in a new world with [`NewState::Random`],
+    /// whatever the [`new_state`](#structfield.new_state) of this configuration is,
+    /// and with a random number generator [seeded](World::set_seed) from `seed`.
+    /// So the report only depends on the configuration and the arguments.
+    /// This configuration is not changed.
+    ///
+    /// A probe stops at the first decision after `budget_steps` steps,
+    /// so it might walk a few more steps when it is backtracking.
+    ///
+    /// Returns an error if the configuration is invalid.
+    pub fn probe(&self, budget_steps: u64, probes: u32, seed: u64) -> Result<ProbeReport, Error> {
+        let config = self.clone().set_new_state(NewState::Random);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let runs = (0..probes)
+            .map(|_| {
+                let mut world = config.world()?;
+                world.set_seed(rng.next_u64());
+                Ok(world.probe_run(budget_steps))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let count = runs.len().max(1) as f64;
+        let steps = runs.iter().map(|run| run.steps).sum::<u64>();
+        let conflicts = runs.iter().map(|run| run.conflicts).sum::<u64>();
+        let found = runs
+            .iter()
+            .filter(|run| run.status == Status::Found)
+            .count();
+        Ok(ProbeReport {
+            mean_depth: runs.iter().map(|run| run.depth).sum::<f64>() / count,
+            conflict_rate: if steps == 0 {
+                0.0
+            } else {
+                conflicts as f64 / steps as f64
+            },
+            success_fraction: found as f64 / count,
+            score: 1.0 - runs.iter().map(ProbeRun::progress).sum::<f64>() / count,
+            runs,
+        })
+    }
+}
@@ -0,0 +1,158 @@
+//! Resumption tokens, which record the position of a search in a few bytes.
+
+use crate::{
+    cells::{State, StateRepr},
+    config::{Config, NewState},
+    error::Error,
+    poly_world::PolyWorld,
+    rules::Rule,
+    search::{Algorithm, Reason},
+    world::World,
+};
+
+/// The alphabet of the URL-safe variant of base64.
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Encodes the bytes in URL-safe base64, without padding.
+fn encode(bytes: &[u8]) -> String {
+    let mut token = String::with_capacity((bytes.len() * 4).div_ceil(3));
+    for chunk in bytes.chunks(3) {
+        let bits = chunk
+            .iter()
+            .enumerate()
+            .fold(0, |bits, (i, &byte)| bits | u32::from(byte) << (16 - 8 * i));
+        for i in 0..=chunk.len() {
+            token.push(BASE64[(bits >> (18 - 6 * i) & 0x3f) as usize] as char);
+        }
+    }
+    token
+}
+
+/// Decodes a string in URL-safe base64, without padding.
+fn decode(token: &str) -> Result<Vec<u8>, Error> {
+    let invalid = || Error::ResumeTokenError(String::from("not in URL-safe base64"));
+    let digits = token
+        .bytes()
+        .map(|c| BASE64.iter().position(|&d| d == c).ok_or_else(invalid))
+        .collect::<Result<Vec<_>, _>>()?;
+    if digits.len() % 4 == 1 {
+        return Err(invalid());
+    }
+    let mut bytes = Vec::with_capacity(digits.len() * 3 / 4);
+    for chunk in digits.chunks(4) {
+        let bits = chunk
+            .iter()
+            .enumerate()
+            .fold(0, |bits, (i, &digit)| bits | (digit as u32) << (18 - 6 * i));
+        for i in 0..chunk.len() - 1 {
+            bytes.push((bits >> (16 - 8 * i)) as u8);
+        }
+    }
+    Ok(bytes)
+}
+
+impl<R: Rule, A: Algorithm<R>> World<R, A> {
+    /// A short string recording the current position of the search,
+    /// so that the search can be resumed later with
+    /// [`Config::world_at_token`], e.g., in another session.
+    ///
+    /// The search is deterministic, so the position is given by the
+    /// decisions on the current branch: for each of them, the chosen state
+    /// and the number of other states that are still to be tried.
+    /// The token encodes them as two bytes each in URL-safe base64.
+    /// This is much smaller than a saved world,
+    /// but restoring it takes a replay of these decisions.
+    ///
+    /// A token is only valid for the identical configuration and the same
+    /// version of this library.
+    ///
+    /// Returns `None` if the search [cannot continue](Self::can_continue),
+    /// or if the [new state](Config#structfield.new_state) is random,
+    /// in which case the rest of the search is not determined by the position.
+    pub fn resume_token(&self) -> Option<String> {
+        if matches!(
+            self.config.new_state,
+            NewState::Random | NewState::RandomBiased { .. }
+        ) || !self.can_continue()
+        {
+            return None;
+        }
+        let gen = self.rule.gen();
+        let mut bytes = Vec::new();
+        for set in &self.set_stack {
+            if let Some(alternatives) = set.reason.alternatives(gen) {
+                bytes.push(set.cell.state.get().unwrap().0 as u8);
+                bytes.push(alternatives as u8);
+            }
+        }
+        Some(encode(&bytes))
+    }
+
+    /// Replays the decisions recorded in a [resume token](Self::resume_token)
+    /// in a new world.
+    ///
+    /// Every decision is made on the same cell as the search would choose,
+    /// after deducing the consequences of the previous ones. Returns
+    /// [`Error::ResumeTokenDiverged`] if there is a conflict before a decision,
+    /// or if there is no cell left to decide.
+    pub(crate) fn resume(&mut self, token: &str) -> Result<(), Error> {
+        let bytes = decode(token)?;
+        if bytes.len() % 2 != 0 {
+            return Err(Error::ResumeTokenError(String::from("odd number of bytes")));
+        }
+        let gen = self.rule.gen();
+        for (i, decision) in bytes.chunks_exact(2).enumerate() {
+            let (state, alternatives) = (State(decision[0] as StateRepr), decision[1] as usize);
+            if state.0 as usize >= gen || alternatives >= gen {
+                return Err(Error::ResumeTokenError(format!("invalid decision {}", i)));
+            }
+            if self.proceed().is_err() {
+                return Err(Error::ResumeTokenDiverged(i));
+            }
+            let cell = self.get_unknown().ok_or(Error::ResumeTokenDiverged(i))?;
+            self.next_unknown = cell.next;
+            let reason = A::Reason::from_alternatives(alternatives, gen);
+            if self.set_cell(cell, state, reason).is_err() {
+                return Err(Error::ResumeTokenDiverged(i));
+            }
+        }
+
+        // The search may have stopped after the last decision,
+        // before deducing its consequences.
+        if self.proceed().is_ok() {
+            if self.get_unknown().is_none() && self.config.reduce_max {
+                // The token was taken at a result.
+                self.config.max_cell_count = Some(self.reduced_max_cell_count());
+            }
+        } else {
+            self.conflicts += 1;
+            self.retreat();
+        }
+        Ok(())
+    }
+}
+
+impl Config {
+    /// Creates a new world from the configuration, and resumes the search
+    /// at the position recorded in a [resume token](World::resume_token).
+    ///
+    /// Searching in the returned world finds the results that the original
+    /// search would find after the position of the token, in the same order.
+    /// For example, if the token was taken right after a result is found,
+    /// the search goes on with the next result.
+    ///
+    /// The token must come from a world with the identical configuration,
+    /// and the same version of this library. When
+    /// [`reduce_max`](Config#structfield.reduce_max) is set, the maximal
+    /// number of living cells is only restored if the token was taken
+    /// right after a result is found.
+    ///
+    /// Returns [`Error::ResumeTokenDiverged`] with the index of the first
+    /// decision that cannot be replayed, which happens when the token comes
+    /// from another configuration.
+    pub fn world_at_token(&self, token: &str) -> Result<PolyWorld, Error> {
+        let mut world = self.world()?;
+        world.resume(token)?;
+        Ok(world)
+    }
+}
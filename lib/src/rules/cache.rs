@@ -0,0 +1,467 @@
+//! A cache of rules, so that worlds with the same rule share the same tables.
+
+use crate::{
+    error::Error,
+    rules::{LifeGen, NtLifeGen, Rule},
+};
+use ca_rules::{ParseLife, ParseLifeGen, ParseNtLife, ParseNtLifeGen};
+use std::{
+    collections::VecDeque,
+    fmt::{self, Debug, Display, Formatter},
+    str::FromStr,
+    sync::{Mutex, OnceLock, PoisonError},
+};
+
+/// The canonical form of a rule string.
+///
+/// It consists of the sorted birth and survival conditions and the number
+/// of states, so different notations of the same rule, e.g., `B3/S23`,
+/// `b3/s23` and `23/3`, have the same canonical form.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct RuleKey {
+    /// Whether the rule is parsed as a totalistic rule.
+    totalistic: bool,
+    /// The birth conditions.
+    b: Vec<u8>,
+    /// The survival conditions.
+    s: Vec<u8>,
+    /// Number of states.
+    gen: usize,
+}
+
+impl RuleKey {
+    pub(super) fn new(totalistic: bool, mut b: Vec<u8>, mut s: Vec<u8>, gen: usize) -> Self {
+        b.sort_unstable();
+        b.dedup();
+        s.sort_unstable();
+        s.dedup();
+        Self {
+            totalistic,
+            b,
+            s,
+            gen,
+        }
+    }
+
+    /// Whether the rule has `B0` and `S8`, and is not a Generations rule.
+    pub(crate) fn is_b0s8(&self) -> bool {
+        let s8 = if self.totalistic { 8 } else { 0xff };
+        self.gen == 2 && self.b.contains(&0) && self.s.contains(&s8)
+    }
+
+    /// The rule where every cell has the opposite state.
+    ///
+    /// A dead cell with `n` living neighbors is born in the inverted rule,
+    /// if and only if a living cell with `n` dead neighbors dies in this rule,
+    /// and vice versa. So a `B0S8` rule becomes a rule without `B0` and `S8`.
+    ///
+    /// For non-totalistic rules, the neighborhoods are complemented instead.
+    ///
+    /// Only meaningful for non-Generations rules.
+    pub(crate) fn inverted(&self) -> Self {
+        let (max, invert): (u8, fn(u8) -> u8) = if self.totalistic {
+            (8, |n| 8 - n)
+        } else {
+            (0xff, |n| !n)
+        };
+        let b = (0..=max)
+            .filter(|n| !self.s.contains(n))
+            .map(invert)
+            .collect();
+        let s = (0..=max)
+            .filter(|n| !self.b.contains(n))
+            .map(invert)
+            .collect();
+        Self::new(self.totalistic, b, s, self.gen)
+    }
+
+    /// Constructs the rule.
+    ///
+    /// This is the slow part, which the cache avoids repeating.
+    pub(crate) fn build(&self) -> Result<CachedRule, Error> {
+        let rule = if self.totalistic {
+            CachedRule::Life(LifeGen::new(&self.b, &self.s, self.gen))
+        } else {
+            CachedRule::NtLife(ParseNtLifeGen::from_bsg(
+                self.b.clone(),
+                self.s.clone(),
+                self.gen,
+            ))
+        };
+        let has_b0_s8 = match &rule {
+            CachedRule::Life(rule) => rule.has_b0_s8(),
+            CachedRule::NtLife(rule) => rule.has_b0_s8(),
+        };
+        if has_b0_s8 {
+            Err(Error::B0S8Error)
+        } else {
+            Ok(rule)
+        }
+    }
+}
+
+/// The alphabet of base64, used by the MAP notation.
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+impl RuleKey {
+    /// The birth and survival conditions as numbers of living neighbors,
+    /// if the rule is totalistic.
+    ///
+    /// A non-totalistic rule is totalistic if, for each number of living neighbors,
+    /// either all or none of the neighborhoods with that number are included.
+    fn totalistic_bs(&self) -> Option<(Vec<u8>, Vec<u8>)> {
+        if self.totalistic {
+            return Some((self.b.clone(), self.s.clone()));
+        }
+        let counts = |set: &[u8]| {
+            (0..=8)
+                .filter_map(|n| {
+                    let mut nbhds = (0..=0xff).filter(|nbhd: &u8| nbhd.count_ones() == n);
+                    if nbhds.clone().all(|nbhd| set.contains(&nbhd)) {
+                        Some(Some(n as u8))
+                    } else if nbhds.any(|nbhd| set.contains(&nbhd)) {
+                        Some(None)
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Option<Vec<_>>>()
+        };
+        Some((counts(&self.b)?, counts(&self.s)?))
+    }
+
+    /// The rule in MAP notation, without the number of states.
+    ///
+    /// The 512 bits are indexed by the neighborhoods including the cell itself,
+    /// which is the fifth bit from the top.
+    fn map_string(&self) -> String {
+        let mut bits = [false; 512];
+        for (c, set) in [&self.b, &self.s].into_iter().enumerate() {
+            for &nbhd in set {
+                let nbhd = nbhd as usize;
+                bits[(nbhd >> 4) << 5 | c << 4 | (nbhd & 0xf)] = true;
+            }
+        }
+        let mut map = String::from("MAP");
+        for chunk in bits.chunks(6) {
+            let digit = chunk
+                .iter()
+                .enumerate()
+                .fold(0, |digit, (i, &bit)| digit | (bit as usize) << (5 - i));
+            map.push(BASE64[digit] as char);
+        }
+        map
+    }
+}
+
+/// The canonical rule string, which is accepted back by the parser.
+///
+/// Totalistic rules are written as `B3/S23`, and other rules in MAP notation.
+/// The number of states of a Generations rule follows, e.g., `B2/S/G3`.
+impl Display for RuleKey {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self.totalistic_bs() {
+            Some((b, s)) => {
+                f.write_str("B")?;
+                for n in b {
+                    write!(f, "{}", n)?;
+                }
+                f.write_str("/S")?;
+                for n in s {
+                    write!(f, "{}", n)?;
+                }
+            }
+            None => f.write_str(&self.map_string())?,
+        }
+        if self.gen > 2 {
+            write!(f, "/G{}", self.gen)?;
+        }
+        Ok(())
+    }
+}
+
+impl ParseLife for RuleKey {
+    fn from_bs(b: Vec<u8>, s: Vec<u8>) -> Self {
+        Self::new(true, b, s, 2)
+    }
+}
+
+impl ParseLifeGen for RuleKey {
+    fn from_bsg(b: Vec<u8>, s: Vec<u8>, gen: usize) -> Self {
+        Self::new(true, b, s, gen)
+    }
+}
+
+impl ParseNtLife for RuleKey {
+    fn from_bs(b: Vec<u8>, s: Vec<u8>) -> Self {
+        Self::new(false, b, s, 2)
+    }
+}
+
+impl ParseNtLifeGen for RuleKey {
+    fn from_bsg(b: Vec<u8>, s: Vec<u8>, gen: usize) -> Self {
+        Self::new(false, b, s, gen)
+    }
+}
+
+/// Tries the totalistic and non-totalistic formats listed in
+/// [`supported_formats`](super::supported_formats), in order.
+impl FromStr for RuleKey {
+    type Err = Error;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        <Self as ParseLife>::parse_rule(input)
+            .or_else(|_| <Self as ParseNtLife>::parse_rule(input))
+            .or_else(|_| <Self as ParseLifeGen>::parse_rule(input))
+            .or_else(|_| <Self as ParseNtLifeGen>::parse_rule(input))
+            .map_err(Error::ParseRuleError)
+    }
+}
+
+/// A rule constructed from a rule string.
+///
+/// Cloning it is cheap, because the table of the rule is shared.
+#[derive(Clone)]
+pub(crate) enum CachedRule {
+    /// A totalistic rule.
+    Life(LifeGen),
+    /// A non-totalistic rule.
+    NtLife(NtLifeGen),
+}
+
+/// A cache of constructed rules, keyed by the canonical forms of the rule strings.
+///
+/// Constructing a rule computes a table of the implications of all
+/// neighborhood descriptors. This takes noticeable time for non-totalistic rules,
+/// whose table has `1 << 20` entries. [`Config::world`](crate::Config::world)
+/// looks up the rule in the [global cache](Self::global) unless
+/// [`cache_rule`](crate::Config#structfield.cache_rule) is `false`,
+/// so that worlds with the same rule share the same table.
+///
+/// Parsing the rule string is still needed to find its canonical form,
+/// e.g., `B3/S23` and `b3/s23` share the same entry.
+///
+/// The cache keeps at most [`capacity`](Self::capacity) rules. When it is full,
+/// the least recently used rule is evicted. Worlds which already use an evicted
+/// rule keep its table until they are dropped.
+pub struct RuleCache {
+    /// The cached rules, from the least recently used to the most recently used.
+    rules: Mutex<VecDeque<(RuleKey, CachedRule)>>,
+    /// The maximal number of cached rules.
+    capacity: usize,
+}
+
+impl RuleCache {
+    /// The capacity of the [global cache](Self::global), and of [`RuleCache::new`].
+    ///
+    /// A non-totalistic rule takes 4 MiB, so the global cache takes at most 16 MiB.
+    pub const DEFAULT_CAPACITY: usize = 4;
+
+    /// Creates an empty cache with the [default capacity](Self::DEFAULT_CAPACITY).
+    pub fn new() -> Self {
+        Self::with_capacity(Self::DEFAULT_CAPACITY)
+    }
+
+    /// Creates an empty cache which keeps at most `capacity` rules.
+    ///
+    /// A cache with capacity `0` caches nothing.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            rules: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+        }
+    }
+
+    /// The global cache used by [`Config::world`](crate::Config::world).
+    pub fn global() -> &'static Self {
+        static GLOBAL: OnceLock<RuleCache> = OnceLock::new();
+        GLOBAL.get_or_init(Self::new)
+    }
+
+    /// The maximal number of cached rules.
+    pub const fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The number of cached rules.
+    pub fn len(&self) -> usize {
+        self.rules
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+
+    /// Whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes all cached rules.
+    ///
+    /// Worlds which are already created keep their rules.
+    pub fn clear(&self) {
+        self.rules
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+
+    /// Gets the rule of the canonical form, constructing and caching it if needed.
+    pub(crate) fn get(&self, key: RuleKey) -> Result<CachedRule, Error> {
+        let mut rules = self.rules.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(index) = rules.iter().position(|(cached, _)| *cached == key) {
+            let entry = rules.remove(index).unwrap();
+            let rule = entry.1.clone();
+            rules.push_back(entry);
+            return Ok(rule);
+        }
+        let rule = key.build()?;
+        if self.capacity > 0 {
+            if rules.len() >= self.capacity {
+                rules.pop_front();
+            }
+            rules.push_back((key, rule.clone()));
+        }
+        Ok(rule)
+    }
+}
+
+impl Default for RuleCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for RuleCache {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("RuleCache")
+            .field("len", &self.len())
+            .field("capacity", &self.capacity)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::Symmetry,
+        rules::{Life, NtLife},
+        Config, PolyWorld,
+    };
+
+    #[test]
+    fn test_rule_key() {
+        let key: RuleKey = "B3/S23".parse().unwrap();
+        assert_eq!(key, "b3/s23".parse().unwrap());
+        assert_eq!(key, "23/3".parse().unwrap());
+        assert_ne!(key, "B36/S23".parse().unwrap());
+        // The MAP string of the same rule is not parsed as a totalistic rule.
+        assert_ne!(
+            key,
+            "MAPARYXfhZofugWaH7oaIDogBZofuhogOiAaIDogIAAgAAWaH7oaIDogGiA6ICAAIAAaIDogIAAgACAAIAAAAAAAA"
+                .parse()
+                .unwrap()
+        );
+        assert!("B3/S23/Q".parse::<RuleKey>().is_err());
+
+        let key: RuleKey = "B0123478/S01234678".parse().unwrap();
+        assert!(key.is_b0s8());
+        assert_eq!(key.inverted(), "B3/S23".parse().unwrap());
+        assert_eq!(key.inverted().inverted(), key);
+        assert!(!key.inverted().is_b0s8());
+        let key: RuleKey = "MAPARYXfhZofugWaH7oaIDogBZofuhogOiAaIDogIAAgAAWaH7oaIDogGiA6ICAAIAAaIDogIAAgACAAIAAAAAAAA"
+            .parse()
+            .unwrap();
+        assert!(!key.is_b0s8());
+        assert!(key.inverted().is_b0s8());
+        assert_eq!(key.inverted().inverted(), key);
+    }
+
+    #[test]
+    fn test_canonical() {
+        let canonical = |rule_string: &str| rule_string.parse::<RuleKey>().unwrap().to_string();
+        assert_eq!(canonical("b3/s23"), "B3/S23");
+        assert_eq!(canonical("23/3"), "B3/S23");
+        assert_eq!(canonical("23/3/3"), "B3/S23/G3");
+        assert_eq!(canonical("B2/S/3"), "B2/S/G3");
+        assert_eq!(
+            canonical("MAPARYXfhZofugWaH7oaIDogBZofuhogOiAaIDogIAAgAAWaH7oaIDogGiA6ICAAIAAaIDogIAAgACAAIAAAAAAAA"),
+            "B3/S23"
+        );
+        assert_eq!(
+            "B36/S23".parse::<Life>().unwrap().rule_string_canonical(),
+            "B36/S23"
+        );
+        assert_eq!(
+            "23/3/3".parse::<LifeGen>().unwrap().rule_string_canonical(),
+            "B3/S23/G3"
+        );
+        assert_eq!(
+            "B36/S23".parse::<NtLife>().unwrap().rule_string_canonical(),
+            "B36/S23"
+        );
+        assert_eq!(
+            "B2/S/3"
+                .parse::<NtLifeGen>()
+                .unwrap()
+                .rule_string_canonical(),
+            "B2/S/G3"
+        );
+
+        // A rule which is not totalistic is written in MAP notation.
+        let key = RuleKey::new(false, vec![0b0000_0111, 0b1000_0001], vec![0b0000_0011], 2);
+        let map = key.to_string();
+        assert!(map.starts_with("MAP"));
+        assert_eq!(map.parse::<RuleKey>().unwrap(), key);
+        let rule = map.parse::<NtLife>().unwrap();
+        assert_eq!(rule.rule_string_canonical(), map);
+        assert_ne!(rule.symmetry(), Symmetry::D8);
+    }
+
+    #[test]
+    fn test_shared_table() {
+        let cache = RuleCache::new();
+        let get = |rule_string: &str| cache.get(rule_string.parse().unwrap()).unwrap();
+        let (rule0, rule1) = match (get("B3/S23"), get("b3/s23")) {
+            (CachedRule::Life(rule0), CachedRule::Life(rule1)) => (rule0, rule1),
+            _ => unreachable!(),
+        };
+        assert!(rule0.shares_table(&rule1));
+        assert_eq!(cache.len(), 1);
+        cache.clear();
+        assert!(cache.is_empty());
+
+        let cache = RuleCache::with_capacity(2);
+        let get = |rule_string: &str| match cache.get(rule_string.parse().unwrap()).unwrap() {
+            CachedRule::Life(rule) => rule,
+            _ => unreachable!(),
+        };
+        let life = get("B3/S23");
+        let highlife = get("B36/S23");
+        assert!(life.shares_table(&get("B3/S23")));
+        get("B2/S");
+        assert_eq!(cache.len(), 2);
+        // `B36/S23` is the least recently used rule, so it is evicted.
+        assert!(!highlife.shares_table(&get("B36/S23")));
+        assert!(!life.shares_table(&get("B3/S23")));
+        assert_eq!(cache.len(), 2);
+
+        let cache = RuleCache::with_capacity(0);
+        cache.get("B3/S23".parse().unwrap()).unwrap();
+        assert!(cache.is_empty());
+
+        let config = Config::new(8, 8, 1).set_rule_string("MAPARYXfhZofugWaH7oaIDogBZofuhogOiAaIDogIAAgAAWaH7oaIDogGiA6ICAAIAAaIDogIAAgACAAIAAAAAAAA");
+        let (world0, world1) = match (config.world().unwrap(), config.world().unwrap()) {
+            (PolyWorld::NtLife(world0), PolyWorld::NtLife(world1)) => (world0, world1),
+            _ => unreachable!(),
+        };
+        assert!(world0.rule.shares_table(&world1.rule));
+
+        let config = config.set_cache_rule(false);
+        let (world0, world1) = match (config.world().unwrap(), config.world().unwrap()) {
+            (PolyWorld::NtLife(world0), PolyWorld::NtLife(world1)) => (world0, world1),
+            _ => unreachable!(),
+        };
+        assert!(!world0.rule.shares_table(&world1.rule));
+    }
+}
@@ -0,0 +1,358 @@
+//! Consistifying a cell, shared by all the rules.
+//!
+//! Every rule precomputes a table which maps each neighborhood descriptor
+//! to some flags, saying what can be deduced from it. Only the layouts
+//! of the flags differ between the rules, so the deduction itself is
+//! written once here.
+
+use crate::{
+    cells::{CellRef, State, StateRepr, ALIVE, DEAD},
+    rules::Rule,
+    search::{Algorithm, Reason},
+    world::World,
+};
+
+/// The flags in the implication table of a rule.
+///
+/// The implications are those of a non-Generations rule, i.e., a neighbor
+/// which is not alive is considered dead.
+pub(super) trait Implication: Copy {
+    /// Whether nothing can be deduced.
+    fn implies_nothing(self) -> bool;
+
+    /// Whether a conflict is detected.
+    fn implies_conflict(self) -> bool;
+
+    /// The implied state of the successor, if any.
+    fn succ_implication(self) -> Option<State>;
+
+    /// The implied state of the cell itself, if any.
+    fn self_implication(self) -> Option<State>;
+
+    /// The implied states of the neighbors, in the order of
+    /// [`nbhd`](crate::cells::LifeCell#structfield.nbhd),
+    /// or `None` if no neighbor is implied.
+    ///
+    /// The implied states of known neighbors are ignored.
+    fn nbhd_implied_states(self) -> Option<[Option<State>; 8]>;
+}
+
+/// Sets the unknown neighbors of a cell to their implied states,
+/// keeping only those accepted by `filter`.
+#[inline]
+fn set_nbhd<R: Rule, A: Algorithm<R>>(
+    world: &mut World<R, A>,
+    cell: CellRef<R>,
+    states: [Option<State>; 8],
+    filter: impl Fn(State) -> bool,
+) -> Result<(), A::ConflReason> {
+    for (&neigh, state) in cell.nbhd.iter().zip(states) {
+        if let (Some(neigh), Some(state)) = (neigh, state) {
+            if filter(state) && neigh.state.get().is_none() {
+                world.set_cell(neigh, state, A::Reason::from_cell(cell))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Consistifies a cell for a non-Generations rule,
+/// given the flags of its neighborhood descriptor.
+#[inline]
+pub(super) fn consistify<R: Rule, A: Algorithm<R>, F: Implication>(
+    world: &mut World<R, A>,
+    cell: CellRef<R>,
+    flags: F,
+) -> Result<(), A::ConflReason> {
+    if flags.implies_nothing() {
+        return Ok(());
+    }
+
+    if flags.implies_conflict() {
+        return Err(A::confl_from_cell(cell));
+    }
+
+    if let Some(state) = flags.succ_implication() {
+        return match cell.succ {
+            Some(succ) => world.set_cell(succ, state, A::Reason::from_cell(cell)),
+            None => Ok(()),
+        };
+    }
+
+    if let Some(state) = flags.self_implication() {
+        world.set_cell(cell, state, A::Reason::from_cell(cell))?;
+    }
+
+    if let Some(states) = flags.nbhd_implied_states() {
+        set_nbhd(world, cell, states, |_| true)?;
+    }
+
+    Ok(())
+}
+
+/// Consistifies a cell for a Generations rule,
+/// given the flags of its neighborhood descriptor,
+/// and the state of its successor.
+///
+/// The flags only know whether a cell is alive, so the dying states
+/// are handled here. With two states there is no dying state,
+/// and the rule is the same as its non-Generations counterpart.
+#[inline]
+pub(super) fn consistify_gen<R: Rule, A: Algorithm<R>, F: Implication>(
+    world: &mut World<R, A>,
+    cell: CellRef<R>,
+    flags: F,
+    succ_state: Option<State>,
+) -> Result<(), A::ConflReason> {
+    let gen = world.rule.gen() as StateRepr;
+    if gen == 2 {
+        return consistify(world, cell, flags);
+    }
+
+    match cell.state.get() {
+        Some(DEAD) => {
+            if let Some(State(j)) = succ_state {
+                if j >= 2 {
+                    return Err(A::confl_from_cell(cell));
+                }
+            }
+            if let Some(state) = flags.succ_implication() {
+                return match cell.succ {
+                    Some(succ) => world.set_cell(succ, state, A::Reason::from_cell(cell)),
+                    None => Ok(()),
+                };
+            }
+        }
+        Some(ALIVE) => {
+            if let Some(State(j)) = succ_state {
+                if j == 0 || j > 2 {
+                    return Err(A::confl_from_cell(cell));
+                }
+            }
+            if let Some(state) = flags.succ_implication() {
+                // A living cell which dies goes to the first dying state.
+                let state = if state == DEAD { State(2) } else { ALIVE };
+                return match cell.succ {
+                    Some(succ) => world.set_cell(succ, state, A::Reason::from_cell(cell)),
+                    None => Ok(()),
+                };
+            }
+        }
+        Some(State(i)) => {
+            return match (succ_state, cell.succ) {
+                (Some(State(j)), _) if j == (i + 1) % gen => Ok(()),
+                (Some(_), _) => Err(A::confl_from_cell(cell)),
+                (None, Some(succ)) => {
+                    world.set_cell(succ, State((i + 1) % gen), A::Reason::from_cell(cell))
+                }
+                (None, None) => Ok(()),
+            };
+        }
+        None => match succ_state {
+            Some(DEAD) => {
+                // Either the cell is dead and not born,
+                // or it is in the last dying state.
+                return if flags.self_implication() == Some(ALIVE) {
+                    world.set_cell(cell, State(gen - 1), A::Reason::from_cell(cell))
+                } else {
+                    Ok(())
+                };
+            }
+            Some(ALIVE) => {
+                if let Some(state) = flags.self_implication() {
+                    world.set_cell(cell, state, A::Reason::from_cell(cell))?;
+                }
+            }
+            Some(State(j)) => {
+                return world.set_cell(cell, State(j - 1), A::Reason::from_cell(cell));
+            }
+            None => return Ok(()),
+        },
+    }
+
+    if flags.implies_nothing() {
+        return Ok(());
+    }
+
+    if flags.implies_conflict() {
+        return Err(A::confl_from_cell(cell));
+    }
+
+    // A neighbor which is not alive may be in a dying state,
+    // so only the living neighbors are implied.
+    if let Some(states) = flags.nbhd_implied_states() {
+        set_nbhd(world, cell, states, |state| state == ALIVE)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        cells::{Coord, State, ALIVE, DEAD},
+        config::{Config, KnownCell},
+        rules::{typebool::False, Hex, Life, LifeGen, Neumann, NtLife, NtLifeGen, Rule},
+        search::{Algorithm, Status},
+        world::World,
+    };
+    use ca_rules::ParseNtLife;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    const RULES: [&str; 5] = ["B3/S23", "B36/S23", "B2/S", "B34/S34", "B3678/S34678"];
+
+    /// Generates a small configuration with some random known cells.
+    fn random_config(rng: &mut StdRng) -> Config {
+        let (width, height, period) = (
+            rng.gen_range(3..=6),
+            rng.gen_range(3..=6),
+            rng.gen_range(1..=3),
+        );
+        let mut known_cells = Vec::new();
+        for x in 0..width {
+            for y in 0..height {
+                for t in 0..period {
+                    if rng.gen_bool(0.15) {
+                        let state = if rng.gen() { ALIVE } else { DEAD };
+                        known_cells.push(KnownCell {
+                            coord: (x, y, t),
+                            state,
+                        });
+                    }
+                }
+            }
+        }
+        Config::new(width, height, period)
+            .set_translate(rng.gen_range(-1..=1), rng.gen_range(-1..=1))
+            .set_known_cells(known_cells)
+    }
+
+    /// The cells in the set stack, with their states.
+    fn set_stack<R: Rule, A: Algorithm<R>>(world: &World<R, A>) -> Vec<(Coord, State)> {
+        world
+            .set_stack
+            .iter()
+            .map(|set| (set.cell.coord, set.cell.state.get().unwrap()))
+            .collect()
+    }
+
+    /// Searches in two worlds side by side, a few steps at a time,
+    /// and checks that they make the same deductions in the same order.
+    fn assert_same_deductions<R0, A0, R1, A1>(mut world0: World<R0, A0>, mut world1: World<R1, A1>)
+    where
+        R0: Rule,
+        A0: Algorithm<R0>,
+        R1: Rule,
+        A1: Algorithm<R1>,
+    {
+        assert_eq!(set_stack(&world0), set_stack(&world1));
+        for _ in 0..16 {
+            let status = world0.search(Some(32));
+            assert_eq!(status, world1.search(Some(32)));
+            assert_eq!(set_stack(&world0), set_stack(&world1));
+            assert_eq!(world0.conflicts, world1.conflicts);
+            if status == Status::None {
+                break;
+            }
+        }
+    }
+
+    #[test]
+    fn test_gen_matches_non_gen() {
+        for rule_string in RULES {
+            let gen_string = format!("{}/C2", rule_string);
+            let (life, life_gen): (Life, LifeGen) =
+                (rule_string.parse().unwrap(), gen_string.parse().unwrap());
+            let (ntlife, ntlife_gen): (NtLife, NtLifeGen) =
+                (rule_string.parse().unwrap(), gen_string.parse().unwrap());
+            assert_eq!((life_gen.gen(), ntlife_gen.gen()), (2, 2));
+
+            let mut rng = StdRng::seed_from_u64(0);
+            for _ in 0..32 {
+                let config = random_config(&mut rng);
+                assert_same_deductions(
+                    World::new_lifesrc(&config, life.clone()),
+                    World::new_lifesrc(&config, life_gen.clone()),
+                );
+                assert_same_deductions(
+                    World::new_lifesrc(&config, ntlife.clone()),
+                    World::new_lifesrc(&config, ntlife_gen.clone()),
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_ntlife_matches_life() {
+        for rule_string in RULES {
+            let (life, ntlife): (Life, NtLife) =
+                (rule_string.parse().unwrap(), rule_string.parse().unwrap());
+
+            let mut rng = StdRng::seed_from_u64(1);
+            for _ in 0..32 {
+                let config = random_config(&mut rng);
+                assert_same_deductions(
+                    World::new_lifesrc(&config, life.clone()),
+                    World::new_lifesrc(&config, ntlife.clone()),
+                );
+                assert_same_deductions(
+                    World::new_backjump(&config, life.clone()),
+                    World::new_backjump(&config, ntlife.clone()),
+                );
+            }
+        }
+    }
+
+    /// The totalistic rule in the Moore neighborhood which only counts
+    /// the neighbors in the given bits of the MAP notation.
+    fn masked_ntlife(b: &[u8], s: &[u8], mask: u8) -> NtLife {
+        let masked = |set: &[u8]| {
+            (0..=0xff)
+                .filter(|nbhd: &u8| set.contains(&((nbhd & mask).count_ones() as u8)))
+                .collect()
+        };
+        ParseNtLife::from_bs(masked(b), masked(s))
+    }
+
+    /// Checks that a rule makes the same deductions as a non-totalistic rule.
+    fn assert_matches_ntlife<R: Rule<IsGen = False> + Clone>(rule: R, ntlife: NtLife, seed: u64) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        for _ in 0..32 {
+            let config = random_config(&mut rng);
+            assert_same_deductions(
+                World::new_lifesrc(&config, rule.clone()),
+                World::new_lifesrc(&config, ntlife.clone()),
+            );
+            assert_same_deductions(
+                World::new_backjump(&config, rule.clone()),
+                World::new_backjump(&config, ntlife.clone()),
+            );
+        }
+    }
+
+    #[test]
+    fn test_neumann_matches_ntlife() {
+        for (b, s) in [
+            (&[2][..], &[0, 1, 3][..]),
+            (&[1, 3], &[]),
+            (&[2, 3], &[2, 4]),
+        ] {
+            // N, W, E and S.
+            let ntlife = masked_ntlife(b, s, 0b_0101_1010);
+            assert_matches_ntlife(Neumann::new(b, s), ntlife, 2);
+        }
+    }
+
+    #[test]
+    fn test_hex_matches_ntlife() {
+        for (b, s) in [
+            (&[2][..], &[3, 4][..]),
+            (&[2, 4, 5], &[]),
+            (&[3], &[2, 3, 6]),
+        ] {
+            // All but NE and SW.
+            let ntlife = masked_ntlife(b, s, 0b_1101_1011);
+            assert_matches_ntlife(Hex::new(b, s), ntlife, 3);
+        }
+    }
+}
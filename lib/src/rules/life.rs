@@ -5,21 +5,22 @@ use crate::{
     config::Symmetry,
     error::Error,
     rules::{
+        consistify::{consistify, consistify_gen, Implication},
         private::Sealed,
         typebool::{False, True},
-        Rule,
+        Rule, RuleKey,
     },
-    search::{Algorithm, Reason},
+    search::Algorithm,
     world::World,
 };
 use bitflags::bitflags;
 use ca_rules::{ParseLife, ParseLifeGen};
-use std::str::FromStr;
+use std::{array, str::FromStr, sync::Arc};
 
 bitflags! {
     /// Flags to imply the state of a cell and its neighbors.
     #[derive(Clone, Copy, Debug, Default ,PartialEq, Eq, Hash)]
-    struct ImplFlags: u8 {
+    pub(super) struct ImplFlags: u8 {
         /// A conflict is detected.
         const CONFLICT = 0b_0000_0001;
 
@@ -52,6 +53,133 @@ bitflags! {
     }
 }
 
+impl Implication for ImplFlags {
+    #[inline]
+    fn implies_nothing(self) -> bool {
+        self.is_empty()
+    }
+
+    #[inline]
+    fn implies_conflict(self) -> bool {
+        self.contains(ImplFlags::CONFLICT)
+    }
+
+    #[inline]
+    fn succ_implication(self) -> Option<State> {
+        if self.contains(ImplFlags::SUCC_DEAD) {
+            Some(DEAD)
+        } else if self.contains(ImplFlags::SUCC_ALIVE) {
+            Some(ALIVE)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn self_implication(self) -> Option<State> {
+        if self.contains(ImplFlags::SELF_DEAD) {
+            Some(DEAD)
+        } else if self.contains(ImplFlags::SELF_ALIVE) {
+            Some(ALIVE)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn nbhd_implied_states(self) -> Option<[Option<State>; 8]> {
+        if self.contains(ImplFlags::NBHD_DEAD) {
+            Some([Some(DEAD); 8])
+        } else if self.contains(ImplFlags::NBHD_ALIVE) {
+            Some([Some(ALIVE); 8])
+        } else {
+            None
+        }
+    }
+}
+
+/// The flags of a table built for a neighborhood with fewer than eight cells,
+/// e.g., by [`Life::with_nbhd_size`].
+///
+/// The bits of `MASK` are the positions of the neighbors in the neighborhood,
+/// in the order of [`nbhd`](crate::cells::LifeCell#structfield.nbhd).
+/// The implied states of the neighbors only apply to them.
+#[derive(Clone, Copy)]
+pub(super) struct MaskedFlags<const MASK: u8>(pub(super) ImplFlags);
+
+impl<const MASK: u8> Implication for MaskedFlags<MASK> {
+    #[inline]
+    fn implies_nothing(self) -> bool {
+        self.0.implies_nothing()
+    }
+
+    #[inline]
+    fn implies_conflict(self) -> bool {
+        self.0.implies_conflict()
+    }
+
+    #[inline]
+    fn succ_implication(self) -> Option<State> {
+        self.0.succ_implication()
+    }
+
+    #[inline]
+    fn self_implication(self) -> Option<State> {
+        self.0.self_implication()
+    }
+
+    #[inline]
+    fn nbhd_implied_states(self) -> Option<[Option<State>; 8]> {
+        let states = self.0.nbhd_implied_states()?;
+        Some(array::from_fn(|i| states[i].filter(|_| MASK >> i & 1 == 1)))
+    }
+}
+
+/// Parses the `b` and `s` data of a totalistic rule with the given number
+/// of neighbors, from a rule string ending with the given suffix,
+/// in the B/S notation, e.g., `B2/S34H`, or in the S/B notation, e.g., `34/2H`.
+pub(super) fn parse_bs_with_suffix(
+    input: &str,
+    suffix: char,
+    size: u32,
+) -> Option<(Vec<u8>, Vec<u8>)> {
+    let input = input
+        .trim()
+        .strip_suffix([suffix.to_ascii_uppercase(), suffix.to_ascii_lowercase()])?;
+    let digits = |conditions: &str| {
+        conditions
+            .chars()
+            .map(|c| c.to_digit(size + 1).map(|n| n as u8))
+            .collect::<Option<Vec<_>>>()
+    };
+    let (first, second) = input.split_once('/')?;
+    match (
+        first.strip_prefix(['B', 'b']),
+        second.strip_prefix(['S', 's']),
+    ) {
+        (Some(b), Some(s)) => Some((digits(b)?, digits(s)?)),
+        (None, None) => Some((digits(second)?, digits(first)?)),
+        _ => None,
+    }
+}
+
+/// The rule string of a totalistic rule with the given number of neighbors,
+/// in the B/S notation with the given suffix, e.g., `B2/S34H`.
+pub(super) fn rule_string_with_suffix(
+    impl_table: &[ImplFlags; 1 << 12],
+    size: u8,
+    suffix: char,
+) -> String {
+    let (b, s) = bs_from_table(impl_table, size);
+    let digits = |conditions: Vec<u8>| {
+        conditions
+            .into_iter()
+            .map(|n| n.to_string())
+            .collect::<String>()
+    };
+    format!("B{}/S{}{}", digits(b), digits(s), suffix)
+}
+
 /// The neighborhood descriptor.
 ///
 /// It is a 12-bit integer of the form `0b_abcd_efgh_ij_kl`,
@@ -69,6 +197,23 @@ bitflags! {
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
 pub struct NbhdDesc(u16);
 
+/// Reads the `b` and `s` data from the implications of the neighborhood
+/// descriptors that have no unknown neighbors, given the number of neighbors.
+fn bs_from_table(impl_table: &[ImplFlags; 1 << 12], size: u8) -> (Vec<u8>, Vec<u8>) {
+    let succ_alive = |alives: u8, state: usize| {
+        let desc = ((size - alives) as usize) << 8 | (alives as usize) << 4;
+        impl_table[desc | state].contains(ImplFlags::SUCC_ALIVE)
+    };
+    (
+        (0..=size)
+            .filter(|&alives| succ_alive(alives, 0b10))
+            .collect(),
+        (0..=size)
+            .filter(|&alives| succ_alive(alives, 0b01))
+            .collect(),
+    )
+}
+
 /// Totalistic Life-like rules.
 #[derive(Clone)]
 pub struct Life {
@@ -77,42 +222,61 @@ pub struct Life {
     /// Whether the rule contains `S8`.
     s8: bool,
     /// An array of actions for all neighborhood descriptors.
-    impl_table: [ImplFlags; 1 << 12],
+    ///
+    /// It is shared between clones of the rule.
+    impl_table: Arc<[ImplFlags; 1 << 12]>,
 }
 
 impl Life {
     /// Constructs a new rule from the `b` and `s` data.
     pub fn new(b: &[u8], s: &[u8]) -> Self {
+        Self::with_nbhd_size(b, s, 8)
+    }
+
+    /// Constructs a new rule from the `b` and `s` data, where a cell
+    /// only has the given number of neighbors.
+    ///
+    /// The table is laid out in the same way, so it can be reused by rules
+    /// with a smaller neighborhood, e.g., [`Neumann`](super::Neumann).
+    /// In this case [`s8`](#structfield.s8) means that the rule survives
+    /// when all the neighbors are alive.
+    pub(super) fn with_nbhd_size(b: &[u8], s: &[u8], size: usize) -> Self {
         let b0 = b.contains(&0);
-        let s8 = s.contains(&8);
+        let s8 = s.contains(&(size as u8));
 
-        let impl_table = [ImplFlags::empty(); 1 << 12];
+        let impl_table = Arc::new([ImplFlags::empty(); 1 << 12]);
 
         Self { b0, s8, impl_table }
-            .init_trans(b, s)
+            .init_trans(b, s, size)
             .init_conflict()
-            .init_impl()
-            .init_impl_nbhd()
+            .init_impl(size)
+            .init_impl_nbhd(size)
+    }
+
+    /// Takes the table of actions for all neighborhood descriptors.
+    pub(super) fn into_impl_table(self) -> Arc<[ImplFlags; 1 << 12]> {
+        self.impl_table
     }
 
     /// Deduces the implication for the successor.
-    fn init_trans(mut self, b: &[u8], s: &[u8]) -> Self {
+    fn init_trans(mut self, b: &[u8], s: &[u8], size: usize) -> Self {
+        let impl_table = Arc::make_mut(&mut self.impl_table);
         // Fills in the positions of the neighborhood descriptors
         // that have no unknown neighbors.
-        for alives in 0..=8 {
-            let desc = ((8 - alives) << 8) | alives << 4;
+        for alives in 0..=size {
+            let desc = ((size - alives) << 8) | alives << 4;
             let alives = alives as u8;
-            self.impl_table[desc | 0b10] |= if b.contains(&alives) {
+            impl_table[desc | 0b10] |= if b.contains(&alives) {
                 ImplFlags::SUCC_ALIVE
             } else {
                 ImplFlags::SUCC_DEAD
             };
-            self.impl_table[desc | 0b01] |= if s.contains(&alives) {
+            impl_table[desc | 0b01] |= if s.contains(&alives) {
                 ImplFlags::SUCC_ALIVE
             } else {
                 ImplFlags::SUCC_DEAD
             };
-            self.impl_table[desc] |= if b.contains(&alives) && s.contains(&alives) {
+            impl_table[desc] |= if b.contains(&alives) && s.contains(&alives) {
                 ImplFlags::SUCC_ALIVE
             } else if !b.contains(&alives) && !s.contains(&alives) {
                 ImplFlags::SUCC_DEAD
@@ -122,17 +286,17 @@ impl Life {
         }
 
         // Fills in other positions.
-        for unknowns in 1..=8 {
-            for alives in 0..=8 - unknowns {
-                let desc = (8 - alives - unknowns) << 8 | alives << 4;
-                let desc0 = (8 - alives - unknowns + 1) << 8 | alives << 4;
-                let desc1 = (8 - alives - unknowns) << 8 | (alives + 1) << 4;
+        for unknowns in 1..=size {
+            for alives in 0..=size - unknowns {
+                let desc = (size - alives - unknowns) << 8 | alives << 4;
+                let desc0 = (size - alives - unknowns + 1) << 8 | alives << 4;
+                let desc1 = (size - alives - unknowns) << 8 | (alives + 1) << 4;
 
                 for state in 0..=2 {
-                    let trans0 = self.impl_table[desc0 | state];
+                    let trans0 = impl_table[desc0 | state];
 
-                    if trans0 == self.impl_table[desc1 | state] {
-                        self.impl_table[desc | state] |= trans0;
+                    if trans0 == impl_table[desc1 | state] {
+                        impl_table[desc | state] |= trans0;
                     }
                 }
             }
@@ -143,14 +307,15 @@ impl Life {
 
     /// Deduces the conflicts.
     fn init_conflict(mut self) -> Self {
+        let impl_table = Arc::make_mut(&mut self.impl_table);
         for nbhd_state in 0..0xff {
             for state in 0..=2 {
                 let desc = nbhd_state << 4 | state;
 
-                if self.impl_table[desc].contains(ImplFlags::SUCC_ALIVE) {
-                    self.impl_table[desc | 0b10 << 2] = ImplFlags::CONFLICT;
-                } else if self.impl_table[desc].contains(ImplFlags::SUCC_DEAD) {
-                    self.impl_table[desc | 0b01 << 2] = ImplFlags::CONFLICT;
+                if impl_table[desc].contains(ImplFlags::SUCC_ALIVE) {
+                    impl_table[desc | 0b10 << 2] = ImplFlags::CONFLICT;
+                } else if impl_table[desc].contains(ImplFlags::SUCC_DEAD) {
+                    impl_table[desc | 0b01 << 2] = ImplFlags::CONFLICT;
                 }
             }
         }
@@ -158,10 +323,11 @@ impl Life {
     }
 
     /// Deduces the implication for the cell itself.
-    fn init_impl(mut self) -> Self {
-        for unknowns in 0..=8 {
-            for alives in 0..=8 - unknowns {
-                let desc = (8 - alives - unknowns) << 8 | alives << 4;
+    fn init_impl(mut self, size: usize) -> Self {
+        let impl_table = Arc::make_mut(&mut self.impl_table);
+        for unknowns in 0..=size {
+            for alives in 0..=size - unknowns {
+                let desc = (size - alives - unknowns) << 8 | alives << 4;
 
                 for succ_state in 1..=2 {
                     let flag = if succ_state == 0b10 {
@@ -170,16 +336,16 @@ impl Life {
                         ImplFlags::SUCC_DEAD | ImplFlags::CONFLICT
                     };
 
-                    let possibly_dead = !self.impl_table[desc | 0b10].intersects(flag);
-                    let possibly_alive = !self.impl_table[desc | 0b01].intersects(flag);
+                    let possibly_dead = !impl_table[desc | 0b10].intersects(flag);
+                    let possibly_alive = !impl_table[desc | 0b01].intersects(flag);
 
                     let index = desc | succ_state << 2;
                     if possibly_dead && !possibly_alive {
-                        self.impl_table[index] |= ImplFlags::SELF_DEAD;
+                        impl_table[index] |= ImplFlags::SELF_DEAD;
                     } else if !possibly_dead && possibly_alive {
-                        self.impl_table[index] |= ImplFlags::SELF_ALIVE;
+                        impl_table[index] |= ImplFlags::SELF_ALIVE;
                     } else if !possibly_dead && !possibly_alive {
-                        self.impl_table[index] = ImplFlags::CONFLICT;
+                        impl_table[index] = ImplFlags::CONFLICT;
                     }
                 }
             }
@@ -189,12 +355,13 @@ impl Life {
     }
 
     ///  Deduces the implication for the neighbors.
-    fn init_impl_nbhd(mut self) -> Self {
-        for unknowns in 1..=8 {
-            for alives in 0..=8 - unknowns {
-                let desc = (8 - alives - unknowns) << 8 | alives << 4;
-                let desc0 = (8 - alives - unknowns + 1) << 8 | alives << 4;
-                let desc1 = (8 - alives - unknowns) << 8 | (alives + 1) << 4;
+    fn init_impl_nbhd(mut self, size: usize) -> Self {
+        let impl_table = Arc::make_mut(&mut self.impl_table);
+        for unknowns in 1..=size {
+            for alives in 0..=size - unknowns {
+                let desc = (size - alives - unknowns) << 8 | alives << 4;
+                let desc0 = (size - alives - unknowns + 1) << 8 | alives << 4;
+                let desc1 = (size - alives - unknowns) << 8 | (alives + 1) << 4;
 
                 for succ_state in 1..=2 {
                     let flag = if succ_state == 0b10 {
@@ -206,15 +373,15 @@ impl Life {
                     let index = desc | succ_state << 2;
 
                     for state in 0..=2 {
-                        let possibly_dead = !self.impl_table[desc0 | state].intersects(flag);
-                        let possibly_alive = !self.impl_table[desc1 | state].intersects(flag);
+                        let possibly_dead = !impl_table[desc0 | state].intersects(flag);
+                        let possibly_alive = !impl_table[desc1 | state].intersects(flag);
 
                         if possibly_dead && !possibly_alive {
-                            self.impl_table[index | state] |= ImplFlags::NBHD_DEAD;
+                            impl_table[index | state] |= ImplFlags::NBHD_DEAD;
                         } else if !possibly_dead && possibly_alive {
-                            self.impl_table[index | state] |= ImplFlags::NBHD_ALIVE;
+                            impl_table[index | state] |= ImplFlags::NBHD_ALIVE;
                         } else if !possibly_dead && !possibly_alive {
-                            self.impl_table[index | state] = ImplFlags::CONFLICT;
+                            impl_table[index | state] = ImplFlags::CONFLICT;
                         }
                     }
                 }
@@ -270,6 +437,11 @@ impl Rule for Life {
         Symmetry::D8
     }
 
+    fn rule_string_canonical(&self) -> String {
+        let (b, s) = bs_from_table(&self.impl_table, 8);
+        RuleKey::new(true, b, s, 2).to_string()
+    }
+
     fn new_desc(state: State, succ_state: State) -> Self::Desc {
         let nbhd_state = match state {
             ALIVE => 0x08,
@@ -316,58 +488,36 @@ impl Rule for Life {
         cell.desc.set(desc);
     }
 
+    #[cfg(any(debug_assertions, feature = "debug-invariants"))]
+    fn desc_from_states(
+        state: Option<State>,
+        succ_state: Option<State>,
+        nbhd: [Option<State>; 8],
+    ) -> Self::Desc {
+        let nbhd_state = nbhd.iter().fold(0, |n, state| match state {
+            Some(ALIVE) => n + 0x01,
+            Some(_) => n + 0x10,
+            None => n,
+        });
+        let bits = |state| match state {
+            Some(ALIVE) => 0b01,
+            Some(_) => 0b10,
+            None => 0b00,
+        };
+        NbhdDesc(nbhd_state << 4 | bits(succ_state) << 2 | bits(state))
+    }
+
+    #[inline]
+    fn is_conflict(&self, cell: CellRef<Self>) -> bool {
+        self.impl_table[cell.desc.get().0 as usize].contains(ImplFlags::CONFLICT)
+    }
+
     fn consistify<A: Algorithm<Self>>(
         world: &mut World<Self, A>,
         cell: CellRef<Self>,
     ) -> Result<(), A::ConflReason> {
         let flags = world.rule.impl_table[cell.desc.get().0 as usize];
-
-        if flags.is_empty() {
-            return Ok(());
-        }
-
-        if flags.contains(ImplFlags::CONFLICT) {
-            return Err(A::confl_from_cell(cell));
-        }
-
-        if flags.intersects(ImplFlags::SUCC) {
-            let state = if flags.contains(ImplFlags::SUCC_DEAD) {
-                DEAD
-            } else {
-                ALIVE
-            };
-            if let Some(succ) = cell.succ {
-                return world.set_cell(succ, state, A::Reason::from_cell(cell));
-            } else {
-                return Ok(());
-            }
-        }
-
-        if flags.intersects(ImplFlags::SELF) {
-            let state = if flags.contains(ImplFlags::SELF_DEAD) {
-                DEAD
-            } else {
-                ALIVE
-            };
-            world.set_cell(cell, state, A::Reason::from_cell(cell))?;
-        }
-
-        if flags.intersects(ImplFlags::NBHD) {
-            let state = if flags.contains(ImplFlags::NBHD_DEAD) {
-                DEAD
-            } else {
-                ALIVE
-            };
-            for &neigh in &cell.nbhd {
-                if let Some(neigh) = neigh {
-                    if neigh.state.get().is_none() {
-                        world.set_cell(neigh, state, A::Reason::from_cell(cell))?;
-                    }
-                }
-            }
-        }
-
-        Ok(())
+        consistify(world, cell, flags)
     }
 }
 
@@ -388,7 +538,9 @@ pub struct LifeGen {
     /// Number of states.
     gen: usize,
     /// An array of actions for all neighborhood descriptors.
-    impl_table: [ImplFlags; 1 << 12],
+    ///
+    /// It is shared between clones of the rule.
+    impl_table: Arc<[ImplFlags; 1 << 12]>,
 }
 
 impl LifeGen {
@@ -406,7 +558,7 @@ impl LifeGen {
     }
 
     /// Converts to the corresponding non-Generations rule.
-    pub const fn non_gen(self) -> Life {
+    pub fn non_gen(self) -> Life {
         Life {
             b0: self.b0,
             s8: self.s8,
@@ -415,6 +567,14 @@ impl LifeGen {
     }
 }
 
+#[cfg(test)]
+impl LifeGen {
+    /// Whether two rules share the same table.
+    pub(super) fn shares_table(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.impl_table, &other.impl_table)
+    }
+}
+
 /// A parser for the rule.
 impl ParseLifeGen for LifeGen {
     fn from_bsg(b: Vec<u8>, s: Vec<u8>, gen: usize) -> Self {
@@ -461,6 +621,11 @@ impl Rule for LifeGen {
         Symmetry::D8
     }
 
+    fn rule_string_canonical(&self) -> String {
+        let (b, s) = bs_from_table(&self.impl_table, 8);
+        RuleKey::new(true, b, s, self.gen).to_string()
+    }
+
     #[inline]
     fn new_desc(state: State, succ_state: State) -> Self::Desc {
         let desc = Life::new_desc(state, succ_state);
@@ -499,111 +664,27 @@ impl Rule for LifeGen {
         cell.desc.set(desc);
     }
 
+    #[cfg(any(debug_assertions, feature = "debug-invariants"))]
+    fn desc_from_states(
+        state: Option<State>,
+        succ_state: Option<State>,
+        nbhd: [Option<State>; 8],
+    ) -> Self::Desc {
+        let desc = Life::desc_from_states(state, succ_state, nbhd);
+        NbhdDescGen(desc.0, succ_state)
+    }
+
+    #[inline]
+    fn is_conflict(&self, cell: CellRef<Self>) -> bool {
+        self.impl_table[cell.desc.get().0 as usize].contains(ImplFlags::CONFLICT)
+    }
+
     fn consistify<A: Algorithm<Self>>(
         world: &mut World<Self, A>,
         cell: CellRef<Self>,
     ) -> Result<(), A::ConflReason> {
         let desc = cell.desc.get();
         let flags = world.rule.impl_table[desc.0 as usize];
-        let gen = world.rule.gen;
-
-        match cell.state.get() {
-            Some(DEAD) => {
-                if let Some(State(j)) = desc.1 {
-                    if j >= 2 {
-                        return Err(A::confl_from_cell(cell));
-                    }
-                }
-
-                if flags.intersects(ImplFlags::SUCC) {
-                    let state = if flags.contains(ImplFlags::SUCC_DEAD) {
-                        DEAD
-                    } else {
-                        ALIVE
-                    };
-                    if let Some(succ) = cell.succ {
-                        return world.set_cell(succ, state, A::Reason::from_cell(cell));
-                    } else {
-                        return Ok(());
-                    }
-                }
-            }
-            Some(ALIVE) => {
-                if let Some(State(j)) = desc.1 {
-                    if j == 0 || j > 2 {
-                        return Err(A::confl_from_cell(cell));
-                    }
-                }
-
-                if flags.intersects(ImplFlags::SUCC) {
-                    let state = if flags.contains(ImplFlags::SUCC_DEAD) {
-                        State(2)
-                    } else {
-                        ALIVE
-                    };
-                    if let Some(succ) = cell.succ {
-                        return world.set_cell(succ, state, A::Reason::from_cell(cell));
-                    } else {
-                        return Ok(());
-                    }
-                }
-            }
-            Some(State(i)) => {
-                if let Some(State(j)) = desc.1 {
-                    if j == (i + 1) % gen {
-                        return Ok(());
-                    } else {
-                        return Err(A::confl_from_cell(cell));
-                    }
-                } else if let Some(succ) = cell.succ {
-                    return world.set_cell(succ, State((i + 1) % gen), A::Reason::from_cell(cell));
-                } else {
-                    return Ok(());
-                }
-            }
-            None => match desc.1 {
-                Some(DEAD) => {
-                    if flags.contains(ImplFlags::SELF_ALIVE) {
-                        return world.set_cell(cell, State(gen - 1), A::Reason::from_cell(cell));
-                    } else {
-                        return Ok(());
-                    }
-                }
-                Some(ALIVE) => {
-                    if flags.intersects(ImplFlags::SELF) {
-                        let state = if flags.contains(ImplFlags::SELF_DEAD) {
-                            DEAD
-                        } else {
-                            ALIVE
-                        };
-                        world.set_cell(cell, state, A::Reason::from_cell(cell))?;
-                    }
-                }
-                Some(State(j)) => {
-                    return world.set_cell(cell, State(j - 1), A::Reason::from_cell(cell));
-                }
-                None => return Ok(()),
-            },
-        }
-
-        if flags.is_empty() {
-            return Ok(());
-        }
-
-        if flags.contains(ImplFlags::CONFLICT) {
-            return Err(A::confl_from_cell(cell));
-        }
-
-        if flags.intersects(ImplFlags::NBHD_ALIVE) {
-            for &neigh in &cell.nbhd {
-                if let Some(neigh) = neigh {
-                    if neigh.state.get().is_none() {
-                        world.set_cell(neigh, ALIVE, A::Reason::from_cell(cell))?;
-                    }
-                }
-            }
-        }
-
-        Ok(())
+        consistify_gen(world, cell, flags, desc.1)
     }
 }
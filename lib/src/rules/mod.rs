@@ -3,21 +3,31 @@
 //! For the notations of rule strings, please see
 //! [this article on LifeWiki](https://conwaylife.com/wiki/Rulestring).
 
+mod cache;
+mod consistify;
+mod hex;
 mod life;
+mod neumann;
 mod ntlife;
 
 use crate::{
     cells::{CellRef, LifeCell, State},
     config::Symmetry,
+    error::Error,
     search::Algorithm,
     world::World,
 };
+pub use cache::RuleCache;
+pub(crate) use cache::{CachedRule, RuleKey};
+pub use hex::Hex;
 pub use life::{Life, LifeGen};
+pub use neumann::Neumann;
 pub use ntlife::{NtLife, NtLifeGen};
 
 #[cfg(doc)]
 use crate::cells::ALIVE;
 
+use std::fmt::Debug;
 use typebool::Bool;
 
 /// Type level boolean values.
@@ -45,6 +55,79 @@ pub(crate) mod typebool {
     }
 }
 
+/// A rule parsed by [`parse_rule`].
+pub(crate) enum ParsedRule {
+    /// A totalistic rule with the von Neumann neighborhood.
+    Neumann(Neumann),
+    /// A totalistic rule with the hexagonal neighborhood.
+    Hex(Hex),
+    /// Any other rule, which is built through the [`RuleCache`].
+    Key(RuleKey),
+}
+
+/// A format of rule strings.
+struct RuleFormat {
+    /// The notations of this format, each with an example.
+    notations: &'static [&'static str],
+    /// Parses a rule string in this format.
+    ///
+    /// Returns `None` if the rule string is not in this format,
+    /// so that the next format is tried.
+    parse: fn(&str) -> Option<Result<ParsedRule, Error>>,
+}
+
+/// The formats of rule strings, in the order they are tried by [`parse_rule`].
+const RULE_FORMATS: [RuleFormat; 3] = [
+    RuleFormat {
+        notations: &["Von Neumann, e.g., B2/S013V"],
+        parse: |input| {
+            input
+                .trim_end()
+                .ends_with(['V', 'v'])
+                .then(|| input.parse().map(ParsedRule::Neumann))
+        },
+    },
+    RuleFormat {
+        notations: &["Hexagonal, e.g., B2/S34H"],
+        // Non-totalistic hexagonal rules are parsed as non-totalistic rules.
+        parse: |input| match input.parse() {
+            Err(Error::ParseHexRuleError(_)) => None,
+            rule => Some(rule.map(ParsedRule::Hex)),
+        },
+    },
+    RuleFormat {
+        notations: &[
+            "Life-like, e.g., B3/S23",
+            "Isotropic non-totalistic, e.g., B2n3/S23-q",
+            "MAP, e.g., MAPARYXfhZofugWaH7oaIDogBZofuhogOiAaIDogIAAgAAWaH7oaIDogGiA6ICAAIAAaIDogIAAgACAAIAAAAAAAA",
+            "Life-like Generations, e.g., B2/S/3 or 23/3/3",
+            "Isotropic non-totalistic Generations, e.g., B2n3/S23-q/3",
+        ],
+        parse: |input| Some(input.parse().map(ParsedRule::Key)),
+    },
+];
+
+/// Parses a rule string, trying the formats in the order listed in [`supported_formats`].
+pub(crate) fn parse_rule(input: &str) -> Result<ParsedRule, Error> {
+    RULE_FORMATS
+        .iter()
+        .find_map(|format| (format.parse)(input))
+        // The last format accepts any rule string.
+        .unwrap()
+}
+
+/// The supported formats of rule strings, each with an example.
+///
+/// The formats are listed in the order they are tried when parsing a rule string,
+/// except that a rule string ending with `V` is only parsed as a von Neumann rule.
+pub fn supported_formats() -> Vec<&'static str> {
+    RULE_FORMATS
+        .iter()
+        .flat_map(|format| format.notations)
+        .copied()
+        .collect()
+}
+
 /// A cellular automaton rule.
 ///
 /// The following rules are supported:
@@ -53,6 +136,8 @@ pub(crate) mod typebool {
 /// - [`LifeGen`]
 /// - [`NtLife`]
 /// - [`NtLifeGen`]
+/// - [`Neumann`]
+/// - [`Hex`]
 ///
 /// This trait is sealed and cannot be implemented outside of this crate.
 #[cfg_attr(not(github_io), doc = "Some of its items are hidden in the doc.")]
@@ -61,7 +146,7 @@ pub trait Rule: private::Sealed {
     ///
     /// It describes the states of the successor and neighbors of a cell,
     /// and is used to determine the state of the cell in the next generation.
-    type Desc: Copy;
+    type Desc: Copy + PartialEq + Debug;
 
     /// Whether the rule is a Generations rule.
     type IsGen: Bool;
@@ -87,6 +172,13 @@ pub trait Rule: private::Sealed {
     /// The symmetry of the rule.
     fn symmetry(&self) -> Symmetry;
 
+    /// The rule string in a canonical notation, which the parser accepts back.
+    ///
+    /// Totalistic rules are written as `B3/S23`, von Neumann rules as `B2/S013V`,
+    /// hexagonal rules as `B2/S34H`, and other rules in MAP notation.
+    /// The number of states of a Generations rule follows, e.g., `B2/S/G3`.
+    fn rule_string_canonical(&self) -> String;
+
     /// Generates a neighborhood descriptor which says that all neighboring
     /// cells have states `state`, and the successor has state `succ_state`.
     #[cfg_attr(not(github_io), doc(hidden))]
@@ -100,6 +192,26 @@ pub trait Rule: private::Sealed {
     #[cfg_attr(not(github_io), doc(hidden))]
     fn update_desc(cell: &LifeCell<Self>, state: State, new: bool);
 
+    /// Whether the neighborhood descriptor of a cell
+    /// shows that it definitely violates the rule.
+    ///
+    /// For Generations rules, the dying states are not checked.
+    #[cfg_attr(not(github_io), doc(hidden))]
+    fn is_conflict(&self, cell: CellRef<Self>) -> bool;
+
+    /// Computes a neighborhood descriptor from scratch, from the states of
+    /// the cell, its successor, and its neighbors, where `None` means unknown.
+    ///
+    /// Only used to check the descriptors maintained by
+    /// [`update_desc`](Self::update_desc).
+    #[cfg(any(debug_assertions, feature = "debug-invariants"))]
+    #[cfg_attr(not(github_io), doc(hidden))]
+    fn desc_from_states(
+        state: Option<State>,
+        succ_state: Option<State>,
+        nbhd: [Option<State>; 8],
+    ) -> Self::Desc;
+
     /// Consistifies a cell.
     ///
     /// Examines the state and the neighborhood descriptor of the cell,
@@ -0,0 +1,194 @@
+//! Totalistic rules with the von Neumann neighborhood.
+//!
+//! A cell only has four neighbors: the orthogonally adjacent cells.
+//! The rule strings end with a `V`, e.g., `B2/S013V`.
+
+use crate::{
+    cells::{CellRef, LifeCell, State, ALIVE},
+    config::Symmetry,
+    error::Error,
+    rules::{
+        consistify::consistify,
+        life::{parse_bs_with_suffix, rule_string_with_suffix, ImplFlags, MaskedFlags},
+        private::Sealed,
+        typebool::False,
+        Life, Rule,
+    },
+    search::Algorithm,
+    world::World,
+};
+use std::{str::FromStr, sync::Arc};
+
+/// The positions of the four orthogonal neighbors
+/// in [`nbhd`](crate::cells::LifeCell#structfield.nbhd).
+const ORTHOGONAL: [usize; 4] = [1, 3, 4, 6];
+
+/// The bits of the [`ORTHOGONAL`] positions.
+const ORTHOGONAL_MASK: u8 = 0b_0101_1010;
+
+/// The neighborhood descriptor.
+///
+/// It is a 12-bit integer of the form `0b_abcd_efgh_ij_kl`,
+/// where:
+///
+/// * `0b_abcd` is the number of dead cells among the four neighbors.
+/// * `0b_efgh` is the number of living cells among the four neighbors.
+/// * `0b_ij` is the state of the successor.
+/// * `0b_kl` is the state of the cell itself.
+///
+/// For `0b_ij` and `0b_kl`:
+/// * `0b_10` means dead,
+/// * `0b_01` means alive,
+/// * `0b_00` means unknown.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct NbhdDesc(u16);
+
+/// Totalistic rules with the von Neumann neighborhood.
+///
+/// Generations rules with this neighborhood are not supported.
+#[derive(Clone)]
+pub struct Neumann {
+    /// Whether the rule contains `B0`.
+    b0: bool,
+    /// Whether the rule contains `S4`.
+    s4: bool,
+    /// An array of actions for all neighborhood descriptors.
+    ///
+    /// It is shared between clones of the rule.
+    impl_table: Arc<[ImplFlags; 1 << 12]>,
+}
+
+impl Neumann {
+    /// Constructs a new rule from the `b` and `s` data.
+    pub fn new(b: &[u8], s: &[u8]) -> Self {
+        Self {
+            b0: b.contains(&0),
+            s4: s.contains(&4),
+            impl_table: Life::with_nbhd_size(b, s, 4).into_impl_table(),
+        }
+    }
+}
+
+impl FromStr for Neumann {
+    type Err = Error;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let (b, s) = parse_bs_with_suffix(input, 'V', 4)
+            .ok_or_else(|| Error::ParseNeumannRuleError(input.to_owned()))?;
+        let rule = Self::new(&b, &s);
+        if rule.has_b0_s8() {
+            Err(Error::B0S8Error)
+        } else {
+            Ok(rule)
+        }
+    }
+}
+
+impl Sealed for Neumann {}
+
+impl Rule for Neumann {
+    type Desc = NbhdDesc;
+    type IsGen = False;
+
+    #[inline]
+    fn has_b0(&self) -> bool {
+        self.b0
+    }
+
+    /// Whether the rule contains both `B0` and `S4`.
+    #[inline]
+    fn has_b0_s8(&self) -> bool {
+        self.b0 && self.s4
+    }
+
+    #[inline]
+    fn gen(&self) -> usize {
+        2
+    }
+
+    #[inline]
+    fn symmetry(&self) -> Symmetry {
+        Symmetry::D8
+    }
+
+    fn rule_string_canonical(&self) -> String {
+        rule_string_with_suffix(&self.impl_table, 4, 'V')
+    }
+
+    fn new_desc(state: State, succ_state: State) -> Self::Desc {
+        let nbhd_state = match state {
+            ALIVE => 0x04,
+            _ => 0x40,
+        };
+        let succ_state = match succ_state {
+            ALIVE => 0b01,
+            _ => 0b10,
+        };
+        let state = match state {
+            ALIVE => 0b01,
+            _ => 0b10,
+        };
+        NbhdDesc(nbhd_state << 4 | succ_state << 2 | state)
+    }
+
+    fn update_desc(cell: &LifeCell<Self>, state: State, new: bool) {
+        let state_num = match state {
+            ALIVE => 0x01,
+            _ => 0x10,
+        };
+        for i in ORTHOGONAL {
+            let neigh = cell.nbhd[i].unwrap();
+            let mut desc = neigh.desc.get();
+            if new {
+                desc.0 += state_num << 4;
+            } else {
+                desc.0 -= state_num << 4;
+            }
+            neigh.desc.set(desc);
+        }
+
+        let change_num = match state {
+            ALIVE => 0b01,
+            _ => 0b10,
+        };
+        if let Some(pred) = cell.pred {
+            let mut desc = pred.desc.get();
+            desc.0 ^= change_num << 2;
+            pred.desc.set(desc);
+        }
+        let mut desc = cell.desc.get();
+        desc.0 ^= change_num;
+        cell.desc.set(desc);
+    }
+
+    #[cfg(any(debug_assertions, feature = "debug-invariants"))]
+    fn desc_from_states(
+        state: Option<State>,
+        succ_state: Option<State>,
+        nbhd: [Option<State>; 8],
+    ) -> Self::Desc {
+        let nbhd_state = ORTHOGONAL.iter().fold(0, |n, &i| match nbhd[i] {
+            Some(ALIVE) => n + 0x01,
+            Some(_) => n + 0x10,
+            None => n,
+        });
+        let bits = |state| match state {
+            Some(ALIVE) => 0b01,
+            Some(_) => 0b10,
+            None => 0b00,
+        };
+        NbhdDesc(nbhd_state << 4 | bits(succ_state) << 2 | bits(state))
+    }
+
+    #[inline]
+    fn is_conflict(&self, cell: CellRef<Self>) -> bool {
+        self.impl_table[cell.desc.get().0 as usize].contains(ImplFlags::CONFLICT)
+    }
+
+    fn consistify<A: Algorithm<Self>>(
+        world: &mut World<Self, A>,
+        cell: CellRef<Self>,
+    ) -> Result<(), A::ConflReason> {
+        let flags = world.rule.impl_table[cell.desc.get().0 as usize];
+        consistify(world, cell, MaskedFlags::<ORTHOGONAL_MASK>(flags))
+    }
+}
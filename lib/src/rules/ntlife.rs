@@ -5,16 +5,17 @@ use crate::{
     config::{Symmetry, Transform},
     error::Error,
     rules::{
+        consistify::{consistify, consistify_gen, Implication},
         private::Sealed,
         typebool::{False, True},
-        Rule,
+        Rule, RuleKey,
     },
-    search::{Algorithm, Reason},
+    search::Algorithm,
     world::World,
 };
 use bitflags::bitflags;
 use ca_rules::{ParseNtLife, ParseNtLifeGen};
-use std::{collections::HashSet, str::FromStr};
+use std::{array, collections::HashSet, str::FromStr, sync::Arc};
 
 /// Permutes the bits of an `u8`.
 fn permute_bits(n: u8, perm: [u32; 8]) -> u8 {
@@ -75,6 +76,56 @@ bitflags! {
     }
 }
 
+impl Implication for ImplFlags {
+    #[inline]
+    fn implies_nothing(self) -> bool {
+        self.is_empty()
+    }
+
+    #[inline]
+    fn implies_conflict(self) -> bool {
+        self.contains(ImplFlags::CONFLICT)
+    }
+
+    #[inline]
+    fn succ_implication(self) -> Option<State> {
+        if self.contains(ImplFlags::SUCC_DEAD) {
+            Some(DEAD)
+        } else if self.contains(ImplFlags::SUCC_ALIVE) {
+            Some(ALIVE)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn self_implication(self) -> Option<State> {
+        if self.contains(ImplFlags::SELF_DEAD) {
+            Some(DEAD)
+        } else if self.contains(ImplFlags::SELF_ALIVE) {
+            Some(ALIVE)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn nbhd_implied_states(self) -> Option<[Option<State>; 8]> {
+        if !self.intersects(ImplFlags::NBHD) {
+            return None;
+        }
+        Some(array::from_fn(|i| {
+            if self.contains(ImplFlags::from_bits_retain(1 << (2 * i + 7))) {
+                Some(DEAD)
+            } else if self.contains(ImplFlags::from_bits_retain(1 << (2 * i + 6))) {
+                Some(ALIVE)
+            } else {
+                None
+            }
+        }))
+    }
+}
+
 /// The neighborhood descriptor.
 ///
 /// It is a 20-bit integer of the form `0b_abcdefgh_ijklmnop_qr_st`,
@@ -89,6 +140,25 @@ bitflags! {
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
 pub struct NbhdDesc(u32);
 
+/// Reads the `b` and `s` data from the implications of the neighborhood
+/// descriptors that have no unknown neighbors.
+///
+/// The neighborhoods are in the orientation of the parser,
+/// i.e., before the fix in [`ParseNtLife::from_bs`].
+fn bs_from_table(impl_table: &[ImplFlags; 1 << 20]) -> (Vec<u8>, Vec<u8>) {
+    let succ_alive = |alives: u8, state: usize| {
+        let desc = (0xff & !alives as usize) << 12 | (alives as usize) << 4;
+        impl_table[desc | state].contains(ImplFlags::SUCC_ALIVE)
+    };
+    let nbhds = |state| {
+        (0..=0xff)
+            .filter(|&alives| succ_alive(alives, state))
+            .map(|alives| transform_neigh(alives, Transform::FlipAntidiag))
+            .collect()
+    };
+    (nbhds(0b10), nbhds(0b01))
+}
+
 /// Non-totalistic Life-like rules.
 ///
 /// This includes any rule that can be converted to a non-totalistic
@@ -104,7 +174,9 @@ pub struct NtLife {
     /// The symmetry of the rule.
     symmetry: Symmetry,
     /// An array of actions for all neighborhood descriptors.
-    impl_table: Vec<ImplFlags>,
+    ///
+    /// It is shared between clones of the rule.
+    impl_table: Arc<[ImplFlags; 1 << 20]>,
 }
 
 impl NtLife {
@@ -113,7 +185,11 @@ impl NtLife {
         let b0 = b.contains(&0x00);
         let s8 = s.contains(&0xff);
         let symmetry = Symmetry::C1;
-        let impl_table = vec![ImplFlags::empty(); 1 << 20];
+        let impl_table: Box<[ImplFlags; 1 << 20]> = vec![ImplFlags::empty(); 1 << 20]
+            .into_boxed_slice()
+            .try_into()
+            .unwrap();
+        let impl_table = Arc::from(impl_table);
 
         Self {
             b0,
@@ -155,22 +231,23 @@ impl NtLife {
 
     /// Deduces the implication for the successor.
     fn init_trans(mut self, b: &[u8], s: &[u8]) -> Self {
+        let impl_table = Arc::make_mut(&mut self.impl_table);
         // Fills in the positions of the neighborhood descriptors
         // that have no unknown neighbors.
         for alives in 0..=0xff {
             let desc = (0xff & !alives) << 12 | alives << 4;
             let alives = alives as u8;
-            self.impl_table[desc | 0b10] |= if b.contains(&alives) {
+            impl_table[desc | 0b10] |= if b.contains(&alives) {
                 ImplFlags::SUCC_ALIVE
             } else {
                 ImplFlags::SUCC_DEAD
             };
-            self.impl_table[desc | 0b01] |= if s.contains(&alives) {
+            impl_table[desc | 0b01] |= if s.contains(&alives) {
                 ImplFlags::SUCC_ALIVE
             } else {
                 ImplFlags::SUCC_DEAD
             };
-            self.impl_table[desc] |= if b.contains(&alives) && s.contains(&alives) {
+            impl_table[desc] |= if b.contains(&alives) && s.contains(&alives) {
                 ImplFlags::SUCC_ALIVE
             } else if !b.contains(&alives) && !s.contains(&alives) {
                 ImplFlags::SUCC_DEAD
@@ -189,10 +266,10 @@ impl NtLife {
                 let desc1 = (0xff & !alives & !unknowns) << 12 | (alives | n) << 4;
 
                 for state in 0..=2 {
-                    let trans0 = self.impl_table[desc0 | state];
+                    let trans0 = impl_table[desc0 | state];
 
-                    if trans0 == self.impl_table[desc1 | state] {
-                        self.impl_table[desc | state] |= trans0;
+                    if trans0 == impl_table[desc1 | state] {
+                        impl_table[desc | state] |= trans0;
                     }
                 }
             }
@@ -203,14 +280,15 @@ impl NtLife {
 
     /// Deduces the conflicts.
     fn init_conflict(mut self) -> Self {
+        let impl_table = Arc::make_mut(&mut self.impl_table);
         for nbhd_state in 0..0xffff {
             for state in 0..=2 {
                 let desc = nbhd_state << 4 | state;
 
-                if self.impl_table[desc].contains(ImplFlags::SUCC_ALIVE) {
-                    self.impl_table[desc | 0b10 << 2] = ImplFlags::CONFLICT;
-                } else if self.impl_table[desc].contains(ImplFlags::SUCC_DEAD) {
-                    self.impl_table[desc | 0b01 << 2] = ImplFlags::CONFLICT;
+                if impl_table[desc].contains(ImplFlags::SUCC_ALIVE) {
+                    impl_table[desc | 0b10 << 2] = ImplFlags::CONFLICT;
+                } else if impl_table[desc].contains(ImplFlags::SUCC_DEAD) {
+                    impl_table[desc | 0b01 << 2] = ImplFlags::CONFLICT;
                 }
             }
         }
@@ -219,6 +297,7 @@ impl NtLife {
 
     /// Deduces the implication for the cell itself.
     fn init_impl(mut self) -> Self {
+        let impl_table = Arc::make_mut(&mut self.impl_table);
         for unknowns in 0..=0xff {
             for alives in (0..=0xff).filter(|a| a & unknowns == 0) {
                 let desc = (0xff & !alives & !unknowns) << 12 | alives << 4;
@@ -230,16 +309,16 @@ impl NtLife {
                         ImplFlags::SUCC_DEAD | ImplFlags::CONFLICT
                     };
 
-                    let possibly_dead = !self.impl_table[desc | 0b10].intersects(flag);
-                    let possibly_alive = !self.impl_table[desc | 0b01].intersects(flag);
+                    let possibly_dead = !impl_table[desc | 0b10].intersects(flag);
+                    let possibly_alive = !impl_table[desc | 0b01].intersects(flag);
 
                     let index = desc | succ_state << 2;
                     if possibly_dead && !possibly_alive {
-                        self.impl_table[index] |= ImplFlags::SELF_DEAD;
+                        impl_table[index] |= ImplFlags::SELF_DEAD;
                     } else if !possibly_dead && possibly_alive {
-                        self.impl_table[index] |= ImplFlags::SELF_ALIVE;
+                        impl_table[index] |= ImplFlags::SELF_ALIVE;
                     } else if !possibly_dead && !possibly_alive {
-                        self.impl_table[index] = ImplFlags::CONFLICT;
+                        impl_table[index] = ImplFlags::CONFLICT;
                     }
                 }
             }
@@ -250,6 +329,7 @@ impl NtLife {
 
     ///  Deduces the implication for the neighbors.
     fn init_impl_nbhd(mut self) -> Self {
+        let impl_table = Arc::make_mut(&mut self.impl_table);
         for unknowns in 1_usize..=0xff {
             // `n` runs through all the non-zero binary digits of `unknowns`.
             for n in (0..8).map(|i| 1 << i).filter(|n| unknowns & n != 0) {
@@ -268,17 +348,17 @@ impl NtLife {
                         let index = desc | succ_state << 2;
 
                         for state in 0..=2 {
-                            let possibly_dead = !self.impl_table[desc0 | state].intersects(flag);
-                            let possibly_alive = !self.impl_table[desc1 | state].intersects(flag);
+                            let possibly_dead = !impl_table[desc0 | state].intersects(flag);
+                            let possibly_alive = !impl_table[desc1 | state].intersects(flag);
 
                             if possibly_dead && !possibly_alive {
-                                self.impl_table[index | state] |=
+                                impl_table[index | state] |=
                                     ImplFlags::from_bits_retain((n.pow(2) << 7) as u32);
                             } else if !possibly_dead && possibly_alive {
-                                self.impl_table[index | state] |=
+                                impl_table[index | state] |=
                                     ImplFlags::from_bits_retain((n.pow(2) << 6) as u32);
                             } else if !possibly_dead && !possibly_alive {
-                                self.impl_table[index | state] = ImplFlags::CONFLICT;
+                                impl_table[index | state] = ImplFlags::CONFLICT;
                             }
                         }
                     }
@@ -290,6 +370,14 @@ impl NtLife {
     }
 }
 
+#[cfg(test)]
+impl NtLife {
+    /// Whether two rules share the same table.
+    pub(super) fn shares_table(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.impl_table, &other.impl_table)
+    }
+}
+
 /// A parser for the rule.
 impl ParseNtLife for NtLife {
     fn from_bs(b: Vec<u8>, s: Vec<u8>) -> Self {
@@ -345,6 +433,11 @@ impl Rule for NtLife {
         self.symmetry
     }
 
+    fn rule_string_canonical(&self) -> String {
+        let (b, s) = bs_from_table(&self.impl_table);
+        RuleKey::new(false, b, s, 2).to_string()
+    }
+
     #[inline]
     fn new_desc(state: State, succ_state: State) -> Self::Desc {
         let nbhd_state = match state {
@@ -388,59 +481,39 @@ impl Rule for NtLife {
         cell.desc.set(desc);
     }
 
+    #[cfg(any(debug_assertions, feature = "debug-invariants"))]
+    fn desc_from_states(
+        state: Option<State>,
+        succ_state: Option<State>,
+        nbhd: [Option<State>; 8],
+    ) -> Self::Desc {
+        let nbhd_state = nbhd
+            .iter()
+            .enumerate()
+            .fold(0, |n, (i, state)| match state {
+                Some(ALIVE) => n | 0x0001 << i,
+                Some(_) => n | 0x0100 << i,
+                None => n,
+            });
+        let bits = |state| match state {
+            Some(ALIVE) => 0b01,
+            Some(_) => 0b10,
+            None => 0b00,
+        };
+        NbhdDesc(nbhd_state << 4 | bits(succ_state) << 2 | bits(state))
+    }
+
+    #[inline]
+    fn is_conflict(&self, cell: CellRef<Self>) -> bool {
+        self.impl_table[cell.desc.get().0 as usize].contains(ImplFlags::CONFLICT)
+    }
+
     fn consistify<A: Algorithm<Self>>(
         world: &mut World<Self, A>,
         cell: CellRef<Self>,
     ) -> Result<(), A::ConflReason> {
         let flags = world.rule.impl_table[cell.desc.get().0 as usize];
-
-        if flags.is_empty() {
-            return Ok(());
-        }
-
-        if flags.contains(ImplFlags::CONFLICT) {
-            return Err(A::confl_from_cell(cell));
-        }
-
-        if flags.intersects(ImplFlags::SUCC) {
-            let state = if flags.contains(ImplFlags::SUCC_DEAD) {
-                DEAD
-            } else {
-                ALIVE
-            };
-            if let Some(succ) = cell.succ {
-                return world.set_cell(succ, state, A::Reason::from_cell(cell));
-            } else {
-                return Ok(());
-            }
-        }
-
-        if flags.intersects(ImplFlags::SELF) {
-            let state = if flags.contains(ImplFlags::SELF_DEAD) {
-                DEAD
-            } else {
-                ALIVE
-            };
-            world.set_cell(cell, state, A::Reason::from_cell(cell))?;
-        }
-
-        if flags.intersects(ImplFlags::NBHD) {
-            for (i, &neigh) in cell.nbhd.iter().enumerate() {
-                if flags.intersects(ImplFlags::from_bits_retain(3 << (2 * i + 6))) {
-                    if let Some(neigh) = neigh {
-                        let state = if flags.contains(ImplFlags::from_bits_retain(1 << (2 * i + 7)))
-                        {
-                            DEAD
-                        } else {
-                            ALIVE
-                        };
-                        world.set_cell(neigh, state, A::Reason::from_cell(cell))?;
-                    }
-                }
-            }
-        }
-
-        Ok(())
+        consistify(world, cell, flags)
     }
 }
 
@@ -466,7 +539,9 @@ pub struct NtLifeGen {
     /// The symmetry of the rule.
     symmetry: Symmetry,
     /// An array of actions for all neighborhood descriptors.
-    impl_table: Vec<ImplFlags>,
+    ///
+    /// It is shared between clones of the rule.
+    impl_table: Arc<[ImplFlags; 1 << 20]>,
 }
 
 impl NtLifeGen {
@@ -551,6 +626,11 @@ impl Rule for NtLifeGen {
         self.symmetry
     }
 
+    fn rule_string_canonical(&self) -> String {
+        let (b, s) = bs_from_table(&self.impl_table);
+        RuleKey::new(false, b, s, self.gen).to_string()
+    }
+
     #[inline]
     fn new_desc(state: State, succ_state: State) -> Self::Desc {
         let desc = NtLife::new_desc(state, succ_state);
@@ -584,111 +664,28 @@ impl Rule for NtLifeGen {
         cell.desc.set(desc);
     }
 
+    #[cfg(any(debug_assertions, feature = "debug-invariants"))]
+    fn desc_from_states(
+        state: Option<State>,
+        succ_state: Option<State>,
+        nbhd: [Option<State>; 8],
+    ) -> Self::Desc {
+        let desc = NtLife::desc_from_states(state, succ_state, nbhd);
+        NbhdDescGen(desc.0, succ_state)
+    }
+
+    #[inline]
+    fn is_conflict(&self, cell: CellRef<Self>) -> bool {
+        self.impl_table[cell.desc.get().0 as usize].contains(ImplFlags::CONFLICT)
+    }
+
     fn consistify<A: Algorithm<Self>>(
         world: &mut World<Self, A>,
         cell: CellRef<Self>,
     ) -> Result<(), A::ConflReason> {
         let desc = cell.desc.get();
         let flags = world.rule.impl_table[desc.0 as usize];
-        let gen = world.rule.gen;
-
-        match cell.state.get() {
-            Some(DEAD) => {
-                if let Some(State(j)) = desc.1 {
-                    if j >= 2 {
-                        return Err(A::confl_from_cell(cell));
-                    }
-                }
-
-                if flags.intersects(ImplFlags::SUCC) {
-                    let state = if flags.contains(ImplFlags::SUCC_DEAD) {
-                        DEAD
-                    } else {
-                        ALIVE
-                    };
-                    if let Some(succ) = cell.succ {
-                        return world.set_cell(succ, state, A::Reason::from_cell(cell));
-                    } else {
-                        return Ok(());
-                    }
-                }
-            }
-            Some(ALIVE) => {
-                if let Some(State(j)) = desc.1 {
-                    if j == 0 || j > 2 {
-                        return Err(A::confl_from_cell(cell));
-                    }
-                }
-                if flags.intersects(ImplFlags::SUCC) {
-                    let state = if flags.contains(ImplFlags::SUCC_DEAD) {
-                        State(2)
-                    } else {
-                        ALIVE
-                    };
-                    if let Some(succ) = cell.succ {
-                        return world.set_cell(succ, state, A::Reason::from_cell(cell));
-                    } else {
-                        return Ok(());
-                    }
-                }
-            }
-            Some(State(i)) => {
-                if let Some(State(j)) = desc.1 {
-                    if j == (i + 1) % gen {
-                        return Ok(());
-                    } else {
-                        return Err(A::confl_from_cell(cell));
-                    }
-                } else if let Some(succ) = cell.succ {
-                    return world.set_cell(succ, State((i + 1) % gen), A::Reason::from_cell(cell));
-                } else {
-                    return Ok(());
-                }
-            }
-            None => match desc.1 {
-                Some(DEAD) => {
-                    if flags.contains(ImplFlags::SELF_ALIVE) {
-                        return world.set_cell(cell, State(gen - 1), A::Reason::from_cell(cell));
-                    } else {
-                        return Ok(());
-                    }
-                }
-                Some(ALIVE) => {
-                    if flags.intersects(ImplFlags::SELF) {
-                        let state = if flags.contains(ImplFlags::SELF_DEAD) {
-                            DEAD
-                        } else {
-                            ALIVE
-                        };
-                        world.set_cell(cell, state, A::Reason::from_cell(cell))?;
-                    }
-                }
-                Some(State(j)) => {
-                    return world.set_cell(cell, State(j - 1), A::Reason::from_cell(cell));
-                }
-                None => return Ok(()),
-            },
-        }
-
-        if flags.is_empty() {
-            return Ok(());
-        }
-
-        if flags.contains(ImplFlags::CONFLICT) {
-            return Err(A::confl_from_cell(cell));
-        }
-
-        if flags.intersects(ImplFlags::NBHD) {
-            for (i, &neigh) in cell.nbhd.iter().enumerate() {
-                if flags.intersects(ImplFlags::from_bits_retain(1 << (2 * i + 6))) {
-                    if let Some(neigh) = neigh {
-                        world.set_cell(neigh, ALIVE, A::Reason::from_cell(cell))?;
-                    }
-                }
-            }
-        }
-
-        Ok(())
+        consistify_gen(world, cell, flags, desc.1)
     }
 }
 
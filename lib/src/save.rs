@@ -14,6 +14,11 @@ use std::time::Duration;
 
 use std::collections::BTreeMap;
 
+/// Used to skip serializing a `false` field.
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
 /// A representation of reasons for setting a cell which can be easily serialized.
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ReasonSer {
@@ -67,6 +72,15 @@ pub struct WorldSer {
     /// Number of conflicts during the search.
     pub conflicts: u64,
 
+    /// Number of conflicts near each edge of the world, in the order
+    /// top, bottom, left, right.
+    ///
+    /// See [`World::border_conflict_summary`].
+    pub border_conflicts: [u64; 4],
+
+    /// Number of steps walked during the search.
+    pub steps: u64,
+
     /// A stack to records the cells whose values are set during the search.
     ///
     /// The cells in this table always have known states.
@@ -79,6 +93,14 @@ pub struct WorldSer {
     /// otherwise rlifesrc might gives the wrong result.
     pub check_index: u32,
 
+    /// Whether the [`set_stack`](#structfield.set_stack) only records the cells
+    /// which cannot be deduced from the cells before them,
+    /// as saved by [`World::ser_compact`].
+    ///
+    /// The other cells are deduced again when the world is restored.
+    #[serde(skip_serializing_if = "is_false")]
+    pub compact: bool,
+
     /// Time used in searching. This field is handled by the frontend.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub timing: Option<Duration>,
@@ -88,30 +110,72 @@ pub struct WorldSer {
     pub extra: BTreeMap<String, String>,
 }
 
+impl SetCellSer {
+    /// Whether the cell is deduced from the cells before it in the set stack,
+    /// so that it can be left out in a [compact](WorldSer#structfield.compact) save.
+    fn is_deduced(&self) -> bool {
+        matches!(self.reason, ReasonSer::Rule(_) | ReasonSer::Sym(_))
+    }
+
+    /// Sets the cell in the world, unless it is already set to the same state.
+    fn deser<R: Rule, A: Algorithm<R>>(&self, world: &mut World<R, A>) -> Result<(), Error> {
+        let (coord, state) = (self.coord, self.state);
+        let cell = world.find_cell(coord).ok_or(Error::SetCellError(coord))?;
+        if let Some(old_state) = cell.state.get() {
+            if old_state != state {
+                return Err(Error::SetCellError(coord));
+            }
+        } else if state.0 as usize >= world.rule.gen() {
+            return Err(Error::InvalidState(coord, state));
+        } else {
+            let reason = A::deser_reason(world, &self.reason)?;
+            world.set_cell(cell, state, reason).ok();
+        }
+        Ok(())
+    }
+}
+
 impl WorldSer {
     /// Restores the world from the [`WorldSer`].
     pub fn deser<R: Rule, A: Algorithm<R>>(&self, world: &mut World<R, A>) -> Result<(), Error> {
-        for &SetCellSer {
-            coord,
-            state,
-            ref reason,
-        } in &self.set_stack
-        {
-            let cell = world.find_cell(coord).ok_or(Error::SetCellError(coord))?;
-            if let Some(old_state) = cell.state.get() {
-                if old_state != state {
-                    return Err(Error::SetCellError(coord));
-                }
-            } else if state.0 >= world.rule.gen() {
-                return Err(Error::InvalidState(coord, state));
-            } else {
-                let reason = A::deser_reason(world, reason)?;
-                world.set_cell(cell, state, reason).ok();
+        if self.compact {
+            // A result found with `reduce_max` may exceed the reduced bound,
+            // so the bound is only restored after the replay.
+            let max_cell_count = world.config.max_cell_count.take();
+            let result = self.replay(world);
+            world.config.max_cell_count = max_cell_count;
+            result?;
+        } else {
+            for set in &self.set_stack {
+                set.deser(world)?;
+            }
+            if self.check_index < self.set_stack.len() as u32 {
+                world.check_index = self.check_index;
             }
         }
+        // Skips the known cells, so that the search does not report
+        // the same result again when the world is saved right after a result.
+        world.get_unknown();
         world.conflicts = self.conflicts;
-        if self.check_index < self.set_stack.len() as u32 {
+        world.border_conflicts = self.border_conflicts;
+        world.steps = self.steps;
+        Ok(())
+    }
+
+    /// Replays the cells in a [compact](#structfield.compact) set stack,
+    /// deducing the other cells between them, as the search did.
+    fn replay<R: Rule, A: Algorithm<R>>(&self, world: &mut World<R, A>) -> Result<(), Error> {
+        for set in &self.set_stack {
+            if world.proceed().is_err() {
+                return Err(Error::SetCellError(set.coord));
+            }
+            set.deser(world)?;
+        }
+        // The search may have stopped before deducing the consequences of the last cell.
+        if self.check_index < world.set_stack.len() as u32 {
             world.check_index = self.check_index;
+        } else {
+            world.proceed().ok();
         }
         Ok(())
     }
@@ -131,13 +195,30 @@ impl<R: Rule, A: Algorithm<R>> World<R, A> {
         WorldSer {
             config: self.config.clone(),
             conflicts: self.conflicts,
+            border_conflicts: self.border_conflicts,
+            steps: self.steps,
             set_stack: self.set_stack.iter().map(SetCell::ser).collect(),
             check_index: self.check_index,
+            compact: false,
             timing: None,
             extra: BTreeMap::new(),
         }
     }
 
+    /// Saves the world as a [compact](WorldSer#structfield.compact) [`WorldSer`].
+    ///
+    /// Only the decisions and the cells deduced from conflicts are saved.
+    /// The cells deduced from the rule or the symmetry are left out, which
+    /// are usually most of the [set stack](WorldSer#structfield.set_stack).
+    /// They are deduced again when the world is restored, so restoring takes
+    /// longer, but the search goes on from exactly the same point.
+    pub fn ser_compact(&self) -> WorldSer {
+        let mut ser = self.ser();
+        ser.set_stack.retain(|set| !set.is_deduced());
+        ser.compact = true;
+        ser
+    }
+
     /// Restores the world from the [`WorldSer`].
     #[inline]
     pub fn deser(&mut self, ser: &WorldSer) -> Result<(), Error> {
@@ -5,7 +5,10 @@ use crate::{
     search::{private::Sealed, Algorithm, Reason as TraitReason, SetCell},
     world::World,
 };
-use educe::Educe;
+use std::{
+    fmt::{self, Debug, Formatter},
+    mem,
+};
 
 #[cfg(feature = "serde")]
 use crate::{error::Error, save::ReasonSer};
@@ -74,10 +77,8 @@ impl<R: Rule<IsGen = False>> Algorithm<R> for Backjump<R> {
             .reserve((world.config.width + world.config.height) as usize);
         for x in -1..=world.config.width {
             for y in -1..=world.config.height {
-                if let Some(d) = world.config.diagonal_width {
-                    if (x - y).abs() > d + 1 {
-                        continue;
-                    }
+                if !world.config.in_strips((x, y, 0), true) {
+                    continue;
                 }
                 for t in 0..world.config.period {
                     if let Some(cell) = world.find_cell((x, y, t)) {
@@ -112,6 +113,12 @@ impl<R: Rule<IsGen = False>> Algorithm<R> for Backjump<R> {
         world.retreat_impl()
     }
 
+    #[inline]
+    fn reset(world: &mut World<R, Self>) {
+        world.algo_data.level = 0;
+        world.algo_data.learnt.clear();
+    }
+
     #[cfg(feature = "serde")]
     #[cfg_attr(any(docs_rs, github_io), doc(cfg(feature = "serde")))]
     #[inline]
@@ -140,8 +147,6 @@ impl<R: Rule<IsGen = False>> Algorithm<R> for Backjump<R> {
 }
 
 /// Reasons for setting a cell, with informations for backjumping.
-#[derive(Educe)]
-#[educe(Clone, Debug, PartialEq, Eq)]
 pub enum Reason<R: Rule> {
     /// Known before the search starts,
     Known,
@@ -164,6 +169,46 @@ pub enum Reason<R: Rule> {
     Clause(Vec<CellRef<R>>),
 }
 
+impl<R: Rule> Clone for Reason<R> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Known => Self::Known,
+            Self::Decide => Self::Decide,
+            Self::Rule(cell) => Self::Rule(*cell),
+            Self::Sym(cell) => Self::Sym(*cell),
+            Self::Deduce => Self::Deduce,
+            Self::Clause(clause) => Self::Clause(clause.clone()),
+        }
+    }
+}
+
+impl<R: Rule> Debug for Reason<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Known => f.write_str("Known"),
+            Self::Decide => f.write_str("Decide"),
+            Self::Rule(cell) => f.debug_tuple("Rule").field(cell).finish(),
+            Self::Sym(cell) => f.debug_tuple("Sym").field(cell).finish(),
+            Self::Deduce => f.write_str("Deduce"),
+            Self::Clause(clause) => f.debug_tuple("Clause").field(clause).finish(),
+        }
+    }
+}
+
+impl<R: Rule> PartialEq for Reason<R> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Rule(cell0), Self::Rule(cell1)) | (Self::Sym(cell0), Self::Sym(cell1)) => {
+                cell0 == cell1
+            }
+            (Self::Clause(clause0), Self::Clause(clause1)) => clause0 == clause1,
+            _ => mem::discriminant(self) == mem::discriminant(other),
+        }
+    }
+}
+
+impl<R: Rule> Eq for Reason<R> {}
+
 impl<R: Rule> Reason<R> {
     /// Cells involved in the reason.
     fn cells(self) -> Vec<CellRef<R>> {
@@ -207,6 +252,27 @@ impl<R: Rule> TraitReason<R> for Reason<R> {
         matches!(self, Self::Decide)
     }
 
+    #[inline]
+    fn alternatives(&self, _gen: usize) -> Option<usize> {
+        match self {
+            Self::Decide => Some(1),
+            Self::Deduce | Self::Clause(_) => Some(0),
+            _ => None,
+        }
+    }
+
+    /// A decision whose other state is already tried is restored as
+    /// [`Reason::Deduce`], even if it was deduced from a learnt clause.
+    /// Then the search only backtracks chronologically from it.
+    #[inline]
+    fn from_alternatives(alternatives: usize, _gen: usize) -> Self {
+        if alternatives == 0 {
+            Self::Deduce
+        } else {
+            Self::Decide
+        }
+    }
+
     #[cfg(feature = "serde")]
     #[cfg_attr(any(docs_rs, github_io), doc(cfg(feature = "serde")))]
     #[inline]
@@ -223,8 +289,6 @@ impl<R: Rule> TraitReason<R> for Reason<R> {
 }
 
 /// Reasons for a conflict.
-#[derive(Educe)]
-#[educe(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ConflReason<R: Rule> {
     /// Conflict from the rule when constitifying another cell.
     Rule(CellRef<R>),
@@ -235,12 +299,50 @@ pub enum ConflReason<R: Rule> {
     /// Conflict from non-empty-front condition.
     Front,
 
+    /// Conflict from the [non-empty set](crate::World#structfield.non_empty_sets)
+    /// with this index.
+    NonEmptySet(usize),
+
     /// Conflict from other conditions.
     ///
     /// A general reason used as a fallback.
     Deduce,
 }
 
+impl<R: Rule> Clone for ConflReason<R> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<R: Rule> Copy for ConflReason<R> {}
+
+impl<R: Rule> Debug for ConflReason<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Rule(cell) => f.debug_tuple("Rule").field(cell).finish(),
+            Self::Sym(cell0, cell1) => f.debug_tuple("Sym").field(cell0).field(cell1).finish(),
+            Self::Front => f.write_str("Front"),
+            Self::NonEmptySet(i) => f.debug_tuple("NonEmptySet").field(i).finish(),
+            Self::Deduce => f.write_str("Deduce"),
+        }
+    }
+}
+
+impl<R: Rule> PartialEq for ConflReason<R> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Rule(cell0), Self::Rule(cell1)) => cell0 == cell1,
+            (Self::Sym(cell0, cell1), Self::Sym(cell2, cell3)) => cell0 == cell2 && cell1 == cell3,
+            (Self::NonEmptySet(i0), Self::NonEmptySet(i1)) => i0 == i1,
+            _ => mem::discriminant(self) == mem::discriminant(other),
+        }
+    }
+}
+
+impl<R: Rule> Eq for ConflReason<R> {}
+
 impl<R: Rule> ConflReason<R> {
     /// Whether this reason should be analyzed before retreating.
     #[inline]
@@ -273,6 +375,10 @@ impl<R: Rule<IsGen = False>> World<R, Backjump<R>> {
                 .algo_data
                 .learnt
                 .extend_from_slice(&self.algo_data.front),
+            ConflReason::NonEmptySet(index) => self
+                .algo_data
+                .learnt
+                .extend_from_slice(&self.non_empty_sets[index].cells),
             ConflReason::Deduce => unreachable!(),
         }
     }
@@ -284,7 +390,9 @@ impl<R: Rule<IsGen = False>> World<R, Backjump<R>> {
     /// The original state of the cell must be unknown.
     ///
     /// Return `false` if the number of living cells exceeds the
-    /// [`max_cell_count`](#structfield.max_cell_count) or the front becomes empty.
+    /// [`max_cell_count`](#structfield.max_cell_count) or the
+    /// [`max_cell_count_per_gen`](crate::Config#structfield.max_cell_count_per_gen),
+    /// or the front becomes empty.
     pub(crate) fn set_cell_impl(
         &mut self,
         cell: CellRef<R>,
@@ -294,10 +402,18 @@ impl<R: Rule<IsGen = False>> World<R, Backjump<R>> {
         cell.state.set(Some(state));
         let mut result = Ok(());
         cell.update_desc(state, true);
+        self.unknown_count[cell.coord.2 as usize] -= 1;
         if state == !cell.background {
             self.cell_count[cell.coord.2 as usize] += 1;
             if let Some(max) = self.config.max_cell_count {
-                if self.cell_count() > max {
+                if self.target_cell_count() > max {
+                    self.max_cell_count_conflicts += 1;
+                    result = Err(ConflReason::Deduce);
+                }
+            }
+            if let Some(max) = self.config.max_cell_count_per_gen {
+                if self.cell_count[cell.coord.2 as usize] > max {
+                    self.max_cell_count_conflicts += 1;
                     result = Err(ConflReason::Deduce);
                 }
             }
@@ -312,11 +428,25 @@ impl<R: Rule<IsGen = False>> World<R, Backjump<R>> {
                 };
             }
         }
+        if state == cell.background && cell.non_empty_list != 0 {
+            for &index in &self.non_empty_lists[cell.non_empty_list as usize] {
+                let set = &mut self.non_empty_sets[index];
+                set.count -= 1;
+                if set.count == 0 {
+                    result = if matches!(reason, Reason::Deduce) {
+                        Err(ConflReason::Deduce)
+                    } else {
+                        Err(ConflReason::NonEmptySet(index))
+                    };
+                }
+            }
+        }
         if reason.is_decided() {
             self.algo_data.level += 1;
         }
         cell.level.set(self.algo_data.level);
         self.set_stack.push(SetCell::new(cell, reason));
+        self.max_depth = self.max_depth.max(self.set_stack.len());
         result
     }
 
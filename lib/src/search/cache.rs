@@ -0,0 +1,395 @@
+//! A transposition cache of refuted search states.
+use crate::{
+    cells::{CellRef, Coord, State},
+    config::{Config, SearchOrder},
+    rules::Rule,
+    search::Algorithm,
+    world::World,
+};
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeMap, HashMap},
+    hash::Hasher,
+};
+
+/// Statistics of the transposition cache.
+///
+/// See [`Config::transposition_cache`](Config#structfield.transposition_cache).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CacheStats {
+    /// Number of times that the current state is found in the cache,
+    /// so that it is refuted without searching.
+    pub hits: u64,
+
+    /// Number of times that the current state is not found in the cache.
+    pub misses: u64,
+
+    /// Number of refuted states currently in the cache.
+    pub len: usize,
+}
+
+/// How to divide the cells into lines according to the search order.
+///
+/// The cache is only consulted when the search enters a new line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Lines {
+    /// Columns, for [`SearchOrder::ColumnFirst`].
+    Column,
+    /// Rows, for [`SearchOrder::RowFirst`].
+    Row,
+    /// Diagonals, for [`SearchOrder::Diagonal`].
+    Diagonal,
+    /// All the generations of a single cell, for [`SearchOrder::FromVec`].
+    Cell(i32),
+}
+
+impl Lines {
+    /// The line containing the cell.
+    #[inline]
+    const fn line(self, (x, y, _): Coord) -> i32 {
+        match self {
+            Self::Column => x,
+            Self::Row => y,
+            Self::Diagonal => x + y,
+            Self::Cell(width) => y * width + x,
+        }
+    }
+}
+
+/// A point in the search tree where the cache was consulted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Node {
+    /// The key of the state at this point.
+    key: u128,
+
+    /// The line of the cell to be decided.
+    line: i32,
+
+    /// Length of the [`set_stack`](World#structfield.set_stack) at this point.
+    depth: usize,
+
+    /// The value of [`taints`](TranspositionCache#structfield.taints) at this point.
+    taints: u64,
+}
+
+/// A capacity-bounded LRU cache of refuted search states.
+///
+/// A state is identified by the set of unknown cells, together with the states
+/// of all known cells that share a neighborhood (including the successor) with
+/// some unknown cell. Other known cells can no longer affect the rest of the search.
+///
+/// A state is stored only when the whole subtree below it was refuted by conflicts
+/// that only depend on these cells. When a result is reached, or a conflict comes
+/// from [`max_cell_count`](Config#structfield.max_cell_count) or the nonempty front,
+/// all states on the current branch are tainted and will not be stored.
+#[derive(Clone, Debug)]
+pub(crate) struct TranspositionCache {
+    /// How to divide the cells into lines.
+    lines: Lines,
+
+    /// The maximal number of states in the cache.
+    capacity: usize,
+
+    /// The refuted states, with the time they were last used.
+    table: HashMap<u128, u64>,
+
+    /// The refuted states, ordered by the time they were last used.
+    lru: BTreeMap<u64, u128>,
+
+    /// A counter for the LRU order.
+    clock: u64,
+
+    /// Points on the current branch where the cache was consulted.
+    nodes: Vec<Node>,
+
+    /// Number of events that prevent the current branch from being stored.
+    taints: u64,
+
+    /// Number of hits and misses.
+    stats: CacheStats,
+
+    /// Number of generations.
+    period: usize,
+
+    /// Number of cells in a column, including those outside the world,
+    /// in all generations.
+    column_len: usize,
+
+    /// The index of the successor of each cell, or [`u32::MAX`] if there is none.
+    ///
+    /// It is computed when the cache is first consulted.
+    succs: Vec<u32>,
+
+    /// Reused buffers for computing the keys.
+    ///
+    /// The masks use `u8` instead of `bool` so that the loops can be vectorized.
+    codes: Vec<u32>,
+    unknown: Vec<u8>,
+    active: Vec<u8>,
+    in_key: Vec<u8>,
+    temp: Vec<u8>,
+    buffer: Vec<u8>,
+}
+
+impl TranspositionCache {
+    /// Creates a new cache with the given capacity.
+    pub(crate) fn new(capacity: usize, search_order: &SearchOrder, config: &Config) -> Self {
+        let lines = match search_order {
+            SearchOrder::ColumnFirst => Lines::Column,
+            SearchOrder::RowFirst => Lines::Row,
+            SearchOrder::Diagonal => Lines::Diagonal,
+            SearchOrder::FromVec(_) => Lines::Cell(config.width),
+        };
+        Self {
+            lines,
+            capacity,
+            table: HashMap::new(),
+            lru: BTreeMap::new(),
+            clock: 0,
+            nodes: Vec::new(),
+            taints: 0,
+            stats: CacheStats::default(),
+            period: config.period as usize,
+            column_len: ((config.height + 2) * config.period) as usize,
+            succs: Vec::new(),
+            codes: Vec::new(),
+            unknown: Vec::new(),
+            active: Vec::new(),
+            in_key: Vec::new(),
+            temp: Vec::new(),
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Looks up a state, and marks it as recently used.
+    fn get(&mut self, key: u128) -> bool {
+        if let Some(time) = self.table.get_mut(&key) {
+            self.lru.remove(time);
+            self.clock += 1;
+            *time = self.clock;
+            self.lru.insert(self.clock, key);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Stores a refuted state, evicting the least recently used one if full.
+    fn insert(&mut self, key: u128) {
+        if self.get(key) {
+            return;
+        }
+        if self.table.len() >= self.capacity {
+            if let Some((_, old_key)) = self.lru.pop_first() {
+                self.table.remove(&old_key);
+            }
+        }
+        self.clock += 1;
+        self.table.insert(key, self.clock);
+        self.lru.insert(self.clock, key);
+    }
+}
+
+/// Computes the cells in the same generation whose distance to some cell in `mask`
+/// is at most 1, i.e., the cells themselves and their neighbors, and writes them to `out`.
+///
+/// The cells are stored column by column, and generation by generation in each column,
+/// so the neighbors in the same column are `period` apart, and the neighbors in
+/// the same row are `column_len` apart.
+fn dilate(mask: &[u8], out: &mut Vec<u8>, temp: &mut Vec<u8>, column_len: usize, period: usize) {
+    /// Adds the cells at distance `shift` in each block.
+    fn dilate_blocks(mask: &[u8], out: &mut [u8], block: usize, shift: usize) {
+        for (mask, out) in mask.chunks(block).zip(out.chunks_mut(block)) {
+            out.copy_from_slice(mask);
+            if shift < block {
+                let len = block - shift;
+                for (o, &m) in out[shift..].iter_mut().zip(&mask[..len]) {
+                    *o |= m;
+                }
+                for (o, &m) in out[..len].iter_mut().zip(&mask[shift..]) {
+                    *o |= m;
+                }
+            }
+        }
+    }
+    let len = mask.len();
+    temp.resize(len, 0);
+    out.resize(len, 0);
+    dilate_blocks(mask, temp, column_len, period);
+    dilate_blocks(temp, out, len, column_len);
+}
+
+impl<R: Rule, A: Algorithm<R>> World<R, A> {
+    /// Computes the key of the current state.
+    ///
+    /// The key is a hash of the unknown cells, and the known cells that share
+    /// a neighborhood (including the successor) with some unknown cell,
+    /// together with their states.
+    fn cache_key(&self, cache: &mut TranspositionCache) -> u128 {
+        if cache.succs.is_empty() {
+            let (height, period) = (self.config.height, self.config.period);
+            cache.succs = self
+                .cell_refs()
+                .map(|cell| {
+                    cell.succ.map_or(u32::MAX, |succ| {
+                        let (x, y, t) = succ.coord;
+                        (((x + 1) * (height + 2) + y + 1) * period + t) as u32
+                    })
+                })
+                .collect();
+        }
+
+        let TranspositionCache {
+            period,
+            column_len,
+            succs,
+            codes,
+            unknown,
+            active,
+            in_key,
+            temp,
+            buffer,
+            ..
+        } = cache;
+
+        // Each cell is encoded as 1 if unknown, and 2 plus its state if known.
+        codes.clear();
+        codes.extend(
+            self.cell_refs()
+                .map(|cell| cell.state.get().map_or(1, |State(j)| j as u32 + 2)),
+        );
+        unknown.clear();
+        unknown.extend(codes.iter().map(|&code| (code == 1) as u8));
+
+        // The cells whose neighborhoods (including the successors) contain unknown cells.
+        dilate(unknown, active, temp, *column_len, *period);
+        for (a, &succ) in active.iter_mut().zip(succs.iter()) {
+            if succ != u32::MAX {
+                *a |= unknown[succ as usize];
+            }
+        }
+
+        // The cells in the neighborhoods of those cells.
+        dilate(active, in_key, temp, *column_len, *period);
+        for (&a, &succ) in active.iter().zip(succs.iter()) {
+            if a != 0 && succ != u32::MAX {
+                in_key[succ as usize] = 1;
+            }
+        }
+
+        // Cells not in the key are encoded as 0.
+        let wide = self.rule.gen() > u8::MAX as usize - 2;
+        buffer.clear();
+        for (&code, &k) in codes.iter().zip(in_key.iter()) {
+            let code = if k != 0 { code } else { 0 };
+            if wide {
+                buffer.extend(code.to_le_bytes());
+            } else {
+                buffer.push(code as u8);
+            }
+        }
+
+        let mut hashers = [DefaultHasher::new(), DefaultHasher::new()];
+        hashers[1].write_u8(1);
+        for hasher in &mut hashers {
+            hasher.write(buffer);
+        }
+        let [low, high] = hashers.map(|hasher| hasher.finish());
+        (high as u128) << 64 | low as u128
+    }
+
+    /// Consults the transposition cache before deciding the state of `cell`.
+    ///
+    /// Returns `true` if the current state is known to be refuted.
+    ///
+    /// To save time, the cache is only consulted when the search enters
+    /// a new line of the search order.
+    pub(crate) fn is_refuted(&mut self, cell: CellRef<R>) -> bool {
+        let mut cache = match self.cache.take() {
+            Some(cache) => cache,
+            None => return false,
+        };
+        let line = cache.lines.line(cell.coord);
+        let refuted = if cache.nodes.last().map(|node| node.line) == Some(line) {
+            false
+        } else {
+            let key = self.cache_key(&mut cache);
+            if cache.get(key) {
+                cache.stats.hits += 1;
+                true
+            } else {
+                cache.stats.misses += 1;
+                cache.nodes.push(Node {
+                    key,
+                    line,
+                    depth: self.set_stack.len(),
+                    taints: cache.taints,
+                });
+                false
+            }
+        };
+        self.cache = Some(cache);
+        refuted
+    }
+
+    /// Closes the points on the branch which have been exhausted after a retreat,
+    /// and stores the refuted states.
+    ///
+    /// A point is exhausted when some cell set before it is cleared or changed,
+    /// i.e., when the [`set_stack`](#structfield.set_stack) is not longer than it was.
+    pub(crate) fn close_cache_nodes(&mut self, retreated: bool) {
+        let depth = if retreated { self.set_stack.len() } else { 0 };
+        if let Some(cache) = &mut self.cache {
+            while let Some(&node) = cache.nodes.last() {
+                if node.depth < depth {
+                    break;
+                }
+                cache.nodes.pop();
+                if node.taints == cache.taints {
+                    cache.insert(node.key);
+                }
+            }
+        }
+    }
+
+    /// Drops the points on the current branch without storing them,
+    /// when the search is [reset](Self::reset) before they are exhausted.
+    pub(crate) fn drop_cache_nodes(&mut self) {
+        if let Some(cache) = &mut self.cache {
+            cache.nodes.clear();
+        }
+    }
+
+    /// The number of times the cache has been tainted,
+    /// or `None` if the cache is not enabled.
+    pub(crate) fn cache_taints(&self) -> Option<u64> {
+        self.cache.as_ref().map(|cache| cache.taints)
+    }
+
+    /// Restores the number of times the cache has been tainted,
+    /// as returned by [`cache_taints`](Self::cache_taints).
+    pub(crate) fn restore_cache_taints(&mut self, taints: Option<u64>) {
+        if let (Some(cache), Some(taints)) = (&mut self.cache, taints) {
+            cache.taints = taints;
+        }
+    }
+
+    /// Prevents all the states on the current branch from being stored.
+    #[inline]
+    pub(crate) fn taint_cache(&mut self) {
+        if let Some(cache) = &mut self.cache {
+            cache.taints += 1;
+        }
+    }
+
+    /// Statistics of the transposition cache.
+    ///
+    /// Returns `None` if the cache is not enabled.
+    /// See [`Config::transposition_cache`](Config#structfield.transposition_cache).
+    #[inline]
+    pub fn cache_stats(&self) -> Option<CacheStats> {
+        self.cache.as_ref().map(|cache| CacheStats {
+            len: cache.table.len(),
+            ..cache.stats
+        })
+    }
+}
@@ -1,10 +1,15 @@
 //! The search process, without backjumping.
 use crate::{
-    cells::{CellRef, State},
+    cells::{CellRef, State, StateRepr},
     rules::{typebool::Bool, Rule},
     search::{private::Sealed, Algorithm, Reason as TraitReason, SetCell},
     world::World,
 };
+use std::{
+    fmt::{self, Debug, Formatter},
+    hash::{Hash, Hasher},
+    mem,
+};
 
 #[cfg(feature = "serde")]
 use crate::{error::Error, save::ReasonSer};
@@ -20,9 +25,13 @@ pub struct LifeSrc;
 impl Sealed for LifeSrc {}
 
 impl<R: Rule> Algorithm<R> for LifeSrc {
-    type Reason = Reason;
+    type Reason = Reason<R>;
 
-    type ConflReason = ();
+    /// An upper bound of the [levels](LifeCell#structfield.level) of the decisions
+    /// the conflict depends on, or `None` if it depends on the whole world.
+    ///
+    /// Only meaningful when [`RetreatStrategy::ConflictDirected`] is used.
+    type ConflReason = Option<u32>;
 
     #[inline]
     fn new() -> Self {
@@ -30,10 +39,14 @@ impl<R: Rule> Algorithm<R> for LifeSrc {
     }
 
     #[inline]
-    fn confl_from_cell(_cell: CellRef<R>) -> Self::ConflReason {}
+    fn confl_from_cell(cell: CellRef<R>) -> Self::ConflReason {
+        Some(involved_level(cell))
+    }
 
     #[inline]
-    fn confl_from_sym(_cell: CellRef<R>, _sym: CellRef<R>) -> Self::ConflReason {}
+    fn confl_from_sym(cell: CellRef<R>, sym: CellRef<R>) -> Self::ConflReason {
+        Some(cell.level.get().max(sym.level.get()))
+    }
 
     #[inline]
     fn init_front(world: World<R, Self>) -> World<R, Self> {
@@ -60,29 +73,43 @@ impl<R: Rule> Algorithm<R> for LifeSrc {
         world.retreat_impl()
     }
 
+    #[inline]
+    fn reset(_world: &mut World<R, Self>) {}
+
     #[cfg(feature = "serde")]
     #[cfg_attr(any(docs_rs, github_io), doc(cfg(feature = "serde")))]
     #[inline]
-    fn deser_reason(_world: &World<R, Self>, ser: &ReasonSer) -> Result<Self::Reason, Error> {
+    fn deser_reason(world: &World<R, Self>, ser: &ReasonSer) -> Result<Self::Reason, Error> {
         Ok(match *ser {
             ReasonSer::Known => Reason::Known,
             ReasonSer::Decide => Reason::Decide,
             ReasonSer::TryAnother(n) => Reason::TryAnother(n),
+            ReasonSer::Rule(coord) => {
+                Reason::Rule(world.find_cell(coord).ok_or(Error::SetCellError(coord))?)
+            }
+            ReasonSer::Sym(coord) => {
+                Reason::Sym(world.find_cell(coord).ok_or(Error::SetCellError(coord))?)
+            }
             _ => Reason::Deduce,
         })
     }
 }
 
 /// Reasons for setting a cell.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
-pub enum Reason {
+pub enum Reason<R: Rule> {
     /// Known before the search starts,
     Known,
 
     /// Decides the state of a cell by choice.
     Decide,
 
-    /// Determines the state of a cell by other cells.
+    /// Deduced from the rule when constitifying another cell.
+    Rule(CellRef<R>),
+
+    /// Deduced from symmetry.
+    Sym(CellRef<R>),
+
+    /// Determines the state of a cell by conflicts.
     Deduce,
 
     /// Tries another state of a cell when the original state
@@ -94,18 +121,66 @@ pub enum Reason {
     TryAnother(usize),
 }
 
-impl<R: Rule> TraitReason<R> for Reason {
+// Not derived, as the derives would add bounds on `R`.
+impl<R: Rule> Clone for Reason<R> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<R: Rule> Copy for Reason<R> {}
+
+impl<R: Rule> Debug for Reason<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Known => f.write_str("Known"),
+            Self::Decide => f.write_str("Decide"),
+            Self::Rule(cell) => f.debug_tuple("Rule").field(cell).finish(),
+            Self::Sym(cell) => f.debug_tuple("Sym").field(cell).finish(),
+            Self::Deduce => f.write_str("Deduce"),
+            Self::TryAnother(n) => f.debug_tuple("TryAnother").field(n).finish(),
+        }
+    }
+}
+
+impl<R: Rule> PartialEq for Reason<R> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Rule(cell0), Self::Rule(cell1)) | (Self::Sym(cell0), Self::Sym(cell1)) => {
+                cell0 == cell1
+            }
+            (Self::TryAnother(n0), Self::TryAnother(n1)) => n0 == n1,
+            _ => mem::discriminant(self) == mem::discriminant(other),
+        }
+    }
+}
+
+impl<R: Rule> Eq for Reason<R> {}
+
+impl<R: Rule> Hash for Reason<R> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        mem::discriminant(self).hash(state);
+        match self {
+            Self::Rule(cell) | Self::Sym(cell) => cell.hash(state),
+            Self::TryAnother(n) => n.hash(state),
+            _ => (),
+        }
+    }
+}
+
+impl<R: Rule> TraitReason<R> for Reason<R> {
     const KNOWN: Self = Self::Known;
     const DECIDED: Self = Self::Decide;
 
     #[inline]
-    fn from_cell(_cell: CellRef<R>) -> Self {
-        Self::Deduce
+    fn from_cell(cell: CellRef<R>) -> Self {
+        Self::Rule(cell)
     }
 
     #[inline]
-    fn from_sym(_cell: CellRef<R>) -> Self {
-        Self::Deduce
+    fn from_sym(cell: CellRef<R>) -> Self {
+        Self::Sym(cell)
     }
 
     #[inline]
@@ -113,6 +188,25 @@ impl<R: Rule> TraitReason<R> for Reason {
         matches!(self, Self::Decide | Self::TryAnother(_))
     }
 
+    #[inline]
+    fn alternatives(&self, gen: usize) -> Option<usize> {
+        match *self {
+            Self::Decide => Some(gen - 1),
+            Self::TryAnother(n) => Some(n),
+            Self::Deduce => Some(0),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn from_alternatives(alternatives: usize, gen: usize) -> Self {
+        match alternatives {
+            0 => Self::Deduce,
+            n if n == gen - 1 => Self::Decide,
+            n => Self::TryAnother(n),
+        }
+    }
+
     #[cfg(feature = "serde")]
     #[cfg_attr(any(docs_rs, github_io), doc(cfg(feature = "serde")))]
     #[inline]
@@ -120,12 +214,29 @@ impl<R: Rule> TraitReason<R> for Reason {
         match self {
             Self::Known => ReasonSer::Known,
             Self::Decide => ReasonSer::Decide,
+            Self::Rule(cell) => ReasonSer::Rule(cell.coord),
+            Self::Sym(cell) => ReasonSer::Sym(cell.coord),
             Self::Deduce => ReasonSer::Deduce,
             Self::TryAnother(n) => ReasonSer::TryAnother(*n),
         }
     }
 }
 
+/// The maximal [level](LifeCell#structfield.level) of the known cells involved
+/// when consistifying a cell, i.e., the cell, its successor and its neighbors.
+#[inline]
+fn involved_level<R: Rule>(cell: CellRef<R>) -> u32 {
+    cell.nbhd
+        .iter()
+        .chain(Some(&cell.succ))
+        .flatten()
+        .chain(Some(&cell))
+        .filter(|cell| cell.state.get().is_some())
+        .map(|cell| cell.level.get())
+        .max()
+        .unwrap_or(0)
+}
+
 impl<R: Rule> World<R, LifeSrc> {
     /// Sets the [`state`](LifeCell#structfield.state) of a cell,
     /// push it to the [`set_stack`](#structfield.set_stack),
@@ -134,31 +245,82 @@ impl<R: Rule> World<R, LifeSrc> {
     /// The original state of the cell must be unknown.
     ///
     /// Return `false` if the number of living cells exceeds the
-    /// [`max_cell_count`](#structfield.max_cell_count) or the front becomes empty.
+    /// [`max_cell_count`](#structfield.max_cell_count) or the
+    /// [`max_cell_count_per_gen`](crate::Config#structfield.max_cell_count_per_gen),
+    /// the first generation can no longer have the
+    /// [`min_cell_count`](crate::Config#structfield.min_cell_count),
+    /// the front becomes empty, a generation becomes known with a wrong
+    /// [population parity](crate::Config#structfield.population_parity),
+    /// or a row can no longer have the right
+    /// [number of living cells](crate::Config#structfield.row_live_counts).
     pub(crate) fn set_cell_impl(
         &mut self,
         cell: CellRef<R>,
         state: State,
-        reason: Reason,
-    ) -> Result<(), ()> {
+        reason: Reason<R>,
+    ) -> Result<(), Option<u32>> {
+        if self.conflict_directed {
+            let level = match reason {
+                Reason::Known => 0,
+                Reason::Decide | Reason::TryAnother(_) => self.set_stack.len() as u32 + 1,
+                Reason::Rule(cause) => involved_level(cause),
+                Reason::Sym(cause) => cause.level.get(),
+                Reason::Deduce => self.set_stack.len() as u32,
+            };
+            cell.level.set(level);
+        }
         cell.state.set(Some(state));
         let mut result = Ok(());
         cell.update_desc(state, true);
+        self.unknown_count[cell.coord.2 as usize] -= 1;
         if state == !cell.background {
             self.cell_count[cell.coord.2 as usize] += 1;
             if let Some(max) = self.config.max_cell_count {
-                if self.cell_count() > max {
-                    result = Err(());
+                if self.target_cell_count() > max {
+                    self.max_cell_count_conflicts += 1;
+                    result = Err(None);
+                }
+            }
+            if let Some(max) = self.config.max_cell_count_per_gen {
+                if self.cell_count[cell.coord.2 as usize] > max {
+                    self.max_cell_count_conflicts += 1;
+                    result = Err(None);
+                }
+            }
+        } else if cell.coord.2 == 0 {
+            if let Some(min) = self.config.min_cell_count {
+                if self.cell_count[0] + self.unknown_count[0] < min {
+                    result = Err(None);
                 }
             }
         }
+        if self.is_parity_wrong_gen(cell.coord.2 as usize) {
+            result = Err(None);
+        }
+        if !self.count_row_cell(cell, state) {
+            result = Err(None);
+        }
         if cell.is_front && state == cell.background {
             self.front_cell_count -= 1;
             if self.non_empty_front && self.front_cell_count == 0 {
-                result = Err(());
+                result = Err(None);
+            }
+        }
+        if state == cell.background && cell.non_empty_list != 0 {
+            for &index in &self.non_empty_lists[cell.non_empty_list as usize] {
+                let set = &mut self.non_empty_sets[index];
+                set.count -= 1;
+                if set.count == 0 {
+                    result = Err(None);
+                }
             }
         }
+        if result.is_err() {
+            // These conflicts depend on the whole world.
+            self.taint_cache();
+        }
         self.set_stack.push(SetCell::new(cell, reason));
+        self.max_depth = self.max_depth.max(self.set_stack.len());
         result
     }
 
@@ -173,10 +335,11 @@ impl<R: Rule> World<R, LifeSrc> {
                 Reason::Decide => {
                     let (state, reason) = if R::IsGen::VALUE {
                         let State(j) = cell.state.get().unwrap();
-                        (
-                            State((j + 1) % self.rule.gen()),
-                            Reason::TryAnother(self.rule.gen() - 2),
-                        )
+                        let reason = match self.rule.gen() - 2 {
+                            0 => Reason::Deduce,
+                            n => Reason::TryAnother(n),
+                        };
+                        (State((j + 1) % self.rule.gen() as StateRepr), reason)
                     } else {
                         (!cell.state.get().unwrap(), Reason::Deduce)
                     };
@@ -190,7 +353,7 @@ impl<R: Rule> World<R, LifeSrc> {
                 }
                 Reason::TryAnother(n) => {
                     let State(j) = cell.state.get().unwrap();
-                    let state = State((j + 1) % self.rule.gen());
+                    let state = State((j + 1) % self.rule.gen() as StateRepr);
                     let reason = if n == 1 {
                         Reason::Deduce
                     } else {
@@ -207,7 +370,7 @@ impl<R: Rule> World<R, LifeSrc> {
                 Reason::Known => {
                     break;
                 }
-                Reason::Deduce => {
+                _ => {
                     self.clear_cell(cell);
                 }
             }
@@ -228,14 +391,41 @@ impl<R: Rule> World<R, LifeSrc> {
     fn go(&mut self, step: &mut u64) -> bool {
         loop {
             *step += 1;
-            if self.proceed().is_ok() {
-                return true;
-            } else {
-                self.conflicts += 1;
-                if !self.retreat_impl() {
-                    return false;
+            match self.proceed() {
+                Ok(()) => return true,
+                Err(level) => {
+                    self.conflicts += 1;
+                    if let Some(level) = level.filter(|_| self.conflict_directed) {
+                        self.skip_decisions_above(level);
+                    }
+                    if !self.retreat() {
+                        return false;
+                    }
                 }
             }
         }
     }
+
+    /// Clears the cells set after the last decision whose
+    /// [level](LifeCell#structfield.level) is at most `level`,
+    /// without trying the other states of the decisions among them.
+    ///
+    /// A decision at position `i` of the [`set_stack`](#structfield.set_stack)
+    /// has level `i + 1`, and every other cell has a level no larger than
+    /// its position. So a conflict whose level is `level` does not depend on
+    /// the cells at position `level` or above, and would still happen
+    /// if they were cleared.
+    ///
+    /// The next [`retreat`](Self::retreat) then switches that last decision.
+    fn skip_decisions_above(&mut self, level: u32) {
+        let level = level as usize;
+        match self.set_stack.get(level..) {
+            Some(skipped) if skipped.iter().any(|s| s.reason.is_decided()) => (),
+            _ => return,
+        }
+        while self.set_stack.len() > level {
+            let SetCell { cell, .. } = self.set_stack.pop().unwrap();
+            self.clear_cell(cell);
+        }
+    }
 }
@@ -1,15 +1,21 @@
 //! The searching algorithms.
 
 use crate::{
-    cells::{CellRef, State},
-    config::NewState,
+    cells::{CellRef, State, StateRepr, ALIVE, DEAD},
+    config::{NewState, Symmetry, Transform},
     rules::Rule,
+    snapshot::{ResultInfo, Solution},
     world::World,
 };
 use rand::{thread_rng, Rng};
+use std::{
+    array, iter,
+    ops::ControlFlow,
+    time::{Duration, Instant},
+};
 
 #[cfg(doc)]
-use crate::cells::LifeCell;
+use crate::{cells::LifeCell, config::Config};
 
 #[cfg(feature = "serde")]
 use crate::{
@@ -20,12 +26,24 @@ use crate::{
 use serde::{Deserialize, Serialize};
 
 mod backjump;
+mod cache;
 mod lifesrc;
 
 pub use backjump::Backjump;
+pub use cache::CacheStats;
+pub(crate) use cache::TranspositionCache;
 pub use lifesrc::LifeSrc;
 pub(crate) use reason::Reason;
 
+/// Number of steps between two calls of the callback in
+/// [`search_with`](World::search_with).
+///
+/// A step takes at least tens of nanoseconds, so a cheap callback such as
+/// reading the clock in [`search_timeout`](World::search_timeout)
+/// costs much less than 1% of the time, and a deadline is overrun
+/// by at most a few milliseconds in typical searches.
+const CALLBACK_STEPS: u64 = 10_000;
+
 /// Reasons for setting a cell.
 mod reason {
     use crate::{cells::CellRef, rules::Rule};
@@ -50,6 +68,19 @@ mod reason {
         /// Decided or trying another state for generations rules.
         fn is_decided(&self) -> bool;
 
+        /// The number of other states still to try, if the cell was set at a
+        /// decision, either by choice or because the states tried before
+        /// led to conflicts.
+        ///
+        /// Returns `None` if the state of the cell is deduced from other cells.
+        fn alternatives(&self, gen: usize) -> Option<usize>;
+
+        /// The reason for setting a cell at a decision,
+        /// with `alternatives` other states still to try.
+        ///
+        /// The inverse of [`alternatives`](Self::alternatives).
+        fn from_alternatives(alternatives: usize, gen: usize) -> Self;
+
         #[cfg(feature = "serde")]
         #[cfg_attr(any(docs_rs, github_io), doc(cfg(feature = "serde")))]
         /// Saves the reason as a [`ReasonSer`].
@@ -71,6 +102,37 @@ pub enum Status {
     Searching,
 }
 
+/// Statistics of the search.
+///
+/// See [`World::stats`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SearchStats {
+    /// Number of steps walked during the search.
+    ///
+    /// See [`World::steps`].
+    pub steps: u64,
+
+    /// Number of conflicts during the search.
+    pub conflicts: u64,
+
+    /// Number of decisions made during the search, i.e., the times that
+    /// an unknown cell is given a state by choice rather than by deduction.
+    ///
+    /// Unlike the steps and the conflicts, this is not kept
+    /// when the world is saved and restored.
+    pub decisions: u64,
+
+    /// Number of cells set since the search started,
+    /// including both decisions and deductions.
+    pub depth: usize,
+
+    /// The maximal [`depth`](#structfield.depth) during the search.
+    pub max_depth: usize,
+
+    /// Number of known cells in the world, in all generations.
+    pub known_cells: u32,
+}
+
 /// The search algorithms.
 ///
 /// Currently only two algorithms are supported:
@@ -87,7 +149,7 @@ pub trait Algorithm<R: Rule>: private::Sealed {
     #[cfg_attr(not(github_io), doc(hidden))]
     type Reason: Reason<R>;
 
-    /// Reasons for a conflict. Ignored in [`LifeSrc`] algorithm.
+    /// Reasons for a conflict.
     #[cfg_attr(not(github_io), doc(hidden))]
     type ConflReason;
 
@@ -126,7 +188,8 @@ pub trait Algorithm<R: Rule>: private::Sealed {
     /// (and returns `false`).
     ///
     /// It also records the number of steps it has walked in the parameter
-    /// `step`.
+    /// `step`. Every implementation must add exactly one step for each round of
+    /// deduction, so that the steps of different algorithms are comparable.
     #[cfg_attr(not(github_io), doc(hidden))]
     fn go(world: &mut World<R, Self>, step: &mut u64) -> bool;
 
@@ -138,6 +201,11 @@ pub trait Algorithm<R: Rule>: private::Sealed {
     #[cfg_attr(not(github_io), doc(hidden))]
     fn retreat(world: &mut World<R, Self>) -> bool;
 
+    /// Resets the algorithm data after all the cells set during the search
+    /// are cleared by [`World::reset`].
+    #[cfg_attr(not(github_io), doc(hidden))]
+    fn reset(world: &mut World<R, Self>);
+
     #[cfg(feature = "serde")]
     #[cfg_attr(any(docs_rs, github_io), doc(cfg(feature = "serde")))]
     /// Restore the reason from a [`ReasonSer`].
@@ -182,6 +250,23 @@ impl<R: Rule, A: Algorithm<R>> SetCell<R, A> {
     }
 }
 
+/// Chooses a random state for [`NewState::Random`] and [`NewState::RandomBiased`].
+fn random_state<G: Rng>(rng: &mut G, new_state: NewState, gen: usize) -> State {
+    match new_state {
+        NewState::RandomBiased { alive_probability } => {
+            if rng.gen_bool(alive_probability) {
+                ALIVE
+            } else {
+                match rng.gen_range(0..gen as StateRepr - 1) {
+                    0 => DEAD,
+                    i => State(i + 1),
+                }
+            }
+        }
+        _ => State(rng.gen_range(0..gen as StateRepr)),
+    }
+}
+
 impl<R: Rule, A: Algorithm<R>> World<R, A> {
     /// Consistifies a cell.
     ///
@@ -190,21 +275,45 @@ impl<R: Rule, A: Algorithm<R>> World<R, A> {
     /// generation. If possible, determines the states of some of the
     /// cells involved.
     ///
-    /// If there is a conflict, returns its reason.
+    /// If there is a conflict, returns its reason, and records it in the
+    /// [`border_conflicts`](#structfield.border_conflicts) if the cell is near an edge.
+    ///
+    /// When defects are allowed, cells in the last generation are not consistified.
+    /// Instead, the number of defects is checked in [`proceed`](Self::proceed).
     #[inline]
     fn consistify(&mut self, cell: CellRef<R>) -> Result<(), A::ConflReason> {
-        Rule::consistify(self, cell)
+        if self.max_defects.is_some() && cell.coord.2 == self.config.period - 1 {
+            return Ok(());
+        }
+        let result = Rule::consistify(self, cell);
+        if result.is_err() {
+            self.record_border_conflict(cell.coord);
+        }
+        result
     }
 
     /// Consistifies a cell, its neighbors, and its predecessor.
     ///
+    /// When the predecessor is the cell itself, e.g., for still lifes,
+    /// it is not consistified twice.
+    ///
+    /// The predecessor comes before the cell itself when
+    /// [`Config::pred_first`](Config#structfield.pred_first) is set.
+    ///
     /// If there is a conflict, returns its reason.
     #[inline]
     fn consistify10(&mut self, cell: CellRef<R>) -> Result<(), A::ConflReason> {
-        self.consistify(cell)?;
-
-        if let Some(pred) = cell.pred {
-            self.consistify(pred)?;
+        let pred = cell.pred.filter(|&pred| pred != cell);
+        if self.config.pred_first {
+            if let Some(pred) = pred {
+                self.consistify(pred)?;
+            }
+            self.consistify(cell)?;
+        } else {
+            self.consistify(cell)?;
+            if let Some(pred) = pred {
+                self.consistify(pred)?;
+            }
         }
         for &neigh in cell.nbhd.iter() {
             if let Some(neigh) = neigh {
@@ -223,13 +332,15 @@ impl<R: Rule, A: Algorithm<R>> World<R, A> {
             let state = cell.state.get().unwrap();
 
             // Determines some cells by symmetry.
-            for &sym in &cell.sym {
-                if let Some(old_state) = sym.state.get() {
-                    if state != old_state {
-                        return Err(A::confl_from_sym(cell, sym));
+            if self.has_symmetry {
+                for &sym in &cell.sym {
+                    if let Some(old_state) = sym.state.get() {
+                        if state != old_state {
+                            return Err(A::confl_from_sym(cell, sym));
+                        }
+                    } else {
+                        self.set_cell(sym, state, A::Reason::from_sym(cell))?;
                     }
-                } else {
-                    self.set_cell(sym, state, A::Reason::from_sym(cell))?;
                 }
             }
 
@@ -238,6 +349,13 @@ impl<R: Rule, A: Algorithm<R>> World<R, A> {
 
             self.check_index += 1;
         }
+
+        // Checks that there are not too many defects.
+        if let Some(max) = self.max_defects {
+            if let Some(cell) = self.defect_cells().nth(max as usize) {
+                return Err(A::confl_from_cell(cell));
+            }
+        }
         Ok(())
     }
 
@@ -248,7 +366,25 @@ impl<R: Rule, A: Algorithm<R>> World<R, A> {
     /// `false` if it goes back to the time before the first cell is set.
     #[inline]
     pub(crate) fn retreat(&mut self) -> bool {
-        A::retreat(self)
+        let result = A::retreat(self);
+        self.close_cache_nodes(result);
+        #[cfg(feature = "debug-invariants")]
+        if self.config.invariant_check_interval.is_some() {
+            self.assert_consistent();
+        }
+        result
+    }
+
+    /// Checks the internal consistency of the world if a multiple of the
+    /// [`invariant_check_interval`](Config#structfield.invariant_check_interval)
+    /// is passed when the number of steps goes from `from` to `to`.
+    #[cfg(feature = "debug-invariants")]
+    fn check_invariants(&self, from: u64, to: u64) {
+        if let Some(interval) = self.config.invariant_check_interval.filter(|&n| n > 0) {
+            if from / interval != to / interval {
+                self.assert_consistent();
+            }
+        }
     }
 
     /// Makes a decision.
@@ -257,15 +393,25 @@ impl<R: Rule, A: Algorithm<R>> World<R, A> {
     /// and push a reference to it to the [`set_stack`](#structfield.set_stack).
     ///
     /// Returns `None` is there is no unknown cell,
-    /// `Some(false)` if the new state leads to an immediate conflict.
+    /// `Some(false)` if the new state leads to an immediate conflict,
+    /// or if the current state is found in the transposition cache.
     #[inline]
     fn decide(&mut self) -> Option<bool> {
         if let Some(cell) = self.get_unknown() {
+            if self.is_refuted(cell) {
+                return Some(false);
+            }
             self.next_unknown = cell.next;
+            self.decisions += 1;
             let state = match self.config.new_state {
                 NewState::ChooseDead => cell.background,
+                // For a dying background of a Generations rule with `B0`,
+                // this is `ALIVE`, so it always differs from the background.
                 NewState::ChooseAlive => !cell.background,
-                NewState::Random => State(thread_rng().gen_range(0..self.rule.gen())),
+                new_state => match &mut self.rng {
+                    Some(rng) => random_state(rng, new_state, self.rule.gen()),
+                    None => random_state(&mut thread_rng(), new_state, self.rule.gen()),
+                },
             };
             Some(self.set_cell(cell, state, A::Reason::DECIDED).is_ok())
         } else {
@@ -280,43 +426,291 @@ impl<R: Rule, A: Algorithm<R>> World<R, A> {
             if self.proceed().is_ok() {
                 self.set_stack.clear();
                 self.check_index = 0;
+                self.search_start = self.next_unknown;
+                self.max_depth = 0;
                 return self;
             } else {
                 self.conflicts += 1;
                 if !self.retreat() {
+                    self.presearch_failed = true;
                     return self;
                 }
             }
         }
     }
 
+    /// Whether another call of [`search`](Self::search) can make progress.
+    ///
+    /// Returns `false` when the search space is exhausted, i.e., when the last
+    /// search returned [`Status::None`], or when a contradiction was found before
+    /// the search started. In this case, [`search`](Self::search) would
+    /// return [`Status::None`] immediately.
+    ///
+    /// Otherwise, e.g., after [`Status::Searching`] or [`Status::Found`],
+    /// the search can be resumed to look for the next result. Note that
+    /// this does not guarantee that there is another result.
+    pub fn can_continue(&self) -> bool {
+        self.next_unknown.is_some() || self.set_stack.iter().any(|s| s.reason.is_decided())
+    }
+
+    /// Restarts the search from the beginning, without rebuilding the world.
+    ///
+    /// All the cells set during the search are cleared, so that the world is
+    /// in the same state as right after it was created, and the statistics,
+    /// such as the numbers of steps and conflicts, start again from zero.
+    /// If [`reduce_max`](Config#structfield.reduce_max) has lowered the
+    /// [`max_cell_count`](Config#structfield.max_cell_count), it is restored.
+    ///
+    /// This is much faster than creating a new world from the same configuration,
+    /// e.g., to search again with another [seed](Self::set_seed)
+    /// for [random states](NewState::Random).
+    pub fn reset(&mut self) {
+        while let Some(SetCell { cell, .. }) = self.set_stack.pop() {
+            self.clear_cell(cell);
+        }
+        self.check_index = 0;
+        self.next_unknown = self.search_start;
+        A::reset(self);
+        self.drop_cache_nodes();
+        self.config.max_cell_count = self.configured_max_cell_count;
+        self.steps = 0;
+        self.conflicts = 0;
+        self.decisions = 0;
+        self.max_depth = 0;
+        self.max_cell_count_conflicts = 0;
+        self.border_conflicts = [0; 4];
+        self.row_count_conflicts = [0; 2];
+    }
+
+    /// A hint explaining why the search returned [`Status::None`].
+    ///
+    /// Returns `None` if the search [can continue](Self::can_continue).
+    /// Otherwise it reports the first of the following constraints that applies:
+    ///
+    /// - a contradiction found before the search started, e.g., between the known cells;
+    /// - the [`infeasibility_check`](Config::infeasibility_check) of the configuration;
+    /// - the [`max_cell_count`](Config#structfield.max_cell_count), if it ever caused
+    ///   a conflict and is not reduced by [`reduce_max`](Config#structfield.reduce_max);
+    /// - the [`max_cell_count_per_gen`](Config#structfield.max_cell_count_per_gen),
+    ///   if it ever caused a conflict;
+    /// - the [`min_cell_count`](Config#structfield.min_cell_count), if there is any;
+    /// - the known cells, the phase masks, the symmetry, the transformation,
+    ///   and the diagonal width, if there are any;
+    /// - the size of the world.
+    ///
+    /// This is only a heuristic. The reported constraint is not necessarily the
+    /// reason why there is no result, but relaxing it is a good first try.
+    pub fn failure_hint(&self) -> Option<String> {
+        if self.can_continue() {
+            return None;
+        }
+        let config = &self.config;
+        if self.presearch_failed {
+            return Some(String::from(if !config.known_cells.is_empty() {
+                "The known cells conflicted before the search started."
+            } else if !config.prefix_cells.is_empty() {
+                "The prefix cells conflicted before the search started."
+            } else if !config.phase_masks.is_empty() {
+                "The phase masks conflicted before the search started."
+            } else {
+                "The constraints conflicted before the search started. The world may be too small."
+            }));
+        }
+        if let Some(hint) = config.infeasibility_check() {
+            return Some(hint);
+        }
+        if let Some(max) = config.max_cell_count {
+            if self.max_cell_count_conflicts > 0 && !config.reduce_max {
+                return Some(format!("max_cell_count={} may be too low.", max));
+            }
+        }
+        if let Some(max) = config.max_cell_count_per_gen {
+            if self.max_cell_count_conflicts > 0 {
+                return Some(format!("max_cell_count_per_gen={} may be too low.", max));
+            }
+        }
+        if let Some(min) = config.min_cell_count {
+            return Some(format!("min_cell_count={} may be too high.", min));
+        }
+        Some(if !config.known_cells.is_empty() {
+            String::from("The known cells may be too restrictive.")
+        } else if !config.phase_masks.is_empty() {
+            String::from("The phase masks may be too restrictive.")
+        } else if config.symmetry != Symmetry::C1 {
+            format!("symmetry={} may be too restrictive.", config.symmetry)
+        } else if config.transform != Transform::Id {
+            format!("transform={} may be too restrictive.", config.transform)
+        } else if let Some(diagonal_width) = config.diagonal_width {
+            format!("diagonal_width={} may be too low.", diagonal_width)
+        } else if let Some((p, q, w)) = config.skew {
+            format!("skew=({}, {}, {}) may be too narrow.", p, q, w)
+        } else {
+            String::from("The world may be too small. Try a larger width or height.")
+        })
+    }
+
     /// The search function.
     ///
     /// Returns [`Status::Found`] if a result is found,
     /// [`Status::None`] if such pattern does not exist,
     /// [`Status::Searching`] if the number of steps exceeds `max_step`
     /// and no results are found.
+    ///
+    /// The trivial result, where every cell has the background state,
+    /// is never reported as found, unless
+    /// [`allow_trivial`](Config#structfield.allow_trivial) is set.
     pub fn search(&mut self, max_step: Option<u64>) -> Status {
         let mut step_count = 0;
+        let status = self.search_steps(max_step, &mut step_count);
+        self.steps += step_count;
+        status
+    }
+
+    /// Searches until a result is found, the search space is exhausted,
+    /// or the given time has passed.
+    ///
+    /// Returns the same statuses as [`search`](Self::search), except that
+    /// [`Status::Searching`] means that the time has run out. The clock is only
+    /// checked every few thousand steps, so the search may take slightly longer.
+    ///
+    /// # Panics
+    ///
+    /// Panics on platforms without a clock, e.g., `wasm32-unknown-unknown`.
+    pub fn search_timeout(&mut self, timeout: Duration) -> Status {
+        let Some(deadline) = Instant::now().checked_add(timeout) else {
+            return self.search(None);
+        };
+        self.search_with(|_| {
+            if Instant::now() < deadline {
+                ControlFlow::Continue(())
+            } else {
+                ControlFlow::Break(())
+            }
+        })
+    }
+
+    /// Searches until a result is found, the search space is exhausted,
+    /// or the callback asks to stop.
+    ///
+    /// The callback is called with the current [`SearchStats`] every
+    /// few thousand steps. If it returns [`ControlFlow::Break`], the search
+    /// stops and returns [`Status::Searching`]. The world is left in a
+    /// consistent state, so the search can be resumed by calling
+    /// [`search`](Self::search) or this method again, or saved and restored.
+    ///
+    /// Otherwise returns the same statuses as [`search`](Self::search).
+    pub fn search_with(&mut self, mut f: impl FnMut(&SearchStats) -> ControlFlow<()>) -> Status {
+        loop {
+            match self.search(Some(CALLBACK_STEPS)) {
+                Status::Searching => {
+                    if f(&self.stats()).is_break() {
+                        return Status::Searching;
+                    }
+                }
+                status => return status,
+            }
+        }
+    }
+
+    /// An iterator of the results, together with some statistics about each result.
+    ///
+    /// Each item is a [`ResultInfo`], containing generation 0 of the result,
+    /// the number of steps and conflicts spent on finding it since the previous result
+    /// (or since this method is called), its population and its bounding box.
+    ///
+    /// The iterator stops when the search space is exhausted.
+    /// Each call of `next` may take arbitrarily long.
+    pub fn results_with_stats(&mut self) -> impl Iterator<Item = ResultInfo> + '_ {
+        let (mut steps, mut conflicts) = (self.steps, self.conflicts);
+        let mut border_conflicts = self.border_conflicts;
+        iter::from_fn(move || {
+            if self.search(None) != Status::Found {
+                return None;
+            }
+            let info = ResultInfo {
+                rle: self.rle_gen(0),
+                steps: self.steps - steps,
+                conflicts: self.conflicts - conflicts,
+                border_conflicts: array::from_fn(|i| {
+                    self.border_conflicts[i] - border_conflicts[i]
+                }),
+                population: self.cell_count(),
+                bounding_box: self.bounding_box_gen(0),
+            };
+            steps = self.steps;
+            conflicts = self.conflicts;
+            border_conflicts = self.border_conflicts;
+            Some(info)
+        })
+    }
+
+    /// An iterator of the results.
+    ///
+    /// Each item is a [`Solution`], containing generation 0 of the result
+    /// in RLE format, its cell count, and the coordinates of its living cells.
+    ///
+    /// The search goes on between the calls of `next`, so each of them may take
+    /// arbitrarily long. The iterator stops when the search space is exhausted,
+    /// and keeps returning `None` after that.
+    ///
+    /// When [`reduce_max`](Config#structfield.reduce_max) is set, the
+    /// [`max_cell_count`](Config#structfield.max_cell_count) is lowered as soon as
+    /// a result is found, so each result has fewer cells than the previous one.
+    pub fn solutions(&mut self) -> impl Iterator<Item = Solution> + '_ {
+        iter::from_fn(move || (self.search(None) == Status::Found).then(|| self.solution())).fuse()
+    }
+
+    /// The current result as a [`Solution`].
+    pub(crate) fn solution(&self) -> Solution {
+        Solution {
+            rle: self.rle_gen(0),
+            cell_count: self.cell_count(),
+            gen0_cells: self
+                .iter_cells(0)
+                .filter(|&(_, state)| state == Some(ALIVE))
+                .map(|(coord, _)| coord)
+                .collect(),
+        }
+    }
+
+    /// The search function, recording the number of steps it has walked
+    /// in the parameter `step_count`.
+    fn search_steps(&mut self, max_step: Option<u64>, step_count: &mut u64) -> Status {
         if self.next_unknown.is_none() && !self.retreat() {
             return Status::None;
         }
-        while A::go(self, &mut step_count) {
+        #[cfg(feature = "debug-invariants")]
+        let mut checked_steps = self.steps + *step_count;
+        while A::go(self, step_count) {
+            #[cfg(feature = "debug-invariants")]
+            {
+                let steps = self.steps + *step_count;
+                self.check_invariants(checked_steps, steps);
+                checked_steps = steps;
+            }
             if let Some(result) = self.decide() {
                 if !result && !self.retreat() {
                     return Status::None;
                 }
-            } else if !self.is_boring() {
-                if self.config.reduce_max {
-                    self.config.max_cell_count = Some(self.cell_count() - 1);
+            } else {
+                // A complete state is reached, so this branch is not refuted.
+                self.taint_cache();
+                if !self.is_boring()
+                    && self.is_parity_correct()
+                    && self.has_required_predecessor()
+                    && self.is_accepted()
+                {
+                    if self.config.reduce_max {
+                        self.config.max_cell_count = Some(self.reduced_max_cell_count());
+                    }
+                    return Status::Found;
+                } else if !self.retreat() {
+                    return Status::None;
                 }
-                return Status::Found;
-            } else if !self.retreat() {
-                return Status::None;
             }
 
             if let Some(max) = max_step {
-                if step_count > max {
+                if *step_count > max {
                     return Status::Searching;
                 }
             }
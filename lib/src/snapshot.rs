@@ -0,0 +1,292 @@
+//! A snapshot of the world.
+
+use crate::{
+    cells::{Coord, State},
+    config::Config,
+    format::Format,
+};
+
+#[cfg(doc)]
+use crate::world::World;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A snapshot of the world, taken by [`World::snapshot`].
+///
+/// Unlike [`World`], it owns all its data, and is [`Send`] and [`Sync`],
+/// so that the results can be sent to another thread and displayed there.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct WorldSnapshot {
+    /// World configuration.
+    pub(crate) config: Config,
+
+    /// Whether the rule is a Generations rule.
+    pub(crate) is_gen_rule: bool,
+
+    /// The background state of each generation.
+    pub(crate) background: Vec<State>,
+
+    /// States of the cells in each generation.
+    ///
+    /// The state of the cell at `(x, y)` is at the index `y * width + x`.
+    pub(crate) cells: Vec<Vec<Option<State>>>,
+
+    /// Number of known living cells in each generation.
+    pub(crate) cell_count: Vec<u32>,
+
+    /// Number of steps walked during the search.
+    pub(crate) steps: u64,
+
+    /// Number of conflicts during the search.
+    pub(crate) conflicts: u64,
+
+    /// Coordinates of the defects of the result.
+    pub(crate) defects: Vec<Coord>,
+}
+
+impl WorldSnapshot {
+    /// Gets the state of a cell.
+    ///
+    /// Cells outside the world have the background state.
+    pub fn get_cell_state(&self, coord: Coord) -> Option<State> {
+        let (x, y, t) = self.config.map_coord_across_period(coord);
+        if self.config.contains((x, y, t), false, false) {
+            self.cells[t as usize][(y * self.config.width + x) as usize]
+        } else {
+            Some(self.background[t as usize])
+        }
+    }
+
+    /// Iterates over the cells of the world in some generation,
+    /// together with their states, in row-major order.
+    ///
+    /// See [`World::iter_cells`].
+    pub fn iter_cells(&self, t: i32) -> impl Iterator<Item = (Coord, Option<State>)> + '_ {
+        let (width, height) = (self.config.width, self.config.height);
+        (0..height)
+            .flat_map(move |y| (0..width).map(move |x| ((x, y, t), self.get_cell_state((x, y, t)))))
+    }
+
+    /// World configuration.
+    #[inline]
+    pub const fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Whether the rule is a Generations rule.
+    #[inline]
+    pub const fn is_gen_rule(&self) -> bool {
+        self.is_gen_rule
+    }
+
+    /// Number of known living cells in some generation.
+    ///
+    /// See [`World::cell_count_gen`].
+    #[inline]
+    pub fn cell_count_gen(&self, t: i32) -> u32 {
+        self.cell_count[t as usize]
+    }
+
+    /// Minimum number of known living cells in all generations.
+    ///
+    /// See [`World::cell_count`].
+    #[inline]
+    pub fn cell_count(&self) -> u32 {
+        *self.cell_count.iter().min().unwrap()
+    }
+
+    /// Number of conflicts during the search.
+    #[inline]
+    pub const fn conflicts(&self) -> u64 {
+        self.conflicts
+    }
+
+    /// Number of steps walked during the search.
+    #[inline]
+    pub const fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    /// Coordinates of the defects of the result.
+    ///
+    /// See [`World::defects`].
+    #[inline]
+    pub fn defects(&self) -> &[Coord] {
+        &self.defects
+    }
+
+    /// Displays the whole world in some generation.
+    ///
+    /// See [`World::rle_gen`].
+    #[inline]
+    pub fn rle_gen(&self, t: i32) -> String {
+        Format::rle_gen(self, t)
+    }
+
+    /// The first generation with the minimum number of known living cells.
+    ///
+    /// See [`World::min_population_gen`].
+    #[inline]
+    pub fn min_population_gen(&self) -> i32 {
+        Format::min_population_gen(self)
+    }
+
+    /// Displays all the generations in a period, starting from generation `offset`.
+    ///
+    /// See [`World::rle_gen_rotated`].
+    #[inline]
+    pub fn rle_gen_rotated(&self, offset: i32) -> Vec<String> {
+        Format::rle_gen_rotated(self, offset)
+    }
+
+    /// Displays all the generations in a period, starting from the generation
+    /// with the minimum number of living cells.
+    ///
+    /// See [`World::rle_all_gens_min_phase`].
+    #[inline]
+    pub fn rle_all_gens_min_phase(&self) -> Vec<String> {
+        self.rle_gen_rotated(self.min_population_gen())
+    }
+
+    /// Displays all the generations in a period in one string.
+    ///
+    /// See [`World::rle_all`].
+    #[inline]
+    pub fn rle_all(&self) -> String {
+        Format::rle_all(self)
+    }
+
+    /// Displays the whole world in some generation, preceded by `#C` comment lines
+    /// describing the search parameters.
+    ///
+    /// See [`World::rle_gen_annotated`].
+    #[inline]
+    pub fn rle_gen_annotated(&self, t: i32) -> String {
+        Format::rle_gen_annotated(self, t)
+    }
+
+    /// Displays the whole world in some generation in Plaintext format.
+    ///
+    /// See [`World::plaintext_gen`].
+    #[inline]
+    pub fn plaintext_gen(&self, t: i32) -> String {
+        Format::plaintext_gen(self, t)
+    }
+
+    /// The smallest rectangle containing all the known non-dead cells in some generation.
+    ///
+    /// See [`World::bounding_box_gen`].
+    #[inline]
+    pub fn bounding_box_gen(&self, t: i32) -> Option<(i32, i32, i32, i32)> {
+        Format::bounding_box_gen(self, t)
+    }
+
+    /// Displays the living cells in some generation as a Golly cell list.
+    ///
+    /// See [`World::python_gen`].
+    #[inline]
+    pub fn python_gen(&self, t: i32) -> String {
+        Format::python_gen(self, t)
+    }
+
+    /// Displays all the generations in a period as a sequence of frames,
+    /// trimmed to the same rectangle.
+    ///
+    /// See [`World::frames`].
+    #[inline]
+    pub fn frames(&self) -> Vec<String> {
+        Format::frames(self)
+    }
+
+    /// The states of the cells in all the generations in a period,
+    /// trimmed to the same rectangle.
+    ///
+    /// See [`World::frames_grid`].
+    #[inline]
+    pub fn frames_grid(&self) -> Vec<Vec<Vec<State>>> {
+        Format::frames_grid(self)
+    }
+}
+
+/// A result of the search, together with some statistics about it.
+///
+/// Yielded by [`World::results_with_stats`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ResultInfo {
+    /// Generation 0 of the result, in the format of [`World::rle_gen`].
+    pub rle: String,
+
+    /// Number of steps walked to find this result,
+    /// counting from the previous result.
+    pub steps: u64,
+
+    /// Number of conflicts met to find this result,
+    /// counting from the previous result.
+    pub conflicts: u64,
+
+    /// Number of conflicts near each edge of the world met to find this result,
+    /// counting from the previous result.
+    ///
+    /// See [`World::border_conflict_summary`].
+    pub border_conflicts: [u64; 4],
+
+    /// Minimum number of living cells in all generations.
+    ///
+    /// See [`World::cell_count`].
+    pub population: u32,
+
+    /// The bounding box of generation 0, as `(x0, y0, x1, y1)`,
+    /// or `None` if it is empty.
+    ///
+    /// See [`World::bounding_box_gen`].
+    pub bounding_box: Option<(i32, i32, i32, i32)>,
+}
+
+/// A result of the search.
+///
+/// Yielded by [`World::solutions`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Solution {
+    /// Generation 0 of the result, in the format of [`World::rle_gen`].
+    pub rle: String,
+
+    /// Minimum number of living cells in all generations.
+    ///
+    /// See [`World::cell_count`].
+    pub cell_count: u32,
+
+    /// Coordinates of the living cells in generation 0, in row-major order,
+    /// as visited by [`World::iter_cells`].
+    pub gen0_cells: Vec<Coord>,
+}
+
+impl Format for WorldSnapshot {
+    #[inline]
+    fn config(&self) -> &Config {
+        &self.config
+    }
+
+    #[inline]
+    fn is_gen_rule(&self) -> bool {
+        self.is_gen_rule
+    }
+
+    #[inline]
+    fn get_cell_state(&self, coord: Coord) -> Option<State> {
+        WorldSnapshot::get_cell_state(self, coord)
+    }
+
+    #[inline]
+    fn background(&self, t: i32) -> State {
+        self.background[t.rem_euclid(self.config.period) as usize]
+    }
+
+    #[inline]
+    fn cell_count_gen(&self, t: i32) -> u32 {
+        self.cell_count[t as usize]
+    }
+}
@@ -0,0 +1,112 @@
+//! Splitting a search into independent work units, e.g., for a distributed search.
+
+use crate::{
+    config::{Config, KnownCell},
+    error::Error,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A part of a search that is [split](Config::split) into work units.
+///
+/// The search in a work unit starts with the first decisions of the
+/// whole search already made, as given by its [`prefix`](WorkUnit::prefix).
+/// Different work units have different prefixes, so they never find
+/// the same result.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct WorkUnit {
+    /// The configuration of the whole search.
+    pub base: Config,
+
+    /// The states of the first decided cells of the whole search
+    /// in this work unit.
+    pub prefix: Vec<KnownCell>,
+
+    /// The index of this work unit, from `0` to `count - 1`.
+    pub index: usize,
+
+    /// The total number of work units.
+    pub count: usize,
+}
+
+impl WorkUnit {
+    /// The configuration of the search in this work unit.
+    ///
+    /// This is the configuration of the whole search, with the
+    /// [`prefix`](WorkUnit::prefix) appended to its
+    /// [prefix cells](Config#structfield.prefix_cells).
+    pub fn config(&self) -> Config {
+        let mut config = self.base.clone();
+        config.prefix_cells.extend_from_slice(&self.prefix);
+        config
+    }
+}
+
+impl Config {
+    /// Splits the search into work units, by enumerating the states of
+    /// the first `depth` cells that the search would decide.
+    ///
+    /// Each state of a decided cell is tried in turn, and the cells
+    /// implied by it are deduced before the next cell is chosen, just as
+    /// in the search. A prefix that leads to a conflict is discarded, so the
+    /// number of work units might be much smaller than the number of all
+    /// possible prefixes.
+    /// A prefix after which there is no unknown cell left is kept as a work
+    /// unit even if it is shorter than `depth`.
+    ///
+    /// The results of the searches in all the work units, taken together,
+    /// are exactly the results of the search with this configuration,
+    /// and no result is found in two work units.
+    ///
+    /// The states are tried in increasing order, whatever the
+    /// [new state](Config#structfield.new_state) is, so the work units
+    /// only depend on the configuration and `depth`. Note that when
+    /// [`reduce_max`](Config#structfield.reduce_max) is set, each work unit
+    /// only reduces its own maximal number of living cells.
+    ///
+    /// Returns an error if the configuration is invalid.
+    pub fn split(&self, depth: u32) -> Result<Vec<WorkUnit>, Error> {
+        let mut prefixes = Vec::new();
+        self.split_prefixes(&mut Vec::new(), depth, &mut prefixes)?;
+        let count = prefixes.len();
+        Ok(prefixes
+            .into_iter()
+            .enumerate()
+            .map(|(index, prefix)| WorkUnit {
+                base: self.clone(),
+                prefix,
+                index,
+                count,
+            })
+            .collect())
+    }
+
+    /// Collects the consistent prefixes that extend `prefix`
+    /// by at most `depth` decided cells.
+    fn split_prefixes(
+        &self,
+        prefix: &mut Vec<KnownCell>,
+        depth: u32,
+        prefixes: &mut Vec<Vec<KnownCell>>,
+    ) -> Result<(), Error> {
+        let mut config = self.clone();
+        config.prefix_cells.extend_from_slice(prefix);
+        let world = config.world()?;
+        if !world.can_continue() {
+            return Ok(());
+        }
+        match world.next_decision() {
+            Some(coord) if depth > 0 => {
+                for state in world.candidate_states(coord) {
+                    prefix.push(KnownCell { coord, state });
+                    self.split_prefixes(prefix, depth - 1, prefixes)?;
+                    prefix.pop();
+                }
+            }
+            _ => prefixes.push(prefix.clone()),
+        }
+        Ok(())
+    }
+}
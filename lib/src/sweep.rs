@@ -0,0 +1,68 @@
+//! Searching with the same configuration in a list of rules.
+
+use crate::{config::Config, error::Error, poly_world::PolyWorld, search::Status};
+
+/// The outcome of the search in one rule of [`Config::search_over_rules`].
+pub struct RuleOutcome {
+    /// The rule string.
+    pub rule_string: String,
+
+    /// The status at the end of the search,
+    /// or the error if the configuration is invalid with this rule.
+    ///
+    /// [`Status::Searching`] means that the budget was used up.
+    pub status: Result<Status, Error>,
+
+    /// The world, if a result is found.
+    ///
+    /// The search can be continued to look for more results.
+    pub world: Option<PolyWorld>,
+}
+
+impl RuleOutcome {
+    /// Whether a result is found in this rule.
+    pub fn is_found(&self) -> bool {
+        self.status == Ok(Status::Found)
+    }
+}
+
+impl Config {
+    /// Searches with this configuration in each of the given rules,
+    /// e.g., to find the rules in which some known cells can be completed.
+    ///
+    /// Each search walks at most about `budget_per_rule` steps,
+    /// or until a result is found or the search space is exhausted
+    /// if `budget_per_rule` is `None`.
+    ///
+    /// The configuration is validated separately for each rule,
+    /// e.g., the states of the known cells are checked against the number
+    /// of states of each rule. An invalid rule does not stop the other searches;
+    /// its error is recorded in the [`status`](RuleOutcome::status) of its outcome.
+    ///
+    /// The [`rule_string`](#structfield.rule_string) of this configuration is ignored,
+    /// and this configuration is not changed.
+    pub fn search_over_rules(
+        &self,
+        rules: &[String],
+        budget_per_rule: Option<u64>,
+    ) -> Vec<RuleOutcome> {
+        rules
+            .iter()
+            .map(|rule_string| {
+                let config = self.clone().set_rule_string(rule_string);
+                let (status, world) = match config.world() {
+                    Ok(mut world) => {
+                        let status = world.search(budget_per_rule);
+                        (Ok(status), Some(world).filter(|_| status == Status::Found))
+                    }
+                    Err(e) => (Err(e), None),
+                };
+                RuleOutcome {
+                    rule_string: rule_string.clone(),
+                    status,
+                    world,
+                }
+            })
+            .collect()
+    }
+}
@@ -1,15 +1,118 @@
 //! The world.
 
 use crate::{
-    cells::{CellRef, Coord, LifeCell, State, ALIVE, DEAD},
-    config::{Config, KnownCell, SearchOrder},
+    cells::{CellRef, Coord, LifeCell, State, StateRepr, DEAD},
+    config::{
+        Config, KnownCell, Line, PhaseMask, PhaseMaskMode, ReduceTarget, RetreatStrategy,
+        SearchOrder, Transform,
+    },
+    error::Error,
+    format::Format,
+    poly_world::PolyWorld,
     rules::{
         typebool::{Bool, False},
         Rule,
     },
-    search::{Algorithm, Backjump, LifeSrc, Reason, SetCell},
+    search::{
+        Algorithm, Backjump, LifeSrc, Reason, SearchStats, SetCell, Status, TranspositionCache,
+    },
+    snapshot::WorldSnapshot,
 };
-use std::{cell::UnsafeCell, convert::TryInto, fmt::Write, mem};
+use rand::{rngs::StdRng, SeedableRng};
+use std::{cell::UnsafeCell, collections::HashSet, convert::TryInto, mem};
+
+#[cfg(doc)]
+use crate::cells::ALIVE;
+
+/// Where to put the old world when the world is [enlarged](World::enlarge).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Anchor {
+    /// The old world is at the top left corner of the new world.
+    #[default]
+    TopLeft,
+    /// The old world is at the center of the new world.
+    ///
+    /// If the size difference is odd, the old world is moved up or left by half a cell.
+    Center,
+}
+
+/// A user-supplied filter of results.
+///
+/// See [`World::set_result_filter`].
+pub type ResultFilter = Box<dyn FnMut(&WorldSnapshot) -> bool>;
+
+/// The living cells of a pattern, up to translation, rotation and reflection.
+///
+/// The cells are translated so that the minimal coordinates are 0, and sorted.
+/// Among the images of the pattern under all transformations, the minimal one is chosen.
+fn canonical_form(cells: &[(i32, i32, State)]) -> Vec<(i32, i32, StateRepr)> {
+    Transform::ALL
+        .iter()
+        .map(|transform| {
+            let cells = cells
+                .iter()
+                .map(|&(x, y, State(state))| {
+                    let (x, y, _) = transform.act_on((x, y, 0), 0, 0);
+                    (x, y, state)
+                })
+                .collect::<Vec<_>>();
+            let x0 = cells.iter().map(|&(x, _, _)| x).min().unwrap_or(0);
+            let y0 = cells.iter().map(|&(_, y, _)| y).min().unwrap_or(0);
+            let mut cells = cells
+                .into_iter()
+                .map(|(x, y, state)| (x - x0, y - y0, state))
+                .collect::<Vec<_>>();
+            cells.sort_unstable();
+            cells
+        })
+        .min()
+        .unwrap()
+}
+
+/// A set of cells which must not all have the background state.
+///
+/// It is generated from a [phase mask](Config#structfield.phase_masks)
+/// with [`PhaseMaskMode::ForceNonBackground`], and works like the
+/// [front](World#structfield.non_empty_front).
+pub(crate) struct NonEmptySet<R: Rule> {
+    /// The cells in the set.
+    pub(crate) cells: Vec<CellRef<R>>,
+
+    /// Number of unknown or non-background cells in the set.
+    pub(crate) count: u32,
+}
+
+/// The number of living cells in a row of generation 0, together with its quota.
+///
+/// See [`Config::row_live_counts`](Config#structfield.row_live_counts).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct RowCount {
+    /// The exact number of living cells that the row should have.
+    pub(crate) quota: u32,
+
+    /// Number of living cells in the row.
+    pub(crate) alive: u32,
+
+    /// Number of unknown cells in the row.
+    pub(crate) unknown: u32,
+}
+
+impl RowCount {
+    /// Whether the quota can still be reached.
+    ///
+    /// Returns `Err(true)` if there are too many living cells,
+    /// and `Err(false)` if there are too few unknown cells left.
+    #[inline]
+    const fn check(&self) -> Result<(), bool> {
+        if self.alive > self.quota {
+            Err(true)
+        } else if self.alive + self.unknown < self.quota {
+            Err(false)
+        } else {
+            Ok(())
+        }
+    }
+}
 
 /// The world.
 pub struct World<R: Rule, A: Algorithm<R>> {
@@ -33,12 +136,52 @@ pub struct World<R: Rule, A: Algorithm<R>> {
     /// For Generations rules, dying cells are not counted.
     pub(crate) cell_count: Vec<u32>,
 
+    /// Number of unknown cells in each generation.
+    ///
+    /// Only used when [`prune_parity`](Config#structfield.prune_parity) is `true`.
+    pub(crate) unknown_count: Vec<u32>,
+
     /// Number of unknown or living cells on the first row or column.
     pub(crate) front_cell_count: u32,
 
     /// Number of conflicts during the search.
     pub(crate) conflicts: u64,
 
+    /// Number of conflicts caused by the
+    /// [`max_cell_count`](Config#structfield.max_cell_count) or the
+    /// [`max_cell_count_per_gen`](Config#structfield.max_cell_count_per_gen).
+    pub(crate) max_cell_count_conflicts: u64,
+
+    /// Number of conflicts found when consistifying a cell on or next to
+    /// each edge of the world, in the order top, bottom, left, right.
+    ///
+    /// See [`border_conflict_summary`](Self::border_conflict_summary).
+    pub(crate) border_conflicts: [u64; 4],
+
+    /// Whether a contradiction was found before the search started.
+    pub(crate) presearch_failed: bool,
+
+    /// The [`next_unknown`](#structfield.next_unknown) when the search starts,
+    /// restored by [`reset`](Self::reset).
+    pub(crate) search_start: Option<CellRef<R>>,
+
+    /// The [`max_cell_count`](Config#structfield.max_cell_count) before it is
+    /// lowered by [`reduce_max`](Config#structfield.reduce_max),
+    /// restored by [`reset`](Self::reset).
+    pub(crate) configured_max_cell_count: Option<u32>,
+
+    /// Number of steps walked during the search.
+    pub(crate) steps: u64,
+
+    /// Number of decisions made during the search.
+    ///
+    /// See [`SearchStats::decisions`].
+    pub(crate) decisions: u64,
+
+    /// The maximal length of the [`set_stack`](#structfield.set_stack)
+    /// during the search.
+    pub(crate) max_depth: usize,
+
     /// A stack to record the cells whose values are set during the search.
     ///
     /// The cells in this stack always have known states.
@@ -64,6 +207,71 @@ pub struct World<R: Rule, A: Algorithm<R>> {
     /// * the first row plus the first column, when the search order is diagonal.
     pub(crate) non_empty_front: bool,
 
+    /// Sets of cells which must not all have the background state,
+    /// one for each generation of each
+    /// [phase mask](Config#structfield.phase_masks) with
+    /// [`PhaseMaskMode::ForceNonBackground`].
+    pub(crate) non_empty_sets: Vec<NonEmptySet<R>>,
+
+    /// Lists of indices of [non-empty sets](#structfield.non_empty_sets).
+    ///
+    /// The [`non_empty_list`](LifeCell#structfield.non_empty_list) of a cell
+    /// is an index of this vector. The first list is always empty,
+    /// and is used by the cells in no set.
+    pub(crate) non_empty_lists: Vec<Vec<usize>>,
+
+    /// The counters of living cells in each row of generation 0,
+    /// or empty if [`Config::row_live_counts`](Config#structfield.row_live_counts)
+    /// is not set.
+    pub(crate) row_counts: Vec<RowCount>,
+
+    /// Number of conflicts because a row has too many living cells,
+    /// and because a row can no longer reach its quota.
+    ///
+    /// See [`row_count_conflicts`](Self::row_count_conflicts).
+    pub(crate) row_count_conflicts: [u64; 2],
+
+    /// Whether the rule is the inverted rule of the given `B0S8` rule,
+    /// so that the states are inverted in the inputs and outputs.
+    ///
+    /// See [`Config::auto_invert_b0s8`](Config#structfield.auto_invert_b0s8).
+    pub(crate) inverted: bool,
+
+    /// Whether any generation has a nontrivial symmetry,
+    /// or there are [frozen cells](Config#structfield.frozen_cells).
+    ///
+    /// If not, the symmetry deduction in [`proceed`](Self::proceed) is skipped.
+    pub(crate) has_symmetry: bool,
+
+    /// The maximal number of defects, if defects are allowed.
+    ///
+    /// See [`Config::max_defects`](Config#structfield.max_defects).
+    pub(crate) max_defects: Option<u32>,
+
+    /// Whether the default algorithm skips the decisions that a conflict does not depend on.
+    ///
+    /// See [`RetreatStrategy::ConflictDirected`].
+    pub(crate) conflict_directed: bool,
+
+    /// Canonical forms of the [known results](Config#structfield.known_results).
+    known_results: HashSet<Vec<(i32, i32, StateRepr)>>,
+
+    /// A user-supplied filter of results.
+    ///
+    /// See [`set_result_filter`](Self::set_result_filter).
+    pub(crate) result_filter: Option<ResultFilter>,
+
+    /// The transposition cache of refuted states, if enabled.
+    ///
+    /// See [`Config::transposition_cache`](Config#structfield.transposition_cache).
+    pub(crate) cache: Option<TranspositionCache>,
+
+    /// A seeded random number generator for [random states](crate::NewState::Random),
+    /// if it is [set](Self::set_seed).
+    ///
+    /// Otherwise the thread-local random number generator is used.
+    pub(crate) rng: Option<StdRng>,
+
     /// Other data used by the algorithm.
     pub(crate) algo_data: A,
 }
@@ -83,25 +291,24 @@ impl<R: Rule> World<R, LifeSrc> {
         // Fills the vector with dead cells,
         // and checks whether it is on the first row or column.
         //
-        // If the rule contains `B0`, then fills the odd generations
-        // with living cells instead.
+        // If the rule contains `B0`, then the background goes through all the
+        // states in turn: dead cells are born, living cells die because there
+        // is no `S8`, and dying cells keep dying. So generation `t` is filled
+        // with state `t % gen` instead. The period is a multiple of `gen`,
+        // so that the background of the last generation evolves into that
+        // of the first generation.
+        let background = |t: i32| {
+            if rule.has_b0() {
+                State((t as usize % rule.gen()) as StateRepr)
+            } else {
+                DEAD
+            }
+        };
         for x in -1..=config.width {
             for y in -1..=config.height {
                 for t in 0..config.period {
-                    let state = if rule.has_b0() {
-                        State(t as usize % rule.gen())
-                    } else {
-                        DEAD
-                    };
-                    let succ_state = if rule.has_b0() {
-                        if t == config.period - 1 {
-                            State(0)
-                        } else {
-                            State((t as usize + 1) % rule.gen())
-                        }
-                    } else {
-                        DEAD
-                    };
+                    let state = background(t);
+                    let succ_state = background(t + 1);
                     let mut cell = LifeCell::new((x, y, t), state, succ_state);
                     if let Some(is_front) = &is_front {
                         if is_front((x, y, t)) && config.contains((x, y, t), false, true) {
@@ -118,12 +325,51 @@ impl<R: Rule> World<R, LifeSrc> {
             rule,
             cells: cells.into_boxed_slice(),
             cell_count: vec![0; config.period as usize],
+            unknown_count: vec![0; config.period as usize],
             front_cell_count: 0,
             conflicts: 0,
+            max_cell_count_conflicts: 0,
+            border_conflicts: [0; 4],
+            presearch_failed: false,
+            search_start: None,
+            configured_max_cell_count: config.max_cell_count,
+            steps: 0,
+            decisions: 0,
+            max_depth: 0,
             set_stack: Vec::with_capacity(size),
             check_index: 0,
             next_unknown: None,
             non_empty_front: is_front.is_some(),
+            non_empty_sets: Vec::new(),
+            non_empty_lists: vec![Vec::new()],
+            row_counts: Vec::new(),
+            row_count_conflicts: [0; 2],
+            inverted: config.is_rule_inverted(),
+            has_symmetry: config.has_symmetry() || !config.frozen_cells.is_empty(),
+            max_defects: config.max_defects().filter(|_| !R::IsGen::VALUE),
+            conflict_directed: config.retreat_strategy == RetreatStrategy::ConflictDirected
+                && config.transposition_cache().is_none()
+                && config.max_defects().is_none(),
+            known_results: config
+                .known_results
+                .iter()
+                .map(|pattern| {
+                    let cells = pattern
+                        .iter()
+                        .filter(|known_cell| known_cell.coord.2 == 0 && known_cell.state != DEAD)
+                        .map(|known_cell| {
+                            (known_cell.coord.0, known_cell.coord.1, known_cell.state)
+                        })
+                        .collect::<Vec<_>>();
+                    canonical_form(&cells)
+                })
+                .collect(),
+            result_filter: None,
+            cache: config
+                .transposition_cache()
+                .filter(|_| config.max_defects().is_none())
+                .map(|capacity| TranspositionCache::new(capacity, &search_order, config)),
+            rng: None,
             algo_data,
         }
         .init_front()
@@ -131,9 +377,13 @@ impl<R: Rule> World<R, LifeSrc> {
         .init_nbhd()
         .init_pred_succ()
         .init_sym()
+        .init_frozen_cells()
         .init_state()
         .init_known_cells(&config.known_cells)
         .init_search_order(search_order.as_ref())
+        .init_prefix_cells()
+        .init_phase_masks()
+        .init_row_counts()
         .presearch()
     }
 
@@ -150,7 +400,10 @@ impl<R: Rule<IsGen = False>> World<R, Backjump<R>> {
     /// using the [`Backjump`] algorithm.
     #[inline]
     pub fn new_backjump(config: &Config, rule: R) -> Self {
-        World::new_with_rule(config, rule)
+        let mut world = World::new_with_rule(config, rule);
+        // Backjumping does not keep track of the exhausted states.
+        world.cache = None;
+        world
     }
 }
 
@@ -162,20 +415,22 @@ impl<R: Rule, A: Algorithm<R>> World<R, A> {
     }
 
     /// Initialize the cells at the borders.
+    ///
+    /// A [torus](Config#structfield.torus) has no border.
     fn init_border(mut self) -> Self {
+        if self.config.torus.is_some() {
+            return self;
+        }
         for x in -1..=self.config.width {
             for y in -1..=self.config.height {
-                if let Some(d) = self.config.diagonal_width {
-                    let abs = (x - y).abs();
-                    if abs >= d {
-                        if abs <= d + 1 {
-                            for t in 0..self.config.period {
-                                let cell = self.find_cell((x, y, t)).unwrap();
-                                self.set_stack.push(SetCell::new(cell, A::Reason::KNOWN));
-                            }
+                if !self.config.in_strips((x, y, 0), false) {
+                    if self.config.in_strips((x, y, 0), true) {
+                        for t in 0..self.config.period {
+                            let cell = self.find_cell((x, y, t)).unwrap();
+                            self.set_stack.push(SetCell::new(cell, A::Reason::KNOWN));
                         }
-                        continue;
                     }
+                    continue;
                 }
                 for t in 0..self.config.period {
                     if x == -1 || x == self.config.width || y == -1 || y == self.config.height {
@@ -192,6 +447,9 @@ impl<R: Rule, A: Algorithm<R>> World<R, A> {
     ///
     /// Note that for cells on the edges of the search range,
     /// some neighbors might point to `None`.
+    ///
+    /// On a [torus](Config#structfield.torus), the neighbors wrap around,
+    /// and the cells at the border are not linked to anything.
     fn init_nbhd(mut self) -> Self {
         /// Relative positions of the neighbors.
         const NBHD: [(i32, i32); 8] = [
@@ -206,15 +464,16 @@ impl<R: Rule, A: Algorithm<R>> World<R, A> {
         ];
         for x in -1..=self.config.width {
             for y in -1..=self.config.height {
-                if let Some(d) = self.config.diagonal_width {
-                    if (x - y).abs() > d + 1 {
-                        continue;
-                    }
+                if !self.config.in_strips((x, y, 0), true) {
+                    continue;
+                }
+                if self.config.torus.is_some() && !self.config.contains((x, y, 0), false, false) {
+                    continue;
                 }
                 for t in 0..self.config.period {
                     let mut nbhd = Vec::with_capacity(NBHD.len());
                     for (nx, ny) in NBHD {
-                        nbhd.push(self.find_cell((x + nx, y + ny, t)));
+                        nbhd.push(self.find_cell(self.config.wrap_torus((x + nx, y + ny, t))));
                     }
                     let cell_mut = self.find_cell_mut((x, y, t)).unwrap();
                     cell_mut.nbhd = nbhd.try_into().unwrap();
@@ -231,13 +490,17 @@ impl<R: Rule, A: Algorithm<R>> World<R, A> {
     ///
     /// If the successor is out of the search range,
     /// then sets it to `None`.
+    ///
+    /// On a [torus](Config#structfield.torus), the cells at the border
+    /// are not linked to anything.
     fn init_pred_succ(mut self) -> Self {
         for x in -1..=self.config.width {
             for y in -1..=self.config.height {
-                if let Some(d) = self.config.diagonal_width {
-                    if (x - y).abs() > d + 1 {
-                        continue;
-                    }
+                if !self.config.in_strips((x, y, 0), true) {
+                    continue;
+                }
+                if self.config.torus.is_some() && !self.config.contains((x, y, 0), false, false) {
+                    continue;
                 }
                 for t in 0..self.config.period {
                     let cell = self.find_cell((x, y, t)).unwrap();
@@ -247,8 +510,8 @@ impl<R: Rule, A: Algorithm<R>> World<R, A> {
                         let cell_mut = self.find_cell_mut((x, y, t)).unwrap();
                         cell_mut.pred = pred;
                     } else {
-                        let coord = self.config.translate((x, y, t - 1));
-                        let pred = self.find_cell(self.config.translate(coord));
+                        let coord = self.config.map_coord_across_period((x, y, t - 1));
+                        let pred = self.find_cell(self.config.map_coord_across_period(coord));
                         if self.config.contains(coord, true, true) && pred.is_some() {
                             let cell_mut = self.find_cell_mut((x, y, t)).unwrap();
                             cell_mut.pred = pred;
@@ -262,7 +525,7 @@ impl<R: Rule, A: Algorithm<R>> World<R, A> {
                     let succ = if t != self.config.period - 1 {
                         self.find_cell((x, y, t + 1))
                     } else {
-                        self.find_cell(self.config.translate((x, y, t + 1)))
+                        self.find_cell(self.config.map_coord_across_period((x, y, t + 1)))
                     };
                     let cell_mut = self.find_cell_mut((x, y, t)).unwrap();
                     cell_mut.succ = succ;
@@ -274,21 +537,23 @@ impl<R: Rule, A: Algorithm<R>> World<R, A> {
 
     /// Links a cell to the symmetric cells.
     ///
+    /// The cell itself is not included, so the list is empty
+    /// when the symmetry is trivial.
+    ///
     /// If some symmetric cell is out of the search range,
     /// then  marks the current cell as known.
     fn init_sym(mut self) -> Self {
         for x in -1..=self.config.width {
             for y in -1..=self.config.height {
-                if let Some(d) = self.config.diagonal_width {
-                    if (x - y).abs() > d + 1 {
-                        continue;
-                    }
+                if !self.config.in_strips((x, y, 0), true) {
+                    continue;
                 }
                 for t in 0..self.config.period {
                     let cell = self.find_cell((x, y, t)).unwrap();
-                    let mut sym = Vec::with_capacity(8);
+                    let members = self.config.symmetry_at(t).members();
+                    let mut sym = Vec::with_capacity(members.len() - 1);
 
-                    for transform in self.config.symmetry.members() {
+                    for transform in members.into_iter().filter(|&t| t != Transform::Id) {
                         let coord =
                             transform.act_on((x, y, t), self.config.width, self.config.height);
                         if self.config.contains(coord, false, true) {
@@ -308,18 +573,48 @@ impl<R: Rule, A: Algorithm<R>> World<R, A> {
         self
     }
 
+    /// Links each frozen cell to its successor in every generation,
+    /// so that they are deduced to have the same state as symmetric cells are.
+    fn init_frozen_cells(mut self) -> Self {
+        let mut pairs = Vec::new();
+        for &(x, y, _) in &self.config.frozen_cells {
+            for t in 0..self.config.period {
+                if let Some(cell) = self.find_cell((x, y, t)) {
+                    if let Some(succ) = cell.succ {
+                        if succ != cell {
+                            pairs.push((cell, succ));
+                            pairs.push((succ, cell));
+                        }
+                    }
+                }
+            }
+        }
+        for (cell, sym) in pairs {
+            // Cells outside the diagonal width are always known,
+            // so it suffices to link the other cell to them.
+            if let Some(cell_mut) = self.find_cell_mut(cell.coord) {
+                if !cell_mut.sym.contains(&sym) {
+                    cell_mut.sym.push(sym);
+                }
+            }
+        }
+        self
+    }
+
     /// Sets states for the cells.
     ///
     /// All cells are set to unknown unless they are at the border,
+    /// outside the [`containment_box`](Config#structfield.containment_box),
     /// or are marked as known in [`init_pred_succ`](Self::init_pred_succ)
     /// or [`init_sym`](Self::init_sym).
     fn init_state(mut self) -> Self {
         for x in 0..self.config.width {
             for y in 0..self.config.height {
-                if let Some(d) = self.config.diagonal_width {
-                    if (x - y).abs() >= d {
-                        continue;
-                    }
+                if !self.config.in_strips((x, y, 0), false) {
+                    continue;
+                }
+                if !self.config.in_containment_box((x, y, 0)) {
+                    continue;
                 }
                 for t in 0..self.config.period {
                     let cell = self.find_cell((x, y, t)).unwrap();
@@ -335,8 +630,9 @@ impl<R: Rule, A: Algorithm<R>> World<R, A> {
     /// Sets the known cells.
     fn init_known_cells(mut self, known_cells: &[KnownCell]) -> Self {
         for &KnownCell { coord, state } in known_cells {
+            let state = self.invert(state);
             if let Some(cell) = self.find_cell(coord) {
-                if cell.state.get().is_none() && state.0 < self.rule.gen() {
+                if cell.state.get().is_none() && (state.0 as usize) < self.rule.gen() {
                     self.set_cell(cell, state, A::Reason::KNOWN).ok();
                 }
             }
@@ -344,6 +640,130 @@ impl<R: Rule, A: Algorithm<R>> World<R, A> {
         self
     }
 
+    /// Sets the [prefix cells](Config#structfield.prefix_cells).
+    ///
+    /// Unlike a known cell, a prefix cell that conflicts with a known cell,
+    /// the front, or the [maximal number of living cells](Config#structfield.max_cell_count)
+    /// is not ignored: there is no unknown cell left to search,
+    /// so the search ends immediately.
+    fn init_prefix_cells(mut self) -> Self {
+        let mut violated = false;
+        let prefix_cells = mem::take(&mut self.config.prefix_cells);
+        for &KnownCell { coord, state } in &prefix_cells {
+            let state = self.invert(state);
+            if let Some(cell) = self.find_cell(coord) {
+                match cell.state.get() {
+                    None if (state.0 as usize) < self.rule.gen() => {
+                        violated |= self.set_cell(cell, state, A::Reason::KNOWN).is_err();
+                    }
+                    None => (),
+                    Some(old_state) => violated |= old_state != state,
+                }
+            }
+        }
+        self.config.prefix_cells = prefix_cells;
+        if violated {
+            self.next_unknown = None;
+            self.presearch_failed = true;
+        }
+        self
+    }
+
+    /// Applies the [phase masks](Config#structfield.phase_masks).
+    ///
+    /// The cells of a mask with [`PhaseMaskMode::ForceBackground`] are set to
+    /// the background state as known cells. The cells of a mask with
+    /// [`PhaseMaskMode::ForceNonBackground`] form a [`NonEmptySet`]
+    /// for each generation.
+    ///
+    /// If a mask is already contradicted by the known cells, there is no
+    /// unknown cell left to search, so the search ends immediately.
+    fn init_phase_masks(mut self) -> Self {
+        let mut violated = false;
+        let phase_masks = mem::take(&mut self.config.phase_masks);
+        for PhaseMask { rect, gens, mode } in &phase_masks {
+            let (x0, y0, x1, y1) = *rect;
+            for &t in gens {
+                let cells = (x0..=x1)
+                    .flat_map(|x| (y0..=y1).map(move |y| (x, y, t)))
+                    .filter(|&coord| self.config.contains(coord, false, true))
+                    .filter_map(|coord| self.find_cell(coord))
+                    .collect::<Vec<_>>();
+                match mode {
+                    PhaseMaskMode::ForceBackground => {
+                        for cell in cells {
+                            match cell.state.get() {
+                                None => {
+                                    self.set_cell(cell, cell.background, A::Reason::KNOWN).ok();
+                                }
+                                Some(state) => violated |= state != cell.background,
+                            }
+                        }
+                    }
+                    PhaseMaskMode::ForceNonBackground => {
+                        let index = self.non_empty_sets.len();
+                        let count = cells
+                            .iter()
+                            .filter(|cell| cell.state.get() != Some(cell.background))
+                            .count() as u32;
+                        violated |= count == 0;
+                        for cell in &cells {
+                            if cell.non_empty_list == 0 {
+                                let list = self.non_empty_lists.len() as u32;
+                                self.non_empty_lists.push(Vec::new());
+                                self.find_cell_mut(cell.coord).unwrap().non_empty_list = list;
+                            }
+                            self.non_empty_lists[cell.non_empty_list as usize].push(index);
+                        }
+                        self.non_empty_sets.push(NonEmptySet { cells, count });
+                    }
+                }
+            }
+        }
+        self.config.phase_masks = phase_masks;
+        if violated {
+            self.next_unknown = None;
+            self.presearch_failed = true;
+        }
+        self
+    }
+
+    /// Counts the living and unknown cells in each row of generation 0,
+    /// if [`Config::row_live_counts`](Config#structfield.row_live_counts) is set.
+    ///
+    /// If a row can already not have the right number of living cells,
+    /// there is no unknown cell left to search, so the search ends immediately.
+    fn init_row_counts(mut self) -> Self {
+        let Some(quotas) = self.config.row_live_counts.clone() else {
+            return self;
+        };
+        let row_counts = quotas
+            .iter()
+            .enumerate()
+            .map(|(y, &quota)| {
+                let mut row = RowCount {
+                    quota,
+                    ..RowCount::default()
+                };
+                for x in 0..self.config.width {
+                    let cell = self.find_cell((x, y as i32, 0)).unwrap();
+                    match cell.state.get() {
+                        None => row.unknown += 1,
+                        Some(state) if state == !cell.background => row.alive += 1,
+                        _ => (),
+                    }
+                }
+                row
+            })
+            .collect::<Vec<_>>();
+        if row_counts.iter().any(|row| row.check().is_err()) {
+            self.next_unknown = None;
+            self.presearch_failed = true;
+        }
+        self.row_counts = row_counts;
+        self
+    }
+
     /// Set the [`next`](LifeCell#structfield.next) of a cell to be
     /// [`next_unknown`](#structfield.next_unknown) and set
     /// [`next_unknown`](#structfield.next_unknown) to be this cell.
@@ -381,7 +801,8 @@ impl<R: Rule, A: Algorithm<R>> World<R, A> {
     /// Finds a cell by its coordinates.
     ///
     /// Unlike [`find_cell`](Self::find_cell), it returns `None` when the cell
-    /// is out of the [`diagonal_width`](Config#structfield.diagonal_width).
+    /// is out of the [`diagonal_width`](Config#structfield.diagonal_width)
+    /// or the [`skew`](Config#structfield.skew).
     fn find_cell_mut(&mut self, coord: Coord) -> Option<&mut LifeCell<R>> {
         let (x, y, t) = coord;
         if self.config.contains((x, y, t), true, true) {
@@ -414,12 +835,56 @@ impl<R: Rule, A: Algorithm<R>> World<R, A> {
         cell.seen.set(false);
         if let Some(old_state) = cell.state.take() {
             cell.update_desc(old_state, false);
+            self.unknown_count[cell.coord.2 as usize] += 1;
             if old_state == !cell.background {
                 self.cell_count[cell.coord.2 as usize] -= 1;
             }
             if cell.is_front && old_state == cell.background {
                 self.front_cell_count += 1;
             }
+            if old_state == cell.background && cell.non_empty_list != 0 {
+                for &index in &self.non_empty_lists[cell.non_empty_list as usize] {
+                    self.non_empty_sets[index].count += 1;
+                }
+            }
+            if let Some(row) = self.row_count_mut(cell.coord) {
+                row.unknown += 1;
+                if old_state == !cell.background {
+                    row.alive -= 1;
+                }
+            }
+        }
+    }
+
+    /// The counter of the row of a cell, if the cell is in generation 0
+    /// and [`Config::row_live_counts`](Config#structfield.row_live_counts) is set.
+    #[inline]
+    fn row_count_mut(&mut self, (x, y, t): Coord) -> Option<&mut RowCount> {
+        if t != 0 || x < 0 || x >= self.config.width {
+            return None;
+        }
+        self.row_counts.get_mut(usize::try_from(y).ok()?)
+    }
+
+    /// Updates the counter of the row of a cell which is just set,
+    /// and checks whether the row can still have the right number of living cells.
+    ///
+    /// Returns `false` if the check fails.
+    #[inline]
+    pub(crate) fn count_row_cell(&mut self, cell: CellRef<R>, state: State) -> bool {
+        let Some(row) = self.row_count_mut(cell.coord) else {
+            return true;
+        };
+        row.unknown -= 1;
+        if state == !cell.background {
+            row.alive += 1;
+        }
+        match row.check() {
+            Ok(()) => true,
+            Err(too_many) => {
+                self.row_count_conflicts[usize::from(!too_many)] += 1;
+                false
+            }
         }
     }
 
@@ -436,17 +901,194 @@ impl<R: Rule, A: Algorithm<R>> World<R, A> {
         None
     }
 
+    /// The coordinates of the cell of the next decision, i.e., the first unknown cell
+    /// since [`next_unknown`](#structfield.next_unknown).
+    pub(crate) fn next_decision(&self) -> Option<Coord> {
+        let mut next = self.next_unknown;
+        while let Some(cell) = next {
+            if cell.state.get().is_none() {
+                return Some(cell.coord);
+            }
+            next = cell.next;
+        }
+        None
+    }
+
     /// Tests if the result is borling.
+    ///
+    /// The trivial result is boring unless it is
+    /// [allowed](Config#structfield.allow_trivial).
+    /// A result with fewer living cells than the
+    /// [`min_cell_count`](Config#structfield.min_cell_count) is always boring.
     pub(crate) fn is_boring(&self) -> bool {
-        self.is_trivial()
-            || self.is_stable()
+        if self
+            .config
+            .min_cell_count
+            .is_some_and(|min| self.cell_count[0] < min)
+        {
+            return true;
+        }
+        if self.is_trivial() {
+            return !self.config.allow_trivial;
+        }
+        self.is_stable()
             || (self.config.skip_subperiod && self.is_subperiodic())
             || (self.config.skip_subsymmetry && self.is_subsymmetric())
+            || (self.config.skip_spacetime_subsymmetry && self.is_spacetime_subsymmetric())
+            || (self.config.clean_border && !self.is_clean())
+            || self.is_known_result()
     }
 
-    /// Tests if the result is trivial.
+    /// Tests if some generation of the result is one of the
+    /// [known results](Config#structfield.known_results).
+    fn is_known_result(&self) -> bool {
+        !self.known_results.is_empty()
+            && (0..self.config.period).any(|t| {
+                let cells = (0..self.config.width)
+                    .flat_map(|x| (0..self.config.height).map(move |y| (x, y)))
+                    .filter_map(|(x, y)| {
+                        let cell = self.find_cell((x, y, t))?;
+                        let state = cell.state.get()?;
+                        (state != cell.background).then_some((x, y, state))
+                    })
+                    .collect::<Vec<_>>();
+                self.known_results.contains(&canonical_form(&cells))
+            })
+    }
+
+    /// Tests if the result satisfies the
+    /// [population parities](Config#structfield.population_parity) and the
+    /// [line parities](Config#structfield.line_parities).
+    pub(crate) fn is_parity_correct(&self) -> bool {
+        let population_parity = self.config.population_parity.iter().flat_map(|parities| {
+            parities
+                .iter()
+                .enumerate()
+                .filter_map(|(t, &odd)| Some((self.cell_count[t], odd?)))
+        });
+        let line_parities = self.config.line_parities.iter().map(|line_parity| {
+            let cells = match line_parity.line {
+                Line::Row(y) => (0..self.config.width)
+                    .map(|x| (x, y, line_parity.gen))
+                    .collect::<Vec<_>>(),
+                Line::Column(x) => (0..self.config.height)
+                    .map(|y| (x, y, line_parity.gen))
+                    .collect(),
+            };
+            let count = cells
+                .into_iter()
+                .filter(|&coord| {
+                    self.find_cell(coord).is_some_and(|cell| {
+                        cell.state
+                            .get()
+                            .is_some_and(|state| state == !cell.background)
+                    })
+                })
+                .count() as u32;
+            (count, line_parity.odd)
+        });
+        population_parity
+            .chain(line_parities)
+            .all(|(count, odd)| (count % 2 == 1) == odd)
+    }
+
+    /// Tests if generation `t` is completely known, and the parity of its population
+    /// is wrong, when [`prune_parity`](Config#structfield.prune_parity) is `true`.
+    pub(crate) fn is_parity_wrong_gen(&self, t: usize) -> bool {
+        self.unknown_count[t] == 0
+            && self.config.prune_parity
+            && self
+                .config
+                .population_parity
+                .as_ref()
+                .and_then(|parities| parities[t])
+                .is_some_and(|odd| (self.cell_count[t] % 2 == 1) != odd)
+    }
+
+    /// Tests if the result has a predecessor other than itself in the world,
+    /// when it is a still life and
+    /// [`require_predecessor`](Config#structfield.require_predecessor) is `true`.
+    ///
+    /// The predecessor is searched in a world of period 2, where generation 1 is
+    /// the result. The rule from generation 1 back to generation 0 is relaxed by
+    /// allowing every cell to be a [defect](Config#structfield.max_defects).
+    /// The result itself is skipped as a stable pattern.
+    pub(crate) fn has_required_predecessor(&self) -> bool {
+        let config = &self.config;
+        if !config.require_predecessor
+            || config.period != 1
+            || config.dx != 0
+            || config.dy != 0
+            || config.transform != Transform::Id
+            || R::IsGen::VALUE
+            || self.rule.has_b0()
+            || self.inverted
+        {
+            return true;
+        }
+        let known_cells = (0..config.width)
+            .flat_map(|x| (0..config.height).map(move |y| (x, y)))
+            .filter_map(|(x, y)| {
+                let state = self.get_cell_state((x, y, 0))?;
+                Some(KnownCell {
+                    coord: (x, y, 1),
+                    state,
+                })
+            })
+            .collect::<Vec<_>>();
+        let max_defects = ((config.width + 2) * (config.height + 2)) as u32;
+        Config {
+            torus: config.torus,
+            ..Config::new(config.width, config.height, 2)
+        }
+        .set_rule_string(config.rule_string.clone())
+        .set_diagonal_width(config.diagonal_width)
+        .set_skew(config.skew)
+        .set_known_cells(known_cells)
+        .set_max_defects(max_defects)
+        .set_skip_subperiod(false)
+        .world()
+        .map_or(true, |mut world| world.search(None) == Status::Found)
+    }
+
+    /// Tests if the living cells of the result form a single object in every generation.
+    ///
+    /// Two living cells are in the same object if the distance between them
+    /// is at most 2 in both directions.
+    fn is_clean(&self) -> bool {
+        (0..self.config.period).all(|t| {
+            let mut alive = (0..self.config.width)
+                .flat_map(|x| (0..self.config.height).map(move |y| (x, y)))
+                .filter(|&(x, y)| {
+                    self.find_cell((x, y, t)).is_some_and(|cell| {
+                        cell.state
+                            .get()
+                            .is_some_and(|state| state != cell.background)
+                    })
+                })
+                .collect::<HashSet<_>>();
+            let mut stack = alive.iter().next().copied().into_iter().collect::<Vec<_>>();
+            while let Some((x, y)) = stack.pop() {
+                alive.remove(&(x, y));
+                for dx in -2..=2 {
+                    for dy in -2..=2 {
+                        if alive.remove(&(x + dx, y + dy)) {
+                            stack.push((x + dx, y + dy));
+                        }
+                    }
+                }
+            }
+            alive.is_empty()
+        })
+    }
+
+    /// Whether there is no known living cell in generation 0.
+    ///
+    /// After a result is found, this means that the result is the trivial one,
+    /// where every cell has the background state, which is only reported when
+    /// [`allow_trivial`](Config#structfield.allow_trivial) is set.
     #[inline]
-    fn is_trivial(&self) -> bool {
+    pub fn is_trivial(&self) -> bool {
         self.cell_count[0] == 0
     }
 
@@ -462,51 +1104,611 @@ impl<R: Rule, A: Algorithm<R>> World<R, A> {
 
     /// Tests if the fundamental period of the result is smaller than the given period.
     fn is_subperiodic(&self) -> bool {
-        (2..=self.config.period).any(|f| {
-            self.config.period % f == 0 && self.config.dx % f == 0 && self.config.dy % f == 0 && {
-                let t = self.config.period / f;
-                let dx = self.config.dx / f;
-                let dy = self.config.dy / f;
-                self.cells
-                    .iter()
-                    .step_by(self.config.period as usize)
-                    .all(|c| unsafe {
-                        let (x, y, _) = self.config.transform.act_on(
-                            (*c.get()).coord,
-                            self.config.width,
-                            self.config.height,
-                        );
-                        (*c.get()).state.get() == self.get_cell_state((x - dx, y - dy, t))
-                    })
-            }
-        })
+        (2..=self.config.period).any(|f| self.is_subperiodic_by(f))
+    }
+
+    /// Tests if the result repeats itself `f` times in the given period,
+    /// i.e., if the given period, `dx` and `dy` can be divided by `f`,
+    /// and generation `period / f` is the transformed and translated generation 0.
+    fn is_subperiodic_by(&self, f: i32) -> bool {
+        self.config.period % f == 0 && self.config.dx % f == 0 && self.config.dy % f == 0 && {
+            let t = self.config.period / f;
+            let dx = self.config.dx / f;
+            let dy = self.config.dy / f;
+            self.cells
+                .iter()
+                .step_by(self.config.period as usize)
+                .all(|c| unsafe {
+                    let (x, y, _) = self.config.transform.act_on(
+                        (*c.get()).coord,
+                        self.config.width,
+                        self.config.height,
+                    );
+                    (*c.get()).state.get() == self.cell_state((x - dx, y - dy, t))
+                })
+        }
     }
 
     /// Tests if the result is invariant under more transformations than
     /// required by the given symmetry.
+    ///
+    /// When [`phase_symmetries`](Config#structfield.phase_symmetries) is given,
+    /// this means that every generation is invariant under more transformations
+    /// than required by its own symmetry.
     fn is_subsymmetric(&self) -> bool {
-        let cosets = self.config.symmetry.cosets();
-        self.cells
-            .iter()
-            .step_by(self.config.period as usize)
-            .all(|c| {
-                cosets.iter().skip(1).any(|t| unsafe {
-                    let coord = t.act_on((*c.get()).coord, self.config.width, self.config.height);
-                    (*c.get()).state.get() == self.get_cell_state(coord)
+        let gens = if self.config.phase_symmetries.is_some() {
+            self.config.period
+        } else {
+            1
+        };
+        (0..gens).all(|t| {
+            let cosets = self.config.symmetry_at(t).cosets();
+            self.cells
+                .iter()
+                .skip(t as usize)
+                .step_by(self.config.period as usize)
+                .all(|c| {
+                    cosets.iter().skip(1).any(|tr| unsafe {
+                        let coord =
+                            tr.act_on((*c.get()).coord, self.config.width, self.config.height);
+                        (*c.get()).state.get() == self.cell_state(coord)
+                    })
+                })
+        })
+    }
+
+    /// Tests if some generation of the result is the image of generation 0
+    /// under a non-identity coset representative of the given symmetry,
+    /// translated proportionally to the time shift.
+    fn is_spacetime_subsymmetric(&self) -> bool {
+        let period = self.config.period;
+        let cosets = self.config.symmetry_at(0).cosets();
+        (1..period).any(|t| {
+            if (t * self.config.dx) % period != 0 || (t * self.config.dy) % period != 0 {
+                return false;
+            }
+            let dx = t * self.config.dx / period;
+            let dy = t * self.config.dy / period;
+            cosets.iter().skip(1).any(|tr| {
+                self.cells.iter().step_by(period as usize).all(|c| unsafe {
+                    let (x, y, _) =
+                        tr.act_on((*c.get()).coord, self.config.width, self.config.height);
+                    (*c.get()).state.get() == self.cell_state((x - dx, y - dy, t))
                 })
             })
+        })
+    }
+
+    /// Recomputes the neighborhood descriptor of every cell from scratch,
+    /// and asserts that it matches the one maintained by
+    /// [`Rule::update_desc`].
+    ///
+    /// Cells out of the search range are assumed to have the background state.
+    /// A cell whose successor does not link back to it as the predecessor
+    /// sees the background state as its successor.
+    ///
+    /// Only available in debug builds, or with the `debug-invariants` feature.
+    ///
+    /// # Panics
+    ///
+    /// Panics if some descriptor does not match.
+    #[cfg(any(debug_assertions, feature = "debug-invariants"))]
+    pub fn verify_descriptors(&self) {
+        let mut succ_states = std::collections::HashMap::new();
+        for cell in self.cells.iter() {
+            let cell = unsafe { &*cell.get() };
+            if let Some(pred) = cell.pred {
+                succ_states.insert(pred.coord, cell.state.get());
+            }
+        }
+        for cell in self.cells.iter() {
+            let cell = unsafe { &*cell.get() };
+            let (_, _, t) = cell.coord;
+            let succ_state = succ_states.get(&cell.coord).copied().unwrap_or_else(|| {
+                let succ = self.find_cell((-1, -1, (t + 1) % self.config.period));
+                succ.map(|succ| succ.background)
+            });
+            let nbhd = cell
+                .nbhd
+                .map(|neigh| neigh.map_or(Some(cell.background), |neigh| neigh.state.get()));
+            let desc = R::desc_from_states(cell.state.get(), succ_state, nbhd);
+            assert!(
+                cell.desc.get() == desc,
+                "wrong neighborhood descriptor at {:?}: stored {:?}, expected {:?}",
+                cell.coord,
+                cell.desc.get(),
+                desc
+            );
+        }
+    }
+
+    /// Asserts that the internal data of the world are consistent.
+    ///
+    /// Besides [`verify_descriptors`](Self::verify_descriptors), this checks that
+    /// the cells in the [`set_stack`](#structfield.set_stack) are known and distinct,
+    /// that the [`check_index`](#structfield.check_index) is inside the stack,
+    /// and that the counters of living cells, unknown cells, the front,
+    /// the non-empty sets and the rows match a recount.
+    ///
+    /// During the search, it is called as configured by
+    /// [`Config::invariant_check_interval`](Config#structfield.invariant_check_interval).
+    ///
+    /// # Panics
+    ///
+    /// Panics if some check fails.
+    #[cfg(feature = "debug-invariants")]
+    #[cfg_attr(any(docs_rs, github_io), doc(cfg(feature = "debug-invariants")))]
+    pub fn assert_consistent(&self) {
+        self.verify_descriptors();
+
+        let mut set = HashSet::new();
+        for SetCell { cell, .. } in &self.set_stack {
+            assert!(
+                cell.state.get().is_some(),
+                "unknown cell at {:?} in the set stack",
+                cell.coord
+            );
+            assert!(
+                set.insert(cell.coord),
+                "cell at {:?} appears twice in the set stack",
+                cell.coord
+            );
+        }
+        assert!(
+            self.check_index as usize <= self.set_stack.len(),
+            "check index {} is beyond the set stack of length {}",
+            self.check_index,
+            self.set_stack.len()
+        );
+
+        let period = self.config.period as usize;
+        let mut cell_count = vec![0; period];
+        let mut unknown_count = vec![0; period];
+        let mut front_cell_count = 0;
+        for cell in self.cell_refs() {
+            let t = cell.coord.2 as usize;
+            match cell.state.get() {
+                None => unknown_count[t] += 1,
+                Some(state) if state == !cell.background => cell_count[t] += 1,
+                _ => (),
+            }
+            if cell.is_front && cell.state.get() != Some(cell.background) {
+                front_cell_count += 1;
+            }
+        }
+        assert_eq!(self.cell_count, cell_count, "wrong cell counts");
+        assert_eq!(
+            self.unknown_count, unknown_count,
+            "wrong unknown cell counts"
+        );
+        assert_eq!(
+            self.front_cell_count, front_cell_count,
+            "wrong front cell count"
+        );
+
+        for (index, set) in self.non_empty_sets.iter().enumerate() {
+            let count = set
+                .cells
+                .iter()
+                .filter(|cell| cell.state.get() != Some(cell.background))
+                .count() as u32;
+            assert_eq!(set.count, count, "wrong count of non-empty set {}", index);
+        }
+
+        for (y, row) in self.row_counts.iter().enumerate() {
+            let (mut alive, mut unknown) = (0, 0);
+            for x in 0..self.config.width {
+                let cell = self.find_cell((x, y as i32, 0)).unwrap();
+                match cell.state.get() {
+                    None => unknown += 1,
+                    Some(state) if state == !cell.background => alive += 1,
+                    _ => (),
+                }
+            }
+            assert_eq!(
+                (row.alive, row.unknown),
+                (alive, unknown),
+                "wrong living and unknown cell counts of row {}",
+                y
+            );
+        }
+    }
+
+    /// Asserts that the current result satisfies the configuration.
+    ///
+    /// This checks that every cell in the world is known, and that the
+    /// [known cells](Config#structfield.known_cells),
+    /// [frozen cells](Config#structfield.frozen_cells), symmetries, parities,
+    /// and the rule are respected, where the rule is checked by the neighborhood
+    /// descriptors, and thus also checks the period, the translation and the
+    /// transformation. At most [`max_defects`](Config#structfield.max_defects)
+    /// cells in the last generation may violate the rule.
+    ///
+    /// It is meant to be called after [`search`](Self::search) returns
+    /// [`Status::Found`](crate::Status::Found), to catch bugs where some
+    /// constraint is dropped during the search.
+    ///
+    /// # Panics
+    ///
+    /// Panics if some constraint is not satisfied.
+    pub fn assert_result_valid(&self) {
+        let period = self.config.period;
+        let mut defects = 0;
+        for cell in self.cell_refs() {
+            if self.config.contains(cell.coord, false, false) {
+                assert!(
+                    cell.state.get().is_some(),
+                    "unknown cell at {:?}",
+                    cell.coord
+                );
+            }
+            if let (true, Some(State(i))) = (R::IsGen::VALUE, cell.state.get()) {
+                // The neighborhood descriptors treat dying cells as dead,
+                // so the transitions of dying cells are checked separately.
+                if let Some(State(j)) = cell.succ.and_then(|succ| succ.state.get()) {
+                    let gen = self.rule.gen() as StateRepr;
+                    let is_valid = match i {
+                        0 => j <= 1,
+                        1 => j == 1 || j == 2 % gen,
+                        _ => j == (i + 1) % gen,
+                    };
+                    assert!(is_valid, "cell at {:?} violates the rule", cell.coord);
+                }
+                if i >= 2 {
+                    continue;
+                }
+            }
+            if self.rule.is_conflict(cell) {
+                assert!(
+                    self.max_defects.is_some() && cell.coord.2 == period - 1,
+                    "cell at {:?} violates the rule",
+                    cell.coord
+                );
+                defects += 1;
+            }
+        }
+        assert!(
+            defects <= self.max_defects.unwrap_or(0),
+            "too many defects: {}",
+            defects
+        );
+
+        // When several known cells have the same coordinates, only the first one is used.
+        let mut seen = HashSet::new();
+        for &KnownCell { coord, state } in self
+            .config
+            .known_cells
+            .iter()
+            .chain(&self.config.prefix_cells)
+        {
+            if seen.insert(coord)
+                && (state.0 as usize) < self.rule.gen()
+                && self.config.contains(coord, false, true)
+            {
+                assert_eq!(
+                    self.get_cell_state(coord),
+                    Some(state),
+                    "known cell at {:?} is not satisfied",
+                    coord
+                );
+            }
+        }
+        for &(x, y, _) in &self.config.frozen_cells {
+            for t in 0..period {
+                assert_eq!(
+                    self.get_cell_state((x, y, t)),
+                    self.get_cell_state((x, y, t + 1)),
+                    "frozen cell at {:?} changes",
+                    (x, y, t)
+                );
+            }
+        }
+        assert!(
+            self.is_parity_correct(),
+            "parity constraint is not satisfied"
+        );
+        for t in 0..period {
+            for transform in self.config.symmetry_at(t).members() {
+                for x in 0..self.config.width {
+                    for y in 0..self.config.height {
+                        let image =
+                            transform.act_on((x, y, t), self.config.width, self.config.height);
+                        assert_eq!(
+                            self.get_cell_state((x, y, t)),
+                            self.get_cell_state(image),
+                            "symmetry {:?} is broken at {:?}",
+                            transform,
+                            (x, y, t)
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// Cells in the last generation that violate the rule,
+    /// i.e., whose successors are not compatible with their neighborhoods.
+    pub(crate) fn defect_cells(&self) -> impl Iterator<Item = CellRef<R>> + '_ {
+        let period = self.config.period as usize;
+        self.cells
+            .iter()
+            .skip(period - 1)
+            .step_by(period)
+            .map(|c| unsafe { CellRef::new(c.get()) })
+            .filter(|&cell| self.rule.is_conflict(cell))
+    }
+
+    /// All the cells, including those outside the world, in the order they are stored.
+    pub(crate) fn cell_refs(&self) -> impl Iterator<Item = CellRef<R>> + '_ {
+        self.cells.iter().map(|c| unsafe { CellRef::new(c.get()) })
+    }
+
+    /// Coordinates of the defects of the current result.
+    ///
+    /// Each defect is a cell in the last generation whose successor does not obey
+    /// the rule. See [`Config::max_defects`](Config#structfield.max_defects).
+    ///
+    /// If this is not empty, the result is not a solution.
+    pub fn defects(&self) -> Vec<Coord> {
+        if self.max_defects.is_some() {
+            self.defect_cells().map(|cell| cell.coord).collect()
+        } else {
+            Vec::new()
+        }
     }
 
     /// Gets the state of a cell. Returns `Err(())` if there is no such cell.
     #[inline]
     pub fn get_cell_state(&self, coord: Coord) -> Option<State> {
-        let (x, y, t) = self.config.translate(coord);
+        self.cell_state(coord).map(|state| self.invert(state))
+    }
+
+    /// Iterates over the cells of the world in some generation,
+    /// together with their states, as given by [`get_cell_state`](Self::get_cell_state).
+    ///
+    /// The cells are visited in row-major order, i.e., from `(0, 0)` to `(width - 1, 0)`,
+    /// then from `(0, 1)` to `(width - 1, 1)`, and so on.
+    /// Each cell inside the world is visited exactly once,
+    /// and the cells outside the world are skipped.
+    ///
+    /// Cells outside the [`diagonal_width`](Config#structfield.diagonal_width)
+    /// or the [`skew`](Config#structfield.skew) are not skipped.
+    /// They are visited with the background state.
+    ///
+    /// When `t` is not in `0..period`, the states are read from the corresponding
+    /// generation in the period, in the same way as [`get_cell_state`](Self::get_cell_state).
+    pub fn iter_cells(&self, t: i32) -> impl Iterator<Item = (Coord, Option<State>)> + '_ {
+        let Config {
+            width,
+            height,
+            period,
+            ..
+        } = self.config;
+        let in_period = (0..period).contains(&t);
+        (0..height).flat_map(move |y| {
+            (0..width).map(move |x| {
+                let state = if in_period {
+                    let index = ((x + 1) * (height + 2) + y + 1) * period + t;
+                    let cell = unsafe { &*self.cells[index as usize].get() };
+                    cell.state.get().map(|state| self.invert(state))
+                } else {
+                    self.get_cell_state((x, y, t))
+                };
+                ((x, y, t), state)
+            })
+        })
+    }
+
+    /// Calls `f` on each cell of the world in some generation, with its state.
+    ///
+    /// The cells are visited in the same order as [`iter_cells`](Self::iter_cells).
+    #[inline]
+    pub fn for_each_cell(&self, t: i32, mut f: impl FnMut(Coord, Option<State>)) {
+        for (coord, state) in self.iter_cells(t) {
+            f(coord, state);
+        }
+    }
+
+    /// Gets the state of a cell in the rule which is actually searched.
+    ///
+    /// Unlike [`get_cell_state`](Self::get_cell_state), the state is not
+    /// inverted back when the rule is [inverted](Config#structfield.auto_invert_b0s8).
+    pub(crate) fn cell_state(&self, coord: Coord) -> Option<State> {
+        let (x, y, t) = self.config.map_coord_across_period(coord);
         self.find_cell((x, y, t)).map_or_else(
             || self.find_cell((0, 0, t)).map(|c1| c1.background),
             |c1| c1.state.get(),
         )
     }
 
+    /// Inverts a state between the given rule and the rule which is actually searched,
+    /// when the rule is [inverted](Config#structfield.auto_invert_b0s8).
+    #[inline]
+    pub(crate) fn invert(&self, state: State) -> State {
+        if self.inverted {
+            !state
+        } else {
+            state
+        }
+    }
+
+    /// The states which are still possible for a cell,
+    /// judging from the neighborhood descriptors and the rule.
+    ///
+    /// For a known cell, this is just its state. For an unknown cell,
+    /// each state is tried in turn: the cell and the unknown cells that must
+    /// have the same state because of the symmetry are temporarily set to this
+    /// state, and the state is kept if none of the neighborhood descriptors
+    /// involving these cells, i.e., those of the cells themselves, their
+    /// predecessors and their neighbors, shows a conflict. The cells are
+    /// unknown again afterwards.
+    ///
+    /// This looks only one step ahead, so a returned state might still lead
+    /// to a conflict later, but a state that is not returned is impossible
+    /// with the currently known cells. After each step of the search, the cells
+    /// whose states are implied by a single neighborhood descriptor are already
+    /// set, so an unknown cell has a single candidate state only when the
+    /// implication comes from its symmetric copies. For Generations rules,
+    /// dying states are not checked, as in the search.
+    pub fn candidate_states(&self, coord: Coord) -> Vec<State> {
+        let (x, y, t) = self.config.map_coord_across_period(coord);
+        let cell = match self.find_cell((x, y, t)) {
+            Some(cell) if cell.state.get().is_none() => cell,
+            _ => return self.get_cell_state(coord).into_iter().collect(),
+        };
+        let mut cells = vec![cell];
+        for &sym in &cell.sym {
+            if sym.state.get().is_none() && !cells.contains(&sym) {
+                cells.push(sym);
+            }
+        }
+        let mut states = (0..self.rule.gen() as StateRepr)
+            .map(State)
+            .filter(|&state| {
+                for &cell in &cells {
+                    cell.state.set(Some(state));
+                    cell.update_desc(state, true);
+                }
+                let is_conflict = cells.iter().any(|cell| {
+                    self.rule.is_conflict(*cell)
+                        || cell.pred.is_some_and(|pred| self.rule.is_conflict(pred))
+                        || cell
+                            .nbhd
+                            .iter()
+                            .flatten()
+                            .any(|&neigh| self.rule.is_conflict(neigh))
+                });
+                for &cell in cells.iter().rev() {
+                    cell.update_desc(state, false);
+                    cell.state.set(None);
+                }
+                !is_conflict
+            })
+            .map(|state| self.invert(state))
+            .collect::<Vec<_>>();
+        states.sort_unstable_by_key(|state| state.0);
+        states
+    }
+
+    /// The cells that would become known if the cell at `coord` were set to `state`,
+    /// without actually setting it, e.g., to preview a move in a user interface.
+    ///
+    /// The cell is set, and the consequences are deduced by the same
+    /// propagation as in the search. Then all the changes are undone,
+    /// so the world is left exactly as it was, and the search can go on.
+    ///
+    /// Returns the coordinates and the states of the cells that become known,
+    /// in the order in which they are deduced. This does not include the cell
+    /// itself, but does include the cells symmetric to it. The list is empty if
+    /// the cell already has this state.
+    ///
+    /// Returns [`Error::AssignmentConflict`] if the assignment immediately
+    /// leads to a conflict, or if the cell already has another state.
+    pub fn probe_assignment(
+        &mut self,
+        coord: Coord,
+        state: State,
+    ) -> Result<Vec<(Coord, State)>, Error> {
+        let coord = self.config.map_coord_across_period(coord);
+        if state.0 as usize >= self.rule.gen() {
+            return Err(Error::InvalidState(coord, state));
+        }
+        let cell = self.find_cell(coord).ok_or(Error::SetCellError(coord))?;
+        match cell.state.get() {
+            None => (),
+            Some(old_state) if old_state == self.invert(state) => return Ok(Vec::new()),
+            Some(_) => return Err(Error::AssignmentConflict(coord, state)),
+        }
+
+        #[cfg(feature = "debug-invariants")]
+        let before = self.search_state();
+        let set_stack_len = self.set_stack.len();
+        let check_index = self.check_index;
+        let max_depth = self.max_depth;
+        let max_cell_count_conflicts = self.max_cell_count_conflicts;
+        let row_count_conflicts = self.row_count_conflicts;
+        let border_conflicts = self.border_conflicts;
+        let cache_taints = self.cache_taints();
+
+        let result = self
+            .set_cell(cell, self.invert(state), A::Reason::KNOWN)
+            .and_then(|()| self.proceed());
+        let consequences = self.set_stack[set_stack_len + 1..]
+            .iter()
+            .map(|set| (set.cell.coord, self.invert(set.cell.state.get().unwrap())))
+            .collect();
+
+        while self.set_stack.len() > set_stack_len {
+            let SetCell { cell, .. } = self.set_stack.pop().unwrap();
+            self.clear_cell(cell);
+        }
+        self.check_index = check_index;
+        self.max_depth = max_depth;
+        self.max_cell_count_conflicts = max_cell_count_conflicts;
+        self.row_count_conflicts = row_count_conflicts;
+        self.border_conflicts = border_conflicts;
+        self.restore_cache_taints(cache_taints);
+        #[cfg(feature = "debug-invariants")]
+        {
+            assert_eq!(
+                self.search_state(),
+                before,
+                "probing the cell at {:?} changed the world",
+                coord
+            );
+            self.assert_consistent();
+        }
+
+        match result {
+            Ok(()) => Ok(consequences),
+            Err(_) => Err(Error::AssignmentConflict(coord, state)),
+        }
+    }
+
+    /// Everything that the search depends on, written out for comparison,
+    /// to check that [`probe_assignment`](Self::probe_assignment) leaves
+    /// the world unchanged.
+    ///
+    /// The levels of the unknown cells are not included,
+    /// as they are only read after the cells are set again.
+    #[cfg(feature = "debug-invariants")]
+    fn search_state(&self) -> String {
+        let cells = self
+            .cell_refs()
+            .map(|cell| {
+                let state = cell.state.get();
+                (state, cell.desc.get(), state.map(|_| cell.level.get()))
+            })
+            .collect::<Vec<_>>();
+        let set_stack = self
+            .set_stack
+            .iter()
+            .map(|set| (set.cell.coord, set.reason.is_decided()))
+            .collect::<Vec<_>>();
+        let non_empty_sets = self
+            .non_empty_sets
+            .iter()
+            .map(|set| set.count)
+            .collect::<Vec<_>>();
+        format!(
+            "{:?}",
+            (
+                cells,
+                set_stack,
+                self.check_index,
+                (&self.cell_count, &self.unknown_count, self.front_cell_count),
+                non_empty_sets,
+                &self.row_counts,
+                (
+                    self.max_cell_count_conflicts,
+                    self.row_count_conflicts,
+                    self.border_conflicts,
+                    self.cache_taints(),
+                ),
+            )
+        )
+    }
+
     /// World configuration.
     #[inline]
     pub const fn config(&self) -> &Config {
@@ -531,34 +1733,214 @@ impl<R: Rule, A: Algorithm<R>> World<R, A> {
     /// Number of known living cells in some generation.
     ///
     /// For Generations rules, dying cells are not counted.
+    ///
+    /// When the world has a symmetry, every symmetric image of a cell
+    /// is counted, so this is the population shown by [`rle_gen`](Self::rle_gen).
     #[inline]
     pub fn cell_count_gen(&self, t: i32) -> u32 {
         self.cell_count[t as usize]
     }
 
-    /// Minimum number of known living cells in all generation.
+    /// Minimum number of known living cells in all generations.
     ///
     /// For Generations rules, dying cells are not counted.
+    ///
+    /// When the world has a symmetry, every symmetric image of a cell
+    /// is counted, so this is the population shown by [`rle_gen`](Self::rle_gen).
+    ///
+    /// With the default [`reduce_target`](Config#structfield.reduce_target),
+    /// this is the number bounded by [`max_cell_count`](Config#structfield.max_cell_count).
+    /// When [`reduce_max`](Config#structfield.reduce_max) is set, it is one more than
+    /// the new `max_cell_count` after a result is found, unless that would go below
+    /// the [`min_cell_count`](Config#structfield.min_cell_count).
     #[inline]
     pub fn cell_count(&self) -> u32 {
         *self.cell_count.iter().min().unwrap()
     }
 
+    /// The number bounded by [`max_cell_count`](Config#structfield.max_cell_count),
+    /// as chosen by the [`reduce_target`](Config#structfield.reduce_target).
+    #[inline]
+    pub(crate) fn target_cell_count(&self) -> u32 {
+        match self.config.reduce_target {
+            ReduceTarget::MaxAcrossGens => self.cell_count(),
+            ReduceTarget::Gen(t) => self.cell_count[t as usize],
+        }
+    }
+
+    /// The new [`max_cell_count`](Config#structfield.max_cell_count) after a result
+    /// is found, when [`reduce_max`](Config#structfield.reduce_max) is set.
+    pub(crate) fn reduced_max_cell_count(&self) -> u32 {
+        let min = self.config.min_cell_count.unwrap_or(0);
+        self.target_cell_count().saturating_sub(1).max(min)
+    }
+
+    /// Number of known living cells in each generation.
+    ///
+    /// For Generations rules, dying cells are not counted.
+    #[inline]
+    pub fn populations(&self) -> Vec<u32> {
+        self.cell_count.clone()
+    }
+
+    /// Number of known living cells and number of unknown cells
+    /// in each row of some generation.
+    ///
+    /// For Generations rules, dying cells are not counted as living.
+    pub fn row_summary(&self, t: i32) -> Vec<(u32, u32)> {
+        (0..self.config.height)
+            .map(|y| {
+                let mut alive = 0;
+                let mut unknown = 0;
+                for x in 0..self.config.width {
+                    if let Some(cell) = self.find_cell((x, y, t)) {
+                        match cell.state.get() {
+                            Some(state) if state == !cell.background => alive += 1,
+                            None => unknown += 1,
+                            _ => {}
+                        }
+                    }
+                }
+                (alive, unknown)
+            })
+            .collect()
+    }
+
+    /// Coordinates of the next unknown cell to be decided in the search.
+    ///
+    /// Returns `None` if all the cells are known.
+    pub fn frontier_coord(&self) -> Option<Coord> {
+        let mut next = self.next_unknown;
+        while let Some(cell) = next {
+            if cell.state.get().is_none() {
+                return Some(cell.coord);
+            }
+            next = cell.next;
+        }
+        None
+    }
+
+    /// A rough estimate of the size of the search space, in bits.
+    ///
+    /// This is the number of unknown cells, times `log2` of the number of states.
+    /// Cells that are symmetric to each other are only counted once,
+    /// and cells outside the [`diagonal_width`](Config#structfield.diagonal_width)
+    /// or the [`skew`](Config#structfield.skew) are not counted.
+    pub fn search_space_bits(&self) -> f64 {
+        let mut seen = vec![false; self.cells.len()];
+        let index = |cell: CellRef<R>| {
+            let (x, y, t) = cell.coord;
+            (((x + 1) * (self.config.height + 2) + y + 1) * self.config.period + t) as usize
+        };
+        let mut free_cell_count = 0;
+        let mut stack = Vec::new();
+        for cell in self.cells.iter() {
+            let cell = unsafe { CellRef::new(cell.get()) };
+            if seen[index(cell)]
+                || cell.state.get().is_some()
+                || !self.config.contains(cell.coord, false, true)
+            {
+                continue;
+            }
+            free_cell_count += 1;
+            seen[index(cell)] = true;
+            stack.push(cell);
+            while let Some(cell) = stack.pop() {
+                for &sym in cell.sym.iter() {
+                    if !seen[index(sym)] {
+                        seen[index(sym)] = true;
+                        stack.push(sym);
+                    }
+                }
+            }
+        }
+        free_cell_count as f64 * (self.rule.gen() as f64).log2()
+    }
+
     /// Number of conflicts during the search.
     #[inline]
     pub const fn conflicts(&self) -> u64 {
         self.conflicts
     }
 
+    /// Statistics of the search so far.
+    pub fn stats(&self) -> SearchStats {
+        let size = self.config.width * self.config.height * self.config.period;
+        let unknown = self.unknown_count.iter().sum::<u32>();
+        SearchStats {
+            steps: self.steps,
+            conflicts: self.conflicts,
+            decisions: self.decisions,
+            depth: self.set_stack.len(),
+            max_depth: self.max_depth,
+            known_cells: size as u32 - unknown,
+        }
+    }
+
+    /// Number of conflicts found when consistifying a cell on or next to
+    /// each edge of the world, in the order top, bottom, left, right.
+    ///
+    /// The cells outside the world are assumed to be in the background state,
+    /// so these conflicts are likely caused by the edges. When a search
+    /// returns [`Status::None`](crate::Status::None) and most conflicts are on the left
+    /// and right edges, a result probably needs a wider world, and vice versa.
+    ///
+    /// A cell in a corner counts for both of its edges.
+    #[inline]
+    pub const fn border_conflict_summary(&self) -> [u64; 4] {
+        self.border_conflicts
+    }
+
+    /// Number of conflicts because a row of generation 0 has more living cells
+    /// than its [quota](Config#structfield.row_live_counts), and because a row
+    /// has too few unknown cells left to reach its quota.
+    #[inline]
+    pub const fn row_count_conflicts(&self) -> [u64; 2] {
+        self.row_count_conflicts
+    }
+
+    /// Records a conflict found when consistifying the cell at `coord`,
+    /// for each edge of the world that the cell is on or next to.
+    #[inline]
+    pub(crate) fn record_border_conflict(&mut self, (x, y, _): Coord) {
+        let edges = [
+            y <= 0,
+            y >= self.config.height - 1,
+            x <= 0,
+            x >= self.config.width - 1,
+        ];
+        for (count, on_edge) in self.border_conflicts.iter_mut().zip(edges) {
+            if on_edge {
+                *count += 1;
+            }
+        }
+    }
+
+    /// Number of steps walked during the search.
+    ///
+    /// A step is a round of deduction from the cells set since the last step,
+    /// followed by backtracking if it leads to a conflict. Steps are counted
+    /// in the same way for all algorithms.
+    ///
+    /// This is the total number of steps over all calls of [`search`](Self::search),
+    /// and is kept when the world is saved and restored. The `max_step`
+    /// argument of [`search`](Self::search) only counts the steps in that call.
+    #[inline]
+    pub const fn steps(&self) -> u64 {
+        self.steps
+    }
+
     /// Set the max cell counts.
     ///
     /// Currently this is the only parameter that you can change
     /// during the search.
     #[inline]
     pub fn set_max_cell_count(&mut self, max_cell_count: Option<u32>) {
+        self.taint_cache();
         self.config.max_cell_count = max_cell_count;
+        self.configured_max_cell_count = max_cell_count;
         if let Some(max) = self.config.max_cell_count {
-            while self.cell_count() > max {
+            while self.target_cell_count() > max {
                 if !self.retreat() {
                     break;
                 }
@@ -566,6 +1948,40 @@ impl<R: Rule, A: Algorithm<R>> World<R, A> {
         }
     }
 
+    /// Uses a random number generator with the given seed to choose
+    /// [random states](crate::NewState::Random), so that the search is reproducible.
+    ///
+    /// The seed is not saved when the world is serialized.
+    #[inline]
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = Some(StdRng::seed_from_u64(seed));
+    }
+
+    /// Sets a filter of results.
+    ///
+    /// When a result is found and is not rejected by the built-in checks,
+    /// the filter is called with a snapshot of the world. If it returns `false`,
+    /// the result is skipped and the search continues.
+    ///
+    /// The filter is not saved when the world is serialized.
+    #[inline]
+    pub fn set_result_filter(&mut self, result_filter: Option<ResultFilter>) {
+        self.result_filter = result_filter;
+    }
+
+    /// Whether the current result is accepted by the
+    /// [result filter](Self::set_result_filter).
+    pub(crate) fn is_accepted(&mut self) -> bool {
+        if self.result_filter.is_none() {
+            return true;
+        }
+        let snapshot = self.snapshot();
+        match &mut self.result_filter {
+            Some(filter) => filter(&snapshot),
+            None => true,
+        }
+    }
+
     /// Displays the whole world in some generation,
     /// in a mix of [Plaintext](https://conwaylife.com/wiki/Plaintext) and
     /// [RLE](https://conwaylife.com/wiki/Rle) format.
@@ -573,67 +1989,355 @@ impl<R: Rule, A: Algorithm<R>> World<R, A> {
     /// * **Dead** cells are represented by `.`;
     /// * **Living** cells are represented by `o` for rules with 2 states,
     ///   `A` for rules with more states;
-    /// * **Dying** cells are represented by uppercase letters starting from `B`;
+    /// * **Dying** cells are represented by uppercase letters starting from `B`,
+    ///   and by `pA`, `pB`, ... for states above 24, as in Golly;
     /// * **Unknown** cells are represented by `?`;
     /// * Each line is ended with `$`;
     /// * The whole pattern is ended with `!`.
+    ///
+    /// When the rule contains `B0`, the background of some generations is not dead,
+    /// and such a generation might look like a solid block. Then the pattern is
+    /// preceded by a `#C` comment line noting the state of the background,
+    /// e.g., `#C Generation 1: the background is alive, as the rule contains B0.`
+    #[inline]
     pub fn rle_gen(&self, t: i32) -> String {
-        let mut str = String::new();
-        writeln!(
-            str,
-            "x = {}, y = {}, rule = {}",
-            self.config().width,
-            self.config().height,
-            self.config().rule_string
+        Format::rle_gen(self, t)
+    }
+
+    /// The first generation with the minimum number of known living cells.
+    ///
+    /// For Generations rules, dying cells are not counted.
+    #[inline]
+    pub fn min_population_gen(&self) -> i32 {
+        Format::min_population_gen(self)
+    }
+
+    /// Displays all the generations in a period, starting from generation `offset`,
+    /// in the same format as [`rle_gen`](Self::rle_gen).
+    ///
+    /// Generations after the end of the period are obtained by applying the
+    /// [`transform`](Config#structfield.transform) to the corresponding generations
+    /// at the beginning of the period, and then shifting them back by `(dx, dy)`,
+    /// so that they stay inside the world. Thus each generation evolves into the next
+    /// one, except that there is a translation of `(dx, dy)` across the period boundary.
+    #[inline]
+    pub fn rle_gen_rotated(&self, offset: i32) -> Vec<String> {
+        Format::rle_gen_rotated(self, offset)
+    }
+
+    /// Displays all the generations in a period, starting from the generation
+    /// with the minimum number of living cells.
+    ///
+    /// See [`min_population_gen`](Self::min_population_gen)
+    /// and [`rle_gen_rotated`](Self::rle_gen_rotated).
+    #[inline]
+    pub fn rle_all_gens_min_phase(&self) -> Vec<String> {
+        self.rle_gen_rotated(self.min_population_gen())
+    }
+
+    /// Displays all the generations in a period in one string, from generation 0
+    /// to generation `period - 1`, in the same format as [`rle_gen`](Self::rle_gen).
+    ///
+    /// Each generation `t` is a separate RLE, preceded by a `#C gen t`
+    /// comment line. So the output can be read back as known cells by
+    /// `Config::set_known_cells_from_rles`, which puts each RLE in its
+    /// generation.
+    #[inline]
+    pub fn rle_all(&self) -> String {
+        Format::rle_all(self)
+    }
+
+    /// The velocity of the result, as `(dx, dy, period)`.
+    ///
+    /// Here `period` is the fundamental period of the result,
+    /// which might be smaller than the given period if
+    /// [`skip_subperiod`](Config#structfield.skip_subperiod) is `false`,
+    /// and `(dx, dy)` is the translation in a fundamental period.
+    pub fn velocity(&self) -> (i32, i32, i32) {
+        let f = (2..=self.config.period)
+            .rev()
+            .find(|&f| self.is_subperiodic_by(f))
+            .unwrap_or(1);
+        (
+            self.config.dx / f,
+            self.config.dy / f,
+            self.config.period / f,
         )
-        .unwrap();
-        for y in 0..self.config().height {
-            for x in 0..self.config().width {
-                let state = self.get_cell_state((x, y, t));
-                match state {
-                    Some(DEAD) => str.push('.'),
-                    Some(ALIVE) => {
-                        if self.is_gen_rule() {
-                            str.push('A');
-                        } else {
-                            str.push('o');
-                        }
-                    }
-                    Some(State(i)) => str.push((b'A' + i as u8 - 1) as char),
-                    _ => str.push('?'),
-                };
-            }
-            if y == self.config().height - 1 {
-                str.push('!');
-            } else {
-                str.push('$');
-            };
-            str.push('\n');
-        }
-        str
+    }
+
+    /// Displays the whole world in generation 0, preceded by a `#C` comment line
+    /// with the [velocity](Self::velocity) of the result.
+    ///
+    /// For example, the comment line for the
+    /// [25P3H1V0.1](https://conwaylife.com/wiki/25P3H1V0.1) spaceship
+    /// is `#C velocity (0, 1)c/3`.
+    pub fn rle_with_velocity(&self) -> String {
+        let (dx, dy, period) = self.velocity();
+        format!(
+            "#C velocity ({}, {})c/{}\n{}",
+            dx,
+            dy,
+            period,
+            self.rle_gen(0)
+        )
+    }
+
+    /// Displays the whole world in some generation in the same format as
+    /// [`rle_gen`](Self::rle_gen), preceded by `#C` comment lines describing
+    /// the search parameters.
+    #[inline]
+    pub fn rle_gen_annotated(&self, t: i32) -> String {
+        Format::rle_gen_annotated(self, t)
     }
 
     /// Displays the whole world in some generation in
     /// [Plaintext](https://conwaylife.com/wiki/Plaintext) format.
     ///
-    /// Do not use this for Generations rules.
+    /// The pattern is preceded by a `!` comment line with the rule and the period,
+    /// e.g., `!rule = B3/S23, period = 3`. Each row of the world is a line,
+    /// without run-length encoding. The cells are represented by the same
+    /// characters as in [`rle_gen`](Self::rle_gen):
     ///
     /// * **Dead** cells are represented by `.`;
-    /// * **Living** and **Dying** cells are represented by `o`;
+    /// * **Living** cells are represented by `o` for rules with 2 states,
+    ///   `A` for rules with more states;
+    /// * **Dying** cells are represented by uppercase letters starting from `B`;
     /// * **Unknown** cells are represented by `?`.
+    ///
+    /// So each row is the same as the corresponding line of `rle_gen`
+    /// without the ending `$` or `!`, and the cells line up, unless the rule
+    /// has more than 25 states, where the later dying states take two characters.
+    ///
+    /// As in [`rle_gen`](Self::rle_gen), a generation whose background is not dead
+    /// is preceded by a `!` comment line noting the state of the background.
+    #[inline]
     pub fn plaintext_gen(&self, t: i32) -> String {
-        let mut str = String::new();
-        for y in 0..self.config().height {
-            for x in 0..self.config().width {
-                let state = self.get_cell_state((x, y, t));
-                match state {
-                    Some(DEAD) => str.push('.'),
-                    Some(_) => str.push('o'),
-                    None => str.push('?'),
-                };
+        Format::plaintext_gen(self, t)
+    }
+
+    /// The smallest rectangle containing all the known non-dead cells in some generation,
+    /// as `(x0, y0, x1, y1)`, where `(x0, y0)` is the upper left corner,
+    /// and `(x1, y1)` is the lower right corner.
+    ///
+    /// Returns `None` if there is no such cell.
+    #[inline]
+    pub fn bounding_box_gen(&self, t: i32) -> Option<(i32, i32, i32, i32)> {
+        Format::bounding_box_gen(self, t)
+    }
+
+    /// Displays the living cells in some generation as a cell list
+    /// for Golly's Python API, which can be passed to `g.putcells`.
+    ///
+    /// For rules with 2 states, it is a flat list `[x0, y0, x1, y1, ...]`.
+    ///
+    /// For Generations rules, it is a list of triples `[x0, y0, state0, ...]`
+    /// which also includes the dying cells,
+    /// padded with a `0` when the length would otherwise be even,
+    /// as Golly requires for multi-state cell lists.
+    ///
+    /// Unknown cells are omitted. As in [`rle_gen`](Self::rle_gen), the list of
+    /// a generation whose background is not dead is followed by a `#` comment
+    /// noting the state of the background.
+    #[inline]
+    pub fn python_gen(&self, t: i32) -> String {
+        Format::python_gen(self, t)
+    }
+
+    /// Displays all the generations in a period as a sequence of frames,
+    /// which can be overlaid or played as an animation.
+    ///
+    /// Each frame is in the format of [`rle_gen`](Self::rle_gen),
+    /// but trimmed to the smallest rectangle containing all the known
+    /// non-dead cells in __all__ generations, rather than in each generation.
+    /// So all the frames have the same size, and a cell at the same position
+    /// in different frames is the same cell of the world.
+    ///
+    /// If there is no such cell, each frame is an empty pattern with
+    /// `x = 0, y = 0`.
+    #[inline]
+    pub fn frames(&self) -> Vec<String> {
+        Format::frames(self)
+    }
+
+    /// The states of the cells in all the generations in a period,
+    /// trimmed to the same rectangle as [`frames`](Self::frames).
+    ///
+    /// It is indexed as `grid[t][y][x]`, where `(x, y)` is relative to the
+    /// upper left corner of the rectangle. Unknown cells are treated as dead.
+    #[inline]
+    pub fn frames_grid(&self) -> Vec<Vec<Vec<State>>> {
+        Format::frames_grid(self)
+    }
+
+    /// Creates a new world of a larger size, with the same configuration.
+    ///
+    /// The [known cells](Config#structfield.known_cells),
+    /// the [prefix cells](Config#structfield.prefix_cells),
+    /// the [frozen cells](Config#structfield.frozen_cells), the
+    /// [containment box](Config#structfield.containment_box) and the
+    /// [phase masks](Config#structfield.phase_masks) are moved
+    /// according to the `anchor`. A [search order](Config#structfield.search_order)
+    /// given by a vector is dropped, since it does not cover the new cells.
+    /// The numbers of steps and conflicts are kept, but the search starts again
+    /// from scratch, and the [result filter](Self::set_result_filter) is not copied.
+    ///
+    /// Returns an error if the new size is smaller than the old size,
+    /// or if the new configuration is invalid. For example, when the configuration
+    /// requires the world to be square, both sizes must grow by the same amount.
+    /// When there are symmetries or transformations, the moved cells must stay
+    /// symmetric, so they can only be moved to the center by an even number of cells.
+    pub fn enlarge(
+        &self,
+        new_width: i32,
+        new_height: i32,
+        anchor: Anchor,
+    ) -> Result<PolyWorld, Error> {
+        let (width, height) = (self.config.width, self.config.height);
+        if new_width < width || new_height < height {
+            return Err(Error::ShrinkError);
+        }
+        let (dx, dy) = match anchor {
+            Anchor::TopLeft => (0, 0),
+            Anchor::Center => ((new_width - width) / 2, (new_height - height) / 2),
+        };
+        let has_positions = !self.config.known_cells.is_empty()
+            || !self.config.prefix_cells.is_empty()
+            || !self.config.frozen_cells.is_empty()
+            || self.config.containment_box.is_some()
+            || !self.config.phase_masks.is_empty();
+        if has_positions
+            && (self.config.has_symmetry() || self.config.transform != Transform::Id)
+            && (2 * dx != new_width - width || 2 * dy != new_height - height)
+        {
+            return Err(Error::EnlargeSymmetryError);
+        }
+
+        let config = Config {
+            width: new_width,
+            height: new_height,
+            known_cells: self
+                .config
+                .known_cells
+                .iter()
+                .map(
+                    |&KnownCell {
+                         coord: (x, y, t),
+                         state,
+                     }| KnownCell {
+                        coord: (x + dx, y + dy, t),
+                        state,
+                    },
+                )
+                .collect(),
+            prefix_cells: self
+                .config
+                .prefix_cells
+                .iter()
+                .map(
+                    |&KnownCell {
+                         coord: (x, y, t),
+                         state,
+                     }| KnownCell {
+                        coord: (x + dx, y + dy, t),
+                        state,
+                    },
+                )
+                .collect(),
+            frozen_cells: self
+                .config
+                .frozen_cells
+                .iter()
+                .map(|&(x, y, t)| (x + dx, y + dy, t))
+                .collect(),
+            containment_box: self
+                .config
+                .containment_box
+                .map(|(x0, y0, x1, y1)| (x0 + dx, y0 + dy, x1 + dx, y1 + dy)),
+            phase_masks: self
+                .config
+                .phase_masks
+                .iter()
+                .map(|phase_mask| PhaseMask {
+                    rect: (
+                        phase_mask.rect.0 + dx,
+                        phase_mask.rect.1 + dy,
+                        phase_mask.rect.2 + dx,
+                        phase_mask.rect.3 + dy,
+                    ),
+                    ..phase_mask.clone()
+                })
+                .collect(),
+            search_order: match &self.config.search_order {
+                Some(SearchOrder::FromVec(_)) => None,
+                search_order => search_order.clone(),
+            },
+            ..self.config.clone()
+        };
+        let mut world = config.world()?;
+        world.set_stats(self.steps, self.conflicts);
+        Ok(world)
+    }
+
+    /// Takes a snapshot of the world, which owns all its data,
+    /// and can be sent to another thread.
+    pub fn snapshot(&self) -> WorldSnapshot {
+        let width = self.config.width;
+        let size = (width * self.config.height) as usize;
+        let mut cells = vec![vec![None; size]; self.config.period as usize];
+        let mut background = Vec::with_capacity(self.config.period as usize);
+        for cell in self.cells.iter() {
+            let cell = unsafe { &*cell.get() };
+            let (x, y, t) = cell.coord;
+            if (x, y) == (-1, -1) {
+                background.push(self.invert(cell.background));
             }
-            str.push('\n');
+            if self.config.contains((x, y, t), false, false) {
+                cells[t as usize][(y * width + x) as usize] =
+                    cell.state.get().map(|state| self.invert(state));
+            }
+        }
+        WorldSnapshot {
+            config: self.config.clone(),
+            is_gen_rule: self.is_gen_rule(),
+            background,
+            cells,
+            cell_count: self.cell_count.clone(),
+            steps: self.steps,
+            conflicts: self.conflicts,
+            defects: self.defects(),
         }
-        str
+    }
+}
+
+impl<R: Rule, A: Algorithm<R>> Format for World<R, A> {
+    #[inline]
+    fn config(&self) -> &Config {
+        &self.config
+    }
+
+    #[inline]
+    fn is_gen_rule(&self) -> bool {
+        R::IsGen::VALUE
+    }
+
+    #[inline]
+    fn get_cell_state(&self, coord: Coord) -> Option<State> {
+        World::get_cell_state(self, coord)
+    }
+
+    #[inline]
+    fn background(&self, t: i32) -> State {
+        let t = t.rem_euclid(self.config.period);
+        self.invert(self.find_cell((-1, -1, t)).unwrap().background)
+    }
+
+    #[inline]
+    fn for_each_cell(&self, t: i32, f: &mut dyn FnMut(Coord, Option<State>)) {
+        World::for_each_cell(self, t, f)
+    }
+
+    #[inline]
+    fn cell_count_gen(&self, t: i32) -> u32 {
+        self.cell_count[t as usize]
     }
 }
@@ -1,4 +1,24 @@
-use rlifesrc_lib::{Config, Error, KnownCell, SearchOrder, Status, Symmetry, Transform, ALIVE};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use rlifesrc_lib::{
+    docs, search::SearchStats, AlgorithmChoice, Anchor, Config, Coord, Error, KnownCell, Line,
+    LineParity, NewState, PhaseMask, PhaseMaskMode, PolyWorld, ReduceTarget, RetreatStrategy,
+    SearchOrder, State, StateRepr, Status, Symmetry, Transform, ALIVE, DEAD,
+};
+use std::{collections::HashSet, ops::ControlFlow, time::Duration};
+
+/// Counts the living cells in some generation, as displayed by `rle_gen`.
+fn population(search: &PolyWorld, t: i32) -> u32 {
+    let config = search.config();
+    let mut count = 0;
+    for x in 0..config.width {
+        for y in 0..config.height {
+            if search.get_cell_state((x, y, t)) == Some(ALIVE) {
+                count += 1;
+            }
+        }
+    }
+    count
+}
 
 #[test]
 fn default() -> Result<(), Error> {
@@ -15,6 +35,60 @@ fn not_found() -> Result<(), Error> {
     Ok(())
 }
 
+#[test]
+fn search_timeout() -> Result<(), Error> {
+    let mut search = Config::new(16, 5, 3).set_translate(0, 1).world()?;
+    assert_eq!(search.search_timeout(Duration::MAX), Status::Found);
+    let mut search = Config::new(5, 5, 3).world()?;
+    assert_eq!(search.search_timeout(Duration::from_secs(60)), Status::None);
+
+    // The clock is checked after a fixed number of steps.
+    let mut search = Config::new(40, 40, 5).set_translate(0, 2).world()?;
+    assert_eq!(search.search_timeout(Duration::ZERO), Status::Searching);
+    let steps = search.steps();
+    assert!(steps > 0);
+    assert_eq!(search.search_timeout(Duration::ZERO), Status::Searching);
+    assert!(search.steps() > steps);
+    Ok(())
+}
+
+#[test]
+fn search_with() -> Result<(), Error> {
+    let config = Config::new(16, 7, 4).set_translate(0, 2);
+    let mut search = config.world()?;
+    assert_eq!(search.search(None), Status::Found);
+    let rle = search.rle_gen(0);
+    let steps = search.steps();
+
+    // Stops at the first callback, then resumes with `search`.
+    let mut search = config.world()?;
+    let mut calls = 0;
+    let status = search.search_with(|stats| {
+        calls += 1;
+        assert!(stats.steps > 0);
+        ControlFlow::Break(())
+    });
+    assert_eq!(status, Status::Searching);
+    assert_eq!(calls, 1);
+    assert!(search.steps() < steps);
+    assert_eq!(search.search(None), Status::Found);
+    assert_eq!(search.rle_gen(0), rle);
+    assert_eq!(search.steps(), steps);
+
+    // A callback that never stops behaves like `search(None)`.
+    let mut search = config.world()?;
+    let mut calls = 0;
+    let status = search.search_with(|_| {
+        calls += 1;
+        ControlFlow::Continue(())
+    });
+    assert_eq!(status, Status::Found);
+    assert!(calls > 0);
+    assert_eq!(search.rle_gen(0), rle);
+    assert_eq!(search.steps(), steps);
+    Ok(())
+}
+
 #[test]
 fn square_world() -> Result<(), Error> {
     let config = Config::new(10, 9, 1).set_symmetry(Symmetry::D2Diag);
@@ -25,6 +99,497 @@ fn square_world() -> Result<(), Error> {
     Ok(())
 }
 
+#[test]
+fn symmetry_from_members() -> Result<(), Error> {
+    let symmetry = "{Id,F\\}".parse().unwrap();
+    let config = Config::new(10, 10, 1).set_symmetry(symmetry);
+    let mut search = config.world()?;
+    assert_eq!(search.search(None), Status::Found);
+    let config = Config::new(10, 10, 1).set_symmetry(Symmetry::D2Diag);
+    let mut named_search = config.world()?;
+    assert_eq!(named_search.search(None), Status::Found);
+    assert_eq!(search.rle_gen(0), named_search.rle_gen(0));
+    Ok(())
+}
+
+#[test]
+fn apply_spec() -> Result<(), Error> {
+    let mut config = Config::new(16, 16, 1).set_max_cell_count(Some(20));
+    config.apply_spec("rule=B36/S23, w=20, h = 10, p=3 , dx=1, dy=-1, trans=R180")?;
+    assert_eq!(
+        config,
+        Config::new(20, 10, 3)
+            .set_rule_string("B36/S23")
+            .set_translate(1, -1)
+            .set_transform(Transform::Rotate180)
+            .set_max_cell_count(Some(20))
+    );
+
+    let mut config = Config::default();
+    config.apply_spec("width=8, height=8, period=2, sym={Id,F\\}, p=4,")?;
+    assert_eq!(
+        config,
+        Config::new(8, 8, 4).set_symmetry("{Id,F\\}".parse().unwrap())
+    );
+
+    let mut config = Config::default();
+    for spec in ["x=1", "w=1,p", "p=two", "sym=D3", "trans=Id, w=4, dx=0.5"] {
+        assert!(matches!(config.apply_spec(spec), Err(Error::SpecError(_))));
+    }
+    assert!(matches!(
+        config.apply_spec("w=4, rule=B3/S23/G"),
+        Err(Error::ParseRuleError(_))
+    ));
+    assert_eq!(config, Config::default());
+    Ok(())
+}
+
+#[test]
+fn speed() -> Result<(), Error> {
+    let config = Config::new(16, 16, 1);
+    for (speed, (dx, dy, period)) in [
+        ("c/2", (0, 1, 2)),
+        ("2c/5", (0, 2, 5)),
+        (" 2c/5o ", (0, 2, 5)),
+        ("c/4d", (1, 1, 4)),
+        ("3c/7d", (3, 3, 7)),
+        ("(2,1)c/6", (2, 1, 6)),
+        ("(-1, 0)c/2", (-1, 0, 2)),
+        ("(0, 0)c/3", (0, 0, 3)),
+        ("c/1", (0, 1, 1)),
+        ("(1, -1)c/1", (1, -1, 1)),
+    ] {
+        let speed_config = config.clone().set_speed(speed)?;
+        assert_eq!(
+            speed_config,
+            Config::new(16, 16, period).set_translate(dx, dy)
+        );
+        let speed_string = speed_config.speed_string();
+        assert_eq!(config.clone().set_speed(&speed_string)?, speed_config);
+    }
+    assert_eq!(
+        Config::new(16, 5, 3).set_translate(0, 1).speed_string(),
+        "c/3"
+    );
+    assert_eq!(
+        Config::new(16, 16, 4).set_translate(1, 1).speed_string(),
+        "c/4d"
+    );
+    assert_eq!(
+        Config::new(16, 16, 5).set_translate(2, 0).speed_string(),
+        "(2, 0)c/5"
+    );
+    assert_eq!(Config::default().speed_string(), "(0, 0)c/1");
+
+    for speed in [
+        "",
+        "c",
+        "c/",
+        "c/0",
+        "c/-2",
+        "c/2.5",
+        "1.5c/3",
+        "0c/3",
+        "-c/3",
+        "(1)c/3",
+        "(1,1)c/4d",
+        "c/4x",
+        "2c/1",
+        "(3, 1)c/2",
+        "c/4dd",
+    ] {
+        assert!(
+            matches!(config.clone().set_speed(speed), Err(Error::SpeedError(_))),
+            "{}",
+            speed
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn phase_symmetries() -> Result<(), Error> {
+    let config = Config::new(8, 8, 2).set_phase_symmetries(vec![Symmetry::D4Ortho, Symmetry::C2]);
+    let mut search = config.world()?;
+    assert_eq!(search.search(None), Status::Found);
+    for x in 0..8 {
+        for y in 0..8 {
+            let state = search.get_cell_state((x, y, 0));
+            assert_eq!(search.get_cell_state((7 - x, y, 0)), state);
+            assert_eq!(search.get_cell_state((x, 7 - y, 0)), state);
+        }
+    }
+
+    let config = Config::new(8, 8, 2).set_phase_symmetries(vec![Symmetry::D4Ortho]);
+    assert_eq!(
+        config.world().err(),
+        Some(Error::PhaseSymmetriesLengthError)
+    );
+
+    let config = Config::new(8, 8, 2)
+        .set_transform(Transform::Rotate90)
+        .set_phase_symmetries(vec![Symmetry::D2Row, Symmetry::D2Row]);
+    assert_eq!(
+        config.world().err(),
+        Some(Error::PhaseSymmetryError(Symmetry::D2Row, Symmetry::D2Col))
+    );
+    Ok(())
+}
+
+#[test]
+fn pred_first() -> Result<(), Error> {
+    let config = Config::new(6, 6, 4).set_translate(1, 1);
+    let mut search = config.world()?;
+    let mut pred_first_search = config.set_pred_first(true).world()?;
+    loop {
+        let status = search.search(None);
+        assert_eq!(pred_first_search.search(None), status);
+        assert_eq!(pred_first_search.rle_gen(0), search.rle_gen(0));
+        assert_eq!(pred_first_search.steps(), search.steps());
+        if status != Status::Found {
+            break;
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn transposition_cache() -> Result<(), Error> {
+    let configs = [
+        Config::new(3, 20, 2),
+        Config::new(5, 12, 2).set_max_cell_count(12),
+        Config::new(4, 12, 2).set_rule_string("3457/357/5"),
+        Config::new(4, 16, 2).set_symmetry(Symmetry::D2Row),
+        Config::new(16, 5, 3).set_translate(0, 1),
+    ];
+    let mut hits = 0;
+    for config in configs {
+        // A tiny capacity to test the eviction.
+        for capacity in [2, 1 << 16] {
+            let mut search = config.world()?;
+            assert_eq!(search.cache_stats(), None);
+            let mut cached_search = config.clone().set_transposition_cache(capacity).world()?;
+            // Compares all the results.
+            loop {
+                let status = search.search(None);
+                assert_eq!(cached_search.search(None), status);
+                assert_eq!(cached_search.rle_gen(0), search.rle_gen(0));
+                if status != Status::Found {
+                    break;
+                }
+            }
+            let stats = cached_search.cache_stats().unwrap();
+            assert!(stats.len <= capacity);
+            hits += stats.hits;
+        }
+    }
+    assert!(hits > 0);
+    Ok(())
+}
+
+#[test]
+fn search_growing() -> Result<(), Error> {
+    let (search, status) = Config::new(1, 1, 1).search_growing(4, 4)?;
+    assert_eq!(status, Status::Found);
+    assert_eq!((search.config().width, search.config().height), (2, 2));
+
+    let (search, status) = Config::new(1, 1, 3).search_growing(3, 3)?;
+    assert_eq!(status, Status::None);
+    assert_eq!((search.config().width, search.config().height), (3, 3));
+
+    let config = Config::new(1, 2, 1).set_symmetry(Symmetry::D2Diag);
+    assert_eq!(
+        config.search_growing(1, 3).err(),
+        Some(Error::SquareWorldError)
+    );
+    Ok(())
+}
+
+#[test]
+fn enlarge() -> Result<(), Error> {
+    let config = Config::new(5, 5, 1).set_max_cell_count(4);
+    let mut search = config.world()?;
+    while search.search(None) == Status::Found {}
+    let steps = search.steps();
+
+    let mut enlarged = search.enlarge(6, 6, Anchor::TopLeft)?;
+    assert_eq!(enlarged.steps(), steps);
+    assert_eq!(enlarged.search(None), Status::Found);
+    assert!(enlarged.steps() > steps);
+    let mut new_search = Config::new(6, 6, 1).set_max_cell_count(4).world()?;
+    assert_eq!(new_search.search(None), Status::Found);
+    assert_eq!(enlarged.rle_gen(0), new_search.rle_gen(0));
+
+    // The known block is moved to the center.
+    let known_cells = [(1, 1), (1, 2), (2, 1), (2, 2)]
+        .into_iter()
+        .map(|(x, y)| KnownCell {
+            coord: (x, y, 0),
+            state: ALIVE,
+        })
+        .collect::<Vec<_>>();
+    let search = Config::new(4, 4, 1).set_known_cells(known_cells).world()?;
+    let mut enlarged = search.enlarge(6, 7, Anchor::Center)?;
+    assert_eq!(enlarged.search(None), Status::Found);
+    for (x, y) in [(2, 2), (2, 3), (3, 2), (3, 3)] {
+        assert_eq!(enlarged.get_cell_state((x, y, 0)), Some(ALIVE));
+    }
+    assert_eq!(
+        search.enlarge(3, 5, Anchor::TopLeft).err(),
+        Some(Error::ShrinkError)
+    );
+
+    let search = Config::new(4, 4, 1)
+        .set_symmetry(Symmetry::D2Diag)
+        .world()?;
+    assert_eq!(
+        search.enlarge(5, 4, Anchor::TopLeft).err(),
+        Some(Error::SquareWorldError)
+    );
+    let search = Config::new(4, 4, 1)
+        .set_symmetry(Symmetry::C2)
+        .set_known_cells(vec![KnownCell {
+            coord: (0, 0, 0),
+            state: DEAD,
+        }])
+        .world()?;
+    assert_eq!(
+        search.enlarge(5, 6, Anchor::Center).err(),
+        Some(Error::EnlargeSymmetryError)
+    );
+    assert!(search.enlarge(6, 6, Anchor::Center).is_ok());
+    Ok(())
+}
+
+#[test]
+fn search_min_population() -> Result<(), Error> {
+    let (search, status) = Config::new(6, 6, 2).search_min_population(10)?;
+    assert_eq!(status, Status::Found);
+    assert_eq!(search.cell_count(), 3);
+    assert_eq!(search.config().max_cell_count, Some(3));
+
+    let config = Config::new(5, 5, 4).set_translate(1, 1);
+    let (search, status) = config.search_min_population(10)?;
+    assert_eq!(status, Status::Found);
+    assert_eq!(search.cell_count(), 5);
+
+    let (search, status) = config.search_min_population(4)?;
+    assert_eq!(status, Status::None);
+    assert_eq!(search.config().max_cell_count, Some(4));
+
+    assert_eq!(
+        config.search_min_population(0).err(),
+        Some(Error::NonPositiveError)
+    );
+    Ok(())
+}
+
+#[test]
+fn infeasibility_check() {
+    let config = Config::new(8, 8, 1).set_max_cell_count(2);
+    assert!(config.infeasibility_check().is_some());
+    assert!(config.set_max_cell_count(3).infeasibility_check().is_none());
+    let config = Config::new(16, 5, 3)
+        .set_translate(0, 1)
+        .set_max_cell_count(2);
+    assert!(config.infeasibility_check().is_some());
+    assert!(Config::new(8, 8, 2)
+        .set_rule_string("B/S23")
+        .infeasibility_check()
+        .is_some());
+    assert!(Config::new(8, 8, 1)
+        .set_rule_string("B3/S")
+        .infeasibility_check()
+        .is_some());
+
+    // A single cell is a still life when it survives without neighbors.
+    let config = Config::new(8, 8, 1)
+        .set_rule_string("B3/S012345678")
+        .set_max_cell_count(1);
+    assert_eq!(config.infeasibility_check(), None);
+    let config = Config::new(8, 8, 2)
+        .set_rule_string("B1/S")
+        .set_max_cell_count(1);
+    assert_eq!(config.infeasibility_check(), None);
+    let config = Config::new(8, 8, 2)
+        .set_rule_string("B0/S2")
+        .set_max_cell_count(1);
+    assert_eq!(config.infeasibility_check(), None);
+}
+
+#[test]
+fn random_biased() -> Result<(), Error> {
+    const TRIALS: u32 = 2000;
+
+    // Frequencies of the first decision being alive and being dead.
+    let frequencies = |config: Config| -> Result<(f64, f64), Error> {
+        let config = config
+            .set_search_order(SearchOrder::FromVec(vec![(2, 2, 0)]))
+            .set_new_state(NewState::RandomBiased {
+                alive_probability: 0.2,
+            });
+        let (mut alive, mut dead) = (0, 0);
+        for _ in 0..TRIALS {
+            let mut search = config.world()?;
+            assert_eq!(search.search(Some(0)), Status::Searching);
+            match search.get_cell_state((2, 2, 0)) {
+                Some(ALIVE) => alive += 1,
+                Some(DEAD) => dead += 1,
+                _ => (),
+            }
+        }
+        Ok((alive as f64 / TRIALS as f64, dead as f64 / TRIALS as f64))
+    };
+
+    let (alive, dead) = frequencies(Config::new(5, 5, 1))?;
+    assert!((0.15..0.25).contains(&alive));
+    assert!((0.75..0.85).contains(&dead));
+
+    let (alive, dead) = frequencies(Config::new(5, 5, 1).set_rule_string("23/3/3"))?;
+    assert!((0.15..0.25).contains(&alive));
+    assert!((0.35..0.45).contains(&dead));
+
+    for alive_probability in [0.0, 1.0, 1.5, f64::NAN] {
+        let config =
+            Config::new(5, 5, 1).set_new_state(NewState::RandomBiased { alive_probability });
+        assert_eq!(config.world().err(), Some(Error::ProbabilityError));
+    }
+    Ok(())
+}
+
+#[test]
+fn can_continue() -> Result<(), Error> {
+    let mut search = Config::new(5, 5, 1).set_max_cell_count(4).world()?;
+    assert!(search.can_continue());
+    assert_eq!(search.search(Some(0)), Status::Searching);
+    assert!(search.can_continue());
+    while search.search(None) == Status::Found {
+        assert!(search.can_continue());
+    }
+    assert!(!search.can_continue());
+    assert_eq!(search.search(None), Status::None);
+
+    let mut search = Config::new(5, 5, 3).world()?;
+    assert_eq!(search.search(None), Status::None);
+    assert!(!search.can_continue());
+    Ok(())
+}
+
+#[test]
+fn reset() -> Result<(), Error> {
+    /// The first few results, with the steps and conflicts to find them.
+    fn results(search: &mut PolyWorld) -> Vec<(String, u64, u64, Option<u32>)> {
+        let mut results = Vec::new();
+        for _ in 0..4 {
+            if search.search(None) != Status::Found {
+                break;
+            }
+            results.push((
+                search.rle_gen(0),
+                search.steps(),
+                search.conflicts(),
+                search.config().max_cell_count,
+            ));
+        }
+        results
+    }
+
+    let configs = [
+        Config::new(16, 5, 3)
+            .set_translate(0, 1)
+            .set_reduce_max(true),
+        Config::new(16, 5, 3)
+            .set_translate(0, 1)
+            .set_reduce_max(true)
+            .set_algorithm(AlgorithmChoice::Backjump),
+        Config::new(5, 5, 1).set_max_cell_count(4),
+        Config::new(4, 4, 3).set_rule_string("23/3/3"),
+        Config::new(5, 5, 3),
+    ];
+    for config in configs {
+        let mut search = config.world()?;
+        let expected = results(&mut search);
+        assert_eq!(results(&mut config.world()?), expected);
+        search.reset();
+        #[cfg(feature = "debug-invariants")]
+        search.assert_consistent();
+        assert_eq!(search.steps(), 0);
+        assert_eq!(search.config().max_cell_count, config.max_cell_count);
+        assert_eq!(search.can_continue(), config.world()?.can_continue());
+        assert_eq!(results(&mut search), expected);
+    }
+    Ok(())
+}
+
+#[test]
+fn stats() -> Result<(), Error> {
+    let mut search = Config::new(16, 5, 3).set_translate(0, 1).world()?;
+    let initial = search.stats();
+    assert_eq!(
+        initial,
+        SearchStats {
+            known_cells: initial.known_cells,
+            ..SearchStats::default()
+        }
+    );
+    assert!(initial.known_cells < 240);
+
+    assert_eq!(search.search(None), Status::Found);
+    let stats = search.stats();
+    assert_eq!(stats.steps, search.steps());
+    assert_eq!(stats.conflicts, search.conflicts());
+    assert!(stats.decisions > 0);
+    assert_eq!(stats.known_cells, 240);
+    assert_eq!(stats.depth as u32, 240 - initial.known_cells);
+    assert!(stats.depth <= stats.max_depth);
+
+    search.reset();
+    assert_eq!(search.stats(), initial);
+    Ok(())
+}
+
+#[test]
+fn failure_hint() -> Result<(), Error> {
+    let mut search = Config::new(6, 6, 1).set_max_cell_count(3).world()?;
+    assert_eq!(search.failure_hint(), None);
+    assert_eq!(search.search(None), Status::None);
+    assert_eq!(
+        search.failure_hint().as_deref(),
+        Some("max_cell_count=3 may be too low.")
+    );
+
+    // A lonely living cell dies.
+    let known_cells = (0..3)
+        .flat_map(|x| (0..3).map(move |y| (x, y)))
+        .map(|(x, y)| KnownCell {
+            coord: (x, y, 0),
+            state: if (x, y) == (1, 1) { ALIVE } else { DEAD },
+        })
+        .collect::<Vec<_>>();
+    let mut search = Config::new(3, 3, 1).set_known_cells(known_cells).world()?;
+    assert!(!search.can_continue());
+    assert_eq!(search.search(None), Status::None);
+    assert_eq!(
+        search.failure_hint().as_deref(),
+        Some("The known cells conflicted before the search started.")
+    );
+
+    let mut search = Config::new(5, 5, 3).world()?;
+    assert_eq!(search.search(None), Status::None);
+    assert_eq!(
+        search.failure_hint().as_deref(),
+        Some("The world may be too small. Try a larger width or height.")
+    );
+
+    let mut search = Config::new(5, 5, 2).set_symmetry(Symmetry::D8).world()?;
+    assert_eq!(search.search(None), Status::None);
+    assert_eq!(
+        search.failure_hint().as_deref(),
+        Some("symmetry=D8 may be too restrictive.")
+    );
+    Ok(())
+}
+
 #[test]
 fn max_cell_count() -> Result<(), Error> {
     let config = Config::new(5, 5, 1).set_max_cell_count(Some(5));
@@ -35,6 +600,112 @@ fn max_cell_count() -> Result<(), Error> {
     Ok(())
 }
 
+#[test]
+fn max_cell_count_per_gen() -> Result<(), Error> {
+    // The phase of the beacon with 6 cells, which becomes 8 cells in generation 1.
+    let beacon = [(0, 0), (1, 0), (0, 1), (3, 2), (2, 3), (3, 3)];
+    let known_cells = (0..4)
+        .flat_map(|x| (0..4).map(move |y| (x, y)))
+        .map(|(x, y)| KnownCell {
+            coord: (x, y, 0),
+            state: if beacon.contains(&(x, y)) {
+                ALIVE
+            } else {
+                DEAD
+            },
+        })
+        .collect::<Vec<_>>();
+    let config = Config::new(4, 4, 2).set_known_cells(known_cells);
+
+    let mut search = config.clone().set_max_cell_count(6).world()?;
+    assert_eq!(search.search(None), Status::Found);
+    assert_eq!(search.populations(), vec![6, 8]);
+
+    let mut search = config.clone().set_max_cell_count_per_gen(7).world()?;
+    assert_eq!(search.search(None), Status::None);
+
+    let mut search = config
+        .set_max_cell_count_per_gen(8)
+        .set_reduce_max(true)
+        .world()?;
+    assert_eq!(search.search(None), Status::Found);
+    assert_eq!(search.config().max_cell_count, Some(5));
+    assert_eq!(search.config().max_cell_count_per_gen, Some(8));
+    Ok(())
+}
+
+#[test]
+fn min_cell_count() -> Result<(), Error> {
+    let populations = |config: Config| -> Result<Vec<u32>, Error> {
+        let mut search = config.world()?;
+        let mut populations = Vec::new();
+        while search.search(None) == Status::Found {
+            populations.push(population(&search, 0));
+        }
+        Ok(populations)
+    };
+
+    let config = Config::new(5, 5, 1);
+    let all = populations(config.clone())?;
+    let large = all.iter().copied().filter(|&n| n >= 7).collect::<Vec<_>>();
+    assert!(!large.is_empty() && large.len() < all.len());
+    assert_eq!(populations(config.clone().set_min_cell_count(7))?, large);
+    assert_eq!(
+        populations(
+            config
+                .clone()
+                .set_min_cell_count(7)
+                .set_algorithm(AlgorithmChoice::Backjump)
+        )?,
+        large
+    );
+
+    // The reduced max cell count never goes below the min cell count.
+    let mut search = config
+        .clone()
+        .set_min_cell_count(6)
+        .set_reduce_max(true)
+        .world()?;
+    while search.search(None) == Status::Found {
+        assert!(population(&search, 0) >= 6);
+        assert!(search.config().max_cell_count >= Some(6));
+    }
+    assert_eq!(search.config().max_cell_count, Some(6));
+
+    assert_eq!(
+        config
+            .set_max_cell_count(4)
+            .set_min_cell_count(5)
+            .infeasibility_check()
+            .as_deref(),
+        Some("The min cell count is 5, but the max cell count is 4.")
+    );
+    Ok(())
+}
+
+#[test]
+fn min_cell_count_trivial() -> Result<(), Error> {
+    // The known cells admit the empty pattern, which is found first.
+    let config = Config::new(5, 5, 1)
+        .set_known_cells(vec![KnownCell {
+            coord: (0, 0, 0),
+            state: DEAD,
+        }])
+        .set_new_state(NewState::ChooseDead)
+        .set_allow_trivial(true);
+    let mut search = config.world()?;
+    assert_eq!(search.search(None), Status::Found);
+    assert!(search.is_trivial());
+
+    let mut search = config.set_min_cell_count(1).world()?;
+    assert_eq!(search.search(None), Status::Found);
+    assert!(!search.is_trivial());
+
+    #[cfg(feature = "serde")]
+    assert_eq!(search.ser().world()?.config().min_cell_count, Some(1));
+    Ok(())
+}
+
 #[test]
 fn reduce_max() -> Result<(), Error> {
     let config = Config::new(5, 5, 1)
@@ -46,6 +717,74 @@ fn reduce_max() -> Result<(), Error> {
     Ok(())
 }
 
+#[test]
+fn reduce_max_symmetry() -> Result<(), Error> {
+    for symmetry in [Symmetry::D4Ortho, Symmetry::D4Diag] {
+        let config = Config::new(8, 8, 1)
+            .set_symmetry(symmetry)
+            .set_reduce_max(true);
+        let mut search = config.world()?;
+        assert_eq!(search.search(None), Status::Found);
+        let count = population(&search, 0);
+        assert_eq!(search.cell_count(), count);
+        assert_eq!(search.config().max_cell_count, Some(count - 1));
+        while search.search(None) == Status::Found {
+            let new_count = population(&search, 0);
+            assert_eq!(search.cell_count(), new_count);
+            assert!(new_count < count);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn reduce_target() -> Result<(), Error> {
+    let config = Config::new(16, 5, 3)
+        .set_translate(0, 1)
+        .set_reduce_max(true)
+        .set_reduce_target(ReduceTarget::Gen(1));
+    let mut search = config.world()?;
+    assert_eq!(search.search(None), Status::Found);
+    let mut count = search.cell_count_gen(1);
+    assert_eq!(search.config().max_cell_count, Some(count - 1));
+    while search.search(None) == Status::Found {
+        let new_count = search.cell_count_gen(1);
+        assert!(new_count < count);
+        assert_eq!(search.config().max_cell_count, Some(new_count - 1));
+        count = new_count;
+    }
+
+    let config = Config::new(16, 5, 3).set_reduce_target(ReduceTarget::Gen(3));
+    assert_eq!(
+        config.world().err(),
+        Some(Error::ReduceTargetOutOfBounds(3))
+    );
+    Ok(())
+}
+
+#[test]
+fn cell_count_gen() -> Result<(), Error> {
+    for config in [
+        Config::new(16, 5, 3).set_translate(0, 1),
+        Config::new(4, 4, 3).set_rule_string("23/3/3"),
+    ] {
+        let mut search = config.set_reduce_max(true).world()?;
+        assert_eq!(search.search(None), Status::Found);
+        let counts = (0..3).map(|t| search.cell_count_gen(t)).collect::<Vec<_>>();
+        for t in 0..3 {
+            // Only the cells in state 1 are counted for Generations rules.
+            assert_eq!(counts[t as usize], population(&search, t));
+            assert_eq!(search.snapshot().cell_count_gen(t), counts[t as usize]);
+        }
+        assert_eq!(search.populations(), counts);
+        let min = *counts.iter().min().unwrap();
+        assert_eq!(search.cell_count(), min);
+        assert_eq!(search.snapshot().cell_count(), min);
+        assert_eq!(search.config().max_cell_count, Some(min - 1));
+    }
+    Ok(())
+}
+
 #[test]
 fn p3_spaceship() -> Result<(), Error> {
     let config = Config::new(16, 5, 3).set_translate(0, 1);
@@ -66,14 +805,18 @@ fn p3_spaceship() -> Result<(), Error> {
 }
 
 #[test]
-fn backjump() -> Result<(), Error> {
-    let config = Config::new(16, 5, 3).set_translate(0, 1).set_backjump(true);
+fn rle_annotated() -> Result<(), Error> {
+    let config = Config::new(16, 5, 3).set_translate(0, 1);
     let mut search = config.world()?;
     assert_eq!(search.search(None), Status::Found);
     assert_eq!(
-        search.rle_gen(0),
+        search.rle_gen_annotated(0),
         String::from(
-            "x = 16, y = 5, rule = B3/S23\n\
+            "#C width = 16, height = 5, period = 3\n\
+             #C dx = 0, dy = 1\n\
+             #C symmetry = C1, transform = Id\n\
+             #C rule = B3/S23\n\
+             x = 16, y = 5, rule = B3/S23\n\
              ........o.......$\n\
              .oo.ooo.ooo.....$\n\
              .oo....o..oo.oo.$\n\
@@ -85,75 +828,645 @@ fn backjump() -> Result<(), Error> {
 }
 
 #[test]
-fn lwss() -> Result<(), Error> {
-    let config = Config::new(6, 6, 4).set_translate(0, 2);
-    let mut search = config.world()?;
-    assert_eq!(search.search(None), Status::Found);
+fn rle_rule_header() -> Result<(), Error> {
+    for (rule_string, canonical) in [
+        ("b3/s23", "B3/S23"),
+        ("32/3", "B3/S23"),
+        ("B36/S23", "B36/S23"),
+        ("23/3/3", "B3/S23/G3"),
+        ("B2/S/3", "B2/S/G3"),
+        ("013/2v", "B2/S013V"),
+        (
+            "MAPARYXfhZofugWaH7oaIDogBZofuhogOiAaIDogIAAgAAWaH7oaIDogGiA6ICAAIAAaIDogIAAgACAAIAAAAAAAA",
+            "B3/S23",
+        ),
+    ] {
+        let config = Config::new(4, 4, 1).set_rule_string(rule_string);
+        assert_eq!(config.rule_string_canonical(), canonical);
+        let search = config.world()?;
+        let rle = search.rle_gen(0);
+        let header = rle.lines().next().unwrap();
+        let rule = header.split("rule = ").nth(1).unwrap();
+        assert_eq!(rule, canonical);
+
+        // The header can be parsed back, and gives the same rule.
+        let new_config = Config::new(4, 4, 1).set_rule_string(rule);
+        assert!(new_config.world().is_ok());
+        assert_eq!(new_config.rule_string_canonical(), canonical);
+    }
     Ok(())
 }
 
 #[test]
-fn lwss_flip() -> Result<(), Error> {
-    let config = Config::new(5, 5, 2)
-        .set_translate(0, 1)
-        .set_transform(Transform::FlipCol);
-    let mut search = config.world()?;
-    assert_eq!(search.search(None), Status::Found);
+fn supported_formats() -> Result<(), Error> {
+    for format in rlifesrc_lib::rules::supported_formats() {
+        let (_, examples) = format.split_once("e.g., ").unwrap();
+        for rule_string in examples.split(" or ") {
+            Config::new(4, 4, 1).set_rule_string(rule_string).world()?;
+        }
+    }
     Ok(())
 }
 
 #[test]
-fn turtle() -> Result<(), Error> {
-    let config = Config::new(12, 13, 3)
-        .set_translate(0, 1)
-        .set_symmetry(Symmetry::D2Col);
+fn neumann() -> Result<(), Error> {
+    // Two cells with a gap between them: the gap is born, since it has two
+    // living neighbors, and then dies again, while the outer cells survive.
+    // The cells above and below the gap only have one living neighbor.
+    let config = Config::new(3, 1, 2).set_rule_string("B2/S013V");
     let mut search = config.world()?;
     assert_eq!(search.search(None), Status::Found);
+    let mut populations = search.populations();
+    populations.sort_unstable();
+    assert_eq!(populations, vec![2, 3]);
+    for t in 0..2 {
+        for x in 0..3 {
+            let expected = if x == 1 && search.cell_count_gen(t) == 2 {
+                DEAD
+            } else {
+                ALIVE
+            };
+            assert_eq!(search.get_cell_state((x, 0, t)), Some(expected));
+        }
+    }
+
+    // With the Moore neighborhood, the cells above and below the gap are born.
+    let config = Config::new(3, 1, 2).set_rule_string("B2/S013");
+    assert_eq!(config.world()?.search(None), Status::None);
+
+    for rule_string in ["B5/S013V", "B2/S013X/V", "B2V"] {
+        let config = Config::new(3, 1, 2).set_rule_string(rule_string);
+        assert!(matches!(
+            config.world().err(),
+            Some(Error::ParseNeumannRuleError(_))
+        ));
+    }
+    let config = Config::new(3, 1, 2).set_rule_string("B0/S4V");
+    assert_eq!(config.world().err(), Some(Error::B0S8Error));
     Ok(())
 }
 
 #[test]
-fn diagonal() -> Result<(), Error> {
-    let config = Config::new(8, 8, 4)
-        .set_translate(1, 1)
-        .set_search_order(Some(SearchOrder::Diagonal));
+fn hexagonal() -> Result<(), Error> {
+    // As in Golly, the northeast and southwest corners are not neighbors.
+    const HEX_NBHD: [(i32, i32); 6] = [(-1, -1), (0, -1), (-1, 0), (1, 0), (0, 1), (1, 1)];
+    let config = Config::new(5, 5, 2).set_rule_string("b2/s34h");
+    assert_eq!(config.rule_string_canonical(), "B2/S34H");
     let mut search = config.world()?;
-    assert_eq!(search.search(None), Status::Found);
+    let mut count = 0;
+    while search.search(None) == Status::Found {
+        count += 1;
+        let header = search.rle_gen(0).lines().next().unwrap().to_owned();
+        assert!(header.ends_with("rule = B2/S34H"));
+        for t in 0..2 {
+            let alive = |x, y| search.get_cell_state((x, y, t)) == Some(ALIVE);
+            for x in -1..=5 {
+                for y in -1..=5 {
+                    let n = HEX_NBHD
+                        .iter()
+                        .filter(|&&(dx, dy)| alive(x + dx, y + dy))
+                        .count();
+                    let next = if alive(x, y) {
+                        n == 3 || n == 4
+                    } else {
+                        n == 2
+                    };
+                    let succ = search.get_cell_state((x, y, (t + 1) % 2));
+                    assert_eq!(succ.unwrap_or(DEAD) == ALIVE, next);
+                }
+            }
+        }
+    }
+    assert!(count > 0);
+
+    // Non-totalistic hexagonal rules are not parsed as totalistic ones.
+    let config = Config::new(5, 5, 2).set_rule_string("B2o/S34H");
+    assert!(!matches!(
+        config.world().err(),
+        Some(Error::ParseHexRuleError(_))
+    ));
+    let config = Config::new(5, 5, 2).set_rule_string("B0/S6H");
+    assert_eq!(config.world().err(), Some(Error::B0S8Error));
     Ok(())
 }
 
+/// The living cells of a 2-state pattern displayed by `rle_gen`.
+fn living_cells(rle: &str) -> HashSet<(i32, i32)> {
+    rle.lines()
+        .filter(|line| !line.starts_with('#') && !line.starts_with('x'))
+        .enumerate()
+        .flat_map(|(y, line)| {
+            line.chars()
+                .enumerate()
+                .filter(|&(_, c)| c == 'o')
+                .map(move |(x, _)| (x as i32, y as i32))
+        })
+        .collect()
+}
+
+/// Evolves a pattern in Conway's Game of Life by one generation.
+fn life_step(cells: &HashSet<(i32, i32)>) -> HashSet<(i32, i32)> {
+    let candidates = cells
+        .iter()
+        .flat_map(|&(x, y)| (-1..=1).flat_map(move |dx| (-1..=1).map(move |dy| (x + dx, y + dy))))
+        .collect::<HashSet<_>>();
+    candidates
+        .into_iter()
+        .filter(|&(x, y)| {
+            let n = (-1..=1)
+                .flat_map(|dx| (-1..=1).map(move |dy| (dx, dy)))
+                .filter(|&(dx, dy)| (dx, dy) != (0, 0) && cells.contains(&(x + dx, y + dy)))
+                .count();
+            n == 3 || (n == 2 && cells.contains(&(x, y)))
+        })
+        .collect()
+}
+
 #[test]
-fn diagonal_width() -> Result<(), Error> {
-    let config = Config::new(10, 10, 4)
-        .set_translate(1, 1)
-        .set_search_order(Some(SearchOrder::Diagonal))
-        .set_diagonal_width(4);
-    let mut search = config.world()?;
-    assert_eq!(search.search(None), Status::Found);
+fn rle_rotated() -> Result<(), Error> {
+    for config in [
+        Config::new(16, 5, 3).set_translate(0, 1),
+        Config::new(9, 9, 3),
+    ] {
+        let (dx, dy, period) = (config.dx, config.dy, config.period);
+        let mut search = config.world()?;
+        assert_eq!(search.search(None), Status::Found);
+        let gens = (0..period).map(|t| search.rle_gen(t)).collect::<Vec<_>>();
+        assert_eq!(search.rle_gen_rotated(0), gens);
+        for offset in 0..period {
+            let rotated = search.rle_gen_rotated(offset);
+            if dx == 0 && dy == 0 {
+                let mut gens = gens.clone();
+                gens.rotate_left(offset as usize);
+                assert_eq!(rotated, gens);
+            }
+            // Each generation evolves into the next one, with a translation
+            // only when crossing the end of the period.
+            for i in 0..period {
+                let (sx, sy) = if offset + i == period - 1 {
+                    (dx, dy)
+                } else {
+                    (0, 0)
+                };
+                let next = life_step(&living_cells(&rotated[i as usize]))
+                    .into_iter()
+                    .map(|(x, y)| (x + sx, y + sy))
+                    .collect::<HashSet<_>>();
+                assert_eq!(next, living_cells(&rotated[((i + 1) % period) as usize]));
+            }
+        }
+        let min_gen = search.min_population_gen();
+        assert!((0..period).all(|t| population(&search, min_gen) <= population(&search, t)));
+        assert_eq!(search.rle_all_gens_min_phase()[0], search.rle_gen(min_gen));
+    }
     Ok(())
 }
 
 #[test]
-fn b0() -> Result<(), Error> {
-    let config = Config::new(3, 3, 2).set_rule_string("B026/S1");
-    let mut search = config.world()?;
-    assert_eq!(search.search(None), Status::Found);
+fn rle_all() -> Result<(), Error> {
+    for config in [
+        Config::new(16, 5, 3).set_translate(0, 1),
+        Config::new(4, 4, 3).set_rule_string("23/3/3"),
+    ] {
+        let mut search = config.world()?;
+        assert_eq!(search.search(None), Status::Found);
+        let rles = search.rle_all();
+        let gens = (0..config.period)
+            .map(|t| format!("#C gen {}\n{}", t, search.rle_gen(t)))
+            .collect::<String>();
+        assert_eq!(rles, gens);
+        assert_eq!(search.snapshot().rle_all(), rles);
+
+        // Reading it back gives the same pattern in every generation.
+        #[cfg(feature = "read-rle")]
+        {
+            let new_config = config.clone().set_known_cells_from_rles(&rles)?;
+            assert_eq!(
+                new_config.known_cells.len() as i32,
+                config.width * config.height * config.period
+            );
+            for KnownCell { coord, state } in new_config.known_cells {
+                assert_eq!(search.get_cell_state(coord), Some(state));
+            }
+        }
+    }
     Ok(())
 }
 
 #[test]
-fn p3_2333() -> Result<(), Error> {
+fn plaintext_gen() -> Result<(), Error> {
+    for config in [
+        Config::new(16, 5, 3).set_translate(0, 1),
+        Config::new(4, 4, 3).set_rule_string("23/3/3"),
+    ] {
+        let mut search = config.world()?;
+        assert_eq!(search.search(None), Status::Found);
+        for t in 0..3 {
+            // The same as the RLE without the header and the line endings.
+            let rle = search.rle_gen(t);
+            let rows = rle
+                .lines()
+                .skip(1)
+                .map(|line| format!("{}\n", &line[..line.len() - 1]))
+                .collect::<String>();
+            let header = format!("!rule = {}, period = 3\n", config.rule_string_canonical());
+            assert_eq!(search.plaintext_gen(t), header + &rows);
+        }
+    }
+
     let config = Config::new(4, 4, 3).set_rule_string("23/3/3");
     let mut search = config.world()?;
+    assert_eq!(
+        search.plaintext_gen(0),
+        String::from("!rule = B3/S23/G3, period = 3\n") + &"????\n".repeat(4)
+    );
     assert_eq!(search.search(None), Status::Found);
+    assert!((0..3).any(|t| search.plaintext_gen(t).contains('B')));
     Ok(())
 }
 
 #[test]
-fn snowflakes() -> Result<(), Error> {
-    let config = Config::new(4, 4, 2)
-        .set_translate(0, 1)
-        .set_rule_string("B2ci3ai4c8/S02ae3eijkq4iz5ar6i7e");
+fn iter_cells() -> Result<(), Error> {
+    let config = Config::new(16, 5, 3).set_translate(0, 1);
+    let mut search = config.world()?;
+    assert_eq!(search.iter_cells(0).count(), 80);
+    assert!(search.iter_cells(1).all(|(_, state)| state.is_none()));
+    assert_eq!(search.search(None), Status::Found);
+    for t in 0..4 {
+        let cells = search.iter_cells(t).collect::<Vec<_>>();
+        assert_eq!(cells.len(), 80);
+        for (i, &((x, y, t1), state)) in cells.iter().enumerate() {
+            assert_eq!((x, y, t1), (i as i32 % 16, i as i32 / 16, t));
+            assert_eq!(state, search.get_cell_state((x, y, t)));
+        }
+        let mut visited = Vec::new();
+        search.for_each_cell(t, |coord, state| visited.push((coord, state)));
+        assert_eq!(visited, cells);
+        assert!(search.snapshot().iter_cells(t).eq(cells.iter().copied()));
+
+        let mut plaintext = String::from("!rule = B3/S23, period = 3\n");
+        for ((x, _, _), state) in cells {
+            plaintext.push(if state == Some(ALIVE) { 'o' } else { '.' });
+            if x == 15 {
+                plaintext.push('\n');
+            }
+        }
+        assert_eq!(search.plaintext_gen(t), plaintext);
+        assert_eq!(search.rle_gen(t), search.snapshot().rle_gen(t));
+    }
+
+    // Cells outside the diagonal width are visited with the background state.
+    let config = Config::new(10, 10, 4)
+        .set_translate(1, 1)
+        .set_search_order(Some(SearchOrder::Diagonal))
+        .set_diagonal_width(4);
+    let search = config.world()?;
+    assert_eq!(search.iter_cells(0).count(), 100);
+    for ((x, y, t), state) in search.iter_cells(0) {
+        if (x - y).abs() >= 4 {
+            assert_eq!(state, Some(DEAD));
+        }
+        assert_eq!(state, search.get_cell_state((x, y, t)));
+    }
+    Ok(())
+}
+
+#[test]
+fn backjump() -> Result<(), Error> {
+    let config = Config::new(16, 5, 3)
+        .set_translate(0, 1)
+        .set_algorithm(AlgorithmChoice::Backjump);
+    let mut search = config.world()?;
+    assert_eq!(search.search(None), Status::Found);
+    assert_eq!(
+        search.rle_gen(0),
+        String::from(
+            "x = 16, y = 5, rule = B3/S23\n\
+             ........o.......$\n\
+             .oo.ooo.ooo.....$\n\
+             .oo....o..oo.oo.$\n\
+             o..o.oo...o..oo.$\n\
+             ............o..o!\n"
+        )
+    );
+    Ok(())
+}
+
+#[test]
+fn lwss() -> Result<(), Error> {
+    let config = Config::new(6, 6, 4).set_translate(0, 2);
+    let mut search = config.world()?;
+    assert_eq!(search.search(None), Status::Found);
+    Ok(())
+}
+
+#[test]
+fn lwss_flip() -> Result<(), Error> {
+    let config = Config::new(5, 5, 2)
+        .set_translate(0, 1)
+        .set_transform(Transform::FlipCol);
+    let mut search = config.world()?;
+    assert_eq!(search.search(None), Status::Found);
+    Ok(())
+}
+
+#[test]
+fn turtle() -> Result<(), Error> {
+    let config = Config::new(12, 13, 3)
+        .set_translate(0, 1)
+        .set_symmetry(Symmetry::D2Col);
+    let mut search = config.world()?;
+    assert_eq!(search.search(None), Status::Found);
+    Ok(())
+}
+
+#[test]
+fn diagonal() -> Result<(), Error> {
+    let config = Config::new(8, 8, 4)
+        .set_translate(1, 1)
+        .set_search_order(Some(SearchOrder::Diagonal));
+    let mut search = config.world()?;
+    assert_eq!(search.search(None), Status::Found);
+    Ok(())
+}
+
+#[test]
+fn diagonal_width() -> Result<(), Error> {
+    let config = Config::new(10, 10, 4)
+        .set_translate(1, 1)
+        .set_search_order(Some(SearchOrder::Diagonal))
+        .set_diagonal_width(4);
+    let mut search = config.world()?;
+    assert_eq!(search.search(None), Status::Found);
+    Ok(())
+}
+
+#[test]
+fn skew() -> Result<(), Error> {
+    // A skew of `(1, 1, n)` is the same as a diagonal width of `n`.
+    let config = Config::new(10, 10, 4)
+        .set_translate(1, 1)
+        .set_search_order(Some(SearchOrder::Diagonal));
+    let mut search = config.clone().set_diagonal_width(4).world()?;
+    let mut skewed = config.set_skew((1, 1, 4)).world()?;
+    assert_eq!(search.search(None), Status::Found);
+    assert_eq!(skewed.search(None), Status::Found);
+    assert_eq!(skewed.rle_gen(0), search.rle_gen(0));
+
+    let config = Config::new(8, 16, 1).set_skew((1, 2, 4));
+    let mut search = config.world()?;
+    assert_eq!(search.search(None), Status::Found);
+    assert_evolution(&search, &[3], &[2, 3], 2);
+    for ((x, y, _), state) in search.iter_cells(0) {
+        if (2 * x - y).abs() >= 4 {
+            assert_eq!(state, Some(DEAD));
+        }
+    }
+
+    assert_eq!(
+        config.clone().set_skew((1, 2, 0)).world().err(),
+        Some(Error::NonPositiveError)
+    );
+    assert_eq!(
+        config.clone().set_symmetry(Symmetry::D2Col).world().err(),
+        Some(Error::SkewError)
+    );
+    assert_eq!(
+        config.set_torus(8, 16).world().err(),
+        Some(Error::TorusDiagonalWidthError)
+    );
+    Ok(())
+}
+
+#[test]
+fn b0() -> Result<(), Error> {
+    let config = Config::new(3, 3, 2).set_rule_string("B026/S1");
+    let mut search = config.world()?;
+    assert_eq!(search.search(None), Status::Found);
+    Ok(())
+}
+
+/// Checks a result of a totalistic rule against a forward simulation
+/// in every generation, including the wrap from the last generation
+/// to the first, and including some cells outside the world.
+fn assert_evolution(search: &PolyWorld, birth: &[u32], survival: &[u32], gen: StateRepr) {
+    let config = search.config();
+    for t in 0..config.period {
+        for x in -3..config.width + 3 {
+            for y in -3..config.height + 3 {
+                let alives = (-1..=1)
+                    .flat_map(|dx| (-1..=1).map(move |dy| (dx, dy)))
+                    .filter(|&(dx, dy)| {
+                        (dx, dy) != (0, 0)
+                            && search.get_cell_state((x + dx, y + dy, t)) == Some(ALIVE)
+                    })
+                    .count() as u32;
+                let state = search.get_cell_state((x, y, t)).unwrap();
+                let next = match state {
+                    DEAD if birth.contains(&alives) => ALIVE,
+                    DEAD => DEAD,
+                    ALIVE if survival.contains(&alives) => ALIVE,
+                    State(j) => State((j + 1) % gen),
+                };
+                assert_eq!(
+                    search.get_cell_state((x, y, t + 1)),
+                    Some(next),
+                    "wrong evolution at {:?} of\n{}",
+                    (x, y, t),
+                    search.rle_gen(0)
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn b0_background() -> Result<(), Error> {
+    let rules: [(&str, &[u32], &[u32], StateRepr); 5] = [
+        ("B0/S2", &[0], &[2], 2),
+        ("B013/S2", &[0, 1, 3], &[2], 2),
+        ("B0/S2/3", &[0], &[2], 3),
+        ("B01/S/3", &[0, 1], &[], 3),
+        ("B0/S23/4", &[0], &[2, 3], 4),
+    ];
+    let moves = [
+        (0, 0, Transform::Id),
+        (0, 1, Transform::Id),
+        (0, 0, Transform::FlipRow),
+        (1, 0, Transform::FlipCol),
+    ];
+    for (rule, birth, survival, gen) in rules {
+        let mut found = 0;
+        for period in 1..=8 {
+            for (dx, dy, transform) in moves {
+                let config = Config::new(4, 4, period)
+                    .set_rule_string(rule)
+                    .set_translate(dx, dy)
+                    .set_transform(transform)
+                    .set_skip_subperiod(false);
+                // The background repeats every `gen` generations.
+                if period % gen as i32 != 0 {
+                    assert_eq!(
+                        config.world().err(),
+                        Some(Error::B0PeriodError(gen as usize))
+                    );
+                    continue;
+                }
+                let mut search = config.world()?;
+                for _ in 0..5 {
+                    if search.search(Some(10000)) != Status::Found {
+                        break;
+                    }
+                    search.assert_result_valid();
+                    assert_evolution(&search, birth, survival, gen);
+                    found += 1;
+                }
+            }
+        }
+        assert!(found > 0, "no result for {}", rule);
+    }
+    Ok(())
+}
+
+#[test]
+fn auto_invert_b0s8() -> Result<(), Error> {
+    // The inverted rule of B3/S23.
+    let rule = "B0123478/S01234678";
+    let config = Config::new(16, 5, 3)
+        .set_translate(0, 1)
+        .set_rule_string(rule);
+    assert_eq!(config.world().err(), Some(Error::B0S8Error));
+
+    let config = config.set_auto_invert_b0s8(true);
+    let mut search = config.world()?;
+    let mut life = config.clone().set_rule_string("B3/S23").world()?;
+    assert_eq!(search.candidate_states((3, 2, 0)), vec![DEAD, ALIVE]);
+    assert_eq!(search.search(None), Status::Found);
+    assert_eq!(life.search(None), Status::Found);
+    for t in 0..3 {
+        for x in -1..=16 {
+            for y in -1..=5 {
+                assert_eq!(
+                    search.get_cell_state((x, y, t)),
+                    life.get_cell_state((x, y, t)).map(|state| !state)
+                );
+            }
+        }
+    }
+    assert_eq!(search.cell_count(), life.cell_count());
+    assert!(search.rle_gen(0).starts_with(
+        "#C Generation 0: the background is alive, as the rule contains B0.\n\
+         x = 16, y = 5, rule = B0123478/S01234678\noooooooo.ooooooo$"
+    ));
+    assert_eq!(search.snapshot().rle_gen(0), search.rle_gen(0));
+    assert_evolution(
+        &search,
+        &[0, 1, 2, 3, 4, 7, 8],
+        &[0, 1, 2, 3, 4, 6, 7, 8],
+        2,
+    );
+    search.assert_result_valid();
+
+    // The known cells are given in the B0S8 rule.
+    let known_cells = vec![KnownCell {
+        coord: (8, 0, 0),
+        state: DEAD,
+    }];
+    let mut search = config.clone().set_known_cells(known_cells).world()?;
+    assert_eq!(search.get_cell_state((8, 0, 0)), Some(DEAD));
+    assert_eq!(search.search(None), Status::Found);
+    assert_eq!(search.get_cell_state((8, 0, 0)), Some(DEAD));
+    search.assert_result_valid();
+    Ok(())
+}
+
+#[test]
+fn allow_trivial() -> Result<(), Error> {
+    // All results, and whether they are trivial.
+    let all_results = |config: Config| -> Result<Vec<(String, bool)>, Error> {
+        let mut search = config.world()?;
+        let mut results = Vec::new();
+        while search.search(None) == Status::Found {
+            results.push((search.rle_gen(0), search.is_trivial()));
+        }
+        Ok(results)
+    };
+
+    let config = Config::new(5, 5, 1);
+    let results = all_results(config.clone())?;
+    assert!(!results.is_empty());
+    assert!(results.iter().all(|&(_, is_trivial)| !is_trivial));
+    let with_trivial = all_results(config.set_allow_trivial(true))?;
+    assert_eq!(
+        with_trivial
+            .iter()
+            .filter(|&&(_, is_trivial)| is_trivial)
+            .count(),
+        1
+    );
+    assert!(results.iter().all(|result| with_trivial.contains(result)));
+
+    // The trivial result is the only oscillator of period 2 in this world.
+    let config = Config::new(2, 2, 2);
+    assert_eq!(config.world()?.search(None), Status::None);
+    let mut search = config.set_allow_trivial(true).world()?;
+    assert_eq!(search.search(None), Status::Found);
+    assert!(search.is_trivial());
+    assert_eq!(search.rle_gen(1), "x = 2, y = 2, rule = B3/S23\n..$\n..!\n");
+    search.assert_result_valid();
+    assert_eq!(search.search(None), Status::None);
+
+    // Nothing is smaller than the trivial result.
+    let mut search = Config::new(4, 4, 1)
+        .set_allow_trivial(true)
+        .set_reduce_max(true)
+        .world()?;
+    while search.search(None) == Status::Found {}
+    Ok(())
+}
+
+#[test]
+fn b0_background_note() -> Result<(), Error> {
+    let mut search = Config::new(5, 5, 2).set_rule_string("B0/S2").world()?;
+    assert_eq!(search.search(None), Status::Found);
+    assert!(search
+        .rle_gen(0)
+        .starts_with("x = 5, y = 5, rule = B0/S2\n"));
+    let note = "Generation 1: the background is alive, as the rule contains B0.";
+    assert!(search
+        .rle_gen(1)
+        .starts_with(&format!("#C {}\nx = 5, y = 5, rule = B0/S2\n", note)));
+    assert!(search
+        .plaintext_gen(1)
+        .starts_with(&format!("!{}\n!rule = B0/S2, period = 2\n", note)));
+    assert!(search.python_gen(1).ends_with(&format!("]  # {}", note)));
+    assert!(search.rle_gen_rotated(1)[1].starts_with("x = 5, y = 5, rule = B0/S2\n"));
+    let snapshot = search.snapshot();
+    assert_eq!(snapshot.rle_gen(1), search.rle_gen(1));
+    assert_eq!(snapshot.plaintext_gen(1), search.plaintext_gen(1));
+
+    let mut search = Config::new(5, 5, 3).set_rule_string("B0/S2/3").world()?;
+    assert_eq!(search.search(None), Status::Found);
+    assert!(!search.rle_gen(0).starts_with("#C"));
+    assert!(search
+        .rle_gen(2)
+        .starts_with("#C Generation 2: the background is in state 2, as the rule contains B0.\n"));
+    Ok(())
+}
+
+#[test]
+fn p3_2333() -> Result<(), Error> {
+    let config = Config::new(4, 4, 3).set_rule_string("23/3/3");
+    let mut search = config.world()?;
+    assert_eq!(search.search(None), Status::Found);
+    Ok(())
+}
+
+#[test]
+fn snowflakes() -> Result<(), Error> {
+    let config = Config::new(4, 4, 2)
+        .set_translate(0, 1)
+        .set_rule_string("B2ci3ai4c8/S02ae3eijkq4iz5ar6i7e");
     let mut search = config.world()?;
     assert_eq!(search.search(None), Status::Found);
     Ok(())
@@ -190,6 +1503,486 @@ fn transform_subperiod() -> Result<(), Error> {
     Ok(())
 }
 
+#[test]
+fn spacetime_subsymmetry() -> Result<(), Error> {
+    let config = Config::new(7, 7, 2).set_transform(Transform::FlipRow);
+
+    // Each result, and whether generation 1 is a transformation of generation 0.
+    let all_results = |config: Config| -> Result<Vec<(String, bool)>, Error> {
+        let mut search = config.world()?;
+        let mut results = Vec::new();
+        while search.search(None) == Status::Found {
+            let is_glide = Transform::ALL.iter().skip(1).any(|tr| {
+                (0..7).all(|x| {
+                    (0..7).all(|y| {
+                        search.get_cell_state((x, y, 0))
+                            == search.get_cell_state(tr.act_on((x, y, 1), 7, 7))
+                    })
+                })
+            });
+            results.push((search.rle_gen(0) + &search.rle_gen(1), is_glide));
+        }
+        Ok(results)
+    };
+
+    let results = all_results(config.clone())?;
+    let skipped = all_results(config.set_skip_spacetime_subsymmetry(true))?;
+    assert!(skipped.len() < results.len());
+    for (result, is_glide) in &results {
+        assert_eq!(skipped.iter().all(|(r, _)| r != result), *is_glide);
+    }
+    Ok(())
+}
+
+#[test]
+fn clean_border() -> Result<(), Error> {
+    let all_results = |config: Config| -> Result<Vec<String>, Error> {
+        let mut search = config.world()?;
+        let mut results = Vec::new();
+        while search.search(None) == Status::Found {
+            results.push(search.rle_gen(0));
+        }
+        Ok(results)
+    };
+
+    // Two blocks with two empty columns between them are separate objects.
+    let config = Config::new(6, 2, 1);
+    let results = all_results(config.clone())?;
+    let clean = all_results(config.set_clean_border(true))?;
+    let two_blocks = "x = 6, y = 2, rule = B3/S23\n\
+                      oo..oo$\n\
+                      oo..oo!\n";
+    assert!(results.iter().any(|r| r == two_blocks));
+    assert!(clean.iter().all(|r| r != two_blocks));
+    assert_eq!(clean.len() + 1, results.len());
+
+    // A glider is clean in every phase.
+    let mut search = Config::new(5, 5, 4)
+        .set_translate(1, 1)
+        .set_clean_border(true)
+        .world()?;
+    assert_eq!(search.search(None), Status::Found);
+    Ok(())
+}
+
+#[test]
+fn repeat() -> Result<(), Error> {
+    let config = Config::new(5, 5, 2)
+        .set_translate(0, 1)
+        .set_repeat(Transform::FlipDiag, 2)?;
+    let manual = Config::new(5, 5, 4).set_translate(1, 1);
+    assert_eq!(config, manual);
+    let mut search = config.world()?;
+    let mut manual_search = manual.world()?;
+    assert_eq!(search.search(None), Status::Found);
+    assert_eq!(manual_search.search(None), Status::Found);
+    assert_eq!(search.rle_gen(0), manual_search.rle_gen(0));
+
+    let config = Config::new(6, 6, 2)
+        .set_translate(1, 0)
+        .set_phase_symmetries(vec![Symmetry::D2Row, Symmetry::D2Row])
+        .set_repeat(Transform::FlipRow, 3);
+    assert_eq!(config, Err(Error::RepeatError(Transform::FlipRow, 3)));
+    let config = Config::new(6, 6, 2)
+        .set_translate(1, 0)
+        .set_phase_symmetries(vec![Symmetry::D4Diag, Symmetry::D2Diag])
+        .set_repeat(Transform::Rotate90, 4)?;
+    assert_eq!(config.period, 8);
+    assert_eq!((config.dx, config.dy), (0, 0));
+    assert_eq!(config.transform, Transform::Id);
+    assert_eq!(
+        config.phase_symmetries,
+        Some(
+            [Symmetry::D2Diag, Symmetry::D2Antidiag]
+                .into_iter()
+                .cycle()
+                .take(4)
+                .flat_map(|sym| [Symmetry::D4Diag, sym])
+                .collect()
+        )
+    );
+    config.world()?;
+
+    assert_eq!(
+        Config::new(6, 5, 1).set_repeat(Transform::Rotate90, 4),
+        Err(Error::SquareWorldError)
+    );
+    assert_eq!(
+        Config::new(6, 6, 1).set_repeat(Transform::Id, 0),
+        Err(Error::NonPositiveError)
+    );
+    Ok(())
+}
+
+#[test]
+fn retreat_strategy() -> Result<(), Error> {
+    let all_results = |config: Config| -> Result<(Vec<String>, u64), Error> {
+        let mut search = config.world()?;
+        let mut results = Vec::new();
+        while search.search(None) == Status::Found {
+            results.push(search.rle_gen(0));
+        }
+        Ok((results, search.conflicts()))
+    };
+
+    let configs = [
+        Config::new(5, 5, 1),
+        Config::new(6, 6, 2),
+        Config::new(5, 5, 4).set_translate(1, 1),
+        Config::new(6, 6, 3).set_rule_string("/2/3"),
+        Config::new(6, 6, 2).set_symmetry(Symmetry::D2Row),
+        Config::new(6, 6, 1).set_max_cell_count(Some(6)),
+    ];
+    for config in configs {
+        let (results, conflicts) = all_results(config.clone())?;
+        let (directed, directed_conflicts) =
+            all_results(config.set_retreat_strategy(RetreatStrategy::ConflictDirected))?;
+        assert_eq!(results, directed);
+        assert!(directed_conflicts <= conflicts);
+    }
+    Ok(())
+}
+
+#[test]
+fn parity() -> Result<(), Error> {
+    // Results with the populations of each generation,
+    // and the number of living cells in the first row of generation 0.
+    let all_results = |config: Config| -> Result<Vec<(String, Vec<u32>, u32)>, Error> {
+        let mut search = config.world()?;
+        let mut results = Vec::new();
+        while search.search(None) == Status::Found {
+            let row = (0..search.config().width)
+                .filter(|&x| search.get_cell_state((x, 0, 0)) == Some(ALIVE))
+                .count() as u32;
+            results.push((search.rle_gen(0), search.populations(), row));
+        }
+        Ok(results)
+    };
+
+    for config in [Config::new(5, 5, 1), Config::new(6, 6, 2)] {
+        let period = config.period as usize;
+        let results = all_results(config.clone())?;
+
+        let mut population_parity = vec![None; period];
+        population_parity[period - 1] = Some(true);
+        let expected = results
+            .iter()
+            .filter(|(_, counts, _)| counts[period - 1] % 2 == 1)
+            .cloned()
+            .collect::<Vec<_>>();
+        assert!(!expected.is_empty() && expected.len() < results.len());
+        let config_parity = config.clone().set_population_parity(population_parity);
+        assert_eq!(all_results(config_parity.clone())?, expected);
+        assert_eq!(all_results(config_parity.set_prune_parity(true))?, expected);
+
+        let expected = results
+            .iter()
+            .filter(|(_, _, row)| row % 2 == 0)
+            .cloned()
+            .collect::<Vec<_>>();
+        assert!(!expected.is_empty() && expected.len() < results.len());
+        let config_parity = config.set_line_parities(vec![LineParity {
+            line: Line::Row(0),
+            gen: 0,
+            odd: false,
+        }]);
+        assert_eq!(all_results(config_parity)?, expected);
+    }
+
+    let config = Config::new(5, 5, 2);
+    assert_eq!(
+        config
+            .clone()
+            .set_population_parity(vec![Some(true)])
+            .world()
+            .err(),
+        Some(Error::PopulationParityLengthError)
+    );
+    assert_eq!(
+        config
+            .clone()
+            .set_line_parities(vec![LineParity {
+                line: Line::Column(5),
+                gen: 0,
+                odd: true,
+            }])
+            .world()
+            .err(),
+        Some(Error::LineOutOfBounds(Line::Column(5), 0))
+    );
+    assert_eq!(
+        config
+            .set_population_parity(vec![Some(true), None])
+            .set_prune_parity(true)
+            .set_algorithm(AlgorithmChoice::Backjump)
+            .world()
+            .err(),
+        Some(Error::UnsupportedAlgorithm(
+            AlgorithmChoice::Backjump,
+            "parity pruning"
+        ))
+    );
+    Ok(())
+}
+
+#[test]
+fn require_predecessor() -> Result<(), Error> {
+    let all_results = |config: Config| -> Result<Vec<String>, Error> {
+        let mut search = config.world()?;
+        let mut results = Vec::new();
+        while search.search(None) == Status::Found {
+            results.push(search.rle_gen(0));
+        }
+        Ok(results)
+    };
+
+    // A block has predecessors with three cells.
+    let config = Config::new(2, 2, 1).set_require_predecessor(true);
+    assert_eq!(all_results(config)?.len(), 1);
+
+    // A ship has no other predecessor in a 3x3 world, but a boat has.
+    let config = Config::new(3, 3, 1);
+    let results = all_results(config.clone())?;
+    let filtered = all_results(config.set_require_predecessor(true))?;
+    let ship = String::from(
+        "x = 3, y = 3, rule = B3/S23\n\
+         oo.$\n\
+         o.o$\n\
+         .oo!\n",
+    );
+    let boat = String::from(
+        "x = 3, y = 3, rule = B3/S23\n\
+         oo.$\n\
+         o.o$\n\
+         .o.!\n",
+    );
+    assert!(results.contains(&ship) && results.contains(&boat));
+    assert!(!filtered.contains(&ship) && filtered.contains(&boat));
+    assert!(filtered.iter().all(|result| results.contains(result)));
+
+    // Oscillators are not affected.
+    let config = Config::new(5, 5, 2);
+    assert_eq!(
+        all_results(config.clone())?,
+        all_results(config.set_require_predecessor(true))?
+    );
+    Ok(())
+}
+
+#[test]
+fn known_results() -> Result<(), Error> {
+    let all_results = |config: Config| -> Result<Vec<String>, Error> {
+        let mut search = config.world()?;
+        let mut results = Vec::new();
+        while search.search(None) == Status::Found {
+            results.push(search.rle_gen(0));
+        }
+        Ok(results)
+    };
+
+    let config = Config::new(5, 5, 2);
+    let mut search = config.world()?;
+    assert_eq!(search.search(None), Status::Found);
+    let first = search.rle_gen(0);
+
+    // Generation 1 of the first result, flipped and moved away.
+    let known = (0..5)
+        .flat_map(|x| (0..5).map(move |y| (x, y)))
+        .filter(|&(x, y)| search.get_cell_state((x, y, 1)) == Some(ALIVE))
+        .map(|(x, y)| KnownCell {
+            coord: (y + 10, x - 3, 0),
+            state: ALIVE,
+        })
+        .collect::<Vec<_>>();
+
+    let results = all_results(config.clone())?;
+    let new_results = all_results(config.set_known_results(vec![known]))?;
+    assert!(!new_results.contains(&first));
+    assert!(new_results.len() < results.len());
+    assert!(new_results.iter().all(|result| results.contains(result)));
+    Ok(())
+}
+
+#[test]
+fn assert_result_valid() -> Result<(), Error> {
+    let known_cells = vec![
+        KnownCell {
+            coord: (2, 2, 0),
+            state: ALIVE,
+        },
+        KnownCell {
+            coord: (0, 0, 1),
+            state: DEAD,
+        },
+    ];
+    let configs = [
+        Config::new(6, 6, 2).set_known_cells(known_cells.clone()),
+        Config::new(6, 6, 2).set_frozen_cells(vec![(2, 3, 0)]),
+        Config::new(8, 8, 2).set_symmetry(Symmetry::D4Diag),
+        Config::new(8, 8, 4)
+            .set_translate(1, 0)
+            .set_transform(Transform::FlipRow),
+        Config::new(5, 5, 2).set_rule_string("B0/S2"),
+        Config::new(4, 4, 3).set_rule_string("23/3/3"),
+        Config::new(6, 6, 3).set_max_defects(1),
+    ];
+    for config in configs {
+        let mut search = config.world()?;
+        for _ in 0..10 {
+            if search.search(None) != Status::Found {
+                break;
+            }
+            search.assert_result_valid();
+        }
+    }
+    Ok(())
+}
+
+#[test]
+#[cfg(debug_assertions)]
+fn verify_descriptors() -> Result<(), Error> {
+    let configs = [
+        Config::new(16, 5, 3).set_translate(0, 1),
+        Config::new(6, 6, 2).set_rule_string("B36/S23"),
+        Config::new(4, 6, 2).set_rule_string(
+            "MAPERYXfhZofugWaH7oaIDogBZofuhogOiAaIDogIAAgAAWaH7oaIDogGiA6ICAAIAAaIDogIAAgACAAIAAAAAAAA",
+        ),
+        Config::new(4, 4, 3).set_rule_string("23/3/3"),
+        Config::new(5, 5, 2).set_rule_string("B0/S2"),
+        Config::new(8, 8, 4)
+            .set_translate(1, 0)
+            .set_transform(Transform::FlipRow),
+        Config::new(8, 8, 1)
+            .set_diagonal_width(3)
+            .set_symmetry(Symmetry::D2Diag),
+        Config::new(6, 6, 1),
+    ];
+    for config in configs {
+        let mut search = config.world()?;
+        search.verify_descriptors();
+        for max_step in [10, 100, 1000] {
+            search.search(Some(max_step));
+            search.verify_descriptors();
+        }
+        search.search(None);
+        search.verify_descriptors();
+    }
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "debug-invariants")]
+fn assert_consistent() -> Result<(), Error> {
+    let configs = [
+        Config::new(5, 5, 4).set_translate(1, 1),
+        Config::new(5, 5, 4)
+            .set_translate(1, 1)
+            .set_algorithm(AlgorithmChoice::Backjump),
+        Config::new(4, 6, 2).set_rule_string(
+            "MAPERYXfhZofugWaH7oaIDogBZofuhogOiAaIDogIAAgAAWaH7oaIDogGiA6ICAAIAAaIDogIAAgACAAIAAAAAAAA",
+        ),
+        Config::new(4, 4, 3).set_rule_string("23/3/3"),
+        Config::new(5, 5, 2).set_rule_string("B0/S2"),
+        Config::new(8, 8, 1)
+            .set_diagonal_width(3)
+            .set_symmetry(Symmetry::D2Diag),
+        Config::new(6, 6, 2)
+            .set_max_cell_count(8)
+            .set_prune_parity(true)
+            .set_retreat_strategy(RetreatStrategy::ConflictDirected),
+        Config::new(5, 5, 2).set_phase_masks(vec![PhaseMask {
+            rect: (2, 0, 2, 4),
+            gens: vec![0, 1],
+            mode: PhaseMaskMode::ForceNonBackground,
+        }]),
+        Config::new(5, 5, 1).set_row_live_counts(vec![2, 2, 0, 2, 2]),
+    ];
+    for config in configs {
+        let mut search = config.set_invariant_check_interval(1).world()?;
+        search.assert_consistent();
+        for _ in 0..3 {
+            if search.search(None) != Status::Found {
+                break;
+            }
+            search.assert_consistent();
+        }
+        search.assert_consistent();
+    }
+    Ok(())
+}
+
+#[test]
+fn frozen_cells() -> Result<(), Error> {
+    let config = Config::new(5, 5, 2);
+
+    // All results, and whether the cell at (2, 2) never changes.
+    let all_results = |config: Config| -> Result<Vec<(String, bool)>, Error> {
+        let mut search = config.world()?;
+        let mut results = Vec::new();
+        while search.search(None) == Status::Found {
+            let is_frozen = search.get_cell_state((2, 2, 0)) == search.get_cell_state((2, 2, 1));
+            results.push((search.rle_gen(0) + &search.rle_gen(1), is_frozen));
+        }
+        results.sort();
+        Ok(results)
+    };
+
+    let results = all_results(config.clone())?;
+    let frozen = all_results(config.set_frozen_cells(vec![(2, 2, 0)]))?;
+    assert!(!frozen.is_empty());
+    assert!(frozen.len() < results.len());
+    assert!(frozen.iter().all(|&(_, is_frozen)| is_frozen));
+    assert_eq!(
+        frozen,
+        results
+            .into_iter()
+            .filter(|&(_, is_frozen)| is_frozen)
+            .collect::<Vec<_>>()
+    );
+    Ok(())
+}
+
+#[test]
+fn snapshot() -> Result<(), Error> {
+    fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+    let configs = [
+        Config::new(16, 5, 3).set_translate(0, 1),
+        Config::new(8, 8, 4)
+            .set_translate(1, 0)
+            .set_transform(Transform::FlipRow),
+        Config::new(4, 4, 3).set_rule_string("23/3/3"),
+        Config::new(5, 5, 2).set_rule_string("B0/S2"),
+        Config::new(8, 8, 1).set_diagonal_width(3),
+    ];
+    for config in configs {
+        let mut search = config.world()?;
+        for max_step in [Some(10), None] {
+            search.search(max_step);
+            let snapshot = search.snapshot();
+            assert_send_sync(&snapshot);
+            assert_eq!(snapshot.config(), search.config());
+            assert_eq!(snapshot.cell_count(), search.cell_count());
+            assert_eq!(snapshot.steps(), search.steps());
+            assert_eq!(snapshot.conflicts(), search.conflicts());
+            for t in 0..config.period {
+                assert_eq!(snapshot.rle_gen(t), search.rle_gen(t));
+                assert_eq!(snapshot.rle_gen_annotated(t), search.rle_gen_annotated(t));
+                assert_eq!(snapshot.plaintext_gen(t), search.plaintext_gen(t));
+                assert_eq!(snapshot.python_gen(t), search.python_gen(t));
+                assert_eq!(snapshot.rle_gen_rotated(t), search.rle_gen_rotated(t));
+            }
+            assert_eq!(
+                snapshot.rle_all_gens_min_phase(),
+                search.rle_all_gens_min_phase()
+            );
+            assert_eq!(snapshot.frames(), search.frames());
+            assert_eq!(snapshot.frames_grid(), search.frames_grid());
+        }
+    }
+    Ok(())
+}
+
 #[test]
 fn known_cells() -> Result<(), Error> {
     let config = Config::new(3, 3, 1).set_known_cells(vec![
@@ -211,6 +2004,1730 @@ fn known_cells() -> Result<(), Error> {
     Ok(())
 }
 
+#[test]
+fn known_cell_out_of_bounds() {
+    for coord in [(100, 0, 0), (16, 0, 0), (-1, 2, 0), (0, 5, 0), (0, 0, 3)] {
+        let config = Config::new(16, 5, 3).set_known_cells(vec![KnownCell {
+            coord,
+            state: ALIVE,
+        }]);
+        assert_eq!(
+            config.world().err(),
+            Some(Error::KnownCellOutOfBounds(coord))
+        );
+    }
+}
+
+#[test]
+fn max_defects() -> Result<(), Error> {
+    let all_results = |config: Config| -> Result<Vec<String>, Error> {
+        let mut search = config.world()?;
+        let mut results = Vec::new();
+        while search.search(None) == Status::Found {
+            assert!(search.defects().is_empty());
+            results.push(search.rle_gen(0) + &search.rle_gen(1));
+        }
+        Ok(results)
+    };
+    let config = Config::new(5, 5, 2);
+    assert_eq!(
+        all_results(config.clone().set_max_defects(0))?,
+        all_results(config)?
+    );
+
+    let config = Config::new(5, 5, 4);
+    let mut search = config.clone().world()?;
+    assert_eq!(search.search(None), Status::None);
+    let mut search = config.set_max_defects(2).world()?;
+    assert_eq!(search.search(None), Status::Found);
+    let defects = search.defects();
+    assert!(!defects.is_empty() && defects.len() <= 2);
+    assert!(defects.iter().all(|&(_, _, t)| t == 3));
+    assert_eq!(search.snapshot().defects(), &defects[..]);
+    Ok(())
+}
+
+#[test]
+fn search_space_bits() -> Result<(), Error> {
+    let bits = |config: Config| -> Result<f64, Error> { Ok(config.world()?.search_space_bits()) };
+    let config = Config::new(4, 4, 2);
+    assert_eq!(bits(config.clone())?, 32.0);
+    assert_eq!(bits(config.clone().set_symmetry(Symmetry::C2))?, 16.0);
+    assert_eq!(bits(config.clone().set_symmetry(Symmetry::D8))?, 6.0);
+    assert_eq!(bits(config.clone().set_diagonal_width(2))?, 20.0);
+    assert_eq!(
+        bits(config.clone().set_known_cells(vec![KnownCell {
+            coord: (0, 0, 0),
+            state: ALIVE,
+        }]))?,
+        31.0
+    );
+    assert_eq!(bits(config.set_rule_string("23/3/3"))?, 32.0 * 3f64.log2());
+    Ok(())
+}
+
+#[test]
+fn aggregates() -> Result<(), Error> {
+    let config = Config::new(16, 5, 3).set_translate(0, 1);
+    let mut search = config.world()?;
+    assert_eq!(search.populations(), vec![0; 3]);
+    assert_eq!(search.row_summary(0).len(), 5);
+    assert!(search
+        .row_summary(0)
+        .iter()
+        .all(|&(alive, unknown)| alive == 0 && unknown > 0));
+    assert!(search.frontier_coord().is_some());
+    assert_eq!(search.search(None), Status::Found);
+    for t in 0..3 {
+        let row_summary = search.row_summary(t);
+        assert!(row_summary.iter().all(|&(_, unknown)| unknown == 0));
+        assert_eq!(
+            row_summary.iter().map(|&(alive, _)| alive).sum::<u32>(),
+            search.populations()[t as usize]
+        );
+        assert_eq!(search.populations()[t as usize], search.cell_count_gen(t));
+    }
+    assert_eq!(search.row_summary(0)[0], (1, 0));
+    assert_eq!(search.frontier_coord(), None);
+    Ok(())
+}
+
+#[test]
+fn python_gen() -> Result<(), Error> {
+    let parse = |list: String| -> Vec<usize> {
+        let list = list.strip_prefix('[').unwrap().strip_suffix(']').unwrap();
+        list.split(", ").map(|i| i.parse().unwrap()).collect()
+    };
+
+    let config = Config::new(16, 5, 3).set_translate(0, 1);
+    let mut search = config.world()?;
+    assert_eq!(search.python_gen(0), "[]");
+    assert_eq!(search.search(None), Status::Found);
+    assert!(search.python_gen(0).starts_with("[8, 0, 1, 1, 2, 1, "));
+    let list = parse(search.python_gen(0));
+    assert_eq!(list.len(), 2 * search.cell_count_gen(0) as usize);
+    for cell in list.chunks(2) {
+        let coord = (cell[0] as i32, cell[1] as i32, 0);
+        assert_eq!(search.get_cell_state(coord), Some(ALIVE));
+    }
+
+    let config = Config::new(4, 4, 3).set_rule_string("23/3/3");
+    let mut search = config.world()?;
+    assert_eq!(search.search(None), Status::Found);
+    for t in 0..3 {
+        let mut list = parse(search.python_gen(t));
+        assert_eq!(list.len() % 2, 1);
+        if list.len() % 3 == 1 {
+            assert_eq!(list.pop(), Some(0));
+        }
+        for cell in list.chunks(3) {
+            let coord = (cell[0] as i32, cell[1] as i32, t);
+            assert_eq!(
+                search.get_cell_state(coord),
+                Some(State(cell[2] as StateRepr))
+            );
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn phase_masks() -> Result<(), Error> {
+    let column = |gens: Vec<i32>, mode| PhaseMask {
+        rect: (2, 0, 2, 5),
+        gens,
+        mode,
+    };
+    let config = Config::new(6, 6, 2).set_phase_masks(vec![
+        column(vec![1], PhaseMaskMode::ForceBackground),
+        column(vec![0], PhaseMaskMode::ForceNonBackground),
+    ]);
+    for algorithm in [AlgorithmChoice::LifeSrc, AlgorithmChoice::Backjump] {
+        let mut search = config.clone().set_algorithm(algorithm).world()?;
+        let mut results = 0;
+        while search.search(None) == Status::Found {
+            let states = |t| {
+                (0..6)
+                    .map(|y| search.get_cell_state((2, y, t)))
+                    .collect::<Vec<_>>()
+            };
+            assert!(states(0).contains(&Some(ALIVE)));
+            assert!(states(1).iter().all(|&state| state == Some(DEAD)));
+            results += 1;
+        }
+        assert!(results > 0);
+    }
+
+    // The column is forced to be both empty and nonempty.
+    let config = Config::new(6, 6, 2).set_phase_masks(vec![
+        column(vec![0, 1], PhaseMaskMode::ForceBackground),
+        column(vec![1], PhaseMaskMode::ForceNonBackground),
+    ]);
+    let mut search = config.world()?;
+    assert!(!search.can_continue());
+    assert_eq!(search.search(Some(0)), Status::None);
+
+    // The known cells leave no room in the column.
+    let known_cells = (0..6)
+        .map(|y| KnownCell {
+            coord: (2, y, 0),
+            state: DEAD,
+        })
+        .collect::<Vec<_>>();
+    let config = Config::new(6, 6, 2)
+        .set_known_cells(known_cells)
+        .set_phase_masks(vec![column(vec![0], PhaseMaskMode::ForceNonBackground)]);
+    let mut search = config.world()?;
+    assert!(!search.can_continue());
+    assert_eq!(search.search(Some(0)), Status::None);
+
+    let mask = PhaseMask {
+        rect: (4, 0, 6, 5),
+        gens: vec![0],
+        mode: PhaseMaskMode::ForceBackground,
+    };
+    let config = Config::new(6, 6, 2).set_phase_masks(vec![mask.clone()]);
+    assert_eq!(
+        config.world().err(),
+        Some(Error::PhaseMaskOutOfBounds(mask))
+    );
+    let mask = column(vec![2], PhaseMaskMode::ForceBackground);
+    let config = Config::new(6, 6, 2).set_phase_masks(vec![mask.clone()]);
+    assert_eq!(
+        config.world().err(),
+        Some(Error::PhaseMaskOutOfBounds(mask))
+    );
+    let mask = column(vec![], PhaseMaskMode::ForceNonBackground);
+    let config = Config::new(6, 6, 2).set_phase_masks(vec![mask.clone()]);
+    assert_eq!(config.world().err(), Some(Error::EmptyPhaseMask(mask)));
+    Ok(())
+}
+
+#[test]
+fn search_over_rules() -> Result<(), Error> {
+    // A block in the middle of the world.
+    let known_cells = [(1, 1), (1, 2), (2, 1), (2, 2)]
+        .into_iter()
+        .map(|(x, y)| KnownCell {
+            coord: (x, y, 0),
+            state: ALIVE,
+        })
+        .collect::<Vec<_>>();
+    let config = Config::new(4, 4, 1).set_known_cells(known_cells);
+    let rules = ["B3/S23", "B2/S", "B0/S8", "foo"].map(String::from);
+    let outcomes = config.search_over_rules(&rules, None);
+    assert_eq!(outcomes.len(), 4);
+    for (outcome, rule) in outcomes.iter().zip(&rules) {
+        assert_eq!(&outcome.rule_string, rule);
+    }
+
+    assert!(outcomes[0].is_found());
+    let world = outcomes[0].world.as_ref().unwrap();
+    assert_eq!(world.config().rule_string, "B3/S23");
+    assert_eq!(world.cell_count(), 4);
+    assert_eq!(outcomes[1].status, Ok(Status::None));
+    assert!(outcomes[1].world.is_none());
+    assert_eq!(outcomes[2].status, Err(Error::B0S8Error));
+    assert!(matches!(outcomes[3].status, Err(Error::ParseRuleError(_))));
+    assert_eq!(config.rule_string, "B3/S23");
+
+    // The states of the known cells are checked against each rule.
+    let config = Config::new(4, 4, 1).set_known_cells(vec![KnownCell {
+        coord: (0, 0, 0),
+        state: State(2),
+    }]);
+    let rules = ["B3/S23", "B3/S23/3"].map(String::from);
+    let outcomes = config.search_over_rules(&rules, Some(1000));
+    assert_eq!(
+        outcomes[0].status,
+        Err(Error::InvalidState((0, 0, 0), State(2)))
+    );
+    assert!(outcomes[1].status.is_ok());
+
+    // A budget of zero steps stops before the first result.
+    let config = Config::new(16, 5, 3).set_translate(0, 1);
+    let outcomes = config.search_over_rules(&rules[..1], Some(0));
+    assert_eq!(outcomes[0].status, Ok(Status::Searching));
+    assert!(outcomes[0].world.is_none());
+    Ok(())
+}
+
+#[test]
+fn probe() -> Result<(), Error> {
+    let easy = Config::new(6, 6, 2);
+    let hard = Config::new(20, 20, 5);
+    let report = easy.probe(1000, 4, 42)?;
+    assert_eq!(report, easy.probe(1000, 4, 42)?);
+    assert_eq!(report.runs.len(), 4);
+    assert!(report.success_fraction > 0.0);
+    for run in &report.runs {
+        assert!(run.steps >= 1000 || run.status != Status::Searching);
+        assert!((0.0..=1.0).contains(&run.depth));
+        if run.status == Status::Found {
+            assert_eq!(run.depth, 1.0);
+        }
+    }
+    let hard_report = hard.probe(1000, 4, 42)?;
+    assert_eq!(hard_report.success_fraction, 0.0);
+    assert!(hard_report.mean_depth < report.mean_depth);
+    assert!(hard_report.score > report.score);
+    assert!((0.0..=1.0).contains(&hard_report.score));
+
+    // The configuration is not changed.
+    assert!(matches!(easy.new_state, NewState::ChooseAlive));
+    assert_eq!(
+        Config::new(0, 6, 2).probe(1000, 4, 42).err(),
+        Some(Error::NonPositiveError)
+    );
+    Ok(())
+}
+
+#[test]
+fn frames() -> Result<(), Error> {
+    let config = Config::new(8, 8, 4).set_translate(1, 1);
+    let mut search = config.world()?;
+    assert_eq!(search.frames(), vec!["x = 0, y = 0, rule = B3/S23\n!\n"; 4]);
+    assert_eq!(search.frames_grid(), vec![Vec::<Vec<State>>::new(); 4]);
+
+    assert_eq!(search.search(None), Status::Found);
+    let boxes = (0..4)
+        .map(|t| search.bounding_box_gen(t).unwrap())
+        .collect::<Vec<_>>();
+    let x0 = boxes.iter().map(|b| b.0).min().unwrap();
+    let y0 = boxes.iter().map(|b| b.1).min().unwrap();
+    let x1 = boxes.iter().map(|b| b.2).max().unwrap();
+    let y1 = boxes.iter().map(|b| b.3).max().unwrap();
+    // The glider moves, so the frames are larger than the glider itself.
+    assert!(x1 - x0 + 1 > 3 && y1 - y0 + 1 > 3);
+
+    let frames = search.frames();
+    let grid = search.frames_grid();
+    assert_eq!(frames.len(), 4);
+    assert_eq!(grid.len(), 4);
+    for t in 0..4 {
+        let header = format!("x = {}, y = {}, rule = B3/S23", x1 - x0 + 1, y1 - y0 + 1);
+        assert_eq!(frames[t as usize].lines().next(), Some(header.as_str()));
+        assert_eq!(grid[t as usize].len() as i32, y1 - y0 + 1);
+        for (y, row) in grid[t as usize].iter().enumerate() {
+            assert_eq!(row.len() as i32, x1 - x0 + 1);
+            for (x, &state) in row.iter().enumerate() {
+                let coord = (x0 + x as i32, y0 + y as i32, t);
+                assert_eq!(search.get_cell_state(coord), Some(state));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn formatters() -> Result<(), Error> {
+    use rlifesrc_lib::{
+        format::{self, PatternFormatter},
+        FormatError, WorldSnapshot,
+    };
+
+    let config = Config::new(16, 5, 3).set_translate(0, 1);
+    let mut search = config.world()?;
+    assert_eq!(search.search(None), Status::Found);
+    let snapshot = search.snapshot();
+    let format = |name: &str, t: i32| format::by_name(name).unwrap().format(&snapshot, t);
+    assert_eq!(
+        format("rle", 0).unwrap(),
+        "x = 16, y = 5, rule = B3/S23\n\
+         ........o.......$\n\
+         .oo.ooo.ooo.....$\n\
+         .oo....o..oo.oo.$\n\
+         o..o.oo...o..oo.$\n\
+         ............o..o!\n"
+    );
+    for t in 0..3 {
+        assert_eq!(format("rle", t).unwrap(), search.rle_gen(t));
+        assert_eq!(
+            format("rle-annotated", t).unwrap(),
+            search.rle_gen_annotated(t)
+        );
+        assert_eq!(format("plaintext", t).unwrap(), search.plaintext_gen(t));
+        assert_eq!(format("python", t).unwrap(), search.python_gen(t));
+    }
+    assert!(format::by_name("population").is_none());
+
+    /// Displays the population of a generation.
+    struct Population;
+
+    impl PatternFormatter for Population {
+        fn name(&self) -> &'static str {
+            "population"
+        }
+
+        fn format(&self, snapshot: &WorldSnapshot, gen: i32) -> Result<String, FormatError> {
+            if gen < 0 {
+                return Err(FormatError::Custom(String::from("negative generation")));
+            }
+            Ok(snapshot.cell_count_gen(gen).to_string())
+        }
+    }
+
+    format::register(&Population).unwrap();
+    assert_eq!(
+        format::register(&Population),
+        Err(FormatError::DuplicateFormatter("population"))
+    );
+    assert_eq!(
+        format::register(&format::Rle),
+        Err(FormatError::DuplicateFormatter("rle"))
+    );
+    let names = format::formatters()
+        .iter()
+        .map(|formatter| formatter.name())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        ["rle", "rle-annotated", "plaintext", "python", "population"]
+    );
+    assert_eq!(format::by_name("population").unwrap().extension(), "txt");
+    assert_eq!(
+        format("population", 0),
+        Ok(search.cell_count_gen(0).to_string())
+    );
+    assert!(format("population", -1).is_err());
+    Ok(())
+}
+
+#[test]
+fn result_filter() -> Result<(), Error> {
+    let config = Config::new(16, 5, 3).set_translate(0, 1);
+    let mut search = config.world()?;
+    assert_eq!(search.search(None), Status::Found);
+    let first = search.rle_gen(0);
+    assert_eq!(search.search(None), Status::Found);
+    let second = search.rle_gen(0);
+
+    let mut search = config.world()?;
+    let rejected = first.clone();
+    search.set_result_filter(Some(Box::new(move |snapshot| {
+        snapshot.rle_gen(0) != rejected
+    })));
+    assert_eq!(search.search(None), Status::Found);
+    assert_eq!(search.rle_gen(0), second);
+
+    search.set_result_filter(Some(Box::new(|_| false)));
+    assert_eq!(search.search(None), Status::None);
+    Ok(())
+}
+
+#[test]
+fn algorithm() -> Result<(), Error> {
+    assert_eq!(Config::default().algorithm, AlgorithmChoice::Auto);
+
+    // `Auto` chooses the default algorithm.
+    let config = Config::new(16, 5, 3).set_translate(0, 1);
+    let mut auto = config.clone().world()?;
+    let mut lifesrc = config.set_algorithm(AlgorithmChoice::LifeSrc).world()?;
+    assert_eq!(auto.search(None), Status::Found);
+    assert_eq!(lifesrc.search(None), Status::Found);
+    assert_eq!(auto.rle_gen(0), lifesrc.rle_gen(0));
+    assert_eq!(auto.steps(), lifesrc.steps());
+
+    let config = Config::new(6, 6, 2).set_algorithm(AlgorithmChoice::Backjump);
+    assert!(config.clone().world().is_ok());
+    assert_eq!(
+        config.clone().set_rule_string("23/3/3").world().err(),
+        Some(Error::UnsupportedAlgorithm(
+            AlgorithmChoice::Backjump,
+            "Generations rules"
+        ))
+    );
+    assert_eq!(
+        config.clone().set_max_cell_count(10).world().err(),
+        Some(Error::UnsupportedAlgorithm(
+            AlgorithmChoice::Backjump,
+            "max cell count"
+        ))
+    );
+    assert_eq!(
+        config.clone().set_max_defects(1).world().err(),
+        Some(Error::UnsupportedAlgorithm(
+            AlgorithmChoice::Backjump,
+            "defects"
+        ))
+    );
+    assert_eq!(
+        config.set_transposition_cache(1024).world().err(),
+        Some(Error::UnsupportedAlgorithm(
+            AlgorithmChoice::Backjump,
+            "transposition cache"
+        ))
+    );
+
+    // These are fine for the default algorithm.
+    let config = Config::new(6, 6, 2)
+        .set_rule_string("23/3/3")
+        .set_max_cell_count(10)
+        .set_algorithm(AlgorithmChoice::LifeSrc);
+    assert!(config.world().is_ok());
+
+    // `PreferBackjump` falls back to the default algorithm,
+    // as the old `backjump` option did.
+    let config = Config::new(16, 5, 3).set_translate(0, 1);
+    #[allow(deprecated)]
+    let prefer_backjump = config.clone().set_backjump(true);
+    assert_eq!(prefer_backjump.algorithm, AlgorithmChoice::PreferBackjump);
+    let mut backjump = config.set_algorithm(AlgorithmChoice::Backjump).world()?;
+    let mut search = prefer_backjump.clone().world()?;
+    assert_eq!(backjump.search(None), Status::Found);
+    assert_eq!(search.search(None), Status::Found);
+    assert_eq!(search.steps(), backjump.steps());
+    for config in [
+        prefer_backjump.clone().set_rule_string("23/3/3"),
+        prefer_backjump.clone().set_max_cell_count(10),
+        prefer_backjump.set_max_defects(1),
+    ] {
+        let mut lifesrc = config
+            .clone()
+            .set_algorithm(AlgorithmChoice::LifeSrc)
+            .world()?;
+        let mut search = config.world()?;
+        assert_eq!(search.search(None), lifesrc.search(None));
+        assert_eq!(search.steps(), lifesrc.steps());
+    }
+    Ok(())
+}
+
+#[test]
+fn steps() -> Result<(), Error> {
+    for algorithm in [AlgorithmChoice::LifeSrc, AlgorithmChoice::Backjump] {
+        let config = Config::new(16, 5, 3)
+            .set_translate(0, 1)
+            .set_algorithm(algorithm);
+        let mut search = config.world()?;
+        let mut steps = search.steps();
+        assert_eq!(steps, 0);
+        while search.search(Some(100)) == Status::Searching {
+            assert!(search.steps() > steps + 100);
+            steps = search.steps();
+        }
+        assert!(search.steps() > steps);
+    }
+    Ok(())
+}
+
+#[test]
+fn many_states() -> Result<(), Error> {
+    let config = Config::new(4, 4, 1).set_rule_string("B3/S23/G50");
+    let mut search = config.clone().set_new_state(NewState::Random).world()?;
+    assert_eq!(search.search(None), Status::Found);
+
+    // Known cells are displayed even if there is no result.
+    let config = config.set_known_cells(vec![KnownCell {
+        coord: (0, 0, 0),
+        state: State(30),
+    }]);
+    let mut search = config.world()?;
+    assert!(search
+        .rle_gen(0)
+        .starts_with("x = 4, y = 4, rule = B3/S23/G50\npF"));
+    assert_eq!(search.search(None), Status::None);
+
+    let known_cells = vec![KnownCell {
+        coord: (0, 0, 0),
+        state: State(50),
+    }];
+    assert_eq!(
+        config.clone().set_known_cells(known_cells).world().err(),
+        Some(Error::InvalidState((0, 0, 0), State(50)))
+    );
+    assert!(config
+        .clone()
+        .set_rule_string("B3/S23/G256")
+        .world()
+        .is_ok());
+    assert_eq!(
+        config.set_rule_string("B3/S23/G257").world().err(),
+        Some(Error::TooManyStatesError(257))
+    );
+    Ok(())
+}
+
+#[cfg(feature = "analysis")]
+#[test]
+fn analysis() -> Result<(), Error> {
+    use rlifesrc_lib::analysis::{Classification, Evolver, Pattern};
+
+    // An anisotropic MAP rule.
+    let map =
+        "MAPARIzbBZodpgSaD8s6ID4owZoLqgoAOzA6ID7hoAAiBwQQGzo6IDQhCiAKcKAACUZYIDpgQAAgJGAAPGRAAAgSA";
+    let configs = [
+        Config::new(16, 5, 3).set_translate(0, 1),
+        Config::new(6, 6, 4).set_translate(1, 1),
+        Config::new(8, 8, 2).set_transform(Transform::FlipCol),
+        Config::new(5, 5, 3).set_rule_string("B2/S/C3"),
+        Config::new(6, 6, 2).set_rule_string(map),
+        Config::new(7, 7, 2)
+            .set_translate(0, 1)
+            .set_rule_string(map),
+    ];
+    for config in configs {
+        let evolver = Evolver::new(&config.rule_string)?;
+        let mut search = config.world()?;
+        for _ in 0..5 {
+            if search.search(None) != Status::Found {
+                break;
+            }
+            let pattern = Pattern::from_world(&search, 0);
+            let classification = Classification::new(&evolver, &pattern, config.period);
+            let (dx, dy, period) = classification.velocity().unwrap();
+            assert_eq!(config.period % period, 0);
+            assert_eq!((dx, dy, period), search.velocity());
+            let mismatches =
+                config.check_pattern(&pattern, Some(&config.rule_string), classification)?;
+            assert!(mismatches.is_empty(), "{:?}", mismatches);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(feature = "analysis")]
+#[test]
+fn apgcode() -> Result<(), Error> {
+    let configs = [
+        (Config::new(2, 2, 1), Some("xs4_33")),
+        (Config::new(3, 3, 2), Some("xp2_7")),
+        (Config::new(5, 5, 4).set_translate(1, 1), Some("xq4_153")),
+        (Config::new(5, 5, 3).set_rule_string("B2/S/C3"), None),
+    ];
+    for (config, code) in configs {
+        let mut search = config.world()?;
+        assert_eq!(search.apgcode(), None);
+        assert_eq!(search.search(None), Status::Found);
+        assert_eq!(search.apgcode().as_deref(), code);
+    }
+    Ok(())
+}
+
+#[cfg(all(feature = "analysis", feature = "read-rle"))]
+#[test]
+fn fixed_object() -> Result<(), Error> {
+    let glider = "x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!";
+    let config = Config::new(8, 8, 4).set_translate(-1, -1);
+
+    let alive = |t, cells: &[(i32, i32)]| {
+        cells
+            .iter()
+            .map(|&(x, y)| KnownCell {
+                coord: (x, y, t),
+                state: ALIVE,
+            })
+            .collect::<Vec<_>>()
+    };
+    let phases = [
+        alive(0, &[(1, 3), (2, 1), (2, 3), (3, 2), (3, 3)]),
+        alive(1, &[(1, 2), (2, 3), (2, 4), (3, 2), (3, 3)]),
+        alive(2, &[(1, 3), (2, 4), (3, 2), (3, 3), (3, 4)]),
+        alive(3, &[(2, 2), (2, 4), (3, 3), (3, 4), (4, 3)]),
+    ];
+    let with_glider = config
+        .clone()
+        .add_fixed_object(glider, (1, 1), (-1, -1, 4), false)?;
+    assert_eq!(with_glider.known_cells, phases.concat());
+
+    // The cells left by the glider in generation 1.
+    let with_glider = config
+        .clone()
+        .add_fixed_object(glider, (1, 1), (-1, -1, 4), true)?;
+    let dead = with_glider
+        .known_cells
+        .iter()
+        .filter(|cell| cell.coord.2 == 1 && cell.state == DEAD)
+        .map(|cell| cell.coord)
+        .collect::<Vec<_>>();
+    assert_eq!(dead, vec![(1, 3, 1), (2, 1, 1)]);
+
+    // The search completes the world around the glider.
+    let mut search = with_glider.world()?;
+    assert_eq!(search.search(None), Status::Found);
+    search.assert_result_valid();
+    for cell in phases.concat() {
+        assert_eq!(search.get_cell_state(cell.coord), Some(ALIVE));
+    }
+
+    assert_eq!(
+        config
+            .clone()
+            .add_fixed_object(glider, (1, 1), (0, -1, 4), false)
+            .err(),
+        Some(Error::FixedObjectVelocityError((0, -1, 4)))
+    );
+    assert_eq!(
+        Config::new(8, 8, 4)
+            .add_fixed_object(glider, (1, 1), (-1, -1, 4), false)
+            .err(),
+        Some(Error::FixedObjectWorldError((-1, -1, 4)))
+    );
+    assert_eq!(
+        config
+            .add_fixed_object(glider, (5, 1), (-1, -1, 4), false)
+            .err(),
+        Some(Error::KnownCellOutOfBounds((8, 3, 3)))
+    );
+    Ok(())
+}
+
+#[test]
+fn solutions() -> Result<(), Error> {
+    let config = Config::new(5, 5, 1);
+    let mut search = config.world()?;
+    let mut rles = Vec::new();
+    while search.search(None) == Status::Found {
+        rles.push(search.rle_gen(0));
+    }
+    assert!(rles.len() > 2);
+
+    let mut search = config.world()?;
+    let solutions = search.solutions().take(2).collect::<Vec<_>>();
+    assert_eq!(
+        solutions.iter().map(|s| &s.rle).collect::<Vec<_>>(),
+        rles[..2].iter().collect::<Vec<_>>()
+    );
+    // The iterator goes on from where the previous one stopped.
+    let solutions = search.solutions().collect::<Vec<_>>();
+    assert_eq!(
+        solutions.iter().map(|s| &s.rle).collect::<Vec<_>>(),
+        rles[2..].iter().collect::<Vec<_>>()
+    );
+    for solution in &solutions {
+        assert_eq!(solution.gen0_cells.len() as u32, solution.cell_count);
+        assert!(solution
+            .gen0_cells
+            .windows(2)
+            .all(|w| (w[0].1, w[0].0) < (w[1].1, w[1].0)));
+    }
+
+    let mut search = config.world()?;
+    let mut solutions = search.solutions();
+    assert_eq!(solutions.by_ref().count(), rles.len());
+    assert_eq!(solutions.next(), None);
+    assert_eq!(solutions.next(), None);
+
+    // Each solution respects the bound reduced by the previous one.
+    let mut search = config.set_reduce_max(true).world()?;
+    let counts = search
+        .solutions()
+        .map(|solution| solution.cell_count)
+        .collect::<Vec<_>>();
+    assert!(!counts.is_empty());
+    assert!(counts.windows(2).all(|w| w[0] > w[1]));
+    assert_eq!(search.search(None), Status::None);
+    Ok(())
+}
+
+#[test]
+fn results_with_stats() -> Result<(), Error> {
+    let config = Config::new(16, 5, 3).set_translate(0, 1);
+    let mut search = config.world()?;
+    let mut rles = Vec::new();
+    while search.search(None) == Status::Found {
+        rles.push(search.rle_gen(0));
+    }
+    let total_steps = search.steps();
+
+    let mut search = config.world()?;
+    let infos = search.results_with_stats().collect::<Vec<_>>();
+    assert_eq!(
+        infos.iter().map(|info| &info.rle).collect::<Vec<_>>(),
+        rles.iter().collect::<Vec<_>>()
+    );
+    assert!(infos.iter().map(|info| info.steps).sum::<u64>() <= total_steps);
+    assert!(infos.iter().map(|info| info.conflicts).sum::<u64>() <= search.conflicts());
+    for info in &infos {
+        let (x0, y0, x1, y1) = info.bounding_box.unwrap();
+        assert!(0 <= x0 && x0 <= x1 && x1 < 16);
+        assert!(0 <= y0 && y0 <= y1 && y1 < 5);
+        assert!(info.population > 0);
+    }
+    for i in 0..4 {
+        assert!(
+            infos
+                .iter()
+                .map(|info| info.border_conflicts[i])
+                .sum::<u64>()
+                <= search.border_conflict_summary()[i]
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn row_live_counts() -> Result<(), Error> {
+    /// All the vectors of `len` numbers between `0` and `max` whose sum is `sum`.
+    fn quotas(max: u32, len: usize, sum: u32) -> Vec<Vec<u32>> {
+        if len == 0 {
+            return if sum == 0 {
+                vec![Vec::new()]
+            } else {
+                Vec::new()
+            };
+        }
+        (0..=max.min(sum))
+            .flat_map(|k| {
+                quotas(max, len - 1, sum - k).into_iter().map(move |mut v| {
+                    v.push(k);
+                    v
+                })
+            })
+            .collect()
+    }
+
+    // The maximal numbers of living cells in still lifes
+    // in 4x4 and 5x5 worlds are 8 and 16.
+    let mut conflicts = [0; 2];
+    for (size, max) in [(4, 8), (5, 16)] {
+        for (sum, exists) in [(max, true), (max + 1, false)] {
+            let mut found = false;
+            for quota in quotas(size as u32, size as usize, sum) {
+                let config = Config::new(size, size, 1).set_row_live_counts(quota.clone());
+                let mut search = config.world()?;
+                if search.search(None) == Status::Found {
+                    found = true;
+                    for (y, &count) in quota.iter().enumerate() {
+                        let alive = (0..size)
+                            .filter(|&x| search.get_cell_state((x, y as i32, 0)) == Some(ALIVE))
+                            .count();
+                        assert_eq!(alive, count as usize);
+                    }
+                }
+                for (total, count) in conflicts.iter_mut().zip(search.row_count_conflicts()) {
+                    *total += count;
+                }
+            }
+            assert_eq!(found, exists);
+        }
+    }
+    assert!(conflicts[0] > 0);
+    assert!(conflicts[1] > 0);
+
+    let config = Config::new(4, 4, 1);
+    assert_eq!(
+        config.clone().set_row_live_counts(vec![2; 3]).world().err(),
+        Some(Error::RowLiveCountsLengthError)
+    );
+    assert_eq!(
+        config
+            .clone()
+            .set_row_live_counts(vec![2, 5, 2, 2])
+            .world()
+            .err(),
+        Some(Error::RowLiveCountTooLarge(1, 5))
+    );
+    assert!(matches!(
+        config
+            .set_row_live_counts(vec![2; 4])
+            .set_algorithm(AlgorithmChoice::Backjump)
+            .world(),
+        Err(Error::UnsupportedAlgorithm(..))
+    ));
+    Ok(())
+}
+
+#[test]
+fn border_conflict_summary() -> Result<(), Error> {
+    // There is no period 2 oscillator of width 2, but the blinker has width 3.
+    let config = Config::new(2, 12, 2);
+    let mut search = config.world()?;
+    assert_eq!(search.search(None), Status::None);
+    let [top, bottom, left, right] = search.border_conflict_summary();
+    assert!(left + right > 2 * (top + bottom));
+    assert!(search.conflicts() > 0);
+    let mut search = Config::new(3, 12, 2).world()?;
+    assert_eq!(search.search(None), Status::Found);
+
+    let config = Config::new(12, 2, 2);
+    let mut search = config.world()?;
+    assert_eq!(search.search(None), Status::None);
+    let [top, bottom, left, right] = search.border_conflict_summary();
+    assert!(top + bottom > 2 * (left + right));
+
+    #[cfg(feature = "serde")]
+    {
+        let world = search.ser().world()?;
+        assert_eq!(
+            world.border_conflict_summary(),
+            search.border_conflict_summary()
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn velocity() -> Result<(), Error> {
+    let config = Config::new(16, 5, 3).set_translate(0, 1);
+    let mut search = config.world()?;
+    assert_eq!(search.search(None), Status::Found);
+    assert_eq!(search.velocity(), (0, 1, 3));
+    assert_eq!(
+        search.rle_with_velocity(),
+        String::from("#C velocity (0, 1)c/3\n") + &search.rle_gen(0)
+    );
+
+    // A glider, found in two of its periods.
+    let config = Config::new(5, 5, 8)
+        .set_translate(2, 2)
+        .set_skip_subperiod(false);
+    let mut search = config.world()?;
+    assert_eq!(search.search(None), Status::Found);
+    assert_eq!(search.velocity(), (1, 1, 4));
+    assert!(search
+        .rle_with_velocity()
+        .starts_with("#C velocity (1, 1)c/4\n"));
+    Ok(())
+}
+
+#[test]
+fn containment_box() -> Result<(), Error> {
+    // A glider needs a 4x4 box over its period.
+    let config = Config::new(6, 6, 4).set_translate(1, 1);
+    let mut search = config.clone().set_containment_box((0, 0, 2, 2)).world()?;
+    assert_eq!(search.search(None), Status::None);
+    let mut search = config.clone().set_containment_box((1, 1, 4, 4)).world()?;
+    assert_eq!(search.search(None), Status::Found);
+    for x in 0..6 {
+        for y in 0..6 {
+            for t in 0..4 {
+                if !(1..=4).contains(&x) || !(1..=4).contains(&y) {
+                    assert_eq!(search.get_cell_state((x, y, t)), Some(DEAD));
+                }
+            }
+        }
+    }
+
+    assert_eq!(
+        config
+            .clone()
+            .set_containment_box((3, 0, 2, 5))
+            .world()
+            .err(),
+        Some(Error::ContainmentBoxError)
+    );
+    let known_cells = vec![KnownCell {
+        coord: (0, 0, 0),
+        state: DEAD,
+    }];
+    assert_eq!(
+        config
+            .set_containment_box((1, 1, 4, 4))
+            .set_known_cells(known_cells)
+            .world()
+            .err(),
+        Some(Error::KnownCellOutsideContainmentBox((0, 0, 0)))
+    );
+    Ok(())
+}
+
+/// Checks that every generation of the result evolves into the next one
+/// under `B3/S23` on the torus.
+fn assert_life_on_torus(search: &PolyWorld) {
+    let config = search.config();
+    let (width, height) = config.torus.unwrap();
+    let state = |x: i32, y: i32, t: i32| {
+        search.get_cell_state((x.rem_euclid(width), y.rem_euclid(height), t))
+    };
+    for x in 0..width {
+        for y in 0..height {
+            for t in 0..config.period {
+                let count = (-1..=1)
+                    .flat_map(|dx| (-1..=1).map(move |dy| (dx, dy)))
+                    .filter(|&d| d != (0, 0) && state(x + d.0, y + d.1, t) == Some(ALIVE))
+                    .count();
+                let alive = count == 3 || (count == 2 && state(x, y, t) == Some(ALIVE));
+                let succ = config.map_coord_across_period((x, y, t + 1));
+                assert_eq!(
+                    search.get_cell_state(succ),
+                    Some(if alive { ALIVE } else { DEAD }),
+                    "wrong successor of {:?}",
+                    (x, y, t)
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn torus() -> Result<(), Error> {
+    let config = Config::new(6, 3, 2).set_torus(4, 4);
+    assert_eq!((config.width, config.height), (4, 4));
+    let mut search = config.world()?;
+    assert_eq!(search.search(None), Status::Found);
+    assert_life_on_torus(&search);
+
+    let config = Config::new(5, 5, 2).set_translate(0, 1).set_torus(5, 5);
+    let mut search = config.world()?;
+    assert_eq!(search.search(None), Status::Found);
+    assert_life_on_torus(&search);
+
+    // A glider which wraps around the corner.
+    let glider = [(4, 3), (0, 4), (3, 0), (4, 0), (0, 0)];
+    let known_cells: Vec<_> = (0..5)
+        .flat_map(|x| (0..5).map(move |y| (x, y)))
+        .map(|(x, y)| KnownCell {
+            coord: (x, y, 0),
+            state: if glider.contains(&(x, y)) {
+                ALIVE
+            } else {
+                DEAD
+            },
+        })
+        .collect();
+    let config = Config::new(5, 5, 4)
+        .set_translate(-1, -1)
+        .set_known_cells(known_cells)
+        .set_torus(5, 5);
+    let mut search = config.world()?;
+    assert_eq!(search.search(None), Status::Found);
+    assert_life_on_torus(&search);
+    let mut search = Config {
+        torus: None,
+        ..config
+    }
+    .world()?;
+    assert_eq!(search.search(None), Status::None);
+
+    // Moving across the whole torus is the same as staying still.
+    let config = Config::new(5, 5, 1).set_translate(5, 0).set_torus(5, 5);
+    let mut search = config.world()?;
+    assert_eq!(search.search(None), Status::Found);
+    assert_life_on_torus(&search);
+    let mut search = Config {
+        torus: None,
+        ..config
+    }
+    .world()?;
+    assert_eq!(search.search(None), Status::None);
+    Ok(())
+}
+
+#[test]
+fn torus_errors() {
+    let config = Config::new(5, 5, 1).set_torus(5, 5);
+    assert_eq!(
+        config.clone().set_diagonal_width(3).world().err(),
+        Some(Error::TorusDiagonalWidthError)
+    );
+    assert_eq!(
+        Config { width: 6, ..config }.world().err(),
+        Some(Error::TorusSizeError((5, 5)))
+    );
+}
+
+#[test]
+fn symmetrize_known_cells() -> Result<(), Error> {
+    let known_cells = |cells: &[(Coord, State)]| {
+        cells
+            .iter()
+            .map(|&(coord, state)| KnownCell { coord, state })
+            .collect::<Vec<_>>()
+    };
+
+    let mut config = Config::new(6, 6, 1)
+        .set_symmetry(Symmetry::D2Row)
+        .set_known_cells(known_cells(&[((1, 0, 0), ALIVE), ((2, 2, 0), DEAD)]));
+    config.symmetrize_known_cells()?;
+    assert_eq!(
+        config.known_cells,
+        known_cells(&[
+            ((1, 0, 0), ALIVE),
+            ((2, 2, 0), DEAD),
+            ((1, 5, 0), ALIVE),
+            ((2, 3, 0), DEAD)
+        ])
+    );
+    let mut search = config.world()?;
+    assert_eq!(search.search(None), Status::Found);
+    assert_eq!(search.get_cell_state((1, 5, 0)), Some(ALIVE));
+
+    let mut config = Config::new(6, 6, 1)
+        .set_symmetry(Symmetry::C2)
+        .set_known_cells(known_cells(&[((1, 0, 0), ALIVE), ((4, 5, 0), DEAD)]));
+    assert_eq!(
+        config.symmetrize_known_cells(),
+        Err(Error::ConflictingKnownCells((4, 5, 0)))
+    );
+    Ok(())
+}
+
+#[test]
+fn duplicate_search_order_cell() {
+    let config = Config::new(4, 2, 2).set_search_order(SearchOrder::FromVec(vec![
+        (1, 1, 1),
+        (3, 1, 1),
+        (1, 1, 1),
+    ]));
+    assert_eq!(
+        config.world().err(),
+        Some(Error::DuplicateSearchOrderCell((1, 1, 1)))
+    );
+}
+
+#[test]
+fn search_order_coverage() -> Result<(), Error> {
+    let config = Config::new(8, 8, 2).set_symmetry(Symmetry::D4Ortho);
+
+    // The upper left quadrant, without the cells at (3, 3).
+    let quadrant = (0..4)
+        .flat_map(|y| (0..4).flat_map(move |x| (0..2).map(move |t| (x, y, t))))
+        .filter(|&(x, y, _)| (x, y) != (3, 3))
+        .collect::<Vec<_>>();
+    let incomplete = config
+        .clone()
+        .set_search_order(SearchOrder::FromVec(quadrant.clone()));
+    assert_eq!(
+        incomplete.world().err(),
+        Some(Error::SearchOrderIncompleteForSymmetry((3, 3, 0)))
+    );
+    // A cell symmetric to the missing cells fills the gap.
+    let mut completed = quadrant;
+    completed.extend([(4, 4, 0), (3, 4, 1)]);
+    assert!(config
+        .clone()
+        .set_search_order(SearchOrder::FromVec(completed))
+        .world()
+        .is_ok());
+
+    let domain = config.fundamental_domain();
+    assert_eq!(domain.len(), 32);
+    assert!(domain.iter().all(|&(x, y, _)| x < 4 && y < 4));
+    let mut search = config
+        .clone()
+        .set_search_order(SearchOrder::FromVec(domain))
+        .world()?;
+    assert_eq!(search.search(None), Status::Found);
+    search.assert_result_valid();
+
+    // Without symmetry, the search order can cover only part of the world.
+    assert!(Config::new(6, 6, 2)
+        .set_search_order(SearchOrder::FromVec(vec![(2, 2, 0)]))
+        .world()
+        .is_ok());
+    assert_eq!(Config::new(6, 6, 2).fundamental_domain().len(), 72);
+
+    // Frozen cells link all the generations of a cell.
+    let config = Config::new(4, 4, 3)
+        .set_symmetry(Symmetry::C2)
+        .set_frozen_cells(vec![(1, 1, 0)]);
+    let domain = config.fundamental_domain();
+    assert_eq!(domain.len(), 22);
+    assert!(domain.contains(&(1, 1, 0)));
+    assert!(!domain.contains(&(1, 1, 1)) && !domain.contains(&(2, 2, 2)));
+    assert!(config
+        .set_search_order(SearchOrder::FromVec(domain))
+        .world()
+        .is_ok());
+    Ok(())
+}
+
+#[test]
+fn split() -> Result<(), Error> {
+    // All results, each with all its generations.
+    let all_results = |config: Config| -> Result<Vec<String>, Error> {
+        let period = config.period;
+        let mut search = config.world()?;
+        let mut results = Vec::new();
+        while search.search(None) == Status::Found {
+            results.push((0..period).map(|t| search.rle_gen(t)).collect());
+        }
+        Ok(results)
+    };
+
+    let configs = [
+        Config::new(5, 5, 2),
+        Config::new(5, 5, 4).set_translate(1, 1),
+        Config::new(6, 6, 1).set_symmetry(Symmetry::D2Row),
+        Config::new(5, 5, 1).set_rule_string("23/3/3"),
+    ];
+    for config in configs {
+        let mut expected = all_results(config.clone())?;
+        assert!(!expected.is_empty());
+        expected.sort();
+
+        let units = config.split(0)?;
+        assert_eq!(units.len(), 1);
+        assert_eq!(units[0].config(), config);
+
+        let mut last_count = 1;
+        for depth in 1..=4 {
+            let units = config.split(depth)?;
+            assert!(units.len() >= last_count);
+            last_count = units.len();
+            let mut results = Vec::new();
+            for (index, unit) in units.iter().enumerate() {
+                assert_eq!((unit.index, unit.count), (index, units.len()));
+                assert!(unit.prefix.len() <= depth as usize);
+                results.extend(all_results(unit.config())?);
+            }
+            let len = results.len();
+            results.sort();
+            results.dedup();
+            assert_eq!(results.len(), len);
+            assert_eq!(results, expected);
+        }
+    }
+
+    assert_eq!(
+        Config::new(5, 5, 2)
+            .set_rule_string("B3/S23/x")
+            .split(2)
+            .err(),
+        Config::new(5, 5, 2)
+            .set_rule_string("B3/S23/x")
+            .world()
+            .err()
+    );
+    Ok(())
+}
+
+#[test]
+fn resume_token() -> Result<(), Error> {
+    // All results, each with the token taken right after it is found.
+    let all_results = |mut search: PolyWorld| -> Vec<(String, Option<String>)> {
+        let mut results = Vec::new();
+        while search.search(None) == Status::Found {
+            results.push((search.rle_gen(0), search.resume_token()));
+        }
+        assert_eq!(search.resume_token(), None);
+        results
+    };
+
+    let configs = [
+        Config::new(5, 5, 2),
+        Config::new(6, 6, 1).set_symmetry(Symmetry::D2Row),
+        Config::new(5, 5, 1).set_rule_string("23/3/3"),
+        Config::new(5, 5, 2).set_algorithm(AlgorithmChoice::Backjump),
+        Config::new(5, 5, 2).set_retreat_strategy(RetreatStrategy::ConflictDirected),
+        Config::new(6, 6, 2).set_reduce_max(true),
+    ];
+    for config in configs {
+        let expected = all_results(config.world()?);
+        assert!(expected.len() >= 2);
+        assert_eq!(all_results(config.world_at_token("")?), expected);
+
+        // Interrupts the search after each result, and resumes from its token.
+        for (i, (_, token)) in expected.iter().enumerate() {
+            let token = token.as_ref().unwrap();
+            let resumed = all_results(config.world_at_token(token)?);
+            assert_eq!(resumed, expected[i + 1..]);
+        }
+
+        // Interrupts the search in the middle.
+        let mut search = config.world()?;
+        assert_eq!(search.search(Some(3)), Status::Searching);
+        let token = search.resume_token().unwrap();
+        assert_eq!(all_results(config.world_at_token(&token)?), expected);
+    }
+
+    let config = Config::new(5, 5, 2);
+    let mut search = config.world()?;
+    assert_eq!(search.search(None), Status::Found);
+    let token = search.resume_token().unwrap();
+    let known_cells: Vec<_> = (0..5)
+        .flat_map(|x| (0..5).flat_map(move |y| (0..2).map(move |t| (x, y, t))))
+        .map(|coord| KnownCell { coord, state: DEAD })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        config
+            .clone()
+            .set_known_cells(known_cells)
+            .world_at_token(&token)
+            .err(),
+        Some(Error::ResumeTokenDiverged(0))
+    );
+    assert!(matches!(
+        config.world_at_token("not a token!"),
+        Err(Error::ResumeTokenError(_))
+    ));
+
+    let mut search = config.set_new_state(NewState::Random).world()?;
+    assert_eq!(search.search(None), Status::Found);
+    assert_eq!(search.resume_token(), None);
+    Ok(())
+}
+
+#[test]
+fn random_configs() {
+    const TRIALS: u32 = 2000;
+
+    let symmetries = [
+        "C1", "C2", "C4", "D2-", "D2|", "D2\\", "D2/", "D4+", "D4X", "D8",
+    ];
+    let transforms = ["Id", "R90", "R180", "R270", "F-", "F|", "F\\", "F/"];
+    let rules = [
+        "B3/S23",
+        "B36/S23",
+        "B2/S",
+        "B0/S2345678",
+        "23/3/3",
+        "B3/S23/5",
+    ];
+
+    let mut rng = StdRng::seed_from_u64(0);
+    for _ in 0..TRIALS {
+        let width = rng.gen_range(1..=6);
+        let height = if rng.gen_bool(0.5) {
+            width
+        } else {
+            rng.gen_range(1..=6)
+        };
+        let period = rng.gen_range(1..=4);
+        // Mostly inside the world, sometimes outside.
+        let coord = |rng: &mut StdRng| {
+            if rng.gen_bool(0.1) {
+                (
+                    rng.gen_range(-2..=7),
+                    rng.gen_range(-2..=7),
+                    rng.gen_range(-1..=4),
+                )
+            } else {
+                (
+                    rng.gen_range(0..width),
+                    rng.gen_range(0..height),
+                    rng.gen_range(0..period),
+                )
+            }
+        };
+        let mut config = Config::new(width, height, period)
+            .set_translate(rng.gen_range(-2..=2), rng.gen_range(-2..=2))
+            .set_symmetry(symmetries.choose(&mut rng).unwrap().parse().unwrap())
+            .set_rule_string(rules.choose(&mut rng).unwrap())
+            .set_algorithm(if rng.gen_bool(0.3) {
+                AlgorithmChoice::Backjump
+            } else {
+                AlgorithmChoice::Auto
+            })
+            .set_skip_subperiod(rng.gen_bool(0.5))
+            .set_skip_subsymmetry(rng.gen_bool(0.5))
+            .set_new_state(NewState::Random);
+        if rng.gen_bool(0.5) {
+            config = config.set_transform(transforms.choose(&mut rng).unwrap().parse().unwrap());
+        }
+        if rng.gen_bool(0.2) {
+            config = config
+                .set_symmetry(Symmetry::C1)
+                .set_diagonal_width(rng.gen_range(-1..=6));
+        }
+        config = config.set_search_order(match rng.gen_range(0..5) {
+            0 => None,
+            1 => Some(SearchOrder::RowFirst),
+            2 => Some(SearchOrder::ColumnFirst),
+            3 => Some(SearchOrder::Diagonal),
+            _ => Some(SearchOrder::FromVec(
+                (0..rng.gen_range(0..20)).map(|_| coord(&mut rng)).collect(),
+            )),
+        });
+        if rng.gen_bool(0.3) {
+            config = config.set_known_cells(
+                (0..rng.gen_range(0..5))
+                    .map(|_| KnownCell {
+                        coord: coord(&mut rng),
+                        state: State(rng.gen_range(0..2)),
+                    })
+                    .collect::<Vec<_>>(),
+            );
+        }
+        if rng.gen_bool(0.2) {
+            config = config.set_frozen_cells(
+                (0..rng.gen_range(0..4))
+                    .map(|_| coord(&mut rng))
+                    .collect::<Vec<_>>(),
+            );
+        }
+        if rng.gen_bool(0.2) {
+            config = config.set_max_cell_count(rng.gen_range(0..10));
+        }
+        if rng.gen_bool(0.2) {
+            config = config.set_max_defects(rng.gen_range(0..3));
+        }
+        if rng.gen_bool(0.1) {
+            config = config
+                .set_population_parity(
+                    (0..period)
+                        .map(|_| rng.gen_bool(0.5).then(|| rng.gen_bool(0.5)))
+                        .collect::<Vec<_>>(),
+                )
+                .set_prune_parity(rng.gen_bool(0.5));
+        }
+
+        // Falls back to the default algorithm when backjumping is not supported.
+        let search = match config.world() {
+            Err(Error::UnsupportedAlgorithm(..)) => {
+                config.set_algorithm(AlgorithmChoice::Auto).world()
+            }
+            search => search,
+        };
+
+        // Either fails to construct, or searches without panicking.
+        // A search order that does not cover every cell might give a wrong result,
+        // otherwise the result satisfies the configuration.
+        if let Ok(mut search) = search {
+            if search.search(Some(1000)) == Status::Found
+                && !matches!(search.config().search_order, Some(SearchOrder::FromVec(_)))
+            {
+                search.assert_result_valid();
+            }
+        }
+    }
+}
+
+#[test]
+fn candidate_states() -> Result<(), Error> {
+    let mut search = Config::new(16, 16, 1).world()?;
+    assert_eq!(search.candidate_states((5, 5, 0)), vec![DEAD, ALIVE]);
+    assert_eq!(search.candidate_states((-5, 5, 0)), vec![DEAD]);
+    assert_eq!(search.search(None), Status::Found);
+    for x in 0..16 {
+        for y in 0..16 {
+            let state = search.get_cell_state((x, y, 0)).unwrap();
+            assert_eq!(search.candidate_states((x, y, 0)), vec![state]);
+        }
+    }
+
+    // The living corners need living neighbors, and the 8 cells next to
+    // the corners have the same state because of the symmetry.
+    let config = Config::new(6, 6, 1)
+        .set_symmetry(Symmetry::D8)
+        .set_known_cells(vec![KnownCell {
+            coord: (0, 0, 0),
+            state: ALIVE,
+        }]);
+    let mut search = config.world()?;
+    assert_eq!(search.get_cell_state((0, 1, 0)), None);
+    assert_eq!(search.candidate_states((0, 1, 0)), vec![ALIVE]);
+    assert_eq!(search.candidate_states((1, 1, 0)), vec![DEAD, ALIVE]);
+    assert_eq!(search.get_cell_state((0, 1, 0)), None);
+    #[cfg(debug_assertions)]
+    search.verify_descriptors();
+
+    // Querying the candidates does not change the search.
+    let mut new_search = config.world()?;
+    assert_eq!(search.search(None), Status::Found);
+    assert_eq!(new_search.search(None), Status::Found);
+    assert_eq!(search.rle_gen(0), new_search.rle_gen(0));
+    assert_eq!(search.get_cell_state((0, 1, 0)), Some(ALIVE));
+    Ok(())
+}
+
+#[test]
+fn probe_assignment() -> Result<(), Error> {
+    // The living cell and its symmetric copies need living neighbors.
+    let config = Config::new(8, 8, 1).set_symmetry(Symmetry::D4Ortho);
+    let mut search = config.world()?;
+    let consequences = search.probe_assignment((0, 0, 0), ALIVE)?;
+    for coord in [(7, 0, 0), (0, 7, 0), (7, 7, 0)] {
+        assert!(consequences.contains(&(coord, ALIVE)));
+    }
+    assert!(!consequences.iter().any(|&(coord, _)| coord == (0, 0, 0)));
+    for &(coord, state) in &consequences {
+        assert_eq!(search.get_cell_state(coord), None);
+        assert!(search.candidate_states(coord).contains(&state));
+    }
+    assert_eq!(search.get_cell_state((0, 0, 0)), None);
+    assert_eq!(
+        search.probe_assignment((0, 0, 8), DEAD)?,
+        search.probe_assignment((0, 0, 0), DEAD)?
+    );
+    assert_eq!(search.probe_assignment((-1, 0, 0), DEAD)?, vec![]);
+    assert_eq!(
+        search.probe_assignment((-1, 0, 0), ALIVE),
+        Err(Error::AssignmentConflict((-1, 0, 0), ALIVE))
+    );
+    assert_eq!(
+        search.probe_assignment((3, 3, 0), State(2)),
+        Err(Error::InvalidState((3, 3, 0), State(2)))
+    );
+    assert_eq!(
+        search.probe_assignment((-5, 0, 0), DEAD),
+        Err(Error::SetCellError((-5, 0, 0)))
+    );
+
+    // Four living cells are too many.
+    let mut search = config.clone().set_max_cell_count(3).world()?;
+    assert_eq!(
+        search.probe_assignment((1, 1, 0), ALIVE),
+        Err(Error::AssignmentConflict((1, 1, 0), ALIVE))
+    );
+    assert_eq!(search.search(None), Status::None);
+
+    // Probing in the middle of a search does not change it.
+    let config = Config::new(16, 5, 3)
+        .set_translate(0, 1)
+        .set_transposition_cache(Some(1 << 10));
+    let mut search = config.world()?;
+    let mut new_search = config.world()?;
+    assert_eq!(search.search(Some(1000)), Status::Searching);
+    assert_eq!(new_search.search(Some(1000)), Status::Searching);
+    for x in 0..16 {
+        for y in 0..5 {
+            for state in [DEAD, ALIVE] {
+                if let Ok(consequences) = search.probe_assignment((x, y, 1), state) {
+                    assert!(consequences
+                        .iter()
+                        .all(|&(coord, _)| search.get_cell_state(coord).is_none()));
+                }
+            }
+        }
+    }
+    for t in 0..3 {
+        assert_eq!(search.rle_gen(t), new_search.rle_gen(t));
+    }
+    assert_eq!(search.search(None), Status::Found);
+    assert_eq!(new_search.search(None), Status::Found);
+    assert_eq!(search.steps(), new_search.steps());
+    assert_eq!(search.rle_gen(0), new_search.rle_gen(0));
+    assert_eq!(search.cache_stats(), new_search.cache_stats());
+    search.assert_result_valid();
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "read-rle")]
+fn known_cells_from_rles() -> Result<(), Error> {
+    let known_cell = |coord, state| KnownCell { coord, state };
+
+    // The second RLE is blank, and the third one has a smaller bounding box.
+    let rles = "x = 3, y = 2, rule = B3/S23\n\
+                ?o$2bo!\n\
+                x = 0, y = 0, rule = B3/S23\n\
+                !\n\
+                \n\
+                x = 1, y = 1, rule = B3/S23\n\
+                ?$bo!\n";
+    let known_cells = KnownCell::from_rles(rles).unwrap();
+    assert_eq!(
+        known_cells,
+        vec![
+            known_cell((1, 0, 0), ALIVE),
+            known_cell((0, 1, 0), DEAD),
+            known_cell((1, 1, 0), DEAD),
+            known_cell((2, 1, 0), ALIVE),
+            known_cell((0, 1, 2), DEAD),
+            known_cell((1, 1, 2), ALIVE),
+        ]
+    );
+
+    let config = Config::new(3, 3, 3)
+        .set_known_cells_from_rles(rles)
+        .unwrap();
+    assert_eq!(config.known_cells, known_cells);
+    let search = config.world()?;
+    assert_eq!(search.get_cell_state((1, 1, 2)), Some(ALIVE));
+    assert_eq!(search.get_cell_state((1, 1, 1)), None);
+
+    // Too many generations.
+    let config = Config::new(3, 3, 2)
+        .set_known_cells_from_rles(rles)
+        .unwrap();
+    assert_eq!(
+        config.world().err(),
+        Some(Error::KnownCellOutOfBounds((0, 1, 2)))
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "read-rle")]
+fn known_cells_from_rles_placement() -> Result<(), Error> {
+    let known_cell = |coord, state| KnownCell { coord, state };
+
+    // The RLE after a tagged one without a tag goes to the next generation.
+    let rles = "#C gen 2\n\
+                #P 1 2\n\
+                o!\n\
+                #R -1 0\n\
+                x = 2, y = 1, rule = B3/S23\n\
+                bo!\n\
+                !\n\
+                #C Not a tag.\n\
+                #C gen 0\n\
+                2o!\n\
+                #C Trailing comment.\n";
+    assert_eq!(
+        KnownCell::from_rles(rles)?,
+        vec![
+            known_cell((1, 2, 2), ALIVE),
+            known_cell((-1, 0, 3), DEAD),
+            known_cell((0, 0, 3), ALIVE),
+            known_cell((0, 0, 0), ALIVE),
+            known_cell((1, 0, 0), ALIVE),
+        ]
+    );
+    assert_eq!(
+        KnownCell::from_rles("#P 1\no!").err(),
+        Some(Error::RleCommentError(String::from("#P 1")))
+    );
+    assert_eq!(
+        KnownCell::from_rles("#C gen one\no!").err(),
+        Some(Error::RleCommentError(String::from("#C gen one")))
+    );
+
+    let mut search = Config::new(5, 5, 2).world()?;
+    assert_eq!(search.search(None), Status::Found);
+    let rles = search.rle_gen(0) + &search.rle_gen(1);
+
+    // Reading the generations in order gives back the same pattern.
+    let config = Config::new(5, 5, 2).set_known_cells_from_rles(&rles)?;
+    assert_eq!(config.known_cells.len(), 50);
+    for KnownCell { coord, state } in config.known_cells {
+        assert_eq!(search.get_cell_state(coord), Some(state));
+    }
+
+    // The same pattern at another place, starting from generation 1.
+    let config = Config::new(9, 9, 3).add_known_cells_from_rle_at(&rles, 1, (2, 3))?;
+    assert_eq!(config.known_cells.len(), 50);
+    let new_search = config.world()?;
+    for x in 0..5 {
+        for y in 0..5 {
+            for t in 0..2 {
+                assert_eq!(
+                    new_search.get_cell_state((x + 2, y + 3, t + 1)),
+                    search.get_cell_state((x, y, t))
+                );
+            }
+        }
+    }
+
+    // Out of the world after moving.
+    assert_eq!(
+        Config::new(9, 9, 3)
+            .add_known_cells_from_rle_at(&rles, 0, (5, 3))
+            .err(),
+        Some(Error::RleCellOutOfBounds((4, 0, 0), (9, 3, 0)))
+    );
+    assert_eq!(
+        Config::new(9, 9, 3)
+            .add_known_cells_from_rle_at(&rles, 2, (0, 0))
+            .err(),
+        Some(Error::RleCellOutOfBounds((0, 0, 1), (0, 0, 3)))
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn algorithm_compat() {
+    use serde::{
+        de::value::{BoolDeserializer, Error, MapDeserializer, StrDeserializer},
+        Deserialize,
+    };
+
+    let algorithm =
+        |value: bool| AlgorithmChoice::deserialize(BoolDeserializer::<Error>::new(value)).unwrap();
+    assert_eq!(algorithm(true), AlgorithmChoice::PreferBackjump);
+    assert_eq!(algorithm(false), AlgorithmChoice::Auto);
+    assert_eq!(
+        AlgorithmChoice::deserialize(StrDeserializer::<Error>::new("LifeSrc")).unwrap(),
+        AlgorithmChoice::LifeSrc
+    );
+    assert!(AlgorithmChoice::deserialize(StrDeserializer::<Error>::new("DFS")).is_err());
+
+    // The old field name.
+    let config = |key: &str| {
+        Config::deserialize(MapDeserializer::<_, Error>::new(std::iter::once((
+            key, true,
+        ))))
+        .unwrap()
+    };
+    assert_eq!(
+        config("backjump").algorithm,
+        AlgorithmChoice::PreferBackjump
+    );
+    assert_eq!(
+        config("algorithm").algorithm,
+        AlgorithmChoice::PreferBackjump
+    );
+
+    // Old configs still work when backjumping is not supported.
+    let config = Config {
+        width: 5,
+        height: 5,
+        ..config("backjump")
+    };
+    assert!(config.clone().set_max_cell_count(4).world().is_ok());
+    assert!(config.set_rule_string("23/3/3").world().is_ok());
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn ser_compact() -> Result<(), Error> {
+    let mut shorter = false;
+    for config in [
+        Config::new(16, 5, 3).set_translate(0, 1),
+        Config::new(16, 5, 3)
+            .set_translate(0, 1)
+            .set_algorithm(AlgorithmChoice::Backjump),
+        Config::new(8, 8, 4)
+            .set_translate(1, 1)
+            .set_symmetry(Symmetry::D2Diag),
+        Config::new(6, 6, 3).set_rule_string("23/3/3"),
+        Config::new(8, 8, 1).set_reduce_max(true),
+    ] {
+        let mut search = config.world()?;
+        let status = search.search(None);
+        let rle = search.rle_gen(0);
+
+        for max_step in [10, 100, 1000] {
+            let mut search = config.world()?;
+            if search.search(Some(max_step)) != Status::Searching {
+                continue;
+            }
+            let ser = search.ser();
+            let compact = search.ser_compact();
+            assert!(compact.compact);
+            assert!(compact.set_stack.len() <= ser.set_stack.len());
+            shorter |= compact.set_stack.len() < ser.set_stack.len();
+
+            // The restored world is exactly the same as the saved one.
+            let mut new_search = compact.world()?;
+            assert_eq!(new_search.ser(), ser);
+            assert_eq!(new_search.search(None), status);
+            assert_eq!(new_search.rle_gen(0), rle);
+        }
+
+        // Saved right after a result is found.
+        if status == Status::Found {
+            let ser = search.ser();
+            let mut new_search = search.ser_compact().world()?;
+            assert_eq!(new_search.ser(), ser);
+            let status = search.search(None);
+            assert_eq!(new_search.search(None), status);
+            assert_eq!(new_search.rle_gen(0), search.rle_gen(0));
+        }
+    }
+    assert!(shorter);
+    Ok(())
+}
+
+#[test]
+fn variant_docs() {
+    // The names shown to users are the notations of the symmetries and transformations.
+    assert_eq!(docs::SYMMETRY.len(), 10);
+    for doc in docs::SYMMETRY {
+        let symmetry = doc.name.parse::<Symmetry>().unwrap();
+        assert_eq!(format!("{:?}", symmetry), doc.variant);
+        assert_eq!(symmetry.to_string(), doc.name);
+    }
+    assert_eq!(docs::TRANSFORM.len(), 8);
+    for doc in docs::TRANSFORM {
+        let transform = doc.name.parse::<Transform>().unwrap();
+        assert_eq!(format!("{:?}", transform), doc.variant);
+    }
+
+    let doc = docs::find(docs::SEARCH_ORDER, "Diagonal").unwrap();
+    assert_eq!(doc.name, "Diagonal");
+    assert_eq!(doc.summary(), "Diagonal.");
+    assert!(doc
+        .doc
+        .ends_with("This search order requires the world to be square."));
+    let doc = docs::find(docs::NEW_STATE, "ChooseAlive").unwrap();
+    assert!(doc.doc.contains("it always chooses `ALIVE`"));
+    assert_eq!(docs::find(docs::NEW_STATE, "ChooseNothing"), None);
+}
+
 #[test]
 #[cfg(feature = "serde")]
 fn ser() -> Result<(), Error> {
@@ -221,6 +3738,7 @@ fn ser() -> Result<(), Error> {
     let save = search.ser();
     let mut new_search = save.world()?;
     assert_eq!(new_search.cell_count(), count);
+    assert_eq!(new_search.steps(), search.steps());
     assert_eq!(new_search.search(None), Status::Found);
     assert_eq!(
         new_search.rle_gen(0),
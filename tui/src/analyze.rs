@@ -0,0 +1,85 @@
+//! A one-shot mode that classifies a pattern and checks it against a config,
+//! and writes the result to stdout as JSON.
+
+use ca_formats::rle::Rle;
+use rlifesrc_lib::{
+    analysis::{Classification, Direction, Evolver, Mismatch, Pattern},
+    Config, State, StateRepr,
+};
+use serde::Serialize;
+use std::{fs, path::Path};
+
+/// The output written to stdout.
+#[derive(Serialize)]
+struct Response {
+    /// The kind of the pattern.
+    classification: Classification,
+    /// The direction, if the pattern is a spaceship.
+    direction: Option<Direction>,
+    /// Number of living cells.
+    population: u32,
+    /// The bounding box of the pattern, as `(x0, y0, x1, y1)`.
+    bounding_box: Option<(i32, i32, i32, i32)>,
+    /// The ways in which the pattern does not satisfy the config.
+    mismatches: Vec<Mismatch>,
+    /// Whether the pattern satisfies the config.
+    satisfies_config: bool,
+}
+
+/// The output written to stderr when there is an error.
+#[derive(Serialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+/// Reads the pattern and the rule in the header from an RLE file.
+fn read_pattern(path: &Path) -> Result<(Pattern, Option<String>), String> {
+    let input = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let rle = Rle::new(input.as_str()).map_err(|e| format!("Invalid RLE: {}", e))?;
+    let rule = rle.header_data().and_then(|header| header.rule.clone());
+    let cells = rle
+        .map(|data| {
+            let data = data.map_err(|e| format!("Invalid RLE: {}", e))?;
+            let (x, y) = data.position;
+            Ok(((x as i32, y as i32), State(data.state as StateRepr)))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok((Pattern::from_cells(cells), rule))
+}
+
+/// Classifies the pattern and checks it against the config.
+fn analyze(config: &Config, path: &Path, max_period: i32) -> Result<Response, String> {
+    let (pattern, rule) = read_pattern(path)?;
+    let evolver = Evolver::new(&config.rule_string).map_err(|e| e.to_string())?;
+    let classification = Classification::new(&evolver, &pattern, max_period.max(config.period));
+    let mismatches = config
+        .check_pattern(&pattern, rule.as_deref(), classification)
+        .map_err(|e| e.to_string())?;
+    Ok(Response {
+        classification,
+        direction: classification.direction(),
+        population: pattern.population(),
+        bounding_box: pattern.bounding_box(),
+        satisfies_config: mismatches.is_empty(),
+        mismatches,
+    })
+}
+
+/// Analyzes the pattern and writes the output to stdout.
+///
+/// Errors are written to stderr. Returns the exit code.
+pub(crate) fn run(config: &Config, path: &Path, max_period: i32) -> i32 {
+    match analyze(config, path, max_period) {
+        Ok(response) => {
+            println!("{}", serde_json::to_string_pretty(&response).unwrap());
+            0
+        }
+        Err(error) => {
+            eprintln!(
+                "{}",
+                serde_json::to_string(&ErrorResponse { error }).unwrap()
+            );
+            1
+        }
+    }
+}
@@ -1,32 +1,82 @@
 //! Parsing command-line arguments.
 
 use clap::{
+    builder::PossibleValuesParser,
     command,
     error::{ErrorKind, Result},
-    value_parser, Arg, ArgAction,
+    value_parser, Arg, ArgAction, ArgMatches, Command,
 };
 use rlifesrc_lib::{
-    rules::NtLifeGen, Config, NewState, PolyWorld, SearchOrder, Symmetry, Transform,
+    docs::{self, VariantDoc},
+    format::{self, PatternFormatter},
+    rules::NtLifeGen,
+    AlgorithmChoice, Config, NewState, PolyWorld, SearchOrder, Symmetry, Transform,
 };
 use std::{
     fs::File,
     io::{BufReader, Read},
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
 
+#[cfg(feature = "tui")]
+use crate::autosave::Autosave;
+#[cfg(feature = "tui")]
+use std::time::Duration;
+
+/// What to do according to the command-line arguments.
+pub enum Mode {
+    /// Searches the world given by the arguments.
+    Search(Box<Args>),
+    /// Reads the config from stdin and writes the results to stdout, both in JSON.
+    StdinJson,
+    /// Lists the supported formats of rule strings.
+    ListRules,
+    /// Explains the choices of an option.
+    HelpOption(&'static [VariantDoc]),
+    /// Classifies a pattern and checks it against a config.
+    Analyze {
+        config: Box<Config>,
+        pattern: PathBuf,
+        max_period: i32,
+    },
+    /// Estimates the difficulty of the config by short random searches.
+    Probe {
+        config: Box<Config>,
+        budget_steps: u64,
+        probes: u32,
+        seed: u64,
+    },
+    /// Searches with the config in each of the rules in a list.
+    RuleSweep {
+        config: Box<Config>,
+        rules: Vec<String>,
+        budget_per_rule: Option<u64>,
+    },
+    /// Splits the search into work units, and writes them as JSON.
+    Split {
+        config: Box<Config>,
+        depth: u32,
+        output: Option<PathBuf>,
+    },
+}
+
 /// A struct to store the parse results.
 pub struct Args {
     pub(crate) world: PolyWorld,
     pub(crate) all: bool,
+    pub(crate) min_phase: bool,
+    pub(crate) output_format: &'static dyn PatternFormatter,
     #[cfg(feature = "tui")]
     pub(crate) reset: bool,
     #[cfg(feature = "tui")]
     pub(crate) no_tui: bool,
+    #[cfg(feature = "tui")]
+    pub(crate) autosave: Option<Autosave>,
 }
 
 impl Args {
-    /// Parses the command-line arguments.
-    pub(crate) fn parse() -> Result<Self> {
+    /// Builds the command-line interface.
+    fn command() -> Command {
         let mut app = command!()
             .long_about(
                 "Searching for patterns in Conway's Game of Life\n\
@@ -38,6 +88,52 @@ impl Args {
                  https://github.com/AlephAlpha/rlifesrc/blob/master/tui/README.md (In Chinese)\n\
                  https://github.com/AlephAlpha/rlifesrc/blob/master/tui/README_en.md (In English)",
             )
+            .args_conflicts_with_subcommands(true)
+            .subcommand_negates_reqs(true)
+            .subcommand(
+                Command::new("analyze")
+                    .about("Classify a pattern and check it against a config")
+                    .long_about(
+                        "Classify a pattern and check it against a config\n\
+                         The pattern is evolved to find its period and translation. \
+                         Then its size, rule, period and translation are compared \
+                         with the config. The result is written to stdout as JSON.",
+                    )
+                    .arg(
+                        Arg::new("CONFIG")
+                            .help("Read config from a file")
+                            .long_help(
+                                "Read config from a file\n\
+                                 Supported formats: JSON, YAML, TOML.",
+                            )
+                            .short('C')
+                            .long("config")
+                            .required(true)
+                            .value_parser(value_parser!(PathBuf)),
+                    )
+                    .arg(
+                        Arg::new("PATTERN")
+                            .help("The pattern to analyze, as an RLE file")
+                            .long_help(
+                                "The pattern to analyze, as an RLE file\n\
+                                 The coordinates are the same as in the world of the config, \
+                                 i.e., the upper left cell of the RLE is (0, 0).",
+                            )
+                            .required(true)
+                            .value_parser(value_parser!(PathBuf)),
+                    )
+                    .arg(
+                        Arg::new("MAXPERIOD")
+                            .help("Maximum period to look for")
+                            .long_help(
+                                "Maximum period to look for\n\
+                                 The period of the config is always included.",
+                            )
+                            .long("max-period")
+                            .default_value("100")
+                            .value_parser(value_parser!(i32).range(1..)),
+                    ),
+            )
             .arg(
                 Arg::new("CONFIG")
                     .help("Read config from a file")
@@ -45,22 +141,162 @@ impl Args {
                         "Read config from a file\n\
                          Supported formats: JSON, YAML, TOML.\n\
                          When a config file is provided, all the other flags and options, \
-                         except --all (-a), --reset-time, --no-tui (-n), are ignored.",
+                         except --spec, --probe, --probe-steps, --probes, --probe-seed, \
+                         --rules-file, --rule-steps, --split, --split-output, \
+                         --all (-a), --resume-token, --reset-time, --no-tui (-n), \
+                         --autosave, --autosave-interval, are ignored.",
                     )
                     .short('C')
                     .long("config")
                     .value_parser(value_parser!(PathBuf)),
             )
+            .arg(
+                Arg::new("SPEC")
+                    .help("Override the size, the rule and the symmetries with a compact spec")
+                    .long_help(
+                        "Override the size, the rule and the symmetries with a compact spec\n\
+                         The spec is a comma-separated list of key=value pairs, \
+                         e.g., \"rule=B3/S23, sym=D2|, dy=1, p=2\". \
+                         The keys are w, h, p, dx, dy, trans, sym and rule. \
+                         The spec is applied after the other flags and options, \
+                         or after reading the config file.",
+                    )
+                    .long("spec"),
+            )
+            .arg(
+                Arg::new("PROBE")
+                    .help("Print a difficulty estimate of the config instead of searching")
+                    .long_help(
+                        "Print a difficulty estimate of the config instead of searching\n\
+                         Runs some short searches with random states, and prints \
+                         how deep they go, how often they meet conflicts, how many of them \
+                         find a result, and a difficulty score between 0 and 1. \
+                         The score is only comparable between configs probed with \
+                         the same --probe-steps and --probes.",
+                    )
+                    .long("probe")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("PROBESTEPS")
+                    .help("Number of steps of each probe")
+                    .long("probe-steps")
+                    .requires("PROBE")
+                    .value_parser(value_parser!(u64))
+                    .default_value("10000"),
+            )
+            .arg(
+                Arg::new("PROBES")
+                    .help("Number of probes")
+                    .long("probes")
+                    .requires("PROBE")
+                    .value_parser(value_parser!(u32).range(1..))
+                    .default_value("8"),
+            )
+            .arg(
+                Arg::new("PROBESEED")
+                    .help("Random seed of the probes")
+                    .long("probe-seed")
+                    .requires("PROBE")
+                    .value_parser(value_parser!(u64))
+                    .default_value("0"),
+            )
+            .arg(
+                Arg::new("RULESFILE")
+                    .help("Search in each of the rules listed in a file, and print a summary")
+                    .long_help(
+                        "Search in each of the rules listed in a file, and print a summary\n\
+                         The file contains one rule string on each line. \
+                         Empty lines and lines starting with # are skipped. \
+                         The rule of the config is ignored. \
+                         For each rule, prints whether a result is found, \
+                         or why the config is invalid with this rule.",
+                    )
+                    .long("rules-file")
+                    .conflicts_with("PROBE")
+                    .value_parser(value_parser!(PathBuf)),
+            )
+            .arg(
+                Arg::new("RULESTEPS")
+                    .help("Maximum number of steps of the search in each rule")
+                    .long_help(
+                        "Maximum number of steps of the search in each rule\n\
+                         If not set, each search goes on until a result is found \
+                         or the search space is exhausted.",
+                    )
+                    .long("rule-steps")
+                    .requires("RULESFILE")
+                    .value_parser(value_parser!(u64)),
+            )
+            .arg(
+                Arg::new("SPLIT")
+                    .help("Split the search into work units, and write them as JSON")
+                    .long_help(
+                        "Split the search into work units, and write them as JSON\n\
+                         Enumerates the states of the first DEPTH cells that the search \
+                         would decide, discarding the ones that lead to a conflict. \
+                         Writes a JSON array of objects of the form \
+                         {\"index\": ..., \"count\": ..., \"config\": ...}. \
+                         Searching with the configs of all the work units finds \
+                         the same results as searching with the original config.",
+                    )
+                    .long("split")
+                    .value_name("DEPTH")
+                    .conflicts_with_all(["PROBE", "RULESFILE"])
+                    .value_parser(value_parser!(u32)),
+            )
+            .arg(
+                Arg::new("SPLITOUTPUT")
+                    .help("Write the work units to a file instead of the standard output")
+                    .long("split-output")
+                    .value_name("PATH")
+                    .requires("SPLIT")
+                    .value_parser(value_parser!(PathBuf)),
+            )
+            .arg(
+                Arg::new("STDINJSON")
+                    .help("Read a JSON config from stdin, and write the results to stdout as JSON")
+                    .long_help(
+                        "Read a JSON config from stdin, and write the results to stdout as JSON\n\
+                         The input is either a config, or an object of the form \
+                         {\"config\": ..., \"max_step\": ..., \"all\": ...}, \
+                         where \"max_step\" and \"all\" are optional.\n\
+                         Errors are written to stderr as JSON.\n\
+                         When this flag is set, all the other flags and options are ignored.",
+                    )
+                    .long("stdin-json")
+                    .conflicts_with("CONFIG")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("LISTRULES")
+                    .help("List the supported formats of rule strings")
+                    .long("list-rules")
+                    .conflicts_with_all(["CONFIG", "STDINJSON"])
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("HELPOPTION")
+                    .help("Explain each choice of an option")
+                    .long_help(
+                        "Explain each choice of an option\n\
+                         The option is given by its long name without the dashes.",
+                    )
+                    .long("help-option")
+                    .value_name("OPTION")
+                    .value_parser(["transform", "symmetry", "order", "choose"])
+                    .conflicts_with_all(["CONFIG", "STDINJSON", "LISTRULES"]),
+            )
             .arg(
                 Arg::new("X")
                     .help("Width of the pattern")
-                    .required_unless_present("CONFIG")
+                    .required_unless_present_any(["CONFIG", "STDINJSON", "LISTRULES", "HELPOPTION"])
                     .value_parser(value_parser!(i32).range(1..)),
             )
             .arg(
                 Arg::new("Y")
                     .help("Height of the pattern")
-                    .required_unless_present("CONFIG")
+                    .required_unless_present_any(["CONFIG", "STDINJSON", "LISTRULES", "HELPOPTION"])
                     .value_parser(value_parser!(i32).range(1..)),
             )
             .arg(
@@ -83,6 +319,24 @@ impl Args {
                     .allow_negative_numbers(true)
                     .value_parser(value_parser!(i32)),
             )
+            .arg(
+                Arg::new("SPEED")
+                    .help("Speed of the pattern, instead of the period and the translations")
+                    .long_help(
+                        "Speed of the pattern, instead of the period and the translations\n\
+                         Supports c/N and Kc/N for orthogonal speeds, \
+                         with an optional suffix o (orthogonal) or d (diagonal), \
+                         and (a, b)c/N for the translation (a, b). \
+                         For example, --speed c/4d is the same as setting P DX DY to 4 1 1.",
+                    )
+                    .long("speed")
+                    .conflicts_with_all(["P", "DX", "DY"])
+                    .value_parser(|speed: &str| {
+                        Config::default()
+                            .set_speed(speed)
+                            .map(|config| (config.period, config.dx, config.dy))
+                    }),
+            )
             .arg(
                 Arg::new("DIAG")
                     .help("Diagonal width")
@@ -109,7 +363,8 @@ impl Args {
                          Supported values are Id, R90, R180, R270, F|, F-, F\\, F/.\n\
                          Id is the identical transformation.\n\
                          R means counterclockwise rotation.\n\
-                         F means flipping (reflection) across an axis.",
+                         F means flipping (reflection) across an axis.\n\
+                         Use --help-option transform to see what each value means.",
                     )
                     .short('t')
                     .long("transform")
@@ -125,7 +380,10 @@ impl Args {
                          The usages of these symmetries are the same as Oscar Cunningham's \
                          Logic Life Search.\n\
                          Supported values are C1, C2, C4, D2|, D2-, D2\\, D2/, D4+, D4X, D8.\n\
-                         See [https://conwaylife.com/wiki/Static_symmetry#Reflectional] ",
+                         The symmetry group can also be given as a list of transformations, \
+                         e.g., {Id,F\\} for D2\\.\n\
+                         See [https://conwaylife.com/wiki/Static_symmetry#Reflectional]\n\
+                         Use --help-option symmetry to see what each value means.",
                     )
                     .short('s')
                     .long("symmetry")
@@ -137,8 +395,9 @@ impl Args {
                     .help("Rule of the cellular automaton")
                     .long_help(
                         "Rule of the cellular automaton\n\
-                         Supports Life-like, isotropic non-totalistic, hexagonal, MAP rules, \
-                         and their corresponding Generations rules.",
+                         Supports Life-like, isotropic non-totalistic, hexagonal, von Neumann, MAP rules, \
+                         and their corresponding Generations rules.\n\
+                         Use --list-rules to see the supported formats with examples.",
                     )
                     .short('r')
                     .long("rule")
@@ -174,6 +433,19 @@ impl Args {
                     .value_parser(["dead", "alive", "random", "d", "a", "r"])
                     .default_value("alive"),
             )
+            .arg(
+                Arg::new("ALIVEPROB")
+                    .help("Probability of choosing a living cell when choosing randomly")
+                    .long_help(
+                        "Probability of choosing a living cell when choosing randomly\n\
+                         Should be strictly between 0 and 1. \
+                         Only used when --choose (-c) is random. \
+                         For Generations rules, the remaining probability is distributed \
+                         uniformly over the other states.",
+                    )
+                    .long("alive-probability")
+                    .value_parser(value_parser!(f64)),
+            )
             .arg(
                 Arg::new("MAX")
                     .help("Upper bound of numbers of minimum living cells in all generations")
@@ -219,16 +491,74 @@ impl Args {
                     .long("skip-subsym")
                     .action(ArgAction::SetTrue),
             )
+            .arg(
+                Arg::new("ALGORITHM")
+                    .help("The search algorithm")
+                    .long_help(
+                        "The search algorithm\n\
+                        auto currently always chooses lifesrc. \
+                        backjump is experimental and very slow, only \
+                        useful for large (e.g., 64x64) still lifes. \
+                        It does not support Generations rules and --max (-m). \
+                        prefer-backjump chooses backjump when it is supported, \
+                        and lifesrc otherwise.",
+                    )
+                    .long("algorithm")
+                    .value_parser(["auto", "lifesrc", "backjump", "prefer-backjump"])
+                    .default_value("auto"),
+            )
             .arg(
                 Arg::new("BACKJUMP")
                     .help("(Experimental) Enable backjumping")
                     .long_help(
                         "(Experimental) Enable backjumping\n\
-                        The current implementation of backjumping is very slow, only \
-                        useful for large (e.g., 64x64) still lifes.",
+                        The same as --algorithm prefer-backjump.",
                     )
                     .long("backjump")
+                    .conflicts_with("ALGORITHM")
+                    .hide(true)
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("MINPHASE")
+                    .help("Prints the results at the phase with the minimum population")
+                    .long_help(
+                        "Prints the results at the phase with the minimum population\n\
+                         With --all, prints all the generations of each result in RLE, \
+                         starting from that phase. \
+                         Only useful when the TUI is not used.",
+                    )
+                    .long("min-phase")
                     .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("OUTPUTFORMAT")
+                    .help("Format of the printed results")
+                    .long_help(
+                        "Format of the printed results\n\
+                         Only useful when the TUI is not used.",
+                    )
+                    .long("output-format")
+                    .value_parser(PossibleValuesParser::new(
+                        format::formatters()
+                            .into_iter()
+                            .map(|formatter| formatter.name()),
+                    ))
+                    .default_value("rle"),
+            )
+            .arg(
+                Arg::new("RESUMETOKEN")
+                    .help("Resumes a search with --all from a token printed with a result")
+                    .long_help(
+                        "Resumes a search with --all from a token printed with a result\n\
+                         With --all, a resume token is printed to stderr after each result. \
+                         Starting again with the same config and this token prints \
+                         the remaining results. The token is only valid for the identical \
+                         config and version of rlifesrc.",
+                    )
+                    .long("resume-token")
+                    .value_name("TOKEN")
+                    .requires("ALL"),
             );
 
         #[cfg(feature = "tui")]
@@ -259,6 +589,30 @@ impl Args {
                         .short('n')
                         .long("no-tui")
                         .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("AUTOSAVE")
+                        .help("Saves the search to a file periodically")
+                        .long_help(
+                            "Saves the search to a file periodically\n\
+                             The search is also saved when quitting the TUI. \
+                             If the file already exists when rlifesrc starts, \
+                             rlifesrc asks whether to resume the saved search. \
+                             It refuses to start if the file was saved with another config.",
+                        )
+                        .long("autosave")
+                        .value_name("PATH")
+                        .conflicts_with("NOTUI")
+                        .value_parser(value_parser!(PathBuf)),
+                )
+                .arg(
+                    Arg::new("AUTOSAVEINTERVAL")
+                        .help("Minutes between two autosaves")
+                        .long("autosave-interval")
+                        .value_name("MINUTES")
+                        .requires("AUTOSAVE")
+                        .default_value("5")
+                        .value_parser(value_parser!(u64).range(1..)),
                 );
         }
 
@@ -274,114 +628,562 @@ impl Args {
             );
         }
 
+        app
+    }
+
+    /// Builds the configuration from the command-line arguments,
+    /// when no config file is provided.
+    ///
+    /// This does not depend on the TUI, so that it can be tested.
+    fn config_from_matches(matches: &ArgMatches) -> Config {
+        let width = *matches.get_one("X").unwrap();
+        let height = *matches.get_one("Y").unwrap();
+        let (period, dx, dy) = match matches.get_one("SPEED") {
+            Some(&speed) => speed,
+            None => (
+                *matches.get_one("P").unwrap(),
+                *matches.get_one("DX").unwrap(),
+                *matches.get_one("DY").unwrap(),
+            ),
+        };
+
+        let transform = *matches.get_one("TRANSFORM").unwrap();
+        let symmetry = *matches.get_one("SYMMETRY").unwrap();
+
+        let search_order = match matches.get_one::<String>("ORDER").unwrap().as_str() {
+            "row" | "r" => Some(SearchOrder::RowFirst),
+            "column" | "c" => Some(SearchOrder::ColumnFirst),
+            "diagonal" | "d" => Some(SearchOrder::Diagonal),
+            _ => None,
+        };
+        let new_state = match matches.get_one::<String>("CHOOSE").unwrap().as_str() {
+            "dead" | "d" => NewState::ChooseDead,
+            "alive" | "a" => NewState::ChooseAlive,
+            "random" | "r" => match matches.get_one::<f64>("ALIVEPROB") {
+                Some(&alive_probability) => NewState::RandomBiased { alive_probability },
+                None => NewState::Random,
+            },
+            _ => NewState::ChooseAlive,
+        };
+        let max_cell_count = *matches.get_one("MAX").unwrap();
+        let max_cell_count = match max_cell_count {
+            0 => None,
+            i => Some(i),
+        };
+        let diagonal_width = *matches.get_one("DIAG").unwrap();
+        let diagonal_width = match diagonal_width {
+            0 => None,
+            i => Some(i),
+        };
+        let reduce_max = matches.get_flag("REDUCE");
+        let skip_subperiod = !matches.get_flag("SUBPERIOD");
+        let skip_subsymmetry = matches.get_flag("SKIPSUBSYM");
+        let algorithm = if matches.get_flag("BACKJUMP") {
+            AlgorithmChoice::PreferBackjump
+        } else {
+            match matches.get_one::<String>("ALGORITHM").unwrap().as_str() {
+                "lifesrc" => AlgorithmChoice::LifeSrc,
+                "backjump" => AlgorithmChoice::Backjump,
+                "prefer-backjump" => AlgorithmChoice::PreferBackjump,
+                _ => AlgorithmChoice::Auto,
+            }
+        };
+
+        let rule_string = matches.get_one::<String>("RULE").unwrap().to_string();
+
+        Config::new(width, height, period)
+            .set_translate(dx, dy)
+            .set_transform(transform)
+            .set_symmetry(symmetry)
+            .set_search_order(search_order)
+            .set_new_state(new_state)
+            .set_max_cell_count(max_cell_count)
+            .set_reduce_max(reduce_max)
+            .set_rule_string(rule_string)
+            .set_diagonal_width(diagonal_width)
+            .set_skip_subperiod(skip_subperiod)
+            .set_skip_subsymmetry(skip_subsymmetry)
+            .set_algorithm(algorithm)
+    }
+
+    /// Parses the command-line arguments.
+    pub(crate) fn parse() -> Result<Mode> {
+        let mut app = Self::command();
         let matches = app.clone().try_get_matches()?;
 
-        let config;
+        if matches.get_flag("STDINJSON") {
+            return Ok(Mode::StdinJson);
+        }
+
+        if matches.get_flag("LISTRULES") {
+            return Ok(Mode::ListRules);
+        }
+
+        if let Some(option) = matches.get_one::<String>("HELPOPTION") {
+            let variants = match option.as_str() {
+                "transform" => docs::TRANSFORM,
+                "symmetry" => docs::SYMMETRY,
+                "order" => docs::SEARCH_ORDER,
+                _ => docs::NEW_STATE,
+            };
+            return Ok(Mode::HelpOption(variants));
+        }
+
+        if let Some(matches) = matches.subcommand_matches("analyze") {
+            let path = matches.get_one::<PathBuf>("CONFIG").unwrap();
+            let config = read_config(path).map_err(|e| app.error(ErrorKind::Io, e))?;
+            let pattern = matches.get_one::<PathBuf>("PATTERN").unwrap().clone();
+            let max_period = *matches.get_one("MAXPERIOD").unwrap();
+            return Ok(Mode::Analyze {
+                config: Box::new(config),
+                pattern,
+                max_period,
+            });
+        }
+
+        let mut config;
 
         if let Some(path) = matches.get_one::<PathBuf>("CONFIG") {
-            let file = File::open(path).map_err(|e| app.error(ErrorKind::Io, e))?;
-            let mut reader = BufReader::new(file);
-            match path.extension().and_then(|s| s.to_str()) {
-                Some("json") => {
-                    config = serde_json::from_reader(reader).map_err(|e| {
-                        app.error(ErrorKind::Io, format!("Invalid config file: {}", e))
-                    })?;
-                }
-                Some("yaml" | "yml") => {
-                    config = serde_yaml::from_reader(reader).map_err(|e| {
-                        app.error(ErrorKind::Io, format!("Invalid config file: {}", e))
-                    })?;
-                }
-                Some("toml") => {
-                    let mut buf = String::new();
-                    reader.read_to_string(&mut buf).map_err(|e| {
-                        app.error(ErrorKind::Io, format!("Invalid config file: {}", e))
-                    })?;
-                    config = toml::from_str(&buf).map_err(|e| {
-                        app.error(ErrorKind::Io, format!("Invalid config file: {}", e))
-                    })?;
-                }
-                _ => return Err(app.error(ErrorKind::Io, "Unsupported config file format")),
-            }
+            config = read_config(path).map_err(|e| app.error(ErrorKind::Io, e))?;
         } else {
-            let width = *matches.get_one("X").unwrap();
-            let height = *matches.get_one("Y").unwrap();
-            let period = *matches.get_one("P").unwrap();
+            config = Self::config_from_matches(&matches);
+        }
 
-            let dx = *matches.get_one("DX").unwrap();
-            let dy = *matches.get_one("DY").unwrap();
+        if let Some(spec) = matches.get_one::<String>("SPEC") {
+            config
+                .apply_spec(spec)
+                .map_err(|e| app.error(ErrorKind::InvalidValue, e))?;
+        }
 
-            let transform = *matches.get_one("TRANSFORM").unwrap();
-            let symmetry = *matches.get_one("SYMMETRY").unwrap();
+        if matches.get_flag("PROBE") {
+            return Ok(Mode::Probe {
+                config: Box::new(config),
+                budget_steps: *matches.get_one("PROBESTEPS").unwrap(),
+                probes: *matches.get_one("PROBES").unwrap(),
+                seed: *matches.get_one("PROBESEED").unwrap(),
+            });
+        }
 
-            let search_order = match matches.get_one::<String>("ORDER").unwrap().as_str() {
-                "row" | "r" => Some(SearchOrder::RowFirst),
-                "column" | "c" => Some(SearchOrder::ColumnFirst),
-                "diagonal" | "d" => Some(SearchOrder::Diagonal),
-                _ => None,
-            };
-            let new_state = match matches.get_one::<String>("CHOOSE").unwrap().as_str() {
-                "dead" | "d" => NewState::ChooseDead,
-                "alive" | "a" => NewState::ChooseAlive,
-                "random" | "r" => NewState::Random,
-                _ => NewState::ChooseAlive,
-            };
-            let max_cell_count = *matches.get_one("MAX").unwrap();
-            let max_cell_count = match max_cell_count {
-                0 => None,
-                i => Some(i),
-            };
-            let diagonal_width = *matches.get_one("DIAG").unwrap();
-            let diagonal_width = match diagonal_width {
-                0 => None,
-                i => Some(i),
-            };
-            let reduce_max = matches.get_flag("REDUCE");
-            let skip_subperiod = !matches.get_flag("SUBPERIOD");
-            let skip_subsymmetry = matches.get_flag("SKIPSUBSYM");
-            let backjump = matches.get_flag("BACKJUMP");
-
-            let rule_string = matches.get_one::<String>("RULE").unwrap().to_string();
-
-            config = Config::new(width, height, period)
-                .set_translate(dx, dy)
-                .set_transform(transform)
-                .set_symmetry(symmetry)
-                .set_search_order(search_order)
-                .set_new_state(new_state)
-                .set_max_cell_count(max_cell_count)
-                .set_reduce_max(reduce_max)
-                .set_rule_string(rule_string)
-                .set_diagonal_width(diagonal_width)
-                .set_skip_subperiod(skip_subperiod)
-                .set_skip_subsymmetry(skip_subsymmetry)
-                .set_backjump(backjump);
+        if let Some(path) = matches.get_one::<PathBuf>("RULESFILE") {
+            let rules = read_rules(path).map_err(|e| app.error(ErrorKind::Io, e))?;
+            return Ok(Mode::RuleSweep {
+                config: Box::new(config),
+                rules,
+                budget_per_rule: matches.get_one("RULESTEPS").copied(),
+            });
+        }
+
+        if let Some(&depth) = matches.get_one::<u32>("SPLIT") {
+            return Ok(Mode::Split {
+                config: Box::new(config),
+                depth,
+                output: matches.get_one::<PathBuf>("SPLITOUTPUT").cloned(),
+            });
         }
 
         let all = matches.get_flag("ALL");
+        let min_phase = matches.get_flag("MINPHASE");
+        let output_format =
+            format::by_name(matches.get_one::<String>("OUTPUTFORMAT").unwrap()).unwrap();
         #[cfg(feature = "tui")]
         let reset = matches.get_flag("RESET");
         #[cfg(feature = "tui")]
         let no_tui = matches.get_flag("NOTUI");
+        #[cfg(feature = "tui")]
+        let autosave = matches.get_one::<PathBuf>("AUTOSAVE").map(|path| {
+            let minutes = *matches.get_one::<u64>("AUTOSAVEINTERVAL").unwrap();
+            Autosave::new(path.clone(), Duration::from_secs(minutes * 60))
+        });
 
-        let world = config
-            .world()
-            .map_err(|e| app.error(ErrorKind::InvalidValue, format!("Invalid config: {}", e)))?;
-
-        if world.is_gen_rule() && config.backjump {
-            return Err(app.error(
-                ErrorKind::InvalidValue,
-                "Backjumping is not yet supported for Generations rules.",
-            ));
+        let world = match matches.get_one::<String>("RESUMETOKEN") {
+            Some(token) => config.world_at_token(token),
+            None => config.world(),
         }
+        .map_err(|e| app.error(ErrorKind::InvalidValue, format!("Invalid config: {}", e)))?;
 
-        Ok(Self {
+        Ok(Mode::Search(Box::new(Self {
             world,
             all,
+            min_phase,
+            output_format,
             #[cfg(feature = "tui")]
             reset,
             #[cfg(feature = "tui")]
             no_tui,
-        })
+            #[cfg(feature = "tui")]
+            autosave,
+        })))
+    }
+}
+
+/// Reads a list of rule strings, one on each line.
+///
+/// Empty lines and lines starting with `#` are skipped.
+fn read_rules(path: &Path) -> Result<Vec<String>, String> {
+    let mut buf = String::new();
+    File::open(path)
+        .and_then(|mut file| file.read_to_string(&mut buf))
+        .map_err(|e| e.to_string())?;
+    Ok(buf
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(String::from)
+        .collect())
+}
+
+/// Reads the config from a JSON, YAML or TOML file.
+fn read_config(path: &Path) -> Result<Config, String> {
+    let file = File::open(path).map_err(|e| e.to_string())?;
+    let mut reader = BufReader::new(file);
+    match path.extension().and_then(|s| s.to_str()) {
+        Some("json") => serde_json::from_reader(reader).map_err(|e| e.to_string()),
+        Some("yaml" | "yml") => serde_yaml::from_reader(reader).map_err(|e| e.to_string()),
+        Some("toml") => {
+            let mut buf = String::new();
+            reader.read_to_string(&mut buf).map_err(|e| e.to_string())?;
+            toml::from_str(&buf).map_err(|e| e.to_string())
+        }
+        _ => return Err(String::from("Unsupported config file format")),
+    }
+    .map_err(|e| format!("Invalid config file: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rlifesrc_lib::Status;
+
+    /// Builds the configuration from a list of command-line arguments.
+    fn config_from_args(args: &[&str]) -> Config {
+        let matches = Args::command()
+            .try_get_matches_from(std::iter::once("rlifesrc").chain(args.iter().copied()))
+            .unwrap();
+        Args::config_from_matches(&matches)
+    }
+
+    #[test]
+    fn default_config() {
+        assert_eq!(config_from_args(&["16", "16"]), Config::default());
+    }
+
+    #[test]
+    fn spaceship_config() {
+        let config = config_from_args(&["16", "5", "3", "0", "1"]);
+        assert_eq!(config, Config::new(16, 5, 3).set_translate(0, 1));
+        let mut world = config.world().unwrap();
+        assert_eq!(world.search(None), Status::Found);
+        assert_eq!(
+            world.rle_gen(0),
+            "x = 16, y = 5, rule = B3/S23\n\
+             ........o.......$\n\
+             .oo.ooo.ooo.....$\n\
+             .oo....o..oo.oo.$\n\
+             o..o.oo...o..oo.$\n\
+             ............o..o!\n"
+        );
+    }
+
+    #[test]
+    fn speed() {
+        let config = config_from_args(&["16", "5", "--speed", "c/3"]);
+        assert_eq!(config, Config::new(16, 5, 3).set_translate(0, 1));
+        let config = config_from_args(&["16", "16", "--speed", "(2, -1)c/6"]);
+        assert_eq!(config, Config::new(16, 16, 6).set_translate(2, -1));
+        for args in [
+            &["rlifesrc", "16", "16", "--speed", "2c/1"][..],
+            &["rlifesrc", "16", "16", "--speed", "c/3x"],
+            &["rlifesrc", "16", "16", "3", "--speed", "c/3"],
+        ] {
+            assert!(Args::command().try_get_matches_from(args).is_err());
+        }
+    }
+
+    #[test]
+    fn alive_probability() {
+        let config = config_from_args(&["16", "16", "-c", "r", "--alive-probability", "0.3"]);
+        assert_eq!(
+            config.new_state,
+            NewState::RandomBiased {
+                alive_probability: 0.3
+            }
+        );
+        let config = config_from_args(&["16", "16", "-c", "r"]);
+        assert_eq!(config.new_state, NewState::Random);
+        let config = config_from_args(&["16", "16", "--alive-probability", "0.3"]);
+        assert_eq!(config.new_state, NewState::ChooseAlive);
+    }
+
+    #[test]
+    fn algorithm() {
+        let config = config_from_args(&["16", "16"]);
+        assert_eq!(config.algorithm, AlgorithmChoice::Auto);
+        let config = config_from_args(&["16", "16", "--algorithm", "lifesrc"]);
+        assert_eq!(config.algorithm, AlgorithmChoice::LifeSrc);
+        let config = config_from_args(&["16", "16", "--algorithm", "backjump"]);
+        assert_eq!(config.algorithm, AlgorithmChoice::Backjump);
+        let config = config_from_args(&["16", "16", "--algorithm", "prefer-backjump"]);
+        assert_eq!(config.algorithm, AlgorithmChoice::PreferBackjump);
+        let config = config_from_args(&["16", "16", "--backjump"]);
+        assert_eq!(config.algorithm, AlgorithmChoice::PreferBackjump);
+        assert!(Args::command()
+            .try_get_matches_from([
+                "rlifesrc",
+                "16",
+                "16",
+                "--algorithm",
+                "lifesrc",
+                "--backjump"
+            ])
+            .is_err());
+    }
+
+    #[test]
+    fn output_format() {
+        let matches = Args::command()
+            .try_get_matches_from(["rlifesrc", "16", "16"])
+            .unwrap();
+        assert_eq!(
+            matches
+                .get_one::<String>("OUTPUTFORMAT")
+                .map(String::as_str),
+            Some("rle")
+        );
+        let matches = Args::command()
+            .try_get_matches_from(["rlifesrc", "16", "16", "--output-format", "plaintext"])
+            .unwrap();
+        assert_eq!(
+            matches
+                .get_one::<String>("OUTPUTFORMAT")
+                .map(String::as_str),
+            Some("plaintext")
+        );
+        assert!(Args::command()
+            .try_get_matches_from(["rlifesrc", "16", "16", "--output-format", "mc"])
+            .is_err());
+    }
+
+    #[test]
+    fn spec() {
+        let matches = Args::command()
+            .try_get_matches_from(["rlifesrc", "16", "16", "--spec", "p=2, dy=1, sym=D2|"])
+            .unwrap();
+        let mut config = Args::config_from_matches(&matches);
+        config
+            .apply_spec(matches.get_one::<String>("SPEC").unwrap())
+            .unwrap();
+        assert_eq!(
+            config,
+            Config::new(16, 16, 2)
+                .set_translate(0, 1)
+                .set_symmetry(Symmetry::D2Col)
+        );
+    }
+
+    #[test]
+    fn probe() {
+        let matches = Args::command()
+            .try_get_matches_from(["rlifesrc", "16", "16", "--probe", "--probes", "4"])
+            .unwrap();
+        assert!(matches.get_flag("PROBE"));
+        assert_eq!(matches.get_one::<u64>("PROBESTEPS"), Some(&10000));
+        assert_eq!(matches.get_one::<u32>("PROBES"), Some(&4));
+        assert_eq!(matches.get_one::<u64>("PROBESEED"), Some(&0));
+        assert!(Args::command()
+            .try_get_matches_from(["rlifesrc", "16", "16", "--probes", "4"])
+            .is_err());
+        assert!(Args::command()
+            .try_get_matches_from(["rlifesrc", "16", "16", "--probe", "--probes", "0"])
+            .is_err());
+    }
+
+    #[test]
+    fn split() {
+        let matches = Args::command()
+            .try_get_matches_from(["rlifesrc", "16", "16", "--split", "3"])
+            .unwrap();
+        assert_eq!(matches.get_one::<u32>("SPLIT"), Some(&3));
+        assert_eq!(matches.get_one::<PathBuf>("SPLITOUTPUT"), None);
+        let matches = Args::command()
+            .try_get_matches_from([
+                "rlifesrc",
+                "16",
+                "16",
+                "--split",
+                "3",
+                "--split-output",
+                "units.json",
+            ])
+            .unwrap();
+        assert_eq!(
+            matches.get_one::<PathBuf>("SPLITOUTPUT"),
+            Some(&PathBuf::from("units.json"))
+        );
+        assert!(Args::command()
+            .try_get_matches_from(["rlifesrc", "16", "16", "--split-output", "units.json"])
+            .is_err());
+        assert!(Args::command()
+            .try_get_matches_from(["rlifesrc", "16", "16", "--split", "3", "--probe"])
+            .is_err());
+    }
+
+    #[test]
+    fn rules_file() {
+        let matches = Args::command()
+            .try_get_matches_from(["rlifesrc", "16", "16", "--rules-file", "rules.txt"])
+            .unwrap();
+        assert_eq!(
+            matches.get_one::<PathBuf>("RULESFILE"),
+            Some(&PathBuf::from("rules.txt"))
+        );
+        assert_eq!(matches.get_one::<u64>("RULESTEPS"), None);
+        let matches = Args::command()
+            .try_get_matches_from([
+                "rlifesrc",
+                "16",
+                "16",
+                "--rules-file",
+                "rules.txt",
+                "--rule-steps",
+                "1000",
+            ])
+            .unwrap();
+        assert_eq!(matches.get_one::<u64>("RULESTEPS"), Some(&1000));
+        assert!(Args::command()
+            .try_get_matches_from(["rlifesrc", "16", "16", "--rule-steps", "1000"])
+            .is_err());
+        assert!(Args::command()
+            .try_get_matches_from(["rlifesrc", "16", "16", "--rules-file", "r", "--probe"])
+            .is_err());
+    }
+
+    #[cfg(feature = "tui")]
+    #[test]
+    fn autosave() {
+        let matches = Args::command()
+            .try_get_matches_from(["rlifesrc", "16", "16", "--autosave", "search.json"])
+            .unwrap();
+        assert_eq!(
+            matches.get_one::<PathBuf>("AUTOSAVE"),
+            Some(&PathBuf::from("search.json"))
+        );
+        assert_eq!(matches.get_one::<u64>("AUTOSAVEINTERVAL"), Some(&5));
+        let matches = Args::command()
+            .try_get_matches_from([
+                "rlifesrc",
+                "16",
+                "16",
+                "--autosave",
+                "search.json",
+                "--autosave-interval",
+                "10",
+            ])
+            .unwrap();
+        assert_eq!(matches.get_one::<u64>("AUTOSAVEINTERVAL"), Some(&10));
+        assert!(Args::command()
+            .try_get_matches_from(["rlifesrc", "16", "16", "--autosave-interval", "10"])
+            .is_err());
+        assert!(Args::command()
+            .try_get_matches_from(["rlifesrc", "16", "16", "--autosave", "s.json", "-n"])
+            .is_err());
+        assert!(Args::command()
+            .try_get_matches_from([
+                "rlifesrc",
+                "16",
+                "16",
+                "--autosave",
+                "s.json",
+                "--autosave-interval",
+                "0",
+            ])
+            .is_err());
+    }
+
+    #[cfg(feature = "tui")]
+    #[test]
+    fn resume_token() {
+        let config = Config::new(5, 5, 2);
+        let mut world = config.world().unwrap();
+        assert_eq!(world.search(None), Status::Found);
+        let token = world.resume_token().unwrap();
+        let args = [
+            "rlifesrc",
+            "5",
+            "5",
+            "2",
+            "-a",
+            "-n",
+            "--resume-token",
+            &token,
+        ];
+        let matches = Args::command().try_get_matches_from(args).unwrap();
+        assert_eq!(Args::config_from_matches(&matches), config);
+        assert_eq!(matches.get_one::<String>("RESUMETOKEN"), Some(&token));
+        assert!(config.world_at_token(&token).is_ok());
+        assert!(Args::command()
+            .try_get_matches_from(["rlifesrc", "5", "5", "2", "-n", "--resume-token", &token])
+            .is_err());
+    }
+
+    #[test]
+    fn list_rules() {
+        let matches = Args::command()
+            .try_get_matches_from(["rlifesrc", "--list-rules"])
+            .unwrap();
+        assert!(matches.get_flag("LISTRULES"));
+    }
+
+    #[test]
+    fn help_option() {
+        let matches = Args::command()
+            .try_get_matches_from(["rlifesrc", "--help-option", "symmetry"])
+            .unwrap();
+        assert_eq!(
+            matches.get_one::<String>("HELPOPTION").map(String::as_str),
+            Some("symmetry")
+        );
+        assert!(Args::command()
+            .try_get_matches_from(["rlifesrc", "--help-option", "rule"])
+            .is_err());
+        assert!(Args::command()
+            .try_get_matches_from(["rlifesrc", "--help-option", "order", "--list-rules"])
+            .is_err());
+    }
+
+    #[test]
+    fn analyze() {
+        let matches = Args::command()
+            .try_get_matches_from(["rlifesrc", "analyze", "-C", "cfg.json", "result.rle"])
+            .unwrap();
+        let matches = matches.subcommand_matches("analyze").unwrap();
+        assert_eq!(
+            matches.get_one::<PathBuf>("CONFIG").unwrap(),
+            &PathBuf::from("cfg.json")
+        );
+        assert_eq!(
+            matches.get_one::<PathBuf>("PATTERN").unwrap(),
+            &PathBuf::from("result.rle")
+        );
+        assert_eq!(matches.get_one::<i32>("MAXPERIOD"), Some(&100));
+        assert!(Args::command()
+            .try_get_matches_from(["rlifesrc", "analyze", "result.rle"])
+            .is_err());
+    }
+
+    #[test]
+    fn full_config() {
+        let config = config_from_args(&[
+            "10", "10", "4", "1", "1", "-s", "C2", "-o", "d", "-c", "d", "-m", "20", "-R",
+        ]);
+        assert_eq!(
+            config,
+            Config::new(10, 10, 4)
+                .set_translate(1, 1)
+                .set_symmetry(Symmetry::C2)
+                .set_search_order(SearchOrder::Diagonal)
+                .set_new_state(NewState::ChooseDead)
+                .set_max_cell_count(20)
+                .set_reduce_max(true)
+        );
     }
 }
@@ -0,0 +1,166 @@
+//! Saving the search periodically, so that it can be resumed after a crash.
+
+use rlifesrc_lib::{save::WorldSer, Config, PolyWorld};
+use std::{
+    ffi::OsString,
+    fs::{self, File},
+    io::{self, stdin, stdout, BufRead, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+/// Where and how often to save the search.
+pub struct Autosave {
+    path: PathBuf,
+    interval: Duration,
+    last_save: Instant,
+}
+
+impl Autosave {
+    /// Saves the search to `path` every `interval`.
+    pub fn new(path: PathBuf, interval: Duration) -> Self {
+        Autosave {
+            path,
+            interval,
+            last_save: Instant::now(),
+        }
+    }
+
+    /// The path of the save file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Whether the interval has passed since the last save.
+    pub fn is_due(&self) -> bool {
+        self.last_save.elapsed() >= self.interval
+    }
+
+    /// Saves the world and the time used in searching.
+    ///
+    /// The world is first written to a temporary file next to the save file,
+    /// which is then renamed to the save file. So a crash during the saving
+    /// never leaves a broken save file.
+    pub fn save(&mut self, world: &PolyWorld, timing: Duration) -> io::Result<()> {
+        let mut ser = world.ser();
+        ser.timing = Some(timing);
+        let tmp_path = self.tmp_path();
+        {
+            let file = File::create(&tmp_path)?;
+            let mut writer = BufWriter::new(file);
+            serde_json::to_writer(&mut writer, &ser)?;
+            writer.flush()?;
+            writer.get_ref().sync_all()?;
+        }
+        fs::rename(&tmp_path, &self.path)?;
+        self.last_save = Instant::now();
+        Ok(())
+    }
+
+    /// Loads the saved search, if the save file exists.
+    ///
+    /// Returns an error if the save file is invalid, or was saved with a config
+    /// other than `config`.
+    pub fn load(&self, config: &Config) -> Result<Option<WorldSer>, String> {
+        let file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.to_string()),
+        };
+        let ser: WorldSer = serde_json::from_reader(BufReader::new(file))
+            .map_err(|e| format!("Invalid autosave file: {}", e))?;
+        if &ser.config != config {
+            return Err(format!(
+                "The autosave file {} was saved with another config. \
+                 Please remove it, or choose another path.",
+                self.path.display()
+            ));
+        }
+        Ok(Some(ser))
+    }
+
+    /// The path of the temporary file.
+    fn tmp_path(&self) -> PathBuf {
+        let mut path = OsString::from(self.path.as_os_str());
+        path.push(".tmp");
+        PathBuf::from(path)
+    }
+}
+
+/// Asks whether to resume the saved search. The default is yes.
+pub fn ask_resume(path: &Path) -> io::Result<bool> {
+    print!(
+        "Found an autosave file {} with the same config. Resume the search? [Y/n] ",
+        path.display()
+    );
+    stdout().flush()?;
+    let mut answer = String::new();
+    stdin().lock().read_line(&mut answer)?;
+    Ok(!matches!(answer.trim(), "n" | "N" | "no" | "No"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rlifesrc_lib::Status;
+
+    /// A new empty directory for the test.
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rlifesrc-{}-{}", name, std::process::id()));
+        fs::remove_dir_all(&dir).ok();
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn save_and_load() {
+        let dir = temp_dir("autosave");
+        let path = dir.join("search.json");
+        let mut autosave = Autosave::new(path.clone(), Duration::from_secs(60));
+        assert!(!autosave.is_due());
+
+        let config = Config::new(16, 5, 3).set_translate(0, 1);
+        let mut world = config.world().unwrap();
+        assert_eq!(autosave.load(&config), Ok(None));
+        assert_eq!(world.search(Some(1000)), Status::Searching);
+        autosave.save(&world, Duration::from_secs(7)).unwrap();
+        assert!(path.exists());
+        assert!(!autosave.tmp_path().exists());
+
+        let ser = autosave.load(&config).unwrap().unwrap();
+        assert_eq!(ser.timing, Some(Duration::from_secs(7)));
+        assert_eq!(ser.steps, world.steps());
+        let mut resumed = ser.world().unwrap();
+        assert_eq!(resumed.search(None), world.search(None));
+        assert_eq!(resumed.rle_gen(0), world.rle_gen(0));
+
+        // A second save replaces the first one.
+        autosave.save(&world, Duration::from_secs(8)).unwrap();
+        let ser = autosave.load(&config).unwrap().unwrap();
+        assert_eq!(ser.timing, Some(Duration::from_secs(8)));
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn load_other_config() {
+        let dir = temp_dir("autosave-mismatch");
+        let path = dir.join("search.json");
+        let mut autosave = Autosave::new(path.clone(), Duration::ZERO);
+        assert!(autosave.is_due());
+
+        let config = Config::new(16, 5, 3).set_translate(0, 1);
+        let world = config.world().unwrap();
+        autosave.save(&world, Duration::default()).unwrap();
+        let other = Config {
+            width: 17,
+            ..config.clone()
+        };
+        assert!(autosave.load(&other).is_err());
+        assert!(autosave.load(&config).unwrap().is_some());
+
+        fs::write(&path, "not json").unwrap();
+        assert!(autosave.load(&config).is_err());
+        fs::remove_dir_all(&dir).ok();
+    }
+}
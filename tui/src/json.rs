@@ -0,0 +1,117 @@
+//! A one-shot mode that reads a JSON config from stdin,
+//! and writes the results to stdout as JSON.
+
+use rlifesrc_lib::{Config, PolyWorld, Status};
+use serde::{Deserialize, Serialize};
+use std::io::{stdin, stdout};
+
+/// The input, either a bare [`Config`], or a [`Request`] wrapping it.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Input {
+    Request(Request),
+    Config(Config),
+}
+
+/// A config with some extra options.
+#[derive(Deserialize)]
+struct Request {
+    /// World configuration.
+    config: Config,
+    /// The maximum number of steps of the whole search.
+    #[serde(default)]
+    max_step: Option<u64>,
+    /// Whether to search for all possible results instead of only the first one.
+    #[serde(default)]
+    all: bool,
+}
+
+/// The output written to stdout.
+#[derive(Serialize)]
+struct Response {
+    /// The status when the search stops.
+    status: Status,
+    /// The results found, each displayed as a list of RLEs, one for each generation.
+    results: Vec<Vec<String>>,
+    /// Number of steps walked during the search.
+    steps: u64,
+    /// Number of conflicts during the search.
+    conflicts: u64,
+}
+
+/// The output written to stderr when there is an error.
+#[derive(Serialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+/// Displays all the generations of the current result.
+fn result(world: &PolyWorld) -> Vec<String> {
+    (0..world.config().period)
+        .map(|t| world.rle_gen(t))
+        .collect()
+}
+
+/// Runs the search.
+///
+/// When `all` is true, keeps searching until there are no more results,
+/// or the total number of steps reaches `max_step`.
+fn search(world: &mut PolyWorld, max_step: Option<u64>, all: bool) -> Response {
+    let mut results = Vec::new();
+    let status = loop {
+        let max_step = match max_step {
+            Some(max) if world.steps() >= max => break Status::Searching,
+            max_step => max_step.map(|max| max - world.steps()),
+        };
+        let status = world.search(max_step);
+        if status == Status::Found {
+            results.push(result(world));
+            if all {
+                continue;
+            }
+        }
+        break status;
+    };
+    Response {
+        status,
+        results,
+        steps: world.steps(),
+        conflicts: world.conflicts(),
+    }
+}
+
+/// Reads the input from stdin and writes the output to stdout.
+///
+/// Errors are written to stderr. Returns the exit code.
+pub(crate) fn run() -> i32 {
+    let response = serde_json::from_reader(stdin().lock())
+        .map_err(|e| format!("Invalid input: {}", e))
+        .and_then(|input| {
+            let (config, max_step, all) = match input {
+                Input::Request(Request {
+                    config,
+                    max_step,
+                    all,
+                }) => (config, max_step, all),
+                Input::Config(config) => (config, None, false),
+            };
+            let mut world = config
+                .world()
+                .map_err(|e| format!("Invalid config: {}", e))?;
+            Ok(search(&mut world, max_step, all))
+        });
+    match response {
+        Ok(response) => {
+            serde_json::to_writer(stdout().lock(), &response).unwrap();
+            println!();
+            0
+        }
+        Err(error) => {
+            eprintln!(
+                "{}",
+                serde_json::to_string(&ErrorResponse { error }).unwrap()
+            );
+            1
+        }
+    }
+}
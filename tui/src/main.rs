@@ -1,54 +1,361 @@
+mod analyze;
 mod args;
+mod json;
 
+#[cfg(feature = "tui")]
+mod autosave;
 #[cfg(feature = "tui")]
 mod tui;
+#[cfg(feature = "tui")]
+mod viewport;
+
+use args::{Args, Mode};
+use rlifesrc_lib::{
+    docs::VariantDoc, format::PatternFormatter, rules::supported_formats, Config, PolyWorld, Status,
+};
+use serde::Serialize;
+use std::{fs, path::Path, process::exit};
+
+#[cfg(feature = "tui")]
+use std::{
+    io::{stdout, IsTerminal},
+    time::Duration,
+};
+
+/// Prints the result in the given format.
+///
+/// If `min_phase` is true, it will print the generation with the minimum population
+/// instead of the generation 0. If `all` is also true, it will print all the generations
+/// in a period in RLE, starting from that generation, each preceded by a `#C gen` line.
+fn print_result(
+    world: &PolyWorld,
+    all: bool,
+    min_phase: bool,
+    output_format: &dyn PatternFormatter,
+) {
+    if all && min_phase {
+        let offset = world.min_population_gen();
+        for (t, rle) in (offset..).zip(world.rle_all_gens_min_phase()) {
+            println!("#C gen {}", t);
+            print!("{}", rle);
+        }
+        println!();
+        return;
+    }
+    let gen = if min_phase {
+        world.min_population_gen()
+    } else {
+        0
+    };
+    match output_format.format(&world.snapshot(), gen) {
+        Ok(result) => println!("{}", result),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    }
+}
+
+/// Prints the supported formats of rule strings, one on each line.
+fn list_rules() {
+    for format in supported_formats() {
+        println!("{}", format);
+    }
+}
 
-use args::Args;
-use rlifesrc_lib::{PolyWorld, Status};
-use std::process::exit;
+/// Prints the name of each choice of an option, followed by its explanation.
+fn help_option(variants: &[VariantDoc]) {
+    for (i, variant) in variants.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        println!("{}", variant.name);
+        // The fences of the code blocks are not shown.
+        for line in variant.doc.lines().filter(|line| !line.starts_with("```")) {
+            if line.is_empty() {
+                println!();
+            } else {
+                println!("    {}", line);
+            }
+        }
+    }
+}
+
+/// Prints a difficulty estimate of the configuration, and returns the exit code.
+fn run_probe(config: &Config, budget_steps: u64, probes: u32, seed: u64) -> i32 {
+    let report = match config.probe(budget_steps, probes, seed) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("Error: Invalid config: {}", e);
+            return 1;
+        }
+    };
+    for (i, run) in report.runs.iter().enumerate() {
+        println!(
+            "Probe {}: depth {:.3}, {} steps, {} conflicts, {:?}",
+            i + 1,
+            run.depth,
+            run.steps,
+            run.conflicts,
+            run.status
+        );
+    }
+    println!("Mean depth: {:.3}", report.mean_depth);
+    println!("Conflict rate: {:.3}", report.conflict_rate);
+    println!("Success fraction: {:.3}", report.success_fraction);
+    println!("Difficulty score: {:.3}", report.score);
+    0
+}
+
+/// Searches with the configuration in each of the rules, prints a summary,
+/// and returns the exit code.
+fn run_rule_sweep(config: &Config, rules: &[String], budget_per_rule: Option<u64>) -> i32 {
+    let outcomes = config.search_over_rules(rules, budget_per_rule);
+    let width = rules.iter().map(String::len).max().unwrap_or(0).max(4);
+    println!("{:1$}  Status", "Rule", width);
+    for outcome in &outcomes {
+        let status = match &outcome.status {
+            Ok(Status::Found) => String::from("Found"),
+            Ok(Status::None) => String::from("None"),
+            Ok(_) => String::from("Unknown (out of steps)"),
+            Err(e) => format!("Error: {}", e),
+        };
+        println!("{:1$}  {2}", outcome.rule_string, width, status);
+    }
+    let found = outcomes.iter().filter(|outcome| outcome.is_found()).count();
+    println!("Found in {} of {} rules.", found, outcomes.len());
+    if found > 0 {
+        0
+    } else {
+        1
+    }
+}
+
+/// A work unit, as written by `--split`.
+#[derive(Serialize)]
+struct WorkUnitJson {
+    index: usize,
+    count: usize,
+    config: Config,
+}
+
+/// Splits the search into work units, writes them as JSON to `output`
+/// or to the standard output, and returns the exit code.
+fn run_split(config: &Config, depth: u32, output: Option<&Path>) -> i32 {
+    let units = match config.split(depth) {
+        Ok(units) => units,
+        Err(e) => {
+            eprintln!("Error: Invalid config: {}", e);
+            return 1;
+        }
+    };
+    let units = units
+        .iter()
+        .map(|unit| WorkUnitJson {
+            index: unit.index,
+            count: unit.count,
+            config: unit.config(),
+        })
+        .collect::<Vec<_>>();
+    let json = serde_json::to_string_pretty(&units).unwrap();
+    match output {
+        Some(path) => {
+            if let Err(e) = fs::write(path, json) {
+                eprintln!("Error: {}", e);
+                return 1;
+            }
+        }
+        None => println!("{}", json),
+    }
+    eprintln!("Split into {} work units.", units.len());
+    0
+}
+
+/// Prints a warning if the configuration is obviously hopeless.
+fn warn_infeasible(world: &PolyWorld) {
+    if let Some(hint) = world.config().infeasibility_check() {
+        eprintln!("Warning: {}", hint);
+    }
+}
 
 /// Runs the search without TUI.
 ///
 /// If `all` is true, it will print all possible results
-/// instead of only the first one.
-fn run_search(world: &mut PolyWorld, all: bool) {
+/// instead of only the first one, each followed by a resume token.
+fn run_search(
+    world: &mut PolyWorld,
+    all: bool,
+    min_phase: bool,
+    output_format: &dyn PatternFormatter,
+) {
     if all {
         let mut found = false;
         loop {
             match world.search(None) {
                 Status::Found => {
                     found = true;
-                    println!("{}", world.rle_gen(0));
+                    print_result(world, true, min_phase, output_format);
+                    if let Some(token) = world.resume_token() {
+                        eprintln!("Resume token: {}", token);
+                    }
                 }
                 Status::None => break,
                 _ => (),
             }
         }
         if !found {
-            eprintln!("Not found.");
-            exit(1);
+            exit_not_found(world);
         }
     } else if world.search(None) == Status::Found {
-        println!("{}", world.rle_gen(0));
+        print_result(world, false, min_phase, output_format);
     } else {
-        eprintln!("Not found.");
-        exit(1);
+        exit_not_found(world);
     }
 }
 
+/// Prints that no result is found, with a hint explaining why, and exits.
+fn exit_not_found(world: &PolyWorld) -> ! {
+    eprintln!("Not found.");
+    if let Some(hint) = world.failure_hint() {
+        eprintln!("Hint: {}", hint);
+    }
+    if let Some(note) = border_note(world.border_conflict_summary()) {
+        eprintln!("{}", note);
+    }
+    exit(1);
+}
+
+/// Describes the edges of the world with the most conflicts,
+/// given the [border conflict summary](PolyWorld::border_conflict_summary).
+///
+/// Returns `None` if there is no conflict near the edges.
+fn border_note(summary: [u64; 4]) -> Option<String> {
+    const EDGES: [&str; 4] = ["top", "bottom", "left", "right"];
+
+    let max = summary.into_iter().max().filter(|&max| max > 0)?;
+    let edges = EDGES
+        .into_iter()
+        .zip(summary)
+        .filter(|&(_, count)| count == max)
+        .map(|(edge, _)| edge)
+        .collect::<Vec<_>>();
+    Some(format!(
+        "Most conflicts pressed against the {} {}.",
+        edges.join(" and "),
+        if edges.len() == 1 { "edge" } else { "edges" }
+    ))
+}
+
 #[cfg(feature = "tui")]
 fn main() {
-    let args = Args::parse().unwrap_or_else(|e| e.exit());
+    let args = match Args::parse().unwrap_or_else(|e| e.exit()) {
+        Mode::Search(args) => *args,
+        Mode::StdinJson => exit(json::run()),
+        Mode::ListRules => return list_rules(),
+        Mode::HelpOption(variants) => return help_option(variants),
+        Mode::Analyze {
+            config,
+            pattern,
+            max_period,
+        } => exit(analyze::run(&config, &pattern, max_period)),
+        Mode::Probe {
+            config,
+            budget_steps,
+            probes,
+            seed,
+        } => exit(run_probe(&config, budget_steps, probes, seed)),
+        Mode::RuleSweep {
+            config,
+            rules,
+            budget_per_rule,
+        } => exit(run_rule_sweep(&config, &rules, budget_per_rule)),
+        Mode::Split {
+            config,
+            depth,
+            output,
+        } => exit(run_split(&config, depth, output.as_deref())),
+    };
     let mut world = args.world;
+    let mut timing = Duration::default();
+    if let Some(autosave) = &args.autosave {
+        match autosave.load(world.config()) {
+            Ok(Some(ser)) => {
+                if autosave::ask_resume(autosave.path()).unwrap_or(false) {
+                    world = ser.world().unwrap_or_else(|e| {
+                        eprintln!("Error: Invalid autosave file: {}", e);
+                        exit(1);
+                    });
+                    timing = ser.timing.unwrap_or_default();
+                }
+            }
+            Ok(None) => (),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                exit(1);
+            }
+        }
+    }
+    warn_infeasible(&world);
     if args.no_tui {
-        run_search(&mut world, args.all);
+        run_search(&mut world, args.all, args.min_phase, args.output_format);
+    } else if !stdout().is_terminal() {
+        eprintln!("The standard output is not a terminal. Searching without the TUI.");
+        run_search(&mut world, args.all, args.min_phase, args.output_format);
     } else {
-        tui::tui(world, args.reset).unwrap();
+        tui::tui(world, args.reset, args.autosave, timing).unwrap();
     }
 }
 
 #[cfg(not(feature = "tui"))]
 fn main() {
-    let mut args = Args::parse().unwrap_or_else(|e| e.exit());
-    run_search(&mut args.world, args.all);
+    let mut args = match Args::parse().unwrap_or_else(|e| e.exit()) {
+        Mode::Search(args) => *args,
+        Mode::StdinJson => exit(json::run()),
+        Mode::ListRules => return list_rules(),
+        Mode::HelpOption(variants) => return help_option(variants),
+        Mode::Analyze {
+            config,
+            pattern,
+            max_period,
+        } => exit(analyze::run(&config, &pattern, max_period)),
+        Mode::Probe {
+            config,
+            budget_steps,
+            probes,
+            seed,
+        } => exit(run_probe(&config, budget_steps, probes, seed)),
+        Mode::RuleSweep {
+            config,
+            rules,
+            budget_per_rule,
+        } => exit(run_rule_sweep(&config, &rules, budget_per_rule)),
+        Mode::Split {
+            config,
+            depth,
+            output,
+        } => exit(run_split(&config, depth, output.as_deref())),
+    };
+    warn_infeasible(&args.world);
+    run_search(
+        &mut args.world,
+        args.all,
+        args.min_phase,
+        args.output_format,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn border_note() {
+        assert_eq!(super::border_note([0; 4]), None);
+        assert_eq!(
+            super::border_note([3, 1, 2, 5]).as_deref(),
+            Some("Most conflicts pressed against the right edge.")
+        );
+        assert_eq!(
+            super::border_note([1, 1, 4, 4]).as_deref(),
+            Some("Most conflicts pressed against the left and right edges.")
+        );
+    }
 }
@@ -1,14 +1,20 @@
+use crate::{
+    autosave::Autosave,
+    border_note,
+    viewport::{fit_line, Layout, Viewport},
+};
 use crossterm::{
     cursor::{Hide, MoveTo, MoveToNextLine, Show},
-    event::{Event, EventStream, KeyCode, KeyEvent},
+    event::{Event, EventStream, KeyCode, KeyEvent, KeyModifiers},
     style::{Color, Print, ResetColor, SetBackgroundColor, SetForegroundColor},
     terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen, SetTitle},
     ExecutableCommand, QueueableCommand,
 };
 use futures_util::{future, select_biased, FutureExt, TryStreamExt};
-use rlifesrc_lib::{PolyWorld, State, Status, ALIVE, DEAD};
+use rlifesrc_lib::{Anchor, PolyWorld, State, Status, ALIVE, DEAD};
 use std::{
-    io::{stdout, Result, Write},
+    cmp::Ordering,
+    io::{self, stdout, Result, Write},
     time::{Duration, Instant},
 };
 
@@ -37,12 +43,19 @@ struct App<'a, W: Write> {
     reset: bool,
     output: &'a mut W,
     term_size: (u16, u16),
-    world_size: (i32, i32),
+    viewport: Viewport,
     mode: Mode,
+    autosave: Option<Autosave>,
+    autosave_error: Option<io::Error>,
 }
 
 impl<'a, W: Write> App<'a, W> {
-    fn new(world: PolyWorld, reset: bool, output: &'a mut W) -> Result<Self> {
+    fn new(
+        world: PolyWorld,
+        reset: bool,
+        output: &'a mut W,
+        term_size: (u16, u16),
+    ) -> Result<Self> {
         let period = world.config().period;
         let mut app = App {
             gen: 0,
@@ -54,9 +67,11 @@ impl<'a, W: Write> App<'a, W> {
             timing: Duration::default(),
             reset,
             output,
-            term_size: (80, 24),
-            world_size: (80, 24),
+            term_size,
+            viewport: Viewport::default(),
             mode: Mode::Main,
+            autosave: None,
+            autosave_error: None,
         };
         app.init()?;
         Ok(app)
@@ -68,16 +83,12 @@ impl<'a, W: Write> App<'a, W> {
             .execute(EnterAlternateScreen)?
             .execute(Hide)?
             .execute(SetTitle("rlifesrc"))?;
-        terminal::enable_raw_mode()?;
-        self.term_size = terminal::size()?;
-        self.world_size.0 = self.world.config().width.min(self.term_size.0 as i32 - 1);
-        self.world_size.1 = self.world.config().height.min(self.term_size.1 as i32 - 3);
+        self.viewport = self.viewport.fit(self.term_size, self.world_size());
         self.update()
     }
 
     /// Quits the program.
     fn quit(&mut self) -> Result<()> {
-        terminal::disable_raw_mode()?;
         self.output
             .execute(Show)?
             .execute(ResetColor)?
@@ -85,26 +96,52 @@ impl<'a, W: Write> App<'a, W> {
         Ok(())
     }
 
+    /// The size of the world.
+    fn world_size(&self) -> (i32, i32) {
+        let config = self.world.config();
+        (config.width, config.height)
+    }
+
+    /// Handles a change of the terminal size, and redraws the screen.
+    fn resize(&mut self, term_size: (u16, u16)) -> Result<()> {
+        self.term_size = term_size;
+        self.viewport = self.viewport.fit(self.term_size, self.world_size());
+        self.output
+            .queue(ResetColor)?
+            .queue(Clear(ClearType::All))?;
+        self.update()
+    }
+
+    /// Scrolls the pattern, and redraws the screen.
+    fn scroll(&mut self, delta: (i32, i32)) -> Result<()> {
+        self.viewport = self.viewport.scroll(delta, self.world_size());
+        self.update()
+    }
+
     /// Updates the header.
     fn update_header(&mut self) -> Result<()> {
+        let Some(row) = Layout::new(self.term_size.1).header else {
+            return Ok(());
+        };
         self.output
-            .queue(MoveTo(0, 0))?
+            .queue(MoveTo(0, row))?
             .queue(SetBackgroundColor(Color::White))?
             .queue(SetForegroundColor(Color::Black))?
-            .queue(Print(format!(
-                "{:1$}",
-                format!(
-                    "Gen: {}  Cells: {}  Confl: {}{}",
+            .queue(Print(fit_line(
+                &format!(
+                    "Gen: {}  Speed: {}  Cells: {}  Confl: {}  Steps: {}{}",
                     self.gen,
+                    self.world.config().speed_string(),
                     self.world.cell_count_gen(self.gen),
                     self.world.conflicts(),
+                    self.world.steps(),
                     if !self.paused {
                         String::new()
                     } else {
                         format!("  Time: {:.2?}", self.timing)
                     }
                 ),
-                self.term_size.0 as usize
+                self.term_size.0,
             )))?;
         Ok(())
     }
@@ -113,20 +150,30 @@ impl<'a, W: Write> App<'a, W> {
     /// Prints the pattern in a mix of
     /// [Plaintext](https://conwaylife.com/wiki/Plaintext) and
     /// [RLE](https://conwaylife.com/wiki/Rle) format.
+    ///
+    /// Only the part of the pattern in the [viewport](Viewport) is printed.
     fn update_main(&mut self) -> Result<()> {
-        self.output
-            .queue(MoveTo(0, 1))?
-            .queue(ResetColor)?
-            .queue(Print(format!(
-                "x = {}, y = {}, rule = {}",
-                self.world.config().width,
-                self.world.config().height,
-                self.world.config().rule_string
-            )))?
-            .queue(MoveToNextLine(1))?;
-        for y in 0..self.world_size.1 {
+        let layout = Layout::new(self.term_size.1);
+        self.output.queue(ResetColor)?;
+        if let Some(row) = layout.info {
+            self.output.queue(MoveTo(0, row))?.queue(Print(fit_line(
+                &format!(
+                    "x = {}, y = {}, rule = {}",
+                    self.world.config().width,
+                    self.world.config().height,
+                    self.world.config().rule_string_canonical()
+                ),
+                self.term_size.0,
+            )))?;
+        }
+        self.output.queue(MoveTo(0, layout.main_start))?;
+        let Viewport {
+            offset: (x0, y0),
+            size: (width, height),
+        } = self.viewport;
+        for y in y0..y0 + height {
             let mut line = String::new();
-            for x in 0..self.world_size.0 {
+            for x in x0..x0 + width {
                 let state = self.world.get_cell_state((x, y, self.gen));
                 match state {
                     Some(DEAD) => line.push('.'),
@@ -137,16 +184,27 @@ impl<'a, W: Write> App<'a, W> {
                             line.push('o');
                         }
                     }
-                    Some(State(i)) => line.push((b'A' + i as u8 - 1) as char),
+                    Some(State(i)) => {
+                        // Golly's extended RLE format for states above 24.
+                        let i = i as usize - 1;
+                        if i >= 24 {
+                            line.push((b'p' + (i / 24 - 1) as u8) as char);
+                        }
+                        line.push((b'A' + (i % 24) as u8) as char);
+                    }
                     _ => line.push('?'),
                 };
             }
-            if y == self.world.config().height - 1 {
-                line.push('!');
-            } else {
-                line.push('$');
-            };
-            self.output.queue(Print(line))?.queue(MoveToNextLine(1))?;
+            if self.viewport.shows_last_column(self.world.config().width) {
+                if y == self.world.config().height - 1 {
+                    line.push('!');
+                } else {
+                    line.push('$');
+                }
+            }
+            self.output
+                .queue(Print(fit_line(&line, self.term_size.0)))?
+                .queue(MoveToNextLine(1))?;
         }
         Ok(())
     }
@@ -155,28 +213,36 @@ impl<'a, W: Write> App<'a, W> {
     fn update_footer(&mut self) -> Result<()> {
         const INITIAL: &str = "Press [space] to start.";
         const FOUND: &str = "Found a result. Press [q] to quit or [space] to search for the next.";
-        const NONE: &str = "No more result. Press [q] to quit.";
+        const NONE: &str = "No more result.";
+        const GROW: &str = "Press [g] to grow the world or [q] to quit.";
         const SEARCHING: &str = "Searching... Press [space] to pause.";
         const PAUSED: &str = "Paused. Press [space] to resume.";
 
+        let Some(row) = Layout::new(self.term_size.1).footer else {
+            return Ok(());
+        };
+        let none = match border_note(self.world.border_conflict_summary()) {
+            Some(note) => format!("{} {} {}", NONE, note, GROW),
+            None => format!("{} {}", NONE, GROW),
+        };
         self.output
-            .queue(MoveTo(0, self.term_size.1 - 1))?
+            .queue(MoveTo(0, row))?
             .queue(SetBackgroundColor(Color::White))?
             .queue(SetForegroundColor(Color::Black))?
-            .queue(Print(format!(
-                "{:1$}",
+            .queue(Print(fit_line(
                 match self.status {
                     Status::Initial => INITIAL,
                     Status::Found => FOUND,
-                    Status::None => NONE,
-                    Status::Searching =>
+                    Status::None => &none,
+                    Status::Searching => {
                         if self.paused {
                             PAUSED
                         } else {
                             SEARCHING
-                        },
+                        }
+                    }
                 },
-                self.term_size.0 as usize
+                self.term_size.0,
             )))?;
         Ok(())
     }
@@ -204,10 +270,23 @@ impl<'a, W: Write> App<'a, W> {
         self.start_time = Some(Instant::now());
     }
 
+    /// Saves the search, if autosave is enabled.
+    ///
+    /// The error of the last save is remembered, and reported after quitting the TUI.
+    fn autosave(&mut self) {
+        let timing = self.timing + self.start_time.map_or(Duration::ZERO, |i| i.elapsed());
+        if let Some(autosave) = &mut self.autosave {
+            self.autosave_error = autosave.save(&self.world, timing).err();
+        }
+    }
+
     /// Searches for one step.
     fn step(&mut self) {
         let s = self.world.search(Some(VIEW_FREQ));
         self.status = s;
+        if self.autosave.as_ref().is_some_and(Autosave::is_due) {
+            self.autosave();
+        }
         if s != Status::Searching {
             self.paused = true;
             if let Some(instant) = self.start_time.take() {
@@ -220,16 +299,49 @@ impl<'a, W: Write> App<'a, W> {
         }
     }
 
+    /// Replaces an exhausted world with a world that is one cell wider or higher.
+    ///
+    /// The world grows in the direction where the most conflicts pressed against
+    /// the edges, or in both directions if there is a tie, or if the world must
+    /// stay square.
+    fn grow(&mut self) -> Result<()> {
+        let config = self.world.config();
+        let [top, bottom, left, right] = self.world.border_conflict_summary();
+        let (dw, dh) = match (left + right).cmp(&(top + bottom)) {
+            Ordering::Greater => (1, 0),
+            Ordering::Less => (0, 1),
+            Ordering::Equal => (1, 1),
+        };
+        let enlarged = self
+            .world
+            .enlarge(config.width + dw, config.height + dh, Anchor::TopLeft)
+            .or_else(|_| {
+                self.world
+                    .enlarge(config.width + 1, config.height + 1, Anchor::TopLeft)
+            });
+        if let Ok(world) = enlarged {
+            self.world = world;
+            self.status = Status::Initial;
+            self.viewport = self.viewport.fit(self.term_size, self.world_size());
+            self.output
+                .queue(ResetColor)?
+                .queue(Clear(ClearType::All))?;
+        }
+        self.update()
+    }
+
     /// Asks whether to quit.
     fn ask_quit(&mut self) -> Result<()> {
         const ASK_QUIT: &str = "Are you sure to quit? [Y/n]";
 
-        self.output
-            .queue(MoveTo(0, self.term_size.1 - 1))?
-            .queue(SetBackgroundColor(Color::White))?
-            .queue(SetForegroundColor(Color::Black))?
-            .queue(Print(format!("{:1$}", ASK_QUIT, self.term_size.0 as usize)))?
-            .flush()?;
+        if let Some(row) = Layout::new(self.term_size.1).footer {
+            self.output
+                .queue(MoveTo(0, row))?
+                .queue(SetBackgroundColor(Color::White))?
+                .queue(SetForegroundColor(Color::Black))?
+                .queue(Print(fit_line(ASK_QUIT, self.term_size.0)))?
+                .flush()?;
+        }
 
         self.mode = Mode::AskingQuit;
         Ok(())
@@ -237,6 +349,21 @@ impl<'a, W: Write> App<'a, W> {
 
     /// Handles a key event. Returns `true` to quit the program.
     fn handle(&mut self, event: Option<Event>) -> Result<bool> {
+        // The terminal is in raw mode, so Ctrl-C is a key event rather than a signal.
+        if let Some(Event::Key(KeyEvent {
+            code: KeyCode::Char('c' | 'C'),
+            modifiers,
+            ..
+        })) = event
+        {
+            if modifiers.contains(KeyModifiers::CONTROL) {
+                if !self.paused {
+                    self.pause();
+                }
+                return Ok(true);
+            }
+        }
+
         match self.mode {
             Mode::Main => match event {
                 Some(Event::Key(KeyEvent {
@@ -253,6 +380,12 @@ impl<'a, W: Write> App<'a, W> {
                         return Ok(true);
                     }
                 }
+                Some(Event::Key(KeyEvent {
+                    code: KeyCode::Char('g' | 'G'),
+                    ..
+                })) if self.status == Status::None => {
+                    self.grow()?;
+                }
                 Some(Event::Key(KeyEvent {
                     code: KeyCode::PageDown,
                     ..
@@ -267,26 +400,33 @@ impl<'a, W: Write> App<'a, W> {
                     self.gen = (self.gen + self.period - 1) % self.period;
                     self.update()?;
                 }
+                Some(Event::Key(KeyEvent {
+                    code: KeyCode::Left,
+                    ..
+                })) => self.scroll((-1, 0))?,
+                Some(Event::Key(KeyEvent {
+                    code: KeyCode::Right,
+                    ..
+                })) => self.scroll((1, 0))?,
+                Some(Event::Key(KeyEvent {
+                    code: KeyCode::Up, ..
+                })) => self.scroll((0, -1))?,
+                Some(Event::Key(KeyEvent {
+                    code: KeyCode::Down,
+                    ..
+                })) => self.scroll((0, 1))?,
                 Some(Event::Key(KeyEvent {
                     code: KeyCode::Char(' ') | KeyCode::Enter,
                     ..
                 })) => {
                     if !self.paused {
                         self.pause();
-                    } else {
+                    } else if self.world.can_continue() {
                         self.start();
                     }
                     self.update()?;
                 }
-                Some(Event::Resize(width, height)) => {
-                    self.term_size = (width, height);
-                    self.world_size.0 = self.world.config().width.min(self.term_size.0 as i32 - 1);
-                    self.world_size.1 = self.world.config().height.min(self.term_size.1 as i32 - 3);
-                    self.output
-                        .queue(ResetColor)?
-                        .queue(Clear(ClearType::All))?;
-                    self.update()?;
-                }
+                Some(Event::Resize(width, height)) => self.resize((width, height))?,
                 Some(_) => (),
                 None => {
                     if !self.paused {
@@ -301,13 +441,7 @@ impl<'a, W: Write> App<'a, W> {
                     ..
                 })) => return Ok(true),
                 Some(Event::Resize(width, height)) => {
-                    self.term_size = (width, height);
-                    self.world_size.0 = self.world_size.0.min(self.term_size.0 as i32 - 1);
-                    self.world_size.1 = self.world_size.1.min(self.term_size.1 as i32 - 3);
-                    self.output
-                        .queue(ResetColor)?
-                        .queue(Clear(ClearType::All))?;
-                    self.update()?;
+                    self.resize((width, height))?;
                     self.ask_quit()?;
                 }
                 Some(_) => {
@@ -351,18 +485,150 @@ impl<W: Write> Drop for App<'_, W> {
     }
 }
 
+/// Keeps the terminal in raw mode until it is dropped.
+struct RawMode;
+
+impl RawMode {
+    fn enable() -> Result<Self> {
+        terminal::enable_raw_mode()?;
+        Ok(RawMode)
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        terminal::disable_raw_mode().ok();
+    }
+}
+
 /// Runs the search with a TUI.
 ///
 /// If `reset` is true, the time will be reset when starting a new search.
-pub fn tui(world: PolyWorld, reset: bool) -> Result<()> {
+///
+/// `timing` is the time already used in searching, e.g., in a resumed search.
+///
+/// If `autosave` is given, the search is saved periodically, and when quitting.
+pub fn tui(
+    world: PolyWorld,
+    reset: bool,
+    autosave: Option<Autosave>,
+    timing: Duration,
+) -> Result<()> {
     let mut stdout = stdout();
     let mut reader = EventStream::new();
     let result;
+    let saved;
     {
-        let mut app = App::new(world, reset, &mut stdout)?;
+        let _raw_mode = RawMode::enable()?;
+        let term_size = terminal::size()?;
+        let mut app = App::new(world, reset, &mut stdout, term_size)?;
+        app.timing = timing;
+        app.autosave = autosave;
         futures_executor::block_on(app.main_loop(&mut reader))?;
+        app.autosave();
         result = app.world.rle_gen(app.gen);
+        saved = app
+            .autosave
+            .take()
+            .map(|autosave| match app.autosave_error.take() {
+                Some(e) => Err((autosave, e)),
+                None => Ok(autosave),
+            });
     }
     println!("{}", result);
+    match saved {
+        Some(Ok(autosave)) => eprintln!("The search is saved to {}.", autosave.path().display()),
+        Some(Err((autosave, e))) => eprintln!(
+            "Warning: Failed to save the search to {}: {}",
+            autosave.path().display(),
+            e
+        ),
+        None => (),
+    }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rlifesrc_lib::Config;
+
+    /// Checks that the viewport fits in both the terminal and the world.
+    fn assert_fits<W: Write>(app: &App<'_, W>) {
+        let Viewport { offset, size } = app.viewport;
+        let (width, height) = app.world_size();
+        assert!(offset.0 >= 0 && offset.1 >= 0);
+        assert!(offset.0 + size.0 <= width && offset.1 + size.1 <= height);
+        assert!(size.0 < app.term_size.0.max(1) as i32);
+        assert!(size.1 <= Layout::new(app.term_size.1).main_rows as i32);
+    }
+
+    #[test]
+    fn resize() -> Result<()> {
+        let world = Config::new(20, 16, 2).world().unwrap();
+        let mut output = Vec::new();
+        let mut app = App::new(world, false, &mut output, (80, 24))?;
+        assert_eq!(app.viewport.size, (20, 16));
+
+        let key = |code| Some(Event::Key(KeyEvent::from(code)));
+        let events = [
+            Some(Event::Resize(10, 8)),
+            key(KeyCode::Right),
+            key(KeyCode::Down),
+            key(KeyCode::Down),
+            Some(Event::Resize(1, 1)),
+            Some(Event::Resize(0, 0)),
+            Some(Event::Resize(80, 1)),
+            Some(Event::Resize(80, 2)),
+            Some(Event::Resize(12, 4)),
+            key(KeyCode::Left),
+            key(KeyCode::PageDown),
+            Some(Event::Resize(80, 24)),
+        ];
+        for event in events {
+            assert!(!app.handle(event)?);
+            assert_fits(&app);
+        }
+        assert_eq!(app.viewport.size, (20, 16));
+        assert_eq!(app.viewport.offset, (0, 0));
+
+        app.handle(Some(Event::Resize(10, 8)))?;
+        for _ in 0..100 {
+            app.handle(key(KeyCode::Right))?;
+            app.handle(key(KeyCode::Down))?;
+        }
+        assert_eq!(app.viewport.offset, (11, 11));
+        app.handle(Some(Event::Resize(16, 12)))?;
+        assert_eq!(app.viewport.offset, (5, 7));
+        assert_fits(&app);
+
+        // Resizing while asking whether to quit keeps asking.
+        app.status = Status::Searching;
+        app.handle(key(KeyCode::Char('q')))?;
+        assert!(app.mode == Mode::AskingQuit);
+        for (width, height) in [(1, 1), (0, 0), (40, 12)] {
+            assert!(!app.handle(Some(Event::Resize(width, height)))?);
+            assert!(app.mode == Mode::AskingQuit);
+            assert_fits(&app);
+        }
+        app.handle(key(KeyCode::Char('n')))?;
+        assert!(app.mode == Mode::Main);
+        Ok(())
+    }
+
+    #[test]
+    fn ctrl_c() -> Result<()> {
+        let world = Config::new(20, 16, 2).world().unwrap();
+        let mut output = Vec::new();
+        let mut app = App::new(world, false, &mut output, (80, 24))?;
+        app.start();
+        let ctrl_c = Some(Event::Key(KeyEvent::new(
+            KeyCode::Char('c'),
+            KeyModifiers::CONTROL,
+        )));
+        assert!(!app.handle(Some(Event::Key(KeyEvent::from(KeyCode::Char('c')))))?);
+        assert!(app.handle(ctrl_c)?);
+        assert!(app.paused);
+        Ok(())
+    }
+}
@@ -0,0 +1,201 @@
+//! The layout of the TUI.
+//!
+//! These are pure functions of the sizes of the terminal and the world,
+//! so that they can be tested without a terminal.
+
+/// The rows of the terminal used by each part of the screen.
+///
+/// When the terminal is too small, the header and the line before the pattern
+/// are dropped first, and then the footer.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Layout {
+    /// The row of the header.
+    pub header: Option<u16>,
+    /// The row of the line `x = ..., y = ..., rule = ...` before the pattern.
+    pub info: Option<u16>,
+    /// The first row of the pattern.
+    pub main_start: u16,
+    /// Number of rows of the pattern.
+    pub main_rows: u16,
+    /// The row of the footer.
+    pub footer: Option<u16>,
+}
+
+impl Layout {
+    /// The layout of a terminal with `rows` rows.
+    pub fn new(rows: u16) -> Self {
+        match rows {
+            0 => Layout::default(),
+            1 => Layout {
+                footer: Some(0),
+                ..Layout::default()
+            },
+            2 => Layout {
+                header: Some(0),
+                footer: Some(1),
+                ..Layout::default()
+            },
+            _ => Layout {
+                header: Some(0),
+                info: Some(1),
+                main_start: 2,
+                main_rows: rows - 3,
+                footer: Some(rows - 1),
+            },
+        }
+    }
+}
+
+/// The part of the world shown on the screen.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Viewport {
+    /// The coordinates of the top left cell shown on the screen.
+    pub offset: (i32, i32),
+    /// Number of columns and rows of cells shown on the screen.
+    pub size: (i32, i32),
+}
+
+impl Viewport {
+    /// Fits the viewport into a terminal of size `term_size`,
+    /// showing a world of size `world_size`.
+    ///
+    /// The offset is kept if possible, and otherwise moved just enough
+    /// to keep the viewport inside the world.
+    pub fn fit(self, term_size: (u16, u16), world_size: (i32, i32)) -> Self {
+        // The last column is left for the `$` or `!` at the end of a row.
+        let width = world_size.0.min(term_size.0 as i32 - 1).max(0);
+        let height = world_size.1.min(Layout::new(term_size.1).main_rows as i32);
+        let size = (width, height);
+        Viewport {
+            offset: clamp_offset(self.offset, size, world_size),
+            size,
+        }
+    }
+
+    /// Scrolls the viewport by `delta`, without leaving the world.
+    pub fn scroll(self, delta: (i32, i32), world_size: (i32, i32)) -> Self {
+        let offset = (self.offset.0 + delta.0, self.offset.1 + delta.1);
+        Viewport {
+            offset: clamp_offset(offset, self.size, world_size),
+            ..self
+        }
+    }
+
+    /// Whether the last column of the world is shown.
+    pub fn shows_last_column(&self, world_width: i32) -> bool {
+        self.offset.0 + self.size.0 >= world_width
+    }
+}
+
+/// Clamps the offset of a viewport of size `size`, so that it is inside the world.
+fn clamp_offset(offset: (i32, i32), size: (i32, i32), world_size: (i32, i32)) -> (i32, i32) {
+    (
+        offset.0.clamp(0, (world_size.0 - size.0).max(0)),
+        offset.1.clamp(0, (world_size.1 - size.1).max(0)),
+    )
+}
+
+/// Truncates or pads a line with spaces, so that it has exactly `width` characters.
+pub fn fit_line(line: &str, width: u16) -> String {
+    format!("{:1$.1$}", line, width as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layout() {
+        assert_eq!(Layout::new(0), Layout::default());
+        assert_eq!(
+            Layout::new(1),
+            Layout {
+                header: None,
+                info: None,
+                main_start: 0,
+                main_rows: 0,
+                footer: Some(0),
+            }
+        );
+        assert_eq!(Layout::new(2).header, Some(0));
+        assert_eq!(Layout::new(2).footer, Some(1));
+        assert_eq!(Layout::new(3).main_rows, 0);
+        assert_eq!(
+            Layout::new(24),
+            Layout {
+                header: Some(0),
+                info: Some(1),
+                main_start: 2,
+                main_rows: 21,
+                footer: Some(23),
+            }
+        );
+    }
+
+    #[test]
+    fn fit() {
+        let viewport = Viewport::default().fit((80, 24), (20, 16));
+        assert_eq!(viewport.size, (20, 16));
+        assert!(viewport.shows_last_column(20));
+
+        let viewport = Viewport::default().fit((10, 8), (20, 16));
+        assert_eq!(viewport.size, (9, 5));
+        assert!(!viewport.shows_last_column(20));
+
+        // Shrinking the terminal keeps the offset when possible.
+        let viewport = Viewport {
+            offset: (3, 4),
+            ..viewport
+        };
+        assert_eq!(viewport.fit((8, 6), (20, 16)).offset, (3, 4));
+
+        // Growing the terminal moves the offset back into the world.
+        let viewport = Viewport {
+            offset: (11, 11),
+            ..viewport
+        };
+        let viewport = viewport.fit((16, 12), (20, 16));
+        assert_eq!(viewport.size, (15, 9));
+        assert_eq!(viewport.offset, (5, 7));
+        assert!(viewport.shows_last_column(20));
+        assert_eq!(viewport.fit((80, 24), (20, 16)).offset, (0, 0));
+    }
+
+    #[test]
+    fn fit_degenerate() {
+        for term_size in [(0, 0), (1, 1), (80, 1), (80, 2), (80, 3)] {
+            let viewport = Viewport {
+                offset: (5, 5),
+                size: (10, 10),
+            }
+            .fit(term_size, (20, 16));
+            assert_eq!(viewport.size.1, 0);
+            assert!(viewport.size.0 >= 0);
+            assert!(viewport.offset.0 + viewport.size.0 <= 20);
+            assert!(viewport.offset.1 + viewport.size.1 <= 16);
+        }
+        let viewport = Viewport::default().fit((1, 24), (20, 16));
+        assert_eq!(viewport.size, (0, 16));
+        let viewport = Viewport::default().fit((80, 4), (20, 16));
+        assert_eq!(viewport.size, (20, 1));
+    }
+
+    #[test]
+    fn scroll() {
+        let viewport = Viewport::default().fit((10, 8), (20, 16));
+        assert_eq!(viewport.scroll((-1, -1), (20, 16)).offset, (0, 0));
+        assert_eq!(viewport.scroll((4, 2), (20, 16)).offset, (4, 2));
+        assert_eq!(viewport.scroll((100, 100), (20, 16)).offset, (11, 11));
+
+        // Nothing to scroll when the whole world is shown.
+        let viewport = Viewport::default().fit((80, 24), (20, 16));
+        assert_eq!(viewport.scroll((1, 1), (20, 16)).offset, (0, 0));
+    }
+
+    #[test]
+    fn fit_line() {
+        assert_eq!(super::fit_line("abc", 5), "abc  ");
+        assert_eq!(super::fit_line("abcdef", 3), "abc");
+        assert_eq!(super::fit_line("abc", 0), "");
+    }
+}
@@ -0,0 +1,93 @@
+use serde_json::Value;
+use std::{
+    io::Write,
+    process::{Command, Output, Stdio},
+};
+
+/// Runs `rlifesrc --stdin-json` with the given input.
+fn run(input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_rlifesrc"))
+        .arg("--stdin-json")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn found() {
+    let output = run(r#"{"config": {"width": 16, "height": 5, "period": 3, "dy": 1}}"#);
+    assert!(output.status.success());
+    let response: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(response["status"], "Found");
+    assert_eq!(response["results"].as_array().unwrap().len(), 1);
+    assert_eq!(response["results"][0].as_array().unwrap().len(), 3);
+    assert_eq!(
+        response["results"][0][0],
+        "x = 16, y = 5, rule = B3/S23\n\
+         ........o.......$\n\
+         .oo.ooo.ooo.....$\n\
+         .oo....o..oo.oo.$\n\
+         o..o.oo...o..oo.$\n\
+         ............o..o!\n"
+    );
+    assert!(response["steps"].as_u64().unwrap() > 0);
+}
+
+#[test]
+fn all() {
+    let output = run(r#"{"config": {"width": 5, "height": 5, "max_cell_count": 4}, "all": true}"#);
+    assert!(output.status.success());
+    let response: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(response["status"], "None");
+    assert!(!response["results"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn max_step() {
+    let config = r#"{"width": 5, "height": 5, "max_cell_count": 4}"#;
+    let output = run(config);
+    let response: Value = serde_json::from_slice(&output.stdout).unwrap();
+    let steps = response["steps"].as_u64().unwrap();
+
+    // The budget is used up by the first result, so the search stops there.
+    let output = run(&format!(
+        r#"{{"config": {}, "all": true, "max_step": {}}}"#,
+        config, steps
+    ));
+    assert!(output.status.success());
+    let response: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(response["status"], "Searching");
+    assert_eq!(response["results"].as_array().unwrap().len(), 1);
+    assert_eq!(response["steps"], steps);
+}
+
+#[test]
+fn backjump_compat() {
+    // Backjumping does not support `max_cell_count`,
+    // so the old `backjump` option falls back to the default algorithm.
+    let output = run(r#"{"width": 5, "height": 5, "backjump": true, "max_cell_count": 4}"#);
+    assert!(output.status.success());
+    let response: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(response["status"], "Found");
+}
+
+#[test]
+fn invalid_rule() {
+    let output = run(r#"{"rule_string": "B3/S23/X"}"#);
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+    let error: Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert!(error["error"]
+        .as_str()
+        .unwrap()
+        .starts_with("Invalid config"));
+}
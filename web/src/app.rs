@@ -3,8 +3,10 @@
 use crate::{
     help::Help,
     settings::Settings,
-    worker::{Request, Response, UpdateMessage, Worker},
-    world::World,
+    worker::{
+        Aggregates, FoundResult, Progress, Request, Response, StatusMessage, UpdateMessage, Worker,
+    },
+    world::{World, MAX_SHOWN_CELLS},
 };
 use build_time::build_time_utc;
 use gloo::{
@@ -17,11 +19,15 @@ use gloo::{
 };
 use js_sys::Array;
 use log::{debug, error};
-use rlifesrc_lib::{Config, Status};
+use rlifesrc_lib::{
+    format::{self, PatternFormatter},
+    Config, Coord, State, Status,
+};
 use std::{rc::Rc, time::Duration};
 use wasm_bindgen::{prelude::wasm_bindgen, JsCast, JsValue};
 use web_sys::{
-    Blob, BlobPropertyBag, Event, FileList, HtmlAnchorElement, HtmlElement, HtmlInputElement, Url,
+    Blob, BlobPropertyBag, Event, FileList, HtmlAnchorElement, HtmlElement, HtmlInputElement,
+    HtmlSelectElement, Url,
 };
 use yew::{events::WheelEvent, html, Component, Context, Html};
 use yew_agent::{Bridge, Bridged};
@@ -34,20 +40,74 @@ extern "C" {
 
 pub struct App {
     config: Config,
-    status: Status,
+    search: SearchState,
     paused: bool,
     gen: i32,
-    cells: u32,
-    world: String,
+    aggregates: Option<Aggregates>,
     max_partial: bool,
     find_all: bool,
-    found_count: u32,
+    export_format: String,
     timing: Duration,
+    /// The cell under the mouse, which is probed by the worker.
+    hovered: Option<Coord>,
+    /// The cells determined by setting the hovered cell alive,
+    /// or the error message if this leads to a conflict.
+    preview: Option<Result<Vec<(Coord, State)>, String>>,
     worker: Box<dyn Bridge<Worker>>,
     interval: Option<Interval>,
     reader: Option<FileReader>,
 }
 
+/// The part of the state of the app that follows the search.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct SearchState {
+    status: Status,
+    world: String,
+    cells: u32,
+    found_count: u32,
+    steps: u64,
+}
+
+impl SearchState {
+    /// Applies a status message from the worker, so that the status and
+    /// the displayed result are updated at the same time.
+    ///
+    /// `gen` is the displayed generation, or `None` if the max partial result
+    /// is displayed instead. Returns the new config if it is changed.
+    fn apply_status(&mut self, msg: StatusMessage, gen: Option<i32>) -> Option<Config> {
+        self.status = msg.status();
+        match msg {
+            StatusMessage::Searching(Progress { steps, populations }) => {
+                self.steps = steps;
+                if let Some(&cells) = gen.and_then(|gen| populations.get(gen as usize)) {
+                    self.cells = cells;
+                }
+                None
+            }
+            StatusMessage::Found(FoundResult {
+                rles,
+                populations,
+                found_count,
+                steps,
+                config,
+            }) => {
+                if let Some(gen) = gen {
+                    if let Some(world) = rles.into_iter().nth(gen as usize) {
+                        self.world = world;
+                    }
+                    if let Some(&cells) = populations.get(gen as usize) {
+                        self.cells = cells;
+                    }
+                }
+                self.found_count = found_count;
+                self.steps = steps;
+                config
+            }
+            StatusMessage::Initial | StatusMessage::None => None,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum Msg {
     Tick,
@@ -57,11 +117,14 @@ pub enum Msg {
     Pause,
     Reset,
     Save,
+    SetExportFormat(String),
+    Export,
     Load(FileList),
     SendFile(String),
     SetMaxPartial,
     SetFindAll,
     Apply(Config),
+    Hover(Option<(i32, i32)>),
     DataReceived(Response),
 }
 
@@ -93,15 +156,22 @@ impl Component for App {
 
         Self {
             config,
-            status,
+            search: SearchState {
+                status,
+                world,
+                cells: 0,
+                found_count: 0,
+                steps: 0,
+            },
             paused: true,
             gen: 0,
-            cells: 0,
-            world,
+            aggregates: None,
             max_partial: false,
             find_all: false,
-            found_count: 0,
+            export_format: format::Rle.name().to_owned(),
             timing: Duration::default(),
+            hovered: None,
+            preview: None,
             worker,
             interval: None,
             reader: None,
@@ -138,6 +208,13 @@ impl Component for App {
             Msg::Pause => self.worker.send(Request::Pause),
             Msg::Reset => self.worker.send(Request::SetWorld(self.config.clone())),
             Msg::Save => self.worker.send(Request::Save),
+            Msg::SetExportFormat(name) => {
+                self.export_format = name;
+                return false;
+            }
+            Msg::Export => self
+                .worker
+                .send(Request::Export(self.export_format.clone(), self.gen)),
             Msg::Load(files) => {
                 let file = &GlooFileList::from(files)[0];
                 let link = ctx.link().clone();
@@ -183,22 +260,41 @@ impl Component for App {
                 activate("pane-world");
                 return true;
             }
+            Msg::Hover(cell) => {
+                let coord = cell
+                    .filter(|_| self.paused && !self.max_partial)
+                    .map(|(x, y)| (x, y, self.gen));
+                if coord != self.hovered {
+                    self.hovered = coord;
+                    self.preview = None;
+                    if let Some(coord) = coord {
+                        self.worker.send(Request::Probe(coord));
+                    }
+                    return true;
+                }
+            }
             Msg::DataReceived(response) => {
                 match response {
                     Response::Update(UpdateMessage {
                         world,
+                        aggregates,
                         cells,
-                        status,
                         paused,
                         found_count,
+                        steps,
                         timing,
                         config,
                     }) => {
                         if let Some(world) = world {
-                            self.world = world;
+                            self.search.world = world.into_text();
+                            self.hovered = None;
+                            self.preview = None;
+                        }
+                        if let Some(aggregates) = aggregates {
+                            self.aggregates = Some(aggregates);
                         }
                         if let Some(cells) = cells {
-                            self.cells = cells;
+                            self.search.cells = cells;
                         }
                         if let Some(config) = config {
                             self.config = config;
@@ -207,12 +303,18 @@ impl Component for App {
                         if paused {
                             self.stop_job()
                         }
-                        self.status = status;
-                        self.found_count = found_count;
+                        self.search.found_count = found_count;
+                        self.search.steps = steps;
                         if let Some(timing) = timing {
                             self.timing = timing;
                         }
                     }
+                    Response::Status(msg) => {
+                        let gen = (!self.max_partial).then_some(self.gen);
+                        if let Some(config) = self.search.apply_status(msg, gen) {
+                            self.config = config;
+                        }
+                    }
                     Response::Error {
                         message,
                         goto_config,
@@ -229,6 +331,16 @@ impl Component for App {
                         }
                         Err(e) => error!("Error generating save file: {}", e),
                     },
+                    Response::Export(name, text) => {
+                        let extension =
+                            format::by_name(&name).map_or("txt", |formatter| formatter.extension());
+                        download(&text, &format!("result.{}", extension), "text/plain").unwrap()
+                    }
+                    Response::Probe(coord, result) => {
+                        if self.hovered == Some(coord) {
+                            self.preview = Some(result);
+                        }
+                    }
                 };
                 return true;
             }
@@ -347,7 +459,13 @@ impl App {
                                         </abbr>
                                     </label>
                                 </div>
-                                <World world={self.world.clone()}/>
+                                <World world={self.search.world.clone()}
+                                    aggregates={self.aggregates.clone()}
+                                    width={self.config.width}
+                                    show_world={self.config.width * self.config.height
+                                        <= MAX_SHOWN_CELLS}
+                                    preview={self.preview_cells()}
+                                    onhover={ctx.link().callback(Msg::Hover)}/>
                                 { self.buttons(ctx) }
                             </div>
                             <div class="mui-tabs__pane" id="pane-settings">
@@ -399,14 +517,21 @@ impl App {
                         { "Cell count" }
                     </abbr>
                     { ": " }
-                    { self.cells }
+                    { self.search.cells }
                 </li>
                 <li class={if self.find_all { "" } else { "mui--hide" }}>
                     <abbr title="Number of found results.">
                         { "Found" }
                     </abbr>
                     { ": " }
-                    { self.found_count }
+                    { self.search.found_count }
+                </li>
+                <li class={if self.paused { "" } else { "mui--hide" }}>
+                    <abbr title="Number of steps walked during the search.">
+                        { "Steps" }
+                    </abbr>
+                    { ": " }
+                    { self.search.steps }
                 </li>
                 <li class={if self.paused { "" } else { "mui--hide" }}>
                     <abbr title="Time taken by the search.">
@@ -415,9 +540,19 @@ impl App {
                     { ": " }
                     { format!("{:?}", self.timing) }
                 </li>
+                if let Some(text) = self.preview_text() {
+                    <li>
+                        <abbr title="The cells determined by setting the cell under the mouse \
+                            alive, which are highlighted.">
+                            { "Preview" }
+                        </abbr>
+                        { ": " }
+                        { text }
+                    </li>
+                }
                 <li>
                     {
-                        match self.status {
+                        match self.search.status {
                             Status::Initial => "",
                             Status::Found => "Found a result.",
                             Status::None => "No more result.",
@@ -433,6 +568,33 @@ impl App {
         }
     }
 
+    /// The cells in the displayed generation that are determined by
+    /// setting the hovered cell alive.
+    fn preview_cells(&self) -> Vec<(i32, i32)> {
+        match &self.preview {
+            Some(Ok(consequences)) => consequences
+                .iter()
+                .filter(|&&((_, _, t), _)| t == self.gen)
+                .map(|&((x, y, _), _)| (x, y))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn preview_text(&self) -> Option<String> {
+        let (x, y, t) = self.hovered?;
+        match self.preview.as_ref()? {
+            Ok(consequences) => Some(format!(
+                "Setting ({}, {}, {}) alive determines {} other cells.",
+                x,
+                y,
+                t,
+                consequences.len()
+            )),
+            Err(message) => Some(message.clone()),
+        }
+    }
+
     fn buttons(&self, ctx: &Context<Self>) -> Html {
         html! {
             <div class="buttons">
@@ -473,6 +635,31 @@ impl App {
                         </abbr>
                     </span>
                 </button>
+                <button class="mui-btn mui-btn--raised"
+                    disabled={!self.paused}
+                    onclick={ctx.link().callback(|_| Msg::Export)}>
+                    <i class="fas fa-file-export"></i>
+                    <span class="mui--hidden-xs">
+                        <abbr title="Export the displayed generation in the chosen format.">
+                            { "Export" }
+                        </abbr>
+                    </span>
+                </button>
+                <div class="mui-select export-format">
+                    <select id="export-format"
+                        onchange={ctx.link().batch_callback(|e: Event| {
+                            let select = e.target()?.dyn_into::<HtmlSelectElement>().ok()?;
+                            Some(Msg::SetExportFormat(select.value()))
+                        })}>
+                        {
+                            for format::formatters().into_iter().map(|formatter| html! {
+                                <option selected={formatter.name() == self.export_format}>
+                                    { formatter.name() }
+                                </option>
+                            })
+                        }
+                    </select>
+                </div>
                 <button class="mui-btn mui-btn--raised"
                     onclick={ctx.link().batch_callback(|_| {
                         click_button("load").unwrap();
@@ -529,3 +716,84 @@ fn click_button(id: &str) -> Result<(), JsValue> {
     button.click();
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn initial() -> SearchState {
+        SearchState {
+            status: Status::Initial,
+            world: String::from("partial"),
+            cells: 1,
+            found_count: 0,
+            steps: 0,
+        }
+    }
+
+    #[test]
+    fn apply_searching() {
+        let mut search = initial();
+        let msg = StatusMessage::Searching(Progress {
+            steps: 42,
+            populations: vec![3, 5],
+        });
+        assert_eq!(search.apply_status(msg, Some(1)), None);
+        assert_eq!(
+            search,
+            SearchState {
+                status: Status::Searching,
+                cells: 5,
+                steps: 42,
+                ..initial()
+            }
+        );
+    }
+
+    #[test]
+    fn apply_found() {
+        let config = Config::new(2, 1, 2).set_max_cell_count(1);
+        let msg = StatusMessage::Found(FoundResult {
+            rles: vec![String::from("gen 0"), String::from("gen 1")],
+            populations: vec![2, 1],
+            found_count: 1,
+            steps: 100,
+            config: Some(config.clone()),
+        });
+
+        let mut search = initial();
+        assert_eq!(search.apply_status(msg.clone(), Some(1)), Some(config));
+        assert_eq!(
+            search,
+            SearchState {
+                status: Status::Found,
+                world: String::from("gen 1"),
+                cells: 1,
+                found_count: 1,
+                steps: 100,
+            }
+        );
+
+        // The max partial result stays on display.
+        let mut search = initial();
+        search.apply_status(msg, None);
+        assert_eq!(search.status, Status::Found);
+        assert_eq!(search.world, "partial");
+        assert_eq!(search.cells, 1);
+    }
+
+    #[test]
+    fn apply_initial_and_none() {
+        let mut search = initial();
+        assert_eq!(search.apply_status(StatusMessage::None, Some(0)), None);
+        assert_eq!(
+            search,
+            SearchState {
+                status: Status::None,
+                ..initial()
+            }
+        );
+        assert_eq!(search.apply_status(StatusMessage::Initial, Some(0)), None);
+        assert_eq!(search, initial());
+    }
+}
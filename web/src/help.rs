@@ -1,5 +1,9 @@
 use once_cell::sync::Lazy;
 use pulldown_cmark::{html::push_html, Parser};
+use rlifesrc_lib::{
+    docs::{self, VariantDoc},
+    rules::supported_formats,
+};
 use std::include_str;
 use web_sys::Node;
 use yew::{virtual_dom::VNode, Component, Context, Html};
@@ -7,11 +11,33 @@ use yew::{virtual_dom::VNode, Component, Context, Html};
 const HELP_TEXT: &str = include_str!("help.md");
 
 static HELP_HTML: Lazy<String> = Lazy::new(|| {
+    let formats: String = supported_formats()
+        .into_iter()
+        .map(|format| format!("* {}\n", format))
+        .collect();
+    let help_text = HELP_TEXT
+        .replace("{supported_formats}", &formats)
+        .replace("{transforms}", &variant_list(docs::TRANSFORM))
+        .replace("{symmetries}", &variant_list(docs::SYMMETRY))
+        .replace("{search_orders}", &variant_list(docs::SEARCH_ORDER))
+        .replace("{new_states}", &variant_list(docs::NEW_STATE));
     let mut html_output = String::new();
-    push_html(&mut html_output, Parser::new(HELP_TEXT));
+    push_html(&mut html_output, Parser::new(&help_text));
     html_output
 });
 
+/// Lists the choices of an option in Markdown, each with its explanation.
+fn variant_list(variants: &[VariantDoc]) -> String {
+    variants
+        .iter()
+        .map(|variant| {
+            // Indents the other lines, so that they stay in the list item.
+            let doc = variant.doc.replace('\n', "\n  ");
+            format!("* `{}`: {}\n", variant.name, doc)
+        })
+        .collect()
+}
+
 pub struct Help;
 
 impl Component for Help {
@@ -34,3 +60,26 @@ impl Component for Help {
         VNode::VRef(Node::from(html))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn variants() {
+        let list = variant_list(docs::SEARCH_ORDER);
+        assert!(list.starts_with(
+            "* `RowFirst`: Searches all cells of one row before going to the next row.\n  \n  \
+             ```plaintext\n  123\n"
+        ));
+        assert_eq!(list.matches("\n* ").count(), docs::SEARCH_ORDER.len() - 1);
+        for placeholder in [
+            "{transforms}",
+            "{symmetries}",
+            "{search_orders}",
+            "{new_states}",
+        ] {
+            assert!(HELP_TEXT.contains(placeholder));
+        }
+    }
+}
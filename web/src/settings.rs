@@ -1,7 +1,9 @@
 use gloo::dialogs;
 use log::warn;
 use rlifesrc_lib::{
-    rules::NtLifeGen, Config, KnownCell, NewState, SearchOrder, Symmetry, Transform,
+    docs::{self, VariantDoc},
+    rules::NtLifeGen,
+    AlgorithmChoice, Config, KnownCell, NewState, SearchOrder, Symmetry, Transform,
 };
 use std::matches;
 use wasm_bindgen::JsCast;
@@ -12,6 +14,7 @@ pub struct Settings {
     config: Config,
     rule_is_valid: bool,
     known_cells_string: Option<String>,
+    invalid_speed: Option<String>,
 }
 
 #[derive(Clone, PartialEq, Properties)]
@@ -27,18 +30,75 @@ pub enum Msg {
     SetPeriod(i32),
     SetDx(i32),
     SetDy(i32),
+    SetSpeed(String),
     SetTrans(Transform),
     SetSym(Symmetry),
     SetRule(String),
     SetOrder(Option<SearchOrder>),
     SetChoose(NewState),
+    SetAliveProbability(f64),
     SetMax(Option<u32>),
     SetDiag(Option<i32>),
     SetKnown(String),
     SetReduce,
     SetSkipSubperiod,
     SetSkipSubsym,
-    SetBackjump,
+    SetAlgorithm(AlgorithmChoice),
+}
+
+/// Updates the configuration according to a message.
+///
+/// Messages that do not directly set a field of the configuration,
+/// i.e., [`Msg::Apply`] and [`Msg::SetKnown`], and [`Msg::SetSpeed`]
+/// with an invalid speed, are returned unchanged,
+/// and should be handled by the component.
+///
+/// This does not depend on the UI, so that it can be tested.
+fn update_config(config: &mut Config, msg: Msg) -> Option<Msg> {
+    match msg {
+        Msg::SetWidth(width) => {
+            config.width = width;
+            if config.require_square_world() {
+                config.height = width;
+            }
+        }
+        Msg::SetHeight(height) => {
+            config.height = height;
+            if config.require_square_world() {
+                config.width = height;
+            }
+        }
+        Msg::SetPeriod(period) => config.period = period,
+        Msg::SetDx(dx) => config.dx = dx,
+        Msg::SetDy(dy) => config.dy = dy,
+        Msg::SetSpeed(speed) => match config.clone().set_speed(&speed) {
+            Ok(new_config) => *config = new_config,
+            Err(_) => return Some(Msg::SetSpeed(speed)),
+        },
+        Msg::SetTrans(transform) => config.transform = transform,
+        Msg::SetSym(symmetry) => config.symmetry = symmetry,
+        Msg::SetRule(rule_string) => config.rule_string = rule_string,
+        Msg::SetOrder(search_order) => config.search_order = search_order,
+        Msg::SetChoose(new_state) => config.new_state = new_state,
+        Msg::SetAliveProbability(alive_probability) => {
+            config.new_state = NewState::RandomBiased { alive_probability }
+        }
+        Msg::SetMax(max_cell_count) => config.max_cell_count = max_cell_count,
+        Msg::SetDiag(diagonal_width) => config.diagonal_width = diagonal_width,
+        Msg::SetReduce => config.reduce_max ^= true,
+        Msg::SetSkipSubperiod => config.skip_subperiod ^= true,
+        Msg::SetSkipSubsym => config.skip_subsymmetry ^= true,
+        Msg::SetAlgorithm(algorithm) => config.algorithm = algorithm,
+        msg @ (Msg::Apply | Msg::SetKnown(_)) => return Some(msg),
+    }
+    None
+}
+
+/// The explanation of a choice of an option, shown when the mouse is over it.
+///
+/// This is the first paragraph of the doc comment of the variant in the lib.
+fn variant_title(variants: &'static [VariantDoc], variant: &str) -> &'static str {
+    docs::find(variants, variant).map_or("", VariantDoc::summary)
 }
 
 impl Component for Settings {
@@ -51,37 +111,20 @@ impl Component for Settings {
             config: ctx.props().config.clone(),
             rule_is_valid,
             known_cells_string: None,
+            invalid_speed: None,
         }
     }
 
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
-        match msg {
-            Msg::SetWidth(width) => {
-                self.config.width = width;
-                if self.config.require_square_world() {
-                    self.config.height = width;
+        let is_rule = matches!(msg, Msg::SetRule(_));
+        match update_config(&mut self.config, msg) {
+            None => {
+                if is_rule {
+                    self.rule_is_valid = self.config.rule_string.parse::<NtLifeGen>().is_ok();
                 }
+                self.invalid_speed = None;
             }
-            Msg::SetHeight(height) => {
-                self.config.height = height;
-                if self.config.require_square_world() {
-                    self.config.width = height;
-                }
-            }
-            Msg::SetPeriod(period) => self.config.period = period,
-            Msg::SetDx(dx) => self.config.dx = dx,
-            Msg::SetDy(dy) => self.config.dy = dy,
-            Msg::SetTrans(transform) => self.config.transform = transform,
-            Msg::SetSym(symmetry) => self.config.symmetry = symmetry,
-            Msg::SetRule(rule_string) => {
-                self.rule_is_valid = rule_string.parse::<NtLifeGen>().is_ok();
-                self.config.rule_string = rule_string;
-            }
-            Msg::SetOrder(search_order) => self.config.search_order = search_order,
-            Msg::SetChoose(new_state) => self.config.new_state = new_state,
-            Msg::SetMax(max_cell_count) => self.config.max_cell_count = max_cell_count,
-            Msg::SetDiag(diagonal_width) => self.config.diagonal_width = diagonal_width,
-            Msg::SetKnown(known_cells_string) => {
+            Some(Msg::SetKnown(known_cells_string)) => {
                 if known_cells_string.is_empty() {
                     self.config.known_cells = Vec::new();
                     self.known_cells_string = None;
@@ -106,18 +149,18 @@ impl Component for Settings {
                     }
                 }
             }
-            Msg::SetReduce => self.config.reduce_max ^= true,
-            Msg::SetSkipSubperiod => self.config.skip_subperiod ^= true,
-            Msg::SetSkipSubsym => self.config.skip_subsymmetry ^= true,
-            Msg::SetBackjump => self.config.backjump ^= true,
-            Msg::Apply => {
+            Some(Msg::SetSpeed(speed)) => self.invalid_speed = Some(speed),
+            Some(Msg::Apply) => {
                 if self.known_cells_string.is_some() {
                     dialogs::alert("Invalid format for known cells.");
+                } else if self.invalid_speed.is_some() {
+                    dialogs::alert("Invalid speed.");
                 } else {
                     ctx.props().callback.emit(self.config.clone());
                 }
                 return false;
             }
+            Some(_) => unreachable!(),
         }
         true
     }
@@ -127,6 +170,7 @@ impl Component for Settings {
             self.config = prop.config.clone();
             self.rule_is_valid = self.config.rule_string.parse::<NtLifeGen>().is_ok();
             self.known_cells_string = None;
+            self.invalid_speed = None;
             true
         }
     }
@@ -168,17 +212,19 @@ impl Settings {
                 { self.set_period(ctx) }
                 { self.set_dx(ctx) }
                 { self.set_dy(ctx) }
+                { self.set_speed(ctx) }
                 { self.set_diag(ctx) }
                 { self.set_trans(ctx) }
                 { self.set_sym(ctx) }
                 { self.set_max(ctx) }
                 { self.set_order(ctx) }
                 { self.set_choose(ctx) }
+                { self.set_alive_probability(ctx) }
                 { self.set_known(ctx) }
                 { self.set_reduce(ctx) }
                 { self.set_skip_subperiod(ctx) }
                 { self.set_skip_subsym(ctx) }
-                { self.set_backjump(ctx) }
+                { self.set_algorithm(ctx) }
             </div>
         }
     }
@@ -192,7 +238,7 @@ impl Settings {
             <div class="mui-textfield">
                 <label for="set_rule">
                     <abbr title="Rule of the cellular automaton. \
-                        Supports Life-like, isotropic non-totalistic, hexagonal, \
+                        Supports Life-like, isotropic non-totalistic, hexagonal, von Neumann, \
                         MAP rules, and their corresponding Generations rules.">
                         { "Rule" }
                     </abbr>
@@ -315,6 +361,34 @@ impl Settings {
         }
     }
 
+    fn set_speed(&self, ctx: &Context<Self>) -> Html {
+        let onchange = ctx.link().batch_callback(|e: Event| {
+            let input = e.target()?.dyn_into::<HtmlInputElement>().ok()?;
+            Some(Msg::SetSpeed(input.value()))
+        });
+        html! {
+            <div class="mui-textfield">
+                <label for="set_speed">
+                    <abbr title="Speed of the pattern, which sets the period, dx and dy. \
+                        Supports c/N and Kc/N for orthogonal speeds, \
+                        with an optional suffix o (orthogonal) or d (diagonal), \
+                        and (a, b)c/N for the translation (a, b).">
+                        { "Speed" }
+                    </abbr>
+                    { ":" }
+                </label>
+                <input id="set_speed"
+                    type="text"
+                    class={self.invalid_speed.is_some().then_some("mui--is-invalid")}
+                    value={self
+                        .invalid_speed
+                        .clone()
+                        .unwrap_or_else(|| self.config.speed_string())}
+                    onchange={onchange}/>
+            </div>
+        }
+    }
+
     fn set_diag(&self, ctx: &Context<Self>) -> Html {
         let value = self.config.diagonal_width.unwrap_or(0);
         let onchange = ctx.link().batch_callback(|e: Event| {
@@ -370,6 +444,11 @@ impl Settings {
                     value={value.to_string()}
                     min="0"
                     onchange={onchange}/>
+                {
+                    for self.config.infeasibility_check().map(|hint| html! {
+                        <div class="mui--text-caption mui--text-danger">{ hint }</div>
+                    })
+                }
             </div>
         }
     }
@@ -401,33 +480,41 @@ impl Settings {
                     { ":" }
                 </label>
                 <select id="set_trans" onchange={onchange}>
-                    <option selected={self.config.transform == Transform::Id}>
+                    <option selected={self.config.transform == Transform::Id}
+                        title={variant_title(docs::TRANSFORM, "Id")}>
                         { "Id" }
                     </option>
                     <option selected={self.config.transform == Transform::Rotate90}
+                        title={variant_title(docs::TRANSFORM, "Rotate90")}
                         disabled={self.config.width != self.config.height || self.config.diagonal_width.is_some()}>
                         { "Rotate 90°" }
                     </option>
-                    <option selected={self.config.transform == Transform::Rotate180}>
+                    <option selected={self.config.transform == Transform::Rotate180}
+                        title={variant_title(docs::TRANSFORM, "Rotate180")}>
                         { "Rotate 180°" }
                     </option>
                     <option selected={self.config.transform == Transform::Rotate270}
+                        title={variant_title(docs::TRANSFORM, "Rotate270")}
                         disabled={self.config.width != self.config.height || self.config.diagonal_width.is_some()}>
                         { "Rotate 270°" }
                     </option>
                     <option selected={self.config.transform == Transform::FlipCol}
+                        title={variant_title(docs::TRANSFORM, "FlipCol")}
                         disabled={self.config.diagonal_width.is_some()}>
                         { "Flip |" }
                     </option>
                     <option selected={self.config.transform == Transform::FlipRow}
+                        title={variant_title(docs::TRANSFORM, "FlipRow")}
                         disabled={self.config.diagonal_width.is_some()}>
                         { "Flip -" }
                     </option>
                     <option selected={self.config.transform == Transform::FlipDiag}
+                        title={variant_title(docs::TRANSFORM, "FlipDiag")}
                         disabled={self.config.width != self.config.height}>
                         { "Flip \\" }
                     </option>
                     <option selected={self.config.transform == Transform::FlipAntidiag}
+                        title={variant_title(docs::TRANSFORM, "FlipAntidiag")}
                         disabled={self.config.width != self.config.height}>
                         { "Flip /" }
                     </option>
@@ -462,41 +549,51 @@ impl Settings {
                     { ":" }
                 </label>
                 <select id="set_sym" onchange={onchange}>
-                    <option selected={self.config.symmetry == Symmetry::C1}>
+                    <option selected={self.config.symmetry == Symmetry::C1}
+                        title={variant_title(docs::SYMMETRY, "C1")}>
                         { "C1" }
                     </option>
-                    <option selected={self.config.symmetry == Symmetry::C2}>
+                    <option selected={self.config.symmetry == Symmetry::C2}
+                        title={variant_title(docs::SYMMETRY, "C2")}>
                         { "C2" }
                     </option>
                     <option selected={self.config.symmetry == Symmetry::C4}
+                        title={variant_title(docs::SYMMETRY, "C4")}
                         disabled={self.config.width != self.config.height || self.config.diagonal_width.is_some()}>
                         { "C4" }
                     </option>
                     <option selected={self.config.symmetry == Symmetry::D2Col}
+                        title={variant_title(docs::SYMMETRY, "D2Col")}
                         disabled={self.config.diagonal_width.is_some()}>
                         { "D2|" }
                     </option>
                     <option selected={self.config.symmetry == Symmetry::D2Row}
+                        title={variant_title(docs::SYMMETRY, "D2Row")}
                         disabled={self.config.diagonal_width.is_some()}>
                         { "D2-" }
                     </option>
                     <option selected={self.config.symmetry == Symmetry::D2Diag}
+                        title={variant_title(docs::SYMMETRY, "D2Diag")}
                         disabled={self.config.width != self.config.height}>
                         { "D2\\" }
                     </option>
                     <option selected={self.config.symmetry == Symmetry::D2Antidiag}
+                        title={variant_title(docs::SYMMETRY, "D2Antidiag")}
                         disabled={self.config.width != self.config.height}>
                         { "D2/" }
                     </option>
                     <option selected={self.config.symmetry == Symmetry::D4Ortho}
+                        title={variant_title(docs::SYMMETRY, "D4Ortho")}
                         disabled={self.config.diagonal_width.is_some()}>
                         { "D4+" }
                     </option>
                     <option selected={self.config.symmetry == Symmetry::D4Diag}
+                        title={variant_title(docs::SYMMETRY, "D4Diag")}
                         disabled={self.config.width != self.config.height}>
                         { "D4X" }
                     </option>
                     <option selected={self.config.symmetry == Symmetry::D8}
+                        title={variant_title(docs::SYMMETRY, "D8")}
                         disabled={self.config.width != self.config.height || self.config.diagonal_width.is_some()}>
                         { "D8" }
                     </option>
@@ -531,16 +628,19 @@ impl Settings {
                         { "Automatic" }
                     </option>
                     <option value="Column"
-                        selected={self.config.search_order == Some(SearchOrder::ColumnFirst)}>
+                        selected={self.config.search_order == Some(SearchOrder::ColumnFirst)}
+                        title={variant_title(docs::SEARCH_ORDER, "ColumnFirst")}>
                         { "Column first" }
                     </option>
                     <option value="Row"
-                        selected={self.config.search_order == Some(SearchOrder::RowFirst)}>
+                        selected={self.config.search_order == Some(SearchOrder::RowFirst)}
+                        title={variant_title(docs::SEARCH_ORDER, "RowFirst")}>
                         { "Row first" }
                     </option>
                     <option value="Diagonal"
                         disabled={self.config.width != self.config.height}
-                        selected={self.config.search_order == Some(SearchOrder::Diagonal)}>
+                        selected={self.config.search_order == Some(SearchOrder::Diagonal)}
+                        title={variant_title(docs::SEARCH_ORDER, "Diagonal")}>
                         { "Diagonal" }
                     </option>
                     <option value=""
@@ -572,13 +672,17 @@ impl Settings {
                     { ":" }
                 </label>
                 <select id="set_choose" onchange={onchange}>
-                    <option selected={self.config.new_state == NewState::ChooseAlive}>
+                    <option selected={self.config.new_state == NewState::ChooseAlive}
+                        title={variant_title(docs::NEW_STATE, "ChooseAlive")}>
                         { "Alive" }
                     </option>
-                    <option selected={self.config.new_state == NewState::ChooseDead}>
+                    <option selected={self.config.new_state == NewState::ChooseDead}
+                        title={variant_title(docs::NEW_STATE, "ChooseDead")}>
                         { "Dead" }
                     </option>
-                    <option selected={self.config.new_state == NewState::Random}>
+                    <option selected={matches!(self.config.new_state,
+                        NewState::Random | NewState::RandomBiased { .. })}
+                        title={variant_title(docs::NEW_STATE, "Random")}>
                         { "Random" }
                     </option>
                 </select>
@@ -586,6 +690,39 @@ impl Settings {
         }
     }
 
+    fn set_alive_probability(&self, ctx: &Context<Self>) -> Html {
+        let (value, disabled) = match self.config.new_state {
+            NewState::RandomBiased { alive_probability } => (alive_probability, false),
+            NewState::Random => (0.5, false),
+            _ => (0.5, true),
+        };
+        let onchange = ctx.link().batch_callback(|e: Event| {
+            let input = e.target()?.dyn_into::<HtmlInputElement>().ok()?;
+            input.value().parse().ok().map(Msg::SetAliveProbability)
+        });
+        html! {
+            <div class="mui-textfield">
+                <label for="set_alive_probability">
+                    <abbr title="Probability of choosing a living cell when choosing randomly.\n\
+                        Should be strictly between 0 and 1.\n\
+                        For Generations rules, the remaining probability is distributed \
+                        uniformly over the other states.">
+                        { "Alive probability" }
+                    </abbr>
+                    { ":" }
+                </label>
+                <input id="set_alive_probability"
+                    type="number"
+                    value={value.to_string()}
+                    min="0"
+                    max="1"
+                    step="0.05"
+                    disabled={disabled}
+                    onchange={onchange}/>
+            </div>
+        }
+    }
+
     fn set_known(&self, ctx: &Context<Self>) -> Html {
         let value = if let Some(known_cells_string) = &self.known_cells_string {
             known_cells_string.clone()
@@ -668,20 +805,138 @@ impl Settings {
         }
     }
 
-    fn set_backjump(&self, ctx: &Context<Self>) -> Html {
+    fn set_algorithm(&self, ctx: &Context<Self>) -> Html {
+        let onchange = ctx.link().batch_callback(|e: Event| {
+            let select = e.target()?.dyn_into::<HtmlSelectElement>().ok()?;
+            match select.value().as_ref() {
+                "Auto" => Some(Msg::SetAlgorithm(AlgorithmChoice::Auto)),
+                "LifeSrc" => Some(Msg::SetAlgorithm(AlgorithmChoice::LifeSrc)),
+                "Backjump" => Some(Msg::SetAlgorithm(AlgorithmChoice::Backjump)),
+                "PreferBackjump" => Some(Msg::SetAlgorithm(AlgorithmChoice::PreferBackjump)),
+                _ => None,
+            }
+        });
         html! {
-            <div class="mui-checkbox">
-                <label>
-                    <input id="set_backjump"
-                        type="checkbox"
-                        checked={self.config.backjump}
-                        onclick={ctx.link().callback(|_| Msg::SetBackjump)}/>
-                    <abbr title="The current implementation of backjumping is very slow, \
-                        only useful for large (e.g., 64x64) still lifes.">
-                        { "(Experimental) Enable backjumping." }
+            <div class="mui-select">
+                <label for="set_algorithm">
+                    <abbr title="The search algorithm. \
+                        Auto currently always chooses LifeSrc. \
+                        Backjump is experimental and very slow, \
+                        only useful for large (e.g., 64x64) still lifes. \
+                        It does not support Generations rules and max cell count. \
+                        PreferBackjump chooses Backjump when it is supported, \
+                        and LifeSrc otherwise.">
+                        { "Algorithm" }
                     </abbr>
+                    { ":" }
                 </label>
+                <select id="set_algorithm" onchange={onchange}>
+                    <option selected={self.config.algorithm == AlgorithmChoice::Auto}>
+                        { "Auto" }
+                    </option>
+                    <option selected={self.config.algorithm == AlgorithmChoice::LifeSrc}>
+                        { "LifeSrc" }
+                    </option>
+                    <option selected={self.config.algorithm == AlgorithmChoice::Backjump}>
+                        { "Backjump" }
+                    </option>
+                    <option selected={self.config.algorithm == AlgorithmChoice::PreferBackjump}>
+                        { "PreferBackjump" }
+                    </option>
+                </select>
             </div>
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Applies a sequence of messages to the default configuration.
+    fn config_from_msgs(msgs: Vec<Msg>) -> Config {
+        let mut config = Config::default();
+        for msg in msgs {
+            assert!(update_config(&mut config, msg).is_none());
+        }
+        config
+    }
+
+    #[test]
+    fn default_config() {
+        assert_eq!(config_from_msgs(vec![]), Config::default());
+    }
+
+    #[test]
+    fn spaceship_config() {
+        let config = config_from_msgs(vec![
+            Msg::SetWidth(16),
+            Msg::SetHeight(5),
+            Msg::SetPeriod(3),
+            Msg::SetDy(1),
+        ]);
+        assert_eq!(config, Config::new(16, 5, 3).set_translate(0, 1));
+    }
+
+    #[test]
+    fn full_config() {
+        let config = config_from_msgs(vec![
+            Msg::SetWidth(10),
+            Msg::SetHeight(10),
+            Msg::SetPeriod(4),
+            Msg::SetDx(1),
+            Msg::SetDy(1),
+            Msg::SetSym(Symmetry::C2),
+            Msg::SetOrder(Some(SearchOrder::Diagonal)),
+            Msg::SetChoose(NewState::ChooseDead),
+            Msg::SetMax(Some(20)),
+            Msg::SetReduce,
+        ]);
+        assert_eq!(
+            config,
+            Config::new(10, 10, 4)
+                .set_translate(1, 1)
+                .set_symmetry(Symmetry::C2)
+                .set_search_order(SearchOrder::Diagonal)
+                .set_new_state(NewState::ChooseDead)
+                .set_max_cell_count(20)
+                .set_reduce_max(true)
+        );
+    }
+
+    #[test]
+    fn speed() {
+        let config = config_from_msgs(vec![
+            Msg::SetWidth(16),
+            Msg::SetHeight(5),
+            Msg::SetSpeed(String::from("c/3")),
+        ]);
+        assert_eq!(config, Config::new(16, 5, 3).set_translate(0, 1));
+        let config = config_from_msgs(vec![
+            Msg::SetSpeed(String::from("(2, 1)c/6")),
+            Msg::SetPeriod(12),
+        ]);
+        assert_eq!(config, Config::new(16, 16, 12).set_translate(2, 1));
+        assert_eq!(config.speed_string(), "(2, 1)c/12");
+
+        let mut config = Config::default();
+        assert!(matches!(
+            update_config(&mut config, Msg::SetSpeed(String::from("c/0"))),
+            Some(Msg::SetSpeed(speed)) if speed == "c/0"
+        ));
+        assert_eq!(config, Config::default());
+    }
+
+    #[test]
+    fn variant_titles() {
+        assert_eq!(
+            variant_title(docs::SYMMETRY, "C2"),
+            "Symmetry under 180° rotation."
+        );
+        assert_eq!(
+            variant_title(docs::SEARCH_ORDER, "RowFirst"),
+            "Searches all cells of one row before going to the next row."
+        );
+        assert_eq!(variant_title(docs::TRANSFORM, "Rotate45"), "");
+    }
+}
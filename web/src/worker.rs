@@ -1,9 +1,14 @@
+use crate::world::MAX_SHOWN_CELLS;
 use gloo::timers::callback::Timeout;
 use instant::Instant;
 use log::{debug, error};
-use rlifesrc_lib::{save::WorldSer, Config, PolyWorld, Status};
+use rlifesrc_lib::{
+    format::{self, PatternFormatter},
+    save::WorldSer,
+    Config, Coord, PolyWorld, State, Status, WorldSnapshot, ALIVE,
+};
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use std::{collections::HashSet, time::Duration};
 use yew_agent::{HandlerId, Public, Worker as YewWorker, WorkerLink};
 
 const VIEW_FREQ: u64 = 100000;
@@ -18,26 +23,145 @@ pub enum Request {
     MaxPartial,
     Save,
     Load(WorldSer),
+    /// Displays a generation of the world in the format with the given name.
+    Export(String, i32),
+    /// Previews the cells determined by setting the cell at the given
+    /// coordinates alive, without changing the world.
+    Probe(Coord),
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub enum Response {
     Update(UpdateMessage),
-    Error { message: String, goto_config: bool },
+    Status(StatusMessage),
+    Error {
+        message: String,
+        goto_config: bool,
+    },
     Save(WorldSer),
+    /// The name of the format, and the displayed world.
+    Export(String, String),
+    /// The probed coordinates, and the cells determined by setting that cell
+    /// alive, or the error message if this leads to a conflict.
+    Probe(Coord, Result<Vec<(Coord, State)>, String>),
+}
+
+/// The world to display.
+#[derive(Debug, Serialize, Deserialize)]
+pub enum WorldView {
+    /// Already displayed as text.
+    Text(String),
+    /// A snapshot of the world and the generation to display,
+    /// so that it is displayed in the UI thread instead of the worker.
+    Snapshot(Box<WorldSnapshot>, i32),
+}
+
+impl WorldView {
+    /// Displays the world as text.
+    pub fn into_text(self) -> String {
+        match self {
+            Self::Text(text) => text,
+            Self::Snapshot(snapshot, gen) => snapshot.rle_gen(gen),
+        }
+    }
+}
+
+/// Aggregate data of the world, for worlds too large to display.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Aggregates {
+    /// Number of known living cells in each generation.
+    pub populations: Vec<u32>,
+    /// Number of known living cells and unknown cells in each row
+    /// of the displayed generation.
+    pub rows: Vec<(u32, u32)>,
+    /// Coordinates of the next unknown cell to be decided.
+    pub frontier: Option<Coord>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UpdateMessage {
-    pub world: Option<String>,
+    pub world: Option<WorldView>,
+    pub aggregates: Option<Aggregates>,
     pub cells: Option<u32>,
-    pub status: Status,
     pub paused: bool,
     pub found_count: u32,
+    pub steps: u64,
     pub timing: Option<Duration>,
     pub config: Option<Config>,
 }
 
+/// The status of the search, sent after each chunk of the search,
+/// together with everything that should be updated at the same time.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StatusMessage {
+    /// Waiting to start.
+    Initial,
+    /// Still searching, or paused.
+    Searching(Progress),
+    /// A result is found.
+    Found(FoundResult),
+    /// No more result.
+    None,
+}
+
+/// A lightweight snapshot of the search progress.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Progress {
+    /// Number of steps walked during the search.
+    pub steps: u64,
+    /// Number of known living cells in each generation.
+    pub populations: Vec<u32>,
+}
+
+/// A complete result.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FoundResult {
+    /// All the generations of the result.
+    pub rles: Vec<String>,
+    /// Number of living cells in each generation.
+    pub populations: Vec<u32>,
+    /// Number of found results, including this one.
+    pub found_count: u32,
+    /// Number of steps walked during the search.
+    pub steps: u64,
+    /// The new config, if the max cell count is reduced.
+    pub config: Option<Config>,
+}
+
+impl StatusMessage {
+    /// The status of the search.
+    pub const fn status(&self) -> Status {
+        match self {
+            Self::Initial => Status::Initial,
+            Self::Searching(_) => Status::Searching,
+            Self::Found(_) => Status::Found,
+            Self::None => Status::None,
+        }
+    }
+
+    /// Builds the message from the current world, which has the given status.
+    fn new(world: &PolyWorld, status: Status, found_count: u32) -> Self {
+        match status {
+            Status::Initial => Self::Initial,
+            Status::Searching => Self::Searching(Progress {
+                steps: world.steps(),
+                populations: world.populations(),
+            }),
+            Status::Found => {
+                let config = world.config();
+                Self::Found(FoundResult {
+                    rles: world.rle_gen_rotated(0),
+                    populations: world.populations(),
+                    found_count,
+                    steps: world.steps(),
+                    config: config.reduce_max.then(|| config.clone()),
+                })
+            }
+            Status::None => Self::None,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum WorkerMsg {
     Step,
@@ -56,6 +180,7 @@ pub struct Worker {
     timing: Duration,
     link: WorkerLink<Worker>,
     timeout: Option<Timeout>,
+    subscribers: HashSet<HandlerId>,
 }
 
 impl Worker {
@@ -98,22 +223,33 @@ impl Worker {
         }
     }
 
+    fn status_message(&self) -> StatusMessage {
+        StatusMessage::new(&self.world, self.status, self.found_count)
+    }
+
+    /// Sends the status to all the connected bridges.
+    fn broadcast_status(&self) {
+        let msg = self.status_message();
+        for &id in &self.subscribers {
+            self.link.respond(id, Response::Status(msg.clone()));
+        }
+    }
+
     fn update_message(&self) -> UpdateMessageBuilder<'_> {
-        let status = self.status;
         let paused = self.paused;
-        let config = (status == Status::Found && self.world.config().reduce_max)
-            .then(|| self.world.config().clone());
         let timing = self.paused.then_some(self.timing);
         let found_count = self.found_count;
+        let steps = self.world.steps();
 
         let msg = UpdateMessage {
             world: None,
+            aggregates: None,
             cells: None,
-            status,
             paused,
             found_count,
+            steps,
             timing,
-            config,
+            config: None,
         };
         UpdateMessageBuilder { msg, worker: self }
     }
@@ -144,6 +280,7 @@ impl YewWorker for Worker {
             timing: Duration::default(),
             link,
             timeout: None,
+            subscribers: HashSet::new(),
         };
         worker.update_max_martial(false);
         worker
@@ -169,6 +306,7 @@ impl YewWorker for Worker {
                     }
                     _ => self.stop_job(),
                 }
+                self.broadcast_status();
             }
         }
     }
@@ -186,6 +324,7 @@ impl YewWorker for Worker {
                 match config.world() {
                     Ok(world) => {
                         self.reset_world(world);
+                        self.broadcast_status();
                         self.update_message().with_config().with_world(0).send(id);
                     }
                     Err(error) => {
@@ -222,6 +361,30 @@ impl YewWorker for Worker {
                 );
                 self.link.respond(id, Response::Save(world_ser));
             }
+            Request::Export(name, gen) => {
+                let result = format::by_name(&name)
+                    .ok_or_else(|| format!("Unknown format: {}", name))
+                    .and_then(|formatter| {
+                        formatter
+                            .format(&self.world.snapshot(), gen)
+                            .map_err(|e| e.to_string())
+                    });
+                let response = match result {
+                    Ok(text) => Response::Export(name, text),
+                    Err(message) => Response::Error {
+                        message,
+                        goto_config: false,
+                    },
+                };
+                self.link.respond(id, response);
+            }
+            Request::Probe(coord) => {
+                let result = self
+                    .world
+                    .probe_assignment(coord, ALIVE)
+                    .map_err(|e| e.to_string());
+                self.link.respond(id, Response::Probe(coord, result));
+            }
             Request::Load(world_ser) => {
                 self.stop_job();
                 match world_ser.world() {
@@ -251,6 +414,7 @@ impl YewWorker for Worker {
                                 }
                             }
                         }
+                        self.broadcast_status();
                         self.update_message().with_config().with_world(0).send(id);
                     }
                     Err(error) => {
@@ -270,9 +434,16 @@ impl YewWorker for Worker {
     }
 
     fn connected(&mut self, id: HandlerId) {
+        self.subscribers.insert(id);
+        self.link
+            .respond(id, Response::Status(self.status_message()));
         self.update_message().with_config().with_world(0).send(id);
     }
 
+    fn disconnected(&mut self, id: HandlerId) {
+        self.subscribers.remove(&id);
+    }
+
     fn name_of_resource() -> &'static str {
         "worker.js"
     }
@@ -289,25 +460,31 @@ struct UpdateMessageBuilder<'a> {
 
 impl<'a> UpdateMessageBuilder<'a> {
     fn with_config(mut self) -> Self {
-        if self.msg.config.is_none() {
-            self.msg.config = Some(self.worker.world.config().clone());
-        }
+        self.msg.config = Some(self.worker.world.config().clone());
         self
     }
 
     fn with_world(mut self, gen: i32) -> Self {
-        self.msg.world =
-            if self.worker.find_all && self.worker.paused && self.worker.found_count > 0 {
-                Some(self.worker.all_found[gen as usize].clone())
-            } else {
-                Some(self.worker.world.rle_gen(gen))
-            };
-        self.msg.cells = Some(self.worker.world.cell_count_gen(gen));
+        let world = &self.worker.world;
+        let config = world.config();
+        self.msg.world = if config.width * config.height > MAX_SHOWN_CELLS {
+            None
+        } else if self.worker.find_all && self.worker.paused && self.worker.found_count > 0 {
+            Some(WorldView::Text(self.worker.all_found[gen as usize].clone()))
+        } else {
+            Some(WorldView::Snapshot(Box::new(world.snapshot()), gen))
+        };
+        self.msg.aggregates = Some(Aggregates {
+            populations: world.populations(),
+            rows: world.row_summary(gen),
+            frontier: world.frontier_coord(),
+        });
+        self.msg.cells = Some(world.cell_count_gen(gen));
         self
     }
 
     fn with_max_partial(mut self) -> Self {
-        self.msg.world = Some(self.worker.max_partial.clone());
+        self.msg.world = Some(WorldView::Text(self.worker.max_partial.clone()));
         self.msg.cells = Some(self.worker.max_partial_count);
         self
     }
@@ -316,3 +493,52 @@ impl<'a> UpdateMessageBuilder<'a> {
         self.worker.link.respond(id, Response::Update(self.msg));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_message() {
+        let config = Config::new(16, 5, 3)
+            .set_translate(0, 1)
+            .set_max_cell_count(30)
+            .set_reduce_max(true);
+        let mut world = config.world().unwrap();
+        assert_eq!(
+            StatusMessage::new(&world, Status::Initial, 0),
+            StatusMessage::Initial
+        );
+
+        let status = world.search(Some(1));
+        assert_eq!(status, Status::Searching);
+        let msg = StatusMessage::new(&world, status, 0);
+        assert_eq!(msg.status(), Status::Searching);
+        assert_eq!(
+            msg,
+            StatusMessage::Searching(Progress {
+                steps: world.steps(),
+                populations: world.populations(),
+            })
+        );
+
+        let status = world.search(None);
+        assert_eq!(status, Status::Found);
+        match StatusMessage::new(&world, status, 1) {
+            StatusMessage::Found(result) => {
+                assert_eq!(result.rles.len(), 3);
+                assert_eq!(result.rles[1], world.rle_gen(1));
+                assert_eq!(result.populations, world.populations());
+                assert_eq!(result.found_count, 1);
+                assert_eq!(result.steps, world.steps());
+                assert_eq!(result.config.as_ref(), Some(world.config()));
+            }
+            msg => panic!("unexpected message: {:?}", msg),
+        }
+
+        assert_eq!(
+            StatusMessage::new(&world, Status::None, 1),
+            StatusMessage::None
+        );
+    }
+}
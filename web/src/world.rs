@@ -1,12 +1,138 @@
-use yew::{events::MouseEvent, html, Component, Context, Html, NodeRef, Properties};
+use crate::worker::Aggregates;
+use std::collections::HashSet;
+use yew::{events::MouseEvent, html, Callback, Component, Context, Html, NodeRef, Properties};
+
+/// Worlds with more cells than this in each generation are too large to display,
+/// so that only their aggregate data are shown.
+pub const MAX_SHOWN_CELLS: i32 = 1 << 16;
+
+/// Worlds with more cells than this in each generation are displayed as
+/// plain text, without previewing the cell under the mouse.
+pub const MAX_HOVERED_CELLS: usize = 1 << 12;
+
+/// Characters for the bars of a histogram, from empty to full.
+const BARS: [char; 9] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Displays a sequence of values as a histogram strip, one character per value.
+///
+/// Non-zero values are always shown as a non-empty bar.
+pub fn histogram(values: &[u32], max: u32) -> String {
+    values
+        .iter()
+        .map(|&value| {
+            if value == 0 || max == 0 {
+                BARS[0]
+            } else {
+                let level = (value as u64 * 8 + max as u64 - 1) / max as u64;
+                BARS[level.min(8) as usize]
+            }
+        })
+        .collect()
+}
+
+/// Displays the aggregate data of a world whose width is `width`.
+pub fn aggregates_text(aggregates: &Aggregates, width: i32) -> String {
+    let populations = aggregates
+        .populations
+        .iter()
+        .map(u32::to_string)
+        .collect::<Vec<_>>()
+        .join(" ");
+    let alive = aggregates
+        .rows
+        .iter()
+        .map(|&(alive, _)| alive)
+        .collect::<Vec<_>>();
+    let unknown = aggregates
+        .rows
+        .iter()
+        .map(|&(_, unknown)| unknown)
+        .collect::<Vec<_>>();
+    let frontier = match aggregates.frontier {
+        Some((x, y, t)) => format!("({}, {}, {})", x, y, t),
+        None => "none".to_owned(),
+    };
+    format!(
+        "Populations: {}\nAlive:    |{}|\nUnknown:  |{}|\nFrontier: {}\n",
+        populations,
+        histogram(&alive, width as u32),
+        histogram(&unknown, width as u32),
+        frontier
+    )
+}
+
+/// A displayed world, split into cells so that each of them can be hovered.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WorldCells<'a> {
+    /// The lines above the cells, i.e., comments and the RLE header.
+    pub header: Vec<&'a str>,
+    /// The cells in each row, and the `$` or `!` that ends the row.
+    pub rows: Vec<(Vec<&'a str>, char)>,
+}
+
+/// Splits a displayed world into cells.
+///
+/// Returns `None` if the text is not a single world in the format of
+/// [`rle_gen`](rlifesrc_lib::WorldSnapshot::rle_gen), e.g., when
+/// all the found results are displayed.
+pub fn split_cells(world: &str) -> Option<WorldCells<'_>> {
+    let mut header = Vec::new();
+    let mut rows = Vec::new();
+    let mut lines = world.lines();
+    for line in lines.by_ref() {
+        header.push(line);
+        if line.starts_with("x = ") {
+            break;
+        } else if !line.starts_with('#') {
+            return None;
+        }
+    }
+    if !header.last()?.starts_with("x = ") {
+        return None;
+    }
+    for line in lines.by_ref() {
+        let end = line.chars().last()?;
+        if end != '$' && end != '!' {
+            return None;
+        }
+        let line = &line[..line.len() - 1];
+        let mut cells = Vec::new();
+        let mut start = 0;
+        for (i, c) in line.char_indices() {
+            if !('p'..='y').contains(&c) {
+                cells.push(&line[start..i + c.len_utf8()]);
+                start = i + c.len_utf8();
+            }
+        }
+        if start != line.len() {
+            return None;
+        }
+        rows.push((cells, end));
+        if end == '!' {
+            break;
+        }
+    }
+    if rows.last()?.1 != '!' || lines.any(|line| !line.is_empty()) {
+        return None;
+    }
+    Some(WorldCells { header, rows })
+}
 
 pub struct World {
     node_ref: NodeRef,
 }
 
-#[derive(Clone, PartialEq, Eq, Properties)]
+#[derive(Clone, PartialEq, Properties)]
 pub struct Props {
     pub world: String,
+    pub aggregates: Option<Aggregates>,
+    pub width: i32,
+    pub show_world: bool,
+    /// The coordinates of the cells to highlight.
+    pub preview: Vec<(i32, i32)>,
+    /// Called with the coordinates of the cell under the mouse,
+    /// or `None` when the mouse leaves the world.
+    pub onhover: Callback<Option<(i32, i32)>>,
 }
 
 pub enum Msg {
@@ -41,12 +167,120 @@ impl Component for World {
             e.prevent_default();
             Msg::Select
         });
+        let props = ctx.props();
+        let cells = split_cells(&props.world).filter(|cells| {
+            cells.rows.iter().map(|(row, _)| row.len()).sum::<usize>() <= MAX_HOVERED_CELLS
+        });
+        html! {
+            <>
+                if let Some(aggregates) = &props.aggregates {
+                    <pre id="aggregates">
+                        { aggregates_text(aggregates, props.width) }
+                    </pre>
+                }
+                if props.show_world {
+                    <pre id="world"
+                        ref={self.node_ref.clone()}
+                        ondblclick={ondblclick}
+                        onmouseleave={props.onhover.reform(|_: MouseEvent| None)}>
+                        if let Some(cells) = cells {
+                            { self.view_cells(ctx, &cells) }
+                        } else {
+                            { &props.world }
+                        }
+                    </pre>
+                }
+            </>
+        }
+    }
+}
+
+impl World {
+    fn view_cells(&self, ctx: &Context<Self>, cells: &WorldCells) -> Html {
+        let props = ctx.props();
+        let preview = props.preview.iter().copied().collect::<HashSet<_>>();
         html! {
-            <pre id="world"
-                ref={self.node_ref.clone()}
-                ondblclick={ondblclick}>
-                { &ctx.props().world }
-            </pre>
+            <>
+                { for cells.header.iter().map(|line| html! { <>{ *line }{ "\n" }</> }) }
+                {
+                    for cells.rows.iter().enumerate().map(|(y, (row, end))| html! {
+                        <>
+                            {
+                                for row.iter().enumerate().map(|(x, cell)| {
+                                    let coord = (x as i32, y as i32);
+                                    let class = if preview.contains(&coord) { "preview" } else { "" };
+                                    html! {
+                                        <span class={class}
+                                            onmouseenter={props.onhover.reform(move |_: MouseEvent| Some(coord))}>
+                                            { *cell }
+                                        </span>
+                                    }
+                                })
+                            }
+                            { end.to_string() }{ "\n" }
+                        </>
+                    })
+                }
+            </>
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn histogram_bars() {
+        assert_eq!(histogram(&[0, 1, 4, 8], 8), " ▁▄█");
+        assert_eq!(histogram(&[1, 50, 100], 100), "▁▄█");
+        assert_eq!(histogram(&[], 8), "");
+        assert_eq!(histogram(&[3], 0), " ");
+    }
+
+    #[test]
+    fn aggregates() {
+        let aggregates = Aggregates {
+            populations: vec![12, 10, 11],
+            rows: vec![(0, 16), (4, 8), (8, 0)],
+            frontier: Some((3, 1, 2)),
+        };
+        assert_eq!(
+            aggregates_text(&aggregates, 16),
+            "Populations: 12 10 11\n\
+             Alive:    | ▂▄|\n\
+             Unknown:  |█▄ |\n\
+             Frontier: (3, 1, 2)\n"
+        );
+        let aggregates = Aggregates {
+            frontier: None,
+            ..aggregates
+        };
+        assert!(aggregates_text(&aggregates, 16).ends_with("Frontier: none\n"));
+    }
+
+    #[test]
+    fn world_cells() {
+        let world = "#C Generation 1: the background is alive, as the rule contains B0.\n\
+                     x = 3, y = 2, rule = B0/S8\n\
+                     .o?$\n\
+                     ApAB!\n";
+        assert_eq!(
+            split_cells(world),
+            Some(WorldCells {
+                header: vec![
+                    "#C Generation 1: the background is alive, as the rule contains B0.",
+                    "x = 3, y = 2, rule = B0/S8",
+                ],
+                rows: vec![(vec![".", "o", "?"], '$'), (vec!["A", "pA", "B"], '!')],
+            })
+        );
+
+        // All the found results.
+        let results = "x = 1, y = 1, rule = B3/S23\no!\nx = 1, y = 1, rule = B3/S23\n.!\n";
+        assert_eq!(split_cells(results), None);
+        assert_eq!(split_cells("Loading..."), None);
+        assert_eq!(split_cells(""), None);
+        assert_eq!(split_cells("x = 2, y = 2, rule = B3/S23\n..$\n"), None);
+    }
+}
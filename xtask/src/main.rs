@@ -0,0 +1,512 @@
+//! Build and test tasks for rlifesrc, run by `cargo xtask <TASK>`.
+//!
+//! Tasks:
+//!
+//! * `features`: checks the lib with every combination of its features.
+//! * `msrv`: checks the lib and the TUI with the minimal supported Rust version,
+//!   which must be installed with `rustup`.
+//! * `docs`: regenerates the documentation of the variants of some enums
+//!   in `lib/src/docs/generated.rs`, from their doc comments.
+//!   With `--check`, fails if that file is out of date instead.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::{exit, Command},
+};
+
+/// A feature of the lib, and the other features of the lib that it enables.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Feature {
+    name: String,
+    implies: Vec<String>,
+}
+
+/// Reads the features from the `[features]` table of a manifest,
+/// except the `default` feature, and the implicit features of
+/// the optional dependencies.
+///
+/// The manifest is assumed to be formatted like the lib's, i.e., each feature
+/// or dependency is on one line, and other lines are blank or comments.
+fn parse_features(manifest: &str) -> Vec<Feature> {
+    let mut features = Vec::new();
+    let mut table = "";
+    for line in manifest.lines().map(str::trim) {
+        if line.starts_with('[') {
+            table = line;
+            continue;
+        }
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if table == "[dependencies]" && line.contains("optional = true") {
+            if let Some((name, _)) = line.split_once('=') {
+                features.push(Feature {
+                    name: name.trim().to_owned(),
+                    implies: Vec::new(),
+                });
+            }
+        } else if table != "[features]" {
+            continue;
+        } else if let Some((name, deps)) = line.split_once('=') {
+            let name = name.trim();
+            if name == "default" {
+                continue;
+            }
+            let implies = deps
+                .trim()
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .split(',')
+                .map(|dep| dep.trim().trim_matches('"'))
+                .filter(|dep| !dep.is_empty() && !dep.contains('/'))
+                .map(str::to_owned)
+                .collect();
+            features.push(Feature {
+                name: name.to_owned(),
+                implies,
+            });
+        }
+    }
+    // Dependencies which are not optional are not features.
+    let names = features
+        .iter()
+        .map(|feature| feature.name.clone())
+        .collect::<Vec<_>>();
+    for feature in &mut features {
+        feature.implies.retain(|dep| names.contains(dep));
+    }
+    features
+}
+
+/// All the combinations of the features.
+///
+/// A combination which contains a feature but not the features it implies is
+/// skipped, since it enables the same code as the combination with them.
+fn feature_sets(features: &[Feature]) -> Vec<Vec<&str>> {
+    (0..1_u32 << features.len())
+        .map(|mask| {
+            features
+                .iter()
+                .enumerate()
+                .filter(|&(i, _)| mask & 1 << i != 0)
+                .map(|(_, feature)| feature.name.as_str())
+                .collect::<Vec<_>>()
+        })
+        .filter(|set| {
+            features
+                .iter()
+                .filter(|feature| set.contains(&feature.name.as_str()))
+                .all(|feature| {
+                    feature
+                        .implies
+                        .iter()
+                        .all(|dep| set.contains(&dep.as_str()))
+                })
+        })
+        .collect()
+}
+
+/// Reads the minimal supported Rust version from the workspace manifest.
+fn parse_msrv(manifest: &str) -> Option<&str> {
+    manifest.lines().find_map(|line| {
+        let (key, value) = line.split_once('=')?;
+        (key.trim() == "rust-version").then(|| value.trim().trim_matches('"'))
+    })
+}
+
+fn workspace_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .unwrap()
+        .to_owned()
+}
+
+fn cargo() -> Command {
+    let mut command = Command::new(env::var_os("CARGO").unwrap_or_else(|| "cargo".into()));
+    command.current_dir(workspace_root());
+    command
+}
+
+fn read_manifest(path: &Path) -> String {
+    fs::read_to_string(path).unwrap_or_else(|e| {
+        eprintln!("Error: Cannot read {}: {}", path.display(), e);
+        exit(1);
+    })
+}
+
+/// Checks the lib with every combination of its features,
+/// and returns the number of failed combinations.
+fn check_features() -> usize {
+    let manifest = read_manifest(&workspace_root().join("lib").join("Cargo.toml"));
+    let features = parse_features(&manifest);
+    let sets = feature_sets(&features);
+    let mut failed = Vec::new();
+    for (i, set) in sets.iter().enumerate() {
+        let features = set.join(",");
+        println!("[{}/{}] Features: [{}]", i + 1, sets.len(), features);
+        let status = cargo()
+            .args(["check", "--package", "rlifesrc-lib", "--all-targets"])
+            .args(["--no-default-features", "--features", &features])
+            .status();
+        if !status.is_ok_and(|status| status.success()) {
+            failed.push(features);
+        }
+    }
+    for features in &failed {
+        eprintln!("Failed with features: [{}]", features);
+    }
+    failed.len()
+}
+
+/// Checks the lib and the TUI with the minimal supported Rust version,
+/// and returns whether it succeeds.
+///
+/// The web app is not checked, because it needs another target.
+fn check_msrv() -> bool {
+    let manifest = read_manifest(&workspace_root().join("Cargo.toml"));
+    let Some(msrv) = parse_msrv(&manifest) else {
+        eprintln!("Error: No rust-version in the workspace manifest.");
+        return false;
+    };
+    println!("MSRV: {}", msrv);
+    // Runs the `cargo` of that toolchain instead of the current one.
+    let status = Command::new("cargo")
+        .current_dir(workspace_root())
+        .arg(format!("+{}", msrv))
+        .args([
+            "check",
+            "--package",
+            "rlifesrc-lib",
+            "--package",
+            "rlifesrc",
+        ])
+        .args(["--all-targets", "--all-features"])
+        .status();
+    status.is_ok_and(|status| status.success())
+}
+
+/// The enums whose variants are documented, the files where they are defined,
+/// and the names of the generated constants.
+const DOCUMENTED_ENUMS: [(&str, &str, &str); 4] = [
+    ("Symmetry", "lib/src/config/d8.rs", "SYMMETRY"),
+    ("Transform", "lib/src/config/d8.rs", "TRANSFORM"),
+    (
+        "SearchOrder",
+        "lib/src/config/search_order.rs",
+        "SEARCH_ORDER",
+    ),
+    ("NewState", "lib/src/config/mod.rs", "NEW_STATE"),
+];
+
+/// The generated file, relative to the workspace root.
+const GENERATED_DOCS: &str = "lib/src/docs/generated.rs";
+
+/// A variant of an enum, and its doc comment.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Variant {
+    variant: String,
+    name: String,
+    doc: String,
+}
+
+/// Reads the variants of the enum `name` and their doc comments
+/// from the source code.
+///
+/// The source is assumed to be formatted by rustfmt, and the attributes
+/// on the variants to be on one line each.
+fn parse_enum(source: &str, name: &str) -> Option<Vec<Variant>> {
+    let mut lines = source.lines().map(str::trim);
+    let header = format!("pub enum {} {{", name);
+    lines.by_ref().find(|line| *line == header)?;
+    let mut variants = Vec::new();
+    let mut doc = Vec::new();
+    let mut depth = 1;
+    for line in lines {
+        if depth == 1 {
+            if let Some(comment) = line.strip_prefix("///") {
+                doc.push(comment.strip_prefix(' ').unwrap_or(comment));
+                continue;
+            } else if line.starts_with("#[") || line.is_empty() {
+                continue;
+            } else if line != "}" {
+                let variant = line
+                    .split(|c: char| !c.is_alphanumeric() && c != '_')
+                    .next()
+                    .unwrap_or_default();
+                variants.push(variant_doc(variant, &doc.join("\n")));
+                doc.clear();
+            }
+        }
+        if !line.starts_with("//") {
+            depth += line.matches('{').count();
+            depth -= line.matches('}').count();
+        }
+        if depth == 0 {
+            return Some(variants);
+        }
+    }
+    None
+}
+
+/// Splits the name shown to users from the doc comment, and removes the links.
+///
+/// A doc comment starting with a paragraph like `` `D2-`. `` gives the name.
+fn variant_doc(variant: &str, doc: &str) -> Variant {
+    let doc = remove_links(doc.trim());
+    let (first, rest) = doc.split_once("\n\n").unwrap_or((&doc, ""));
+    let name = first
+        .strip_prefix('`')
+        .and_then(|first| first.strip_suffix("`."))
+        .filter(|name| !name.contains('`'));
+    match name {
+        Some(name) => Variant {
+            variant: variant.to_owned(),
+            name: name.to_owned(),
+            doc: rest.to_owned(),
+        },
+        None => Variant {
+            variant: variant.to_owned(),
+            name: variant.to_owned(),
+            doc,
+        },
+    }
+}
+
+/// Replaces the Markdown links to other items by their texts,
+/// since they are not meaningful outside the documentation of the lib.
+///
+/// Links to web pages are kept.
+fn remove_links(doc: &str) -> String {
+    let mut result = String::new();
+    let mut rest = doc;
+    while let Some(start) = rest.find('[') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find(']') else {
+            break;
+        };
+        let text = &rest[1..end];
+        let after = &rest[end + 1..];
+        if let Some(target) = after.strip_prefix('(') {
+            let Some(target_end) = target.find(')') else {
+                break;
+            };
+            if target.starts_with("http") {
+                result.push_str(&rest[..end + target_end + 3]);
+            } else {
+                result.push_str(text);
+            }
+            rest = &target[target_end + 1..];
+        } else {
+            if text.starts_with('`') && text.ends_with('`') {
+                result.push_str(text);
+            } else {
+                result.push_str(&rest[..end + 1]);
+            }
+            rest = after;
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Generates the content of `lib/src/docs/generated.rs`.
+fn generate_docs() -> Result<String, String> {
+    let mut output = String::from(
+        "// This file is generated by `cargo xtask docs` from the doc comments\n\
+         // of the variants. Do not edit it by hand.\n\
+         \n\
+         use super::VariantDoc;\n",
+    );
+    for (name, path, constant) in DOCUMENTED_ENUMS {
+        let source = fs::read_to_string(workspace_root().join(path))
+            .map_err(|e| format!("Cannot read {}: {}", path, e))?;
+        let variants =
+            parse_enum(&source, name).ok_or_else(|| format!("Cannot find {} in {}", name, path))?;
+        output.push_str(&format!(
+            "\n/// The variants of [`{0}`](crate::{0}).\n\
+             pub const {1}: &[VariantDoc] = &[\n",
+            name, constant
+        ));
+        for Variant { variant, name, doc } in variants {
+            output.push_str(&format!(
+                "    VariantDoc {{\n        \
+                         variant: {:?},\n        \
+                         name: {:?},\n        \
+                         doc: {:?},\n    \
+                     }},\n",
+                variant, name, doc
+            ));
+        }
+        output.push_str("];\n");
+    }
+    Ok(output)
+}
+
+/// Regenerates the documentation of the variants, or checks that it is
+/// up to date, and returns whether it succeeds.
+fn update_docs(check: bool) -> bool {
+    let path = workspace_root().join(GENERATED_DOCS);
+    let docs = match generate_docs() {
+        Ok(docs) => docs,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return false;
+        }
+    };
+    if check {
+        let up_to_date = fs::read_to_string(&path).is_ok_and(|old| old == docs);
+        if !up_to_date {
+            eprintln!(
+                "Error: {} is out of date. Please run `cargo xtask docs`.",
+                GENERATED_DOCS
+            );
+        }
+        up_to_date
+    } else if let Err(e) = fs::write(&path, docs) {
+        eprintln!("Error: Cannot write {}: {}", GENERATED_DOCS, e);
+        false
+    } else {
+        true
+    }
+}
+
+fn main() {
+    let success = match env::args().nth(1).as_deref() {
+        Some("features") => check_features() == 0,
+        Some("msrv") => check_msrv(),
+        Some("docs") => update_docs(env::args().nth(2).as_deref() == Some("--check")),
+        _ => {
+            eprintln!("Usage: cargo xtask <features|msrv|docs [--check]>");
+            false
+        }
+    };
+    if !success {
+        exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lib_features() {
+        let manifest = read_manifest(&workspace_root().join("lib").join("Cargo.toml"));
+        let features = parse_features(&manifest);
+        assert!(features.iter().all(|feature| feature.name != "default"));
+        let read_rle = features
+            .iter()
+            .find(|feature| feature.name == "read-rle")
+            .unwrap();
+        assert_eq!(read_rle.implies, vec!["serde", "ca-formats"]);
+        let js = features
+            .iter()
+            .find(|feature| feature.name == "js")
+            .unwrap();
+        assert!(js.implies.is_empty());
+    }
+
+    #[test]
+    fn sets() {
+        let manifest = "[dependencies]\n\
+                        a = { version = \"1.0\", optional = true }\n\
+                        dep = \"1.0\"\n\
+                        \n\
+                        [features]\n\
+                        default = [\"a\"]\n\
+                        \n\
+                        # Comment.\n\
+                        b = [\"a\", \"dep\", \"dep/c\"]\n\
+                        c = []\n";
+        let features = parse_features(manifest);
+        assert_eq!(
+            features,
+            vec![
+                Feature {
+                    name: "a".to_owned(),
+                    implies: vec![],
+                },
+                Feature {
+                    name: "b".to_owned(),
+                    implies: vec!["a".to_owned()],
+                },
+                Feature {
+                    name: "c".to_owned(),
+                    implies: vec![],
+                },
+            ]
+        );
+        assert_eq!(
+            feature_sets(&features),
+            vec![
+                vec![],
+                vec!["a"],
+                vec!["a", "b"],
+                vec!["c"],
+                vec!["a", "c"],
+                vec!["a", "b", "c"],
+            ]
+        );
+    }
+
+    #[test]
+    fn msrv() {
+        let manifest = read_manifest(&workspace_root().join("Cargo.toml"));
+        assert!(parse_msrv(&manifest).is_some());
+        assert_eq!(parse_msrv("rust-version = \"1.74\"\n"), Some("1.74"));
+        assert_eq!(parse_msrv("[workspace]\n"), None);
+    }
+
+    #[test]
+    fn docs_up_to_date() {
+        assert!(
+            update_docs(true),
+            "the generated docs are out of date, please run `cargo xtask docs`"
+        );
+    }
+
+    #[test]
+    fn enum_variants() {
+        let source = "/// Not this one.\n\
+                      pub enum Other {\n\
+                      }\n\
+                      \n\
+                      pub enum Choice {\n    \
+                          /// `A`.\n    \
+                          ///\n    \
+                          /// The first choice, see [`Choice::B`].\n    \
+                          #[default]\n    \
+                          First,\n\
+                      \n    \
+                          /// The [second](Self::Second) choice, with [a link](https://example.com).\n    \
+                          Second {\n        \
+                              /// A field.\n        \
+                              field: f64,\n    \
+                          },\n    \
+                          Third(Vec<i32>),\n\
+                      }\n";
+        assert_eq!(
+            parse_enum(source, "Choice"),
+            Some(vec![
+                Variant {
+                    variant: "First".to_owned(),
+                    name: "A".to_owned(),
+                    doc: "The first choice, see `Choice::B`.".to_owned(),
+                },
+                Variant {
+                    variant: "Second".to_owned(),
+                    name: "Second".to_owned(),
+                    doc: "The second choice, with [a link](https://example.com).".to_owned(),
+                },
+                Variant {
+                    variant: "Third".to_owned(),
+                    name: "Third".to_owned(),
+                    doc: String::new(),
+                },
+            ])
+        );
+        assert_eq!(parse_enum(source, "Missing"), None);
+    }
+}